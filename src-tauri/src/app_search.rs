@@ -6,7 +6,11 @@ use std::process::Command;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AppInfo {
     pub name: String,
+    /// 网址快捷方式（is_url 为 true）时为网址本身
     pub path: String,
+    /// 来自 .url 文件或用户添加的网址快捷方式，通过 open_url 打开
+    #[serde(default)]
+    pub is_url: bool,
     pub icon: Option<String>,
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -15,6 +19,433 @@ pub struct AppInfo {
     pub name_pinyin_initials: Option<String>, // Cached pinyin initials for faster search
 }
 
+/// 应用搜索的一页结果
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSearchPage {
+    pub items: Vec<AppInfo>,
+    /// 匹配的应用总数（不受分页影响）
+    pub total_count: usize,
+    pub offset: usize,
+}
+
+/// 未指定 limit 时每页返回的应用数
+pub const DEFAULT_APP_SEARCH_LIMIT: usize = 20;
+/// 空查询未指定 limit 时只列出前几个应用
+pub const EMPTY_QUERY_APP_LIMIT: usize = 10;
+
+/// 未指定 limit 时的每页数量：空查询最多 EMPTY_QUERY_APP_LIMIT 个，其余使用设置中的结果数
+pub fn default_app_search_limit(query: &str, max_app_results: usize) -> usize {
+    if query.is_empty() {
+        max_app_results.min(EMPTY_QUERY_APP_LIMIT)
+    } else {
+        max_app_results
+    }
+}
+
+/// 扫描应用时默认跳过的条目：卸载程序、说明文件
+pub const DEFAULT_APP_SCAN_IGNORE: &[&str] = &["uninstall", "卸载", "readme"];
+
+#[derive(Debug, Clone)]
+enum IgnorePattern {
+    Glob(glob::Pattern),
+    Substring(String),
+}
+
+/// 应用扫描的忽略规则，忽略大小写匹配文件名或完整路径：
+/// 含 * ? [ 的按通配符匹配，其余按子串匹配
+#[derive(Debug, Clone, Default)]
+pub struct AppScanIgnore {
+    patterns: Vec<IgnorePattern>,
+}
+
+const IGNORE_MATCH_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+impl AppScanIgnore {
+    /// 内置规则加上用户规则；用户规则以 ! 开头时移除同名的内置规则，如 "!readme"
+    pub fn new(user_patterns: &[String]) -> Self {
+        let removed: Vec<String> = user_patterns
+            .iter()
+            .filter_map(|p| p.trim().strip_prefix('!'))
+            .map(|p| p.trim().to_lowercase())
+            .collect();
+        let defaults = DEFAULT_APP_SCAN_IGNORE
+            .iter()
+            .filter(|p| !removed.contains(&p.to_lowercase()))
+            .map(|p| p.to_string());
+        let additions = user_patterns
+            .iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty() && !p.starts_with('!'))
+            .map(str::to_string);
+
+        let patterns = defaults
+            .chain(additions)
+            .filter_map(|p| {
+                if p.contains(['*', '?', '[']) {
+                    // 无效的通配符忽略掉，不影响其他规则
+                    glob::Pattern::new(&p).ok().map(IgnorePattern::Glob)
+                } else {
+                    Some(IgnorePattern::Substring(p.to_lowercase()))
+                }
+            })
+            .collect();
+        AppScanIgnore { patterns }
+    }
+
+    /// name 为应用名或文件名，path 为完整路径
+    pub fn is_ignored(&self, name: &str, path: &str) -> bool {
+        let name_lower = name.to_lowercase();
+        let path_lower = path.to_lowercase();
+        self.patterns.iter().any(|pattern| match pattern {
+            IgnorePattern::Glob(glob) => {
+                glob.matches_with(name, IGNORE_MATCH_OPTIONS)
+                    || glob.matches_with(path, IGNORE_MATCH_OPTIONS)
+            }
+            IgnorePattern::Substring(text) => {
+                name_lower.contains(text.as_str()) || path_lower.contains(text.as_str())
+            }
+        })
+    }
+}
+
+/// 解析 .url（Internet Shortcut）文件：取 [InternetShortcut] 节中的 URL= 值，
+/// 缺少该行、值为空或不带协议（如 "https:"、"steam:"）时返回 None
+pub fn parse_url_file(data: &[u8]) -> Option<String> {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => std::borrow::Cow::Borrowed(text),
+        Err(_) => encoding_rs::GBK.decode(data).0,
+    };
+
+    let mut in_section = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line.eq_ignore_ascii_case("[InternetShortcut]");
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if !key.trim().eq_ignore_ascii_case("URL") {
+            continue;
+        }
+        let url = value.trim();
+        return is_url_with_scheme(url).then(|| url.to_string());
+    }
+    None
+}
+
+/// 形如 "scheme:rest" 的网址；协议至少两个字符，以免把 "C:\..." 这样的路径当作网址
+pub fn is_url_with_scheme(url: &str) -> bool {
+    let Some((scheme, rest)) = url.split_once(':') else {
+        return false;
+    };
+    scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && !rest.is_empty()
+}
+
+/// .lnk（Shell Link，MS-SHLLINK）文件中解析出的目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LnkTarget {
+    /// LinkInfo 中的绝对路径（本地路径或 \\server\share\... 网络路径）
+    Absolute(String),
+    /// StringData 中相对于 .lnk 所在目录的路径
+    Relative(String),
+}
+
+const LNK_HAS_TARGET_ID_LIST: u32 = 0x01;
+const LNK_HAS_LINK_INFO: u32 = 0x02;
+const LNK_HAS_NAME: u32 = 0x04;
+const LNK_HAS_RELATIVE_PATH: u32 = 0x08;
+const LNK_HAS_WORKING_DIR: u32 = 0x10;
+const LNK_HAS_ARGUMENTS: u32 = 0x20;
+const LNK_HAS_ICON_LOCATION: u32 = 0x40;
+const LNK_IS_UNICODE: u32 = 0x80;
+/// ExtraData 中 EnvironmentVariableDataBlock 的签名，块内保存含环境变量的目标路径
+const LNK_ENVIRONMENT_BLOCK: u32 = 0xA000_0001;
+
+/// .lnk 文件中与目标相关的字段，字符串字段为空时为 None
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShellLink {
+    /// LinkInfo 中的绝对路径
+    pub link_info_path: Option<String>,
+    /// 快捷方式的说明（StringData 中的 NAME）
+    pub description: Option<String>,
+    pub relative_path: Option<String>,
+    pub working_dir: Option<String>,
+    pub arguments: Option<String>,
+    pub icon_location: Option<String>,
+    pub icon_index: i32,
+    /// EnvironmentVariableDataBlock 中未展开的目标，如 "%windir%\\notepad.exe"
+    pub environment_target: Option<String>,
+}
+
+fn read_u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// 以 0 结尾的 ANSI 字符串；非 UTF-8 内容按 GBK 解码（中文系统的默认代码页）
+fn read_ansi_z(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let bytes = &bytes[..bytes.iter().position(|&b| b == 0)?];
+    if bytes.is_empty() {
+        return None;
+    }
+    Some(match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => encoding_rs::GBK.decode(bytes).0.into_owned(),
+    })
+}
+
+/// 以 0 结尾的 UTF-16LE 字符串
+fn read_unicode_z(data: &[u8], offset: usize) -> Option<String> {
+    let units: Vec<u16> = data
+        .get(offset..)?
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    (!units.is_empty()).then(|| String::from_utf16_lossy(&units))
+}
+
+/// LinkInfo 结构中的目标路径：VolumeID + LocalBasePath，或 CommonNetworkRelativeLink 的 NetName，
+/// 再拼上 CommonPathSuffix；存在 Unicode 版本的偏移时优先使用
+fn lnk_link_info_path(info: &[u8]) -> Option<String> {
+    let header_size = read_u32_at(info, 4)?;
+    let flags = read_u32_at(info, 8)?;
+    let has_unicode = header_size >= 0x24;
+    let field = |offset: usize| read_u32_at(info, offset).map(|v| v as usize).filter(|&v| v > 0);
+
+    let suffix = if has_unicode {
+        field(0x20).and_then(|offset| read_unicode_z(info, offset))
+    } else {
+        None
+    }
+    .or_else(|| field(0x18).and_then(|offset| read_ansi_z(info, offset)))
+    .unwrap_or_default();
+
+    // VolumeIDAndLocalBasePath
+    if flags & 0x01 != 0 {
+        let base = if has_unicode {
+            field(0x1C).and_then(|offset| read_unicode_z(info, offset))
+        } else {
+            None
+        }
+        .or_else(|| field(0x10).and_then(|offset| read_ansi_z(info, offset)))?;
+        return Some(format!("{}{}", base, suffix));
+    }
+
+    // CommonNetworkRelativeLinkAndPathSuffix
+    if flags & 0x02 != 0 {
+        let link = info.get(field(0x14)?..)?;
+        let net_name_offset = read_u32_at(link, 8)? as usize;
+        let net_name = if net_name_offset > 0x14 {
+            read_u32_at(link, 0x14).and_then(|offset| read_unicode_z(link, offset as usize))
+        } else {
+            None
+        }
+        .or_else(|| read_ansi_z(link, net_name_offset))?;
+        return Some(if suffix.is_empty() {
+            net_name
+        } else {
+            format!("{}\\{}", net_name.trim_end_matches('\\'), suffix)
+        });
+    }
+
+    None
+}
+
+/// StringData 中的一项：2 字节字符数 + 字符（IsUnicode 时为 UTF-16），返回字符串和下一项的偏移
+fn read_lnk_string(data: &[u8], offset: usize, unicode: bool) -> Option<(String, usize)> {
+    let count = read_u16_at(data, offset)? as usize;
+    let end = offset + 2 + count * if unicode { 2 } else { 1 };
+    let bytes = data.get(offset + 2..end)?;
+    let text = if unicode {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_string(),
+            Err(_) => encoding_rs::GBK.decode(bytes).0.into_owned(),
+        }
+    };
+    Some((text, end))
+}
+
+/// 在 ExtraData 的数据块中查找 EnvironmentVariableDataBlock 的目标路径（优先 Unicode 版本）
+fn lnk_environment_target(data: &[u8], mut offset: usize) -> Option<String> {
+    loop {
+        let size = read_u32_at(data, offset)? as usize;
+        // BlockSize 小于 4 表示 TerminalBlock
+        if size < 4 {
+            return None;
+        }
+        let block = data.get(offset..offset.checked_add(size)?)?;
+        if read_u32_at(block, 4)? == LNK_ENVIRONMENT_BLOCK && size >= 0x314 {
+            return read_unicode_z(&block[0x10C..0x314], 0)
+                .or_else(|| read_ansi_z(&block[8..0x10C], 0));
+        }
+        offset += size;
+    }
+}
+
+/// 解析 .lnk 文件内容；文件头无效或结构被截断时返回错误
+pub fn parse_shell_link(data: &[u8]) -> Result<ShellLink, String> {
+    const TRUNCATED: &str = "快捷方式文件已损坏（结构不完整）";
+    // ShellLinkHeader：HeaderSize 固定为 0x4C，LinkFlags 位于偏移 0x14，IconIndex 位于 0x38
+    if read_u32_at(data, 0) != Some(0x4C) {
+        return Err("不是有效的快捷方式文件（文件头无效）".to_string());
+    }
+    let flags = read_u32_at(data, 0x14).ok_or(TRUNCATED)?;
+    let mut link = ShellLink {
+        icon_index: read_u32_at(data, 0x38).ok_or(TRUNCATED)? as i32,
+        ..Default::default()
+    };
+    let mut offset = 0x4C;
+
+    if flags & LNK_HAS_TARGET_ID_LIST != 0 {
+        offset += 2 + read_u16_at(data, offset).ok_or(TRUNCATED)? as usize;
+    }
+
+    if flags & LNK_HAS_LINK_INFO != 0 {
+        let size = read_u32_at(data, offset).ok_or(TRUNCATED)? as usize;
+        let info = offset
+            .checked_add(size)
+            .and_then(|end| data.get(offset..end))
+            .ok_or(TRUNCATED)?;
+        link.link_info_path = lnk_link_info_path(info);
+        offset += size;
+    }
+
+    // StringData 依次为 NAME、RELATIVE_PATH、WORKING_DIR、ARGUMENTS、ICON_LOCATION
+    let unicode = flags & LNK_IS_UNICODE != 0;
+    let fields = [
+        (LNK_HAS_NAME, &mut link.description),
+        (LNK_HAS_RELATIVE_PATH, &mut link.relative_path),
+        (LNK_HAS_WORKING_DIR, &mut link.working_dir),
+        (LNK_HAS_ARGUMENTS, &mut link.arguments),
+        (LNK_HAS_ICON_LOCATION, &mut link.icon_location),
+    ];
+    for (flag, field) in fields {
+        if flags & flag != 0 {
+            let (text, next) = read_lnk_string(data, offset, unicode).ok_or(TRUNCATED)?;
+            *field = Some(text).filter(|text| !text.is_empty());
+            offset = next;
+        }
+    }
+
+    link.environment_target = lnk_environment_target(data, offset);
+    Ok(link)
+}
+
+/// 解析 .lnk 文件内容得到目标路径；只有 IDList 而没有路径信息的快捷方式（如指向控制面板项）返回 None
+pub fn parse_lnk_target(data: &[u8]) -> Option<LnkTarget> {
+    let link = parse_shell_link(data).ok()?;
+    link.link_info_path
+        .map(LnkTarget::Absolute)
+        .or(link.relative_path.map(LnkTarget::Relative))
+        .or(link.environment_target.map(LnkTarget::Absolute))
+}
+
+/// 读取 .lnk 文件并解析出目标的绝对路径（相对路径按 .lnk 所在目录解析，环境变量会展开），不检查目标是否存在
+pub fn resolve_lnk_target(lnk_path: &Path) -> Option<PathBuf> {
+    let data = fs::read(lnk_path).ok()?;
+    lnk_target_path(lnk_path, parse_lnk_target(&data)?)
+}
+
+fn lnk_target_path(lnk_path: &Path, target: LnkTarget) -> Option<PathBuf> {
+    match target {
+        LnkTarget::Absolute(path) => Some(PathBuf::from(crate::path_utils::expand_path(&path))),
+        LnkTarget::Relative(relative) => Some(lnk_path.parent()?.join(relative)),
+    }
+}
+
+/// resolve_shortcut 命令的返回值
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutInfo {
+    /// .lnk 文件本身的路径
+    pub path: String,
+    /// 目标路径，指向虚拟位置（如控制面板项）的快捷方式为 None
+    pub target: Option<String>,
+    pub target_exists: bool,
+    pub arguments: Option<String>,
+    pub working_dir: Option<String>,
+    pub icon_location: Option<String>,
+    pub icon_index: i32,
+    pub description: Option<String>,
+    /// 解析方式："native"（直接解析二进制格式）或 "wscript"（WScript.Shell）
+    pub resolved_by: String,
+}
+
+/// 解析快捷方式的目标、参数、工作目录和图标位置；
+/// 优先直接解析 .lnk 二进制格式，得不到目标时在 Windows 上退回 WScript.Shell
+pub fn resolve_shortcut(path: &str) -> Result<ShortcutInfo, String> {
+    let lnk_path = PathBuf::from(crate::path_utils::expand_path(path.trim()));
+    let is_lnk = lnk_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"));
+    if !is_lnk {
+        return Err(format!("不是 .lnk 快捷方式: {}", lnk_path.display()));
+    }
+    let data = fs::read(&lnk_path)
+        .map_err(|e| format!("读取快捷方式失败 {}: {}", lnk_path.display(), e))?;
+
+    let target = parse_lnk_target(&data)
+        .and_then(|target| lnk_target_path(&lnk_path, target))
+        .map(|target| target.to_string_lossy().to_string());
+    let native = parse_shell_link(&data).map(|link| ShortcutInfo {
+        path: lnk_path.to_string_lossy().to_string(),
+        target,
+        target_exists: false,
+        arguments: link.arguments,
+        working_dir: link.working_dir,
+        icon_location: link.icon_location,
+        icon_index: link.icon_index,
+        description: link.description,
+        resolved_by: "native".to_string(),
+    });
+
+    #[cfg(target_os = "windows")]
+    let native = match native {
+        Ok(info) if info.target.is_some() => Ok(info),
+        // 目标只在 IDList 中或文件无法解析时交给 WScript.Shell；它也失败时保留原生解析的结果或错误
+        other => match windows::resolve_shortcut_with_wscript(&lnk_path) {
+            Ok(fallback) if fallback.target.is_some() => Ok(fallback),
+            _ => other,
+        },
+    };
+
+    let mut info = native?;
+    info.target_exists = info
+        .target
+        .as_deref()
+        .is_some_and(|target| Path::new(target).exists());
+    Ok(info)
+}
+
 #[cfg(target_os = "windows")]
 pub mod windows {
     use super::*;
@@ -64,7 +495,10 @@ pub mod windows {
     }
 
     // Windows-specific implementation
-    pub fn scan_start_menu(tx: Option<std::sync::mpsc::Sender<(u8, String)>>) -> Result<Vec<AppInfo>, String> {
+    pub fn scan_start_menu(
+        tx: Option<std::sync::mpsc::Sender<(u8, String)>>,
+        ignore: &AppScanIgnore,
+    ) -> Result<Vec<AppInfo>, String> {
         let mut apps = Vec::new();
 
         // Common start menu paths - scan user, local user, and system start menus
@@ -107,7 +541,7 @@ pub mod windows {
                     let _ = tx.send((10 + (idx as u8 * 15), format!("正在扫描: {}", path_name)));
                 }
                 // Start scanning from depth 0, limit to 3 levels for better coverage
-                if let Err(_e) = scan_directory(&start_menu_path, &mut apps, 0) {
+                if let Err(_e) = scan_directory(&start_menu_path, &mut apps, 0, ignore) {
                     // Continue on error
                 }
             }
@@ -119,7 +553,7 @@ pub mod windows {
         }
         for desktop_path in desktop_paths.into_iter().flatten() {
             if desktop_path.exists() {
-                if let Err(_e) = scan_directory(&desktop_path, &mut apps, 0) {
+                if let Err(_e) = scan_directory(&desktop_path, &mut apps, 0, ignore) {
                     // Continue on error
                 }
             }
@@ -130,6 +564,7 @@ pub mod windows {
             let _ = tx.send((70, "正在扫描 Microsoft Store 应用...".to_string()));
         }
         if let Ok(mut uwp_apps) = scan_uwp_apps() {
+            uwp_apps.retain(|app| !ignore.is_ignored(&app.name, &app.path));
             apps.append(&mut uwp_apps);
         }
 
@@ -247,6 +682,7 @@ pub mod windows {
             let builtin_settings = AppInfo {
                 name: "设置".to_string(),
                 path: "ms-settings:".to_string(),
+                is_url: false,
                 icon: None,
                 description: Some("Windows 系统设置".to_string()),
                 name_pinyin: Some("shezhi".to_string()),
@@ -275,6 +711,7 @@ pub mod windows {
             let builtin_calculator = AppInfo {
                 name: "计算器".to_string(),
                 path: "shell:AppsFolder\\Microsoft.WindowsCalculator_8wekyb3d8bbwe!App".to_string(),
+                is_url: false,
                 icon: None,
                 description: Some("Windows 计算器".to_string()),
                 name_pinyin: Some("jisuanqi".to_string()),
@@ -308,10 +745,10 @@ pub mod windows {
 
     /// 扫描特定路径并返回找到的应用
     /// 用于在搜索时实时发现新应用
-    pub fn scan_specific_path(path: &Path) -> Result<Vec<AppInfo>, String> {
+    pub fn scan_specific_path(path: &Path, ignore: &AppScanIgnore) -> Result<Vec<AppInfo>, String> {
         let mut apps = Vec::new();
         if path.exists() {
-            scan_directory(path, &mut apps, 0)?;
+            scan_directory(path, &mut apps, 0, ignore)?;
         }
         Ok(apps)
     }
@@ -408,6 +845,7 @@ pub mod windows {
             apps.push(AppInfo {
                 name: name_string,
                 path,
+                is_url: false,
                 icon: None,
                 description: None,
                 name_pinyin,
@@ -418,7 +856,33 @@ pub mod windows {
         Ok(apps)
     }
 
-    fn scan_directory(dir: &Path, apps: &mut Vec<AppInfo>, depth: usize) -> Result<(), String> {
+    /// 网址快捷方式对应的 AppInfo，path 为网址
+    pub fn url_app_info(name: String, url: String, icon: Option<String>) -> AppInfo {
+        let (name_pinyin, name_pinyin_initials) = if contains_chinese(&name) {
+            (
+                Some(to_pinyin(&name).to_lowercase()),
+                Some(to_pinyin_initials(&name).to_lowercase()),
+            )
+        } else {
+            (None, None)
+        };
+        AppInfo {
+            name,
+            path: url,
+            is_url: true,
+            icon,
+            description: None,
+            name_pinyin,
+            name_pinyin_initials,
+        }
+    }
+
+    fn scan_directory(
+        dir: &Path,
+        apps: &mut Vec<AppInfo>,
+        depth: usize,
+        ignore: &AppScanIgnore,
+    ) -> Result<(), String> {
         // Limit recursion depth to avoid scanning too deep (increased to 3 for better coverage)
         const MAX_DEPTH: usize = 3;
         if depth > MAX_DEPTH {
@@ -449,9 +913,15 @@ pub mod windows {
 
             if path.is_dir() {
                 // Recursively scan subdirectories
-                if let Err(_) = scan_directory(&path, apps, depth + 1) {
+                if let Err(_) = scan_directory(&path, apps, depth + 1, ignore) {
                     // Continue on error
                 }
+            } else if ignore.is_ignored(
+                &entry.file_name().to_string_lossy(),
+                &path.to_string_lossy(),
+            ) {
+                // 命中忽略规则（卸载程序、说明文件等）
+                continue;
             } else if path
                 .extension()
                 .and_then(|s| s.to_str())
@@ -474,12 +944,22 @@ pub mod windows {
                     apps.push(AppInfo {
                         name: name_str,
                         path: path.to_string_lossy().to_string(),
+                        is_url: false,
                         icon: None, // Will be extracted in background
                         description: None,
                         name_pinyin,
                         name_pinyin_initials,
                     });
                 }
+            } else if path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("url"))
+            {
+                // 网址快捷方式：读取其中的 URL，无效的文件跳过
+                let url = fs::read(&path).ok().and_then(|data| parse_url_file(&data));
+                if let (Some(url), Some(name)) = (url, path.file_stem().and_then(|s| s.to_str())) {
+                    apps.push(url_app_info(name.to_string(), url, None));
+                }
             } else if path
                 .extension()
                 .and_then(|s| s.to_str())
@@ -501,6 +981,7 @@ pub mod windows {
                     apps.push(AppInfo {
                         name: name_str,
                         path: path.to_string_lossy().to_string(),
+                        is_url: false,
                         icon: None, // Will be extracted in background
                         description: None,
                         name_pinyin,
@@ -1003,35 +1484,6 @@ try {
         }
     }
 
-    // 辅助函数：展开环境变量路径（使用 Rust 实现，不依赖 PowerShell）
-    fn expand_env_path(path: &str) -> String {
-        use std::env;
-        
-        // 简单的环境变量展开实现
-        let mut result = path.to_string();
-        
-        // 展开常见环境变量
-        let common_vars = [
-            ("%windir%", env::var("WINDIR").unwrap_or_else(|_| "C:\\Windows".to_string())),
-            ("%SystemRoot%", env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string())),
-            ("%ProgramFiles%", env::var("ProgramFiles").unwrap_or_else(|_| "C:\\Program Files".to_string())),
-            ("%ProgramFiles(x86)%", env::var("ProgramFiles(x86)").unwrap_or_else(|_| "C:\\Program Files (x86)".to_string())),
-            ("%ProgramData%", env::var("ProgramData").unwrap_or_else(|_| "C:\\ProgramData".to_string())),
-            ("%USERPROFILE%", env::var("USERPROFILE").unwrap_or_else(|_| "C:\\Users\\User".to_string())),
-            ("%APPDATA%", env::var("APPDATA").unwrap_or_else(|_| "C:\\Users\\User\\AppData\\Roaming".to_string())),
-            ("%LOCALAPPDATA%", env::var("LOCALAPPDATA").unwrap_or_else(|_| "C:\\Users\\User\\AppData\\Local".to_string())),
-        ];
-        
-        for (var, value) in &common_vars {
-            result = result.replace(var, value);
-            result = result.replace(&var.to_lowercase(), value);
-        }
-        
-        // 尝试展开其他环境变量（使用正则表达式匹配 %VAR% 格式）
-        // 这里使用简单的字符串替换，对于复杂情况可能需要更完整的实现
-        result
-    }
-
     // 辅助函数：直接解析 .lnk 文件二进制格式获取 IconLocation 和 TargetPath
     // 由于 PowerShell 在约束语言模式下无法工作，我们直接解析 .lnk 文件的二进制格式
     fn get_lnk_icon_location(lnk_path: &Path) -> Option<(PathBuf, i32)> {
@@ -1266,7 +1718,7 @@ try {
             }
         }        // 优先使用 TargetPath（如果存在且有效），否则使用 IconLocation
         if let Some(ref target_path_str) = target_path {
-            let expanded_path = expand_env_path(target_path_str);
+            let expanded_path = crate::path_utils::expand_path(target_path_str);
             let target_path_buf = PathBuf::from(&expanded_path);            // 如果 TargetPath 存在且是文件，优先使用它
             if target_path_buf.exists() && target_path_buf.is_file() {
                 return Some((target_path_buf, 0));
@@ -1275,13 +1727,13 @@ try {
         
         // 如果 TargetPath 不存在或无效，尝试使用 IconLocation
         if let Some(ref icon_path_str) = icon_location {
-            let expanded_path = expand_env_path(icon_path_str);
+            let expanded_path = crate::path_utils::expand_path(icon_path_str);
             let icon_path = PathBuf::from(&expanded_path);            return Some((icon_path, icon_index));
         }
         
         // 如果 IconLocation 也不存在，但 TargetPath 存在（即使是目录），也返回它
         if let Some(ref target_path_str) = target_path {
-            let expanded_path = expand_env_path(target_path_str);
+            let expanded_path = crate::path_utils::expand_path(target_path_str);
             let target_path_buf = PathBuf::from(&expanded_path);
             
             if target_path_buf.exists() {
@@ -1638,6 +2090,7 @@ public class IconExtractor {
         Ok(AppInfo {
             name,
             path: target,
+            is_url: false,
             icon: None,
             description: None,
             name_pinyin,
@@ -1645,6 +2098,60 @@ public class IconExtractor {
         })
     }
 
+    /// 通过 WScript.Shell 读取快捷方式，用于原生解析得不到目标（如目标只保存在 IDList 中）的情况
+    pub fn resolve_shortcut_with_wscript(lnk_path: &Path) -> Result<ShortcutInfo, String> {
+        let path_str = lnk_path.to_string_lossy().replace('\'', "''"); // Escape single quotes for PowerShell
+        let ps_command = format!(
+            r#"[Console]::OutputEncoding = [System.Text.Encoding]::UTF8; $s = (New-Object -ComObject WScript.Shell).CreateShortcut('{}'); $s.TargetPath; $s.Arguments; $s.WorkingDirectory; $s.IconLocation; $s.Description"#,
+            path_str
+        );
+
+        let output = Command::new("powershell")
+            .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-Command", &ps_command])
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW - 隐藏 PowerShell 窗口
+            .output()
+            .map_err(|e| format!("Failed to execute PowerShell: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "WScript.Shell 无法读取快捷方式: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.split('\n').map(|line| {
+            let line = line.trim_end_matches('\r').trim();
+            (!line.is_empty()).then(|| line.to_string())
+        });
+        let target = lines.next().flatten();
+        let arguments = lines.next().flatten();
+        let working_dir = lines.next().flatten();
+        // IconLocation 格式为 "path,index"，没有图标时为 ",0"
+        let (icon_location, icon_index) = match lines.next().flatten() {
+            Some(location) => match location.rsplit_once(',') {
+                Some((icon_path, index)) => (
+                    Some(icon_path.trim().to_string()).filter(|p| !p.is_empty()),
+                    index.trim().parse().unwrap_or(0),
+                ),
+                None => (Some(location), 0),
+            },
+            None => (None, 0),
+        };
+        let description = lines.next().flatten();
+
+        Ok(ShortcutInfo {
+            path: lnk_path.to_string_lossy().to_string(),
+            target,
+            target_exists: false,
+            arguments,
+            working_dir,
+            icon_location,
+            icon_index,
+            description,
+            resolved_by: "wscript".to_string(),
+        })
+    }
+
     // Convert Chinese characters to pinyin (full pinyin)
     fn to_pinyin(text: &str) -> String {
         text.to_pinyin()
@@ -1676,115 +2183,88 @@ public class IconExtractor {
         })
     }
 
-    pub fn search_apps(query: &str, apps: &[AppInfo]) -> Vec<AppInfo> {
-        if query.is_empty() {
-            return apps.iter().take(10).cloned().collect();
-        }
-
-        let query_lower = query.to_lowercase();
-        let query_is_pinyin = !contains_chinese(&query_lower);
-
-        // Pre-allocate with capacity estimate to reduce allocations
-        let mut results: Vec<(usize, i32)> = Vec::with_capacity(20);
-        
-        // Track perfect matches for early exit optimization
-        let mut perfect_matches = 0;
-        const MAX_PERFECT_MATCHES: usize = 3; // Early exit if we find 3 perfect matches (reduced from 5 for faster response)
-        
-        // Check all apps to ensure we find matches regardless of their position in the list
-        // Early exit optimization is still in place for perfect matches to maintain performance
-
-        // Use indices instead of cloning to avoid expensive clones
-        for (idx, app) in apps.iter().enumerate() {
-            let mut score = 0;
-
-            // Direct text match (highest priority) - use case-insensitive comparison
-            // Optimize: compute to_lowercase once per app name
-            let name_lower = app.name.to_lowercase();
-            if name_lower == query_lower {
-                score += 1000;
-                perfect_matches += 1;
-                // For short queries (like "qq"), exit immediately on first perfect match
-                // This ensures fast response for specific app searches
-                if query_lower.len() <= 3 && perfect_matches >= 1 {
-                    results.push((idx, score));
-                    break;
-                }
-                // Early exit if we have enough perfect matches (reduced threshold for faster response)
-                if perfect_matches >= MAX_PERFECT_MATCHES {
-                    // If we have perfect matches, prioritize them and return early
-                    results.push((idx, score));
-                    break;
-                }
-            } else if name_lower.starts_with(&query_lower) {
-                score += 500;
-            } else if name_lower.contains(&query_lower) {
-                score += 100;
-            }
-
-            // Pinyin matching (if query is pinyin) - use cached pinyin if available
-            if query_is_pinyin {
-                // Use cached pinyin if available (much faster than computing on the fly)
-                if let (Some(ref name_pinyin), Some(ref name_pinyin_initials)) =
-                    (&app.name_pinyin, &app.name_pinyin_initials)
-                {
-                    // Full pinyin match
-                    if name_pinyin.as_str() == query_lower {
-                        score += 800; // High score for full pinyin match
-                        perfect_matches += 1;
-                        // Early exit if we have enough perfect matches
-                        if perfect_matches >= MAX_PERFECT_MATCHES {
-                            results.push((idx, score));
-                            break;
-                        }
-                    } else if name_pinyin.starts_with(&query_lower) {
-                        score += 400;
-                    } else if name_pinyin.contains(&query_lower) {
-                        score += 150;
-                    }
-
-                    // Pinyin initials match
-                    if name_pinyin_initials.as_str() == query_lower {
-                        score += 600; // High score for initials match
-                    } else if name_pinyin_initials.starts_with(&query_lower) {
-                        score += 300;
-                    } else if name_pinyin_initials.contains(&query_lower) {
-                        score += 120;
-                    }
+    /// 单个应用与查询的匹配分数，0 表示不匹配
+    fn score_app(app: &AppInfo, query_lower: &str, query_is_pinyin: bool) -> i32 {
+        let mut score = 0;
+
+        // Direct text match (highest priority) - use case-insensitive comparison
+        let name_lower = app.name.to_lowercase();
+        if name_lower == query_lower {
+            score += 1000;
+        } else if name_lower.starts_with(query_lower) {
+            score += 500;
+        } else if name_lower.contains(query_lower) {
+            score += 100;
+        }
+
+        // Pinyin matching (if query is pinyin) - use cached pinyin if available
+        if query_is_pinyin {
+            // Use cached pinyin if available (much faster than computing on the fly)
+            if let (Some(ref name_pinyin), Some(ref name_pinyin_initials)) =
+                (&app.name_pinyin, &app.name_pinyin_initials)
+            {
+                // Full pinyin match
+                if name_pinyin.as_str() == query_lower {
+                    score += 800; // High score for full pinyin match
+                } else if name_pinyin.starts_with(query_lower) {
+                    score += 400;
+                } else if name_pinyin.contains(query_lower) {
+                    score += 150;
                 }
-                // If no cached pinyin, skip pinyin matching (app name likely doesn't contain Chinese)
-            }
 
-            // Path match gets lower score (only check if no name match to save time)
-            if score == 0 {
-                let path_lower = app.path.to_lowercase();
-                if path_lower.contains(&query_lower) {
-                    score += 10;
+                // Pinyin initials match
+                if name_pinyin_initials.as_str() == query_lower {
+                    score += 600; // High score for initials match
+                } else if name_pinyin_initials.starts_with(query_lower) {
+                    score += 300;
+                } else if name_pinyin_initials.contains(query_lower) {
+                    score += 120;
                 }
             }
+            // If no cached pinyin, skip pinyin matching (app name likely doesn't contain Chinese)
+        }
 
-            if score > 0 {
-                results.push((idx, score));
-            }
+        // Path match gets lower score (only check if no name match to save time)
+        if score == 0 && app.path.to_lowercase().contains(query_lower) {
+            score += 10;
         }
 
-        // If we have perfect matches and early exited, return them immediately without sorting
-        if perfect_matches >= MAX_PERFECT_MATCHES && results.len() <= MAX_PERFECT_MATCHES {
-            return results
-                .into_iter()
-                .map(|(idx, _)| apps[idx].clone())
-                .collect();
+        score
+    }
+
+    /// 返回所有匹配应用的下标，按分数从高到低排列（同分保持原有顺序）；空查询返回全部应用
+    pub fn rank_apps(query: &str, apps: &[AppInfo]) -> Vec<usize> {
+        if query.is_empty() {
+            return (0..apps.len()).collect();
         }
 
-        // Sort by score (descending) only if we need to
+        let query_lower = query.to_lowercase();
+        let query_is_pinyin = !contains_chinese(&query_lower);
+
+        // Use indices instead of cloning to avoid expensive clones
+        let mut results: Vec<(usize, i32)> = apps
+            .iter()
+            .enumerate()
+            .map(|(idx, app)| (idx, score_app(app, &query_lower, query_is_pinyin)))
+            .filter(|(_, score)| *score > 0)
+            .collect();
         results.sort_by(|a, b| b.1.cmp(&a.1));
+        results.into_iter().map(|(idx, _)| idx).collect()
+    }
 
-        // Limit to top 20 results for performance, clone only the selected apps
-        results
-            .into_iter()
-            .take(20)
-            .map(|(idx, _)| apps[idx].clone())
-            .collect()
+    /// 搜索应用并返回 [offset, offset + limit) 范围内的结果，只克隆当前页的应用（图标数据较大）
+    pub fn search_apps(query: &str, apps: &[AppInfo], offset: usize, limit: usize) -> AppSearchPage {
+        let ranked = rank_apps(query, apps);
+        AppSearchPage {
+            total_count: ranked.len(),
+            offset,
+            items: ranked
+                .into_iter()
+                .skip(offset)
+                .take(limit)
+                .map(|idx| apps[idx].clone())
+                .collect(),
+        }
     }
 
     pub fn launch_app(app: &AppInfo) -> Result<(), String> {
@@ -1970,15 +2450,401 @@ public class IconExtractor {
 pub mod windows {
     use super::*;
 
-    pub fn scan_start_menu() -> Result<Vec<AppInfo>, String> {
+    pub fn scan_start_menu(
+        _tx: Option<std::sync::mpsc::Sender<(u8, String)>>,
+        _ignore: &AppScanIgnore,
+    ) -> Result<Vec<AppInfo>, String> {
         Err("App search is only supported on Windows".to_string())
     }
 
-    pub fn search_apps(_query: &str, _apps: &[AppInfo]) -> Vec<AppInfo> {
-        vec![]
+    pub fn search_apps(_query: &str, _apps: &[AppInfo], offset: usize, _limit: usize) -> AppSearchPage {
+        AppSearchPage {
+            items: vec![],
+            total_count: 0,
+            offset,
+        }
     }
 
     pub fn launch_app(_app: &AppInfo) -> Result<(), String> {
         Err("App launch is only supported on Windows".to_string())
     }
+
+    pub fn url_app_info(name: String, url: String, icon: Option<String>) -> AppInfo {
+        AppInfo {
+            name,
+            path: url,
+            is_url: true,
+            icon,
+            description: None,
+            name_pinyin: None,
+            name_pinyin_initials: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn lnk_header(flags: u32, icon_index: i32) -> Vec<u8> {
+        let mut data = vec![0u8; 0x4C];
+        data[0..4].copy_from_slice(&0x4Cu32.to_le_bytes());
+        data[0x14..0x18].copy_from_slice(&flags.to_le_bytes());
+        data[0x38..0x3C].copy_from_slice(&icon_index.to_le_bytes());
+        data
+    }
+
+    fn ansi_z(text: &str) -> Vec<u8> {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.push(0);
+        bytes
+    }
+
+    fn unicode_z(text: &str) -> Vec<u8> {
+        text.encode_utf16()
+            .chain(std::iter::once(0))
+            .flat_map(u16::to_le_bytes)
+            .collect()
+    }
+
+    fn lnk_string(text: &str, unicode: bool) -> Vec<u8> {
+        let (count, bytes) = if unicode {
+            let units: Vec<u16> = text.encode_utf16().collect();
+            (
+                units.len(),
+                units.into_iter().flat_map(u16::to_le_bytes).collect(),
+            )
+        } else {
+            (text.len(), text.as_bytes().to_vec())
+        };
+        let mut data = (count as u16).to_le_bytes().to_vec();
+        data.extend(bytes);
+        data
+    }
+
+    /// LinkInfo 头（0x1C 字节）后接各字符串，offsets 为 (头中的字段位置, 字符串)
+    fn link_info(flags: u32, strings: &[(usize, Vec<u8>)]) -> Vec<u8> {
+        let mut info = vec![0u8; 0x1C];
+        info[4..8].copy_from_slice(&0x1Cu32.to_le_bytes());
+        info[8..12].copy_from_slice(&flags.to_le_bytes());
+        for (field, bytes) in strings {
+            let offset = info.len() as u32;
+            info[*field..*field + 4].copy_from_slice(&offset.to_le_bytes());
+            info.extend(bytes);
+        }
+        let size = info.len() as u32;
+        info[0..4].copy_from_slice(&size.to_le_bytes());
+        info
+    }
+
+    fn environment_block(ansi: &str, unicode: &str) -> Vec<u8> {
+        let mut block = vec![0u8; 0x314];
+        block[0..4].copy_from_slice(&0x314u32.to_le_bytes());
+        block[4..8].copy_from_slice(&LNK_ENVIRONMENT_BLOCK.to_le_bytes());
+        let ansi = ansi_z(ansi);
+        block[8..8 + ansi.len()].copy_from_slice(&ansi);
+        let unicode = unicode_z(unicode);
+        block[0x10C..0x10C + unicode.len()].copy_from_slice(&unicode);
+        block
+    }
+
+    #[test]
+    fn shell_link_string_fields() {
+        let flags = LNK_HAS_NAME
+            | LNK_HAS_WORKING_DIR
+            | LNK_HAS_ARGUMENTS
+            | LNK_HAS_ICON_LOCATION
+            | LNK_IS_UNICODE;
+        let mut data = lnk_header(flags, -3);
+        data.extend(lnk_string("Open the editor", true));
+        data.extend(lnk_string("C:\\Work", true));
+        data.extend(lnk_string("--new-window \"a b.txt\"", true));
+        data.extend(lnk_string("%SystemRoot%\\system32\\shell32.dll", true));
+
+        let link = parse_shell_link(&data).unwrap();
+        assert_eq!(
+            link,
+            ShellLink {
+                description: Some("Open the editor".to_string()),
+                working_dir: Some("C:\\Work".to_string()),
+                arguments: Some("--new-window \"a b.txt\"".to_string()),
+                icon_location: Some("%SystemRoot%\\system32\\shell32.dll".to_string()),
+                icon_index: -3,
+                ..Default::default()
+            }
+        );
+        // 没有路径信息时没有目标
+        assert_eq!(parse_lnk_target(&data), None);
+    }
+
+    #[test]
+    fn shell_link_ansi_fields_and_empty_strings() {
+        let flags = LNK_HAS_LINK_INFO | LNK_HAS_NAME | LNK_HAS_ARGUMENTS | LNK_HAS_WORKING_DIR;
+        let mut data = lnk_header(flags, 0);
+        data.extend(link_info(
+            0x01,
+            &[(0x10, ansi_z("C:\\Tools\\app.exe")), (0x18, ansi_z(""))],
+        ));
+        data.extend(lnk_string("", false));
+        data.extend(lnk_string("D:\\", false));
+        data.extend(lnk_string("-v", false));
+
+        let link = parse_shell_link(&data).unwrap();
+        assert_eq!(link.link_info_path.as_deref(), Some("C:\\Tools\\app.exe"));
+        assert_eq!(link.description, None);
+        assert_eq!(link.working_dir.as_deref(), Some("D:\\"));
+        assert_eq!(link.arguments.as_deref(), Some("-v"));
+        assert_eq!(link.icon_location, None);
+    }
+
+    #[test]
+    fn resolve_shortcut_reads_lnk_file() {
+        let dir = temp_dir("lnk", "resolve");
+        fs::write(dir.join("target.txt"), b"").unwrap();
+
+        let flags = LNK_HAS_RELATIVE_PATH | LNK_HAS_ARGUMENTS | LNK_IS_UNICODE;
+        let mut data = lnk_header(flags, 1);
+        data.extend(lnk_string("target.txt", true));
+        data.extend(lnk_string("--flag", true));
+        let lnk = dir.join("Target.LNK");
+        fs::write(&lnk, &data).unwrap();
+
+        let info = resolve_shortcut(&lnk.to_string_lossy()).unwrap();
+        assert_eq!(
+            info.target.as_deref(),
+            Some(dir.join("target.txt").to_string_lossy().as_ref())
+        );
+        assert!(info.target_exists);
+        assert_eq!(info.arguments.as_deref(), Some("--flag"));
+        assert_eq!(info.icon_index, 1);
+        assert_eq!(info.resolved_by, "native");
+
+        let not_lnk = dir.join("target.txt");
+        assert!(resolve_shortcut(&not_lnk.to_string_lossy()).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn link_info_local_path() {
+        let mut data = lnk_header(LNK_HAS_TARGET_ID_LIST | LNK_HAS_LINK_INFO, 0);
+        // IDList 只按长度跳过
+        data.extend(4u16.to_le_bytes());
+        data.extend([0xAA; 4]);
+        data.extend(link_info(
+            0x01,
+            &[(0x10, ansi_z("C:\\Tools\\")), (0x18, ansi_z("app.exe"))],
+        ));
+
+        assert_eq!(
+            parse_lnk_target(&data),
+            Some(LnkTarget::Absolute("C:\\Tools\\app.exe".to_string()))
+        );
+    }
+
+    #[test]
+    fn link_info_network_path() {
+        let mut network = vec![0u8; 0x14];
+        network[8..12].copy_from_slice(&0x14u32.to_le_bytes());
+        network.extend(ansi_z("\\\\server\\share"));
+        let size = network.len() as u32;
+        network[0..4].copy_from_slice(&size.to_le_bytes());
+
+        let mut data = lnk_header(LNK_HAS_LINK_INFO, 0);
+        data.extend(link_info(
+            0x02,
+            &[(0x14, network), (0x18, ansi_z("docs\\report.txt"))],
+        ));
+
+        assert_eq!(
+            parse_lnk_target(&data),
+            Some(LnkTarget::Absolute(
+                "\\\\server\\share\\docs\\report.txt".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn relative_path_resolves_next_to_lnk() {
+        let mut data = lnk_header(LNK_HAS_RELATIVE_PATH | LNK_IS_UNICODE, 0);
+        data.extend(lnk_string("..\\bin\\app.exe", true));
+
+        let target = parse_lnk_target(&data).unwrap();
+        assert_eq!(target, LnkTarget::Relative("..\\bin\\app.exe".to_string()));
+        assert_eq!(
+            lnk_target_path(
+                Path::new("/recent/a.lnk"),
+                LnkTarget::Relative("b.txt".to_string())
+            ),
+            Some(PathBuf::from("/recent/b.txt"))
+        );
+    }
+
+    #[test]
+    fn unicode_string_data() {
+        let flags = LNK_HAS_NAME | LNK_HAS_RELATIVE_PATH | LNK_IS_UNICODE;
+        let mut data = lnk_header(flags, 0);
+        data.extend(lnk_string("年度报告", true));
+        data.extend(lnk_string(".\\文档\\报告.docx", true));
+
+        let link = parse_shell_link(&data).unwrap();
+        assert_eq!(link.description.as_deref(), Some("年度报告"));
+        assert_eq!(link.relative_path.as_deref(), Some(".\\文档\\报告.docx"));
+    }
+
+    #[test]
+    fn environment_variable_block() {
+        let mut data = lnk_header(0, 0);
+        data.extend(environment_block(
+            "%windir%\\ansi.exe",
+            "%windir%\\notepad.exe",
+        ));
+        // TerminalBlock
+        data.extend(0u32.to_le_bytes());
+
+        assert_eq!(
+            parse_lnk_target(&data),
+            Some(LnkTarget::Absolute("%windir%\\notepad.exe".to_string()))
+        );
+    }
+
+    #[test]
+    fn truncated_or_invalid_input() {
+        assert!(parse_shell_link(&[]).is_err());
+        assert!(parse_shell_link(b"not a shortcut file").is_err());
+        assert!(parse_shell_link(&lnk_header(0, 0)[..0x20]).is_err());
+
+        // LinkInfo 声明的长度超出文件
+        let mut data = lnk_header(LNK_HAS_LINK_INFO, 0);
+        data.extend(0x100u32.to_le_bytes());
+        assert!(parse_shell_link(&data).is_err());
+
+        // StringData 的字符数超出文件
+        let mut data = lnk_header(LNK_HAS_NAME | LNK_IS_UNICODE, 0);
+        data.extend(10u16.to_le_bytes());
+        data.extend([0x41, 0x00]);
+        assert!(parse_shell_link(&data).is_err());
+        assert_eq!(parse_lnk_target(&data), None);
+
+        // 只有 IDList 的快捷方式没有可用的目标
+        let mut data = lnk_header(LNK_HAS_TARGET_ID_LIST, 0);
+        data.extend(0u16.to_le_bytes());
+        assert_eq!(parse_lnk_target(&data), None);
+    }
+
+    fn ignored(ignore: &AppScanIgnore, name: &str) -> bool {
+        ignore.is_ignored(name, &format!("C:\\Apps\\{}.lnk", name))
+    }
+
+    #[test]
+    fn default_scan_ignore_patterns() {
+        let ignore = AppScanIgnore::new(&[]);
+        assert!(ignored(&ignore, "卸载 QQ"));
+        assert!(ignored(&ignore, "Uninstall Foo"));
+        assert!(ignored(&ignore, "README"));
+        assert!(!ignored(&ignore, "Notepad"));
+        // 按完整路径匹配
+        assert!(ignore.is_ignored("Foo", "C:\\Foo\\uninstall\\Foo.lnk"));
+    }
+
+    #[test]
+    fn user_scan_ignore_patterns() {
+        let user = ["  Beta ".to_string(), String::new(), "!README".to_string()];
+        let ignore = AppScanIgnore::new(&user);
+        assert!(ignored(&ignore, "App BETA"));
+        // !readme 只移除同名的内置规则
+        assert!(!ignored(&ignore, "Readme"));
+        assert!(ignored(&ignore, "卸载 QQ"));
+        assert!(ignored(&ignore, "uninstall"));
+    }
+
+    #[test]
+    fn glob_and_substring_scan_ignore_patterns() {
+        let ignore = AppScanIgnore::new(&["test*".to_string(), "helper".to_string()]);
+        // 通配符需匹配整个名称，子串只需包含
+        assert!(ignore.is_ignored("TestApp", "D:\\TestApp.lnk"));
+        assert!(!ignore.is_ignored("MyTestApp", "D:\\MyTestApp.lnk"));
+        assert!(ignore.is_ignored("My Helper Tool", "D:\\x.lnk"));
+        assert!(ignore.is_ignored("Tool", "D:\\HELPER\\Tool.lnk"));
+
+        let ignore = AppScanIgnore::new(&["*\\temp\\*".to_string(), "[".to_string()]);
+        assert!(ignore.is_ignored("Tool", "C:\\TEMP\\Tool.lnk"));
+        assert!(!ignore.is_ignored("Tool", "C:\\Apps\\Tool.lnk"));
+        // 无效的通配符被跳过，内置规则仍然生效
+        assert!(ignored(&ignore, "uninstall"));
+    }
+
+    #[test]
+    fn url_file_with_bom_and_crlf() {
+        let data =
+            b"\xEF\xBB\xBF[InternetShortcut]\r\nURL=https://example.com/a?b=c\r\nIconIndex=0\r\n";
+        assert_eq!(
+            parse_url_file(data).as_deref(),
+            Some("https://example.com/a?b=c")
+        );
+        let data = b"[{000214A0-0000-0000-C000-000000000046}]\nProp3=19,11\n[internetshortcut]\n url = steam://run/570 \n";
+        assert_eq!(parse_url_file(data).as_deref(), Some("steam://run/570"));
+    }
+
+    #[test]
+    fn url_file_in_gbk() {
+        let (encoded, _, _) =
+            encoding_rs::GBK.encode("[InternetShortcut]\nURL=https://例子.cn/页面\n");
+        assert!(std::str::from_utf8(&encoded).is_err());
+        assert_eq!(
+            parse_url_file(&encoded).as_deref(),
+            Some("https://例子.cn/页面")
+        );
+    }
+
+    #[test]
+    fn url_file_without_usable_url() {
+        // URL 不在 [InternetShortcut] 节中
+        assert_eq!(parse_url_file(b"[Other]\nURL=https://example.com\n"), None);
+        assert_eq!(parse_url_file(b"URL=https://example.com\n"), None);
+        assert_eq!(parse_url_file(b"[InternetShortcut]\nURL=\n"), None);
+        assert_eq!(
+            parse_url_file(b"[InternetShortcut]\nIconFile=a.ico\n"),
+            None
+        );
+        assert_eq!(
+            parse_url_file(b"[InternetShortcut]\nURL=C:\\Windows\\notepad.exe\n"),
+            None
+        );
+        assert_eq!(parse_url_file(b""), None);
+    }
+
+    #[test]
+    fn url_scheme_detection() {
+        for url in [
+            "https://a.com",
+            "mailto:me@a.com",
+            "steam://run/570",
+            "ms-settings:display",
+            "git+ssh://h/r",
+        ] {
+            assert!(is_url_with_scheme(url), "{}", url);
+        }
+        for url in [
+            "C:\\Windows",
+            "c:/x",
+            "https:",
+            "example.com",
+            "1http://a",
+            ":x",
+            "",
+        ] {
+            assert!(!is_url_with_scheme(url), "{}", url);
+        }
+    }
+
+    #[test]
+    fn empty_query_lists_fewer_apps_by_default() {
+        assert_eq!(
+            default_app_search_limit("", DEFAULT_APP_SEARCH_LIMIT),
+            EMPTY_QUERY_APP_LIMIT
+        );
+        assert_eq!(default_app_search_limit("", 5), 5);
+        assert_eq!(default_app_search_limit("qq", 50), 50);
+    }
 }
@@ -0,0 +1,157 @@
+// 计算器历史：保存在 SQLite 的 calc_history 表中，关闭计算器窗口后仍可查看
+
+use crate::db;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 最多保留的记录数，超出时删除最旧的记录
+pub const MAX_CALC_HISTORY: usize = 500;
+/// get_calc_history 未指定 limit 时返回的条数
+const DEFAULT_CALC_HISTORY_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalcEntry {
+    pub id: i64,
+    pub expression: String,
+    pub result: String,
+    pub created_at: u64,
+}
+
+fn now_ts() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 添加一条记录并裁剪到 MAX_CALC_HISTORY 条
+pub fn add_entry(
+    app_data_dir: &Path,
+    expression: &str,
+    result: &str,
+) -> Result<CalcEntry, String> {
+    let expression = expression.trim();
+    if expression.is_empty() {
+        return Err("表达式不能为空".to_string());
+    }
+    let mut conn = db::get_connection(app_data_dir)?;
+    let created_at = now_ts();
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start calc history transaction: {}", e))?;
+    tx.execute(
+        "INSERT INTO calc_history (expression, result, created_at) VALUES (?1, ?2, ?3)",
+        params![expression, result.trim(), created_at as i64],
+    )
+    .map_err(|e| format!("Failed to insert calc entry: {}", e))?;
+    let id = tx.last_insert_rowid();
+    // id 单调递增，按 id 保留最新的记录（同一秒内的多条也有确定顺序）
+    tx.execute(
+        "DELETE FROM calc_history WHERE id NOT IN
+            (SELECT id FROM calc_history ORDER BY id DESC LIMIT ?1)",
+        params![MAX_CALC_HISTORY as i64],
+    )
+    .map_err(|e| format!("Failed to trim calc history: {}", e))?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit calc entry: {}", e))?;
+
+    Ok(CalcEntry {
+        id,
+        expression: expression.to_string(),
+        result: result.trim().to_string(),
+        created_at,
+    })
+}
+
+/// 最新的记录在前
+pub fn list_entries(app_data_dir: &Path, limit: Option<usize>) -> Result<Vec<CalcEntry>, String> {
+    let limit = limit
+        .unwrap_or(DEFAULT_CALC_HISTORY_LIMIT)
+        .min(MAX_CALC_HISTORY);
+    let conn = db::get_connection(app_data_dir)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, expression, result, created_at FROM calc_history
+             ORDER BY id DESC LIMIT ?1",
+        )
+        .map_err(|e| format!("Failed to prepare calc history query: {}", e))?;
+    let rows = stmt
+        .query_map(params![limit as i64], |row| {
+            Ok(CalcEntry {
+                id: row.get(0)?,
+                expression: row.get(1)?,
+                result: row.get(2)?,
+                created_at: row.get::<_, i64>(3)? as u64,
+            })
+        })
+        .map_err(|e| format!("Failed to query calc history: {}", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read calc history row: {}", e))
+}
+
+/// 清空历史，返回删除的条数
+pub fn clear_entries(app_data_dir: &Path) -> Result<usize, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    conn.execute("DELETE FROM calc_history", [])
+        .map_err(|e| format!("Failed to clear calc history: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+    use std::fs;
+
+    #[test]
+    fn add_entry_trims_and_rejects_empty_expression() {
+        let dir = temp_dir("calc-history", "insert");
+        let entry = add_entry(&dir, "  1 + 2 ", " 3 ").unwrap();
+        assert_eq!(entry.expression, "1 + 2");
+        assert_eq!(entry.result, "3");
+        assert!(add_entry(&dir, "   ", "0").is_err());
+
+        let entries = list_entries(&dir, None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, entry.id);
+        assert_eq!(clear_entries(&dir).unwrap(), 1);
+        assert!(list_entries(&dir, None).unwrap().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn list_entries_returns_newest_first() {
+        let dir = temp_dir("calc-history", "order");
+        for i in 0..5 {
+            add_entry(&dir, &format!("{} * 2", i), &(i * 2).to_string()).unwrap();
+        }
+        let expressions: Vec<String> = list_entries(&dir, Some(3))
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.expression)
+            .collect();
+        assert_eq!(expressions, vec!["4 * 2", "3 * 2", "2 * 2"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn history_is_capped_at_max_entries() {
+        let dir = temp_dir("calc-history", "cap");
+        for i in 0..MAX_CALC_HISTORY + 3 {
+            add_entry(&dir, &format!("{} + 0", i), &i.to_string()).unwrap();
+        }
+        let entries = list_entries(&dir, Some(MAX_CALC_HISTORY + 10)).unwrap();
+        assert_eq!(entries.len(), MAX_CALC_HISTORY);
+        // 最旧的三条被删除
+        assert_eq!(entries.last().unwrap().expression, "3 + 0");
+        assert_eq!(
+            entries[0].expression,
+            format!("{} + 0", MAX_CALC_HISTORY + 2)
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,230 @@
+// 颜色格式转换：解析 hex / rgb() / hsl() 颜色字符串，输出全部表示形式和建议的文字颜色
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorConversion {
+    /// 如 "#1E90FF"，带透明度时为 "#1E90FF80"
+    pub hex: String,
+    /// 如 "rgb(30, 144, 255)" 或 "rgba(30, 144, 255, 0.5)"
+    pub rgb: String,
+    /// 如 "hsl(210, 100%, 56%)" 或 "hsla(210, 100%, 56%, 0.5)"
+    pub hsl: String,
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: f64,
+    /// 在该颜色背景上对比度更高的文字颜色："#000000" 或 "#FFFFFF"
+    pub text_color: String,
+    /// 文字颜色与背景的 WCAG 对比度
+    pub contrast_ratio: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rgba {
+    r: u8,
+    g: u8,
+    b: u8,
+    a: f64,
+}
+
+fn parse_hex(hex: &str) -> Option<Rgba> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    // #rgb / #rgba 展开为 #rrggbb / #rrggbbaa
+    let expanded: String = match hex.len() {
+        3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex.to_string(),
+        _ => return None,
+    };
+    let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).ok();
+    Some(Rgba {
+        r: channel(0)?,
+        g: channel(2)?,
+        b: channel(4)?,
+        a: if expanded.len() == 8 {
+            channel(6)? as f64 / 255.0
+        } else {
+            1.0
+        },
+    })
+}
+
+/// 拆分函数写法的参数，兼容逗号分隔和 CSS 4 的空格 / 斜杠写法，如 "rgb(30 144 255 / 50%)"
+fn function_args<'a>(input: &'a str, names: &[&str]) -> Option<Vec<&'a str>> {
+    let open = input.find('(')?;
+    let name = input[..open].trim();
+    if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+        return None;
+    }
+    let body = input[open + 1..].strip_suffix(')')?;
+    let args: Vec<&str> = body
+        .split([',', '/', ' '])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    matches!(args.len(), 3 | 4).then_some(args)
+}
+
+/// 解析透明度："0.5" 或 "50%"
+fn parse_alpha(text: Option<&&str>) -> Option<f64> {
+    let Some(text) = text else {
+        return Some(1.0);
+    };
+    let alpha = match text.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().ok()? / 100.0,
+        None => text.parse::<f64>().ok()?,
+    };
+    (0.0..=1.0).contains(&alpha).then_some(alpha)
+}
+
+fn parse_rgb(input: &str) -> Option<Rgba> {
+    let args = function_args(input, &["rgb", "rgba"])?;
+    let channel = |text: &str| -> Option<u8> {
+        let value = match text.strip_suffix('%') {
+            Some(percent) => percent.parse::<f64>().ok()? * 255.0 / 100.0,
+            None => text.parse::<f64>().ok()?,
+        };
+        (0.0..=255.0).contains(&value).then(|| value.round() as u8)
+    };
+    Some(Rgba {
+        r: channel(args[0])?,
+        g: channel(args[1])?,
+        b: channel(args[2])?,
+        a: parse_alpha(args.get(3))?,
+    })
+}
+
+fn parse_hsl(input: &str) -> Option<Rgba> {
+    let args = function_args(input, &["hsl", "hsla"])?;
+    let hue = args[0].trim_end_matches("deg").parse::<f64>().ok()?;
+    let percent = |text: &str| -> Option<f64> {
+        let value = text.strip_suffix('%').unwrap_or(text).parse::<f64>().ok()?;
+        (0.0..=100.0).contains(&value).then_some(value / 100.0)
+    };
+    let (r, g, b) = hsl_to_rgb(hue, percent(args[1])?, percent(args[2])?);
+    Some(Rgba {
+        r,
+        g,
+        b,
+        a: parse_alpha(args.get(3))?,
+    })
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let h = hue.rem_euclid(360.0) / 60.0;
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let to_u8 = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+/// 返回 (色相 0–360, 饱和度 0–100, 亮度 0–100)
+fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness * 100.0);
+    }
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (hue, saturation * 100.0, lightness * 100.0)
+}
+
+/// WCAG 2.x 相对亮度
+fn relative_luminance(r: u8, g: u8, b: u8) -> f64 {
+    let linear = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+fn contrast_ratio(a: f64, b: f64) -> f64 {
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+fn format_alpha(alpha: f64) -> String {
+    let text = format!("{:.2}", alpha);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// 解析颜色字符串，支持 "#1e90ff"、"1e90ff"、"#fff"、"rgb(30, 144, 255)"、"rgba(…, 0.5)"、"hsl(210, 100%, 56%)"
+pub fn convert_color(input: &str) -> Result<ColorConversion, String> {
+    let text = input.trim();
+    let color = if text.contains('(') {
+        parse_rgb(text).or_else(|| parse_hsl(text))
+    } else {
+        parse_hex(text.strip_prefix('#').unwrap_or(text))
+    }
+    .ok_or_else(|| format!("无法识别的颜色: {}（支持 hex、rgb()、hsl()）", text))?;
+
+    let Rgba { r, g, b, a } = color;
+    let opaque = a >= 1.0;
+    let (h, s, l) = rgb_to_hsl(r, g, b);
+    let (h, s, l) = (h.round() as u32 % 360, s.round() as u32, l.round() as u32);
+
+    let hex = if opaque {
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    } else {
+        format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, (a * 255.0).round() as u8)
+    };
+    let (rgb, hsl) = if opaque {
+        (
+            format!("rgb({}, {}, {})", r, g, b),
+            format!("hsl({}, {}%, {}%)", h, s, l),
+        )
+    } else {
+        let alpha = format_alpha(a);
+        (
+            format!("rgba({}, {}, {}, {})", r, g, b, alpha),
+            format!("hsla({}, {}%, {}%, {})", h, s, l, alpha),
+        )
+    };
+
+    // 文字颜色按不透明的底色计算
+    let luminance = relative_luminance(r, g, b);
+    let on_black = contrast_ratio(luminance, 0.0);
+    let on_white = contrast_ratio(luminance, 1.0);
+    let (text_color, ratio) = if on_black >= on_white {
+        ("#000000", on_black)
+    } else {
+        ("#FFFFFF", on_white)
+    };
+
+    Ok(ColorConversion {
+        hex,
+        rgb,
+        hsl,
+        red: r,
+        green: g,
+        blue: b,
+        alpha: a,
+        text_color: text_color.to_string(),
+        contrast_ratio: (ratio * 100.0).round() / 100.0,
+    })
+}
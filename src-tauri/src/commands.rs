@@ -1,16 +1,48 @@
 use crate::app_search;
+use crate::calc_history;
+use crate::color_convert;
+use crate::data_transfer;
 use crate::db;
+use crate::diagnostics;
+use crate::document_text;
+use crate::error::AppError;
 use crate::everything_search;
 use crate::everything_filters;
+use crate::favicon;
+use crate::events;
+use crate::expression;
 use crate::file_history;
+use crate::file_search_fallback;
+use crate::folder_stats;
 use crate::hooks;
+use crate::json_path;
+use crate::json_tools;
+use crate::logger;
+use crate::{log_debug, log_error, log_info, log_warn};
+use crate::media_info;
 use crate::memos;
+use crate::ocr;
+use crate::path_utils;
 use crate::open_history;
+use crate::plugin_install;
+use crate::plugin_manifest::{self, PluginManifest};
 use crate::plugin_usage;
-use crate::recording::{RecordingMeta, RecordingState};
+use crate::process_utils;
+use crate::qr_code;
+use crate::query_guard;
+use crate::recent_documents;
+use crate::recording::{CaptureEnv, RecordingMeta, RecordingState};
+use crate::replace_journal::{self, ReplaceJournal};
 use crate::replay::ReplayState;
 use crate::settings;
 use crate::shortcuts;
+use crate::single_instance;
+use crate::system_folders_search;
+use crate::translation;
+use crate::trash;
+use crate::search_ranking;
+use crate::unit_convert;
+use crate::url_shortcuts;
 use crate::window_config;
 use base64::{engine::general_purpose, Engine as _};
 use chrono::{DateTime, Utc};
@@ -36,6 +68,106 @@ static REPLAY_STATE: LazyLock<Arc<Mutex<ReplayState>>> =
 pub(crate) static APP_CACHE: LazyLock<Arc<Mutex<Option<Vec<app_search::AppInfo>>>>> =
     LazyLock::new(|| Arc::new(Mutex::new(None)));
 
+// 应用扫描管理器：同一时间只运行一次完整扫描，扫描期间的其他调用等待它完成并共享结果，
+// 避免重复调用 PowerShell（Get-StartApps）以及并发写 APP_CACHE 和磁盘缓存
+struct AppScanManager {
+    in_progress: bool,
+    /// 每完成一次扫描加 1，等待者据此判断所等的扫描已结束
+    generation: u64,
+    /// 最近一次扫描的错误，成功时为 None（结果在 APP_CACHE 中）
+    last_error: Option<String>,
+    /// 正在进行的扫描的进度接收方：发起扫描的调用和合并进来的调用各一个
+    progress_listeners: Vec<std::sync::mpsc::Sender<(u8, String)>>,
+    /// 正在进行的扫描最近一次的进度，合并进来的调用先收到它
+    last_progress: Option<(u8, String)>,
+}
+
+static APP_SCAN_MANAGER: LazyLock<(Mutex<AppScanManager>, std::sync::Condvar)> =
+    LazyLock::new(|| {
+        (
+            Mutex::new(AppScanManager {
+                in_progress: false,
+                generation: 0,
+                last_error: None,
+                progress_listeners: Vec::new(),
+                last_progress: None,
+            }),
+            std::sync::Condvar::new(),
+        )
+    });
+
+/// 执行一次完整扫描并写入 APP_CACHE 和磁盘缓存；已有扫描在进行时不再启动新的扫描，
+/// 而是等待其完成并返回同一结果。扫描期间不持有 APP_CACHE 的锁，搜索仍可使用旧缓存。
+/// 扫描进度转发给每个调用的 progress，包括合并到进行中扫描的调用
+fn run_app_scan<F>(
+    app_data_dir: &Path,
+    progress: std::sync::mpsc::Sender<(u8, String)>,
+    scan: F,
+) -> Result<Vec<app_search::AppInfo>, String>
+where
+    F: FnOnce(std::sync::mpsc::Sender<(u8, String)>) -> Result<Vec<app_search::AppInfo>, String>,
+{
+    let (lock, finished) = &*APP_SCAN_MANAGER;
+    let mut manager = lock.lock().map_err(|e| e.to_string())?;
+    if manager.in_progress {
+        log_info!("AppSearch", "已有应用扫描在进行，等待其完成");
+        if let Some(last) = manager.last_progress.clone() {
+            let _ = progress.send(last);
+        }
+        manager.progress_listeners.push(progress);
+        let generation = manager.generation;
+        let manager = finished
+            .wait_while(manager, |m| m.generation == generation)
+            .map_err(|e| e.to_string())?;
+        if let Some(e) = &manager.last_error {
+            return Err(e.clone());
+        }
+        drop(manager);
+        return APP_CACHE
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone()
+            .ok_or_else(|| "应用扫描未产生结果".to_string());
+    }
+    manager.in_progress = true;
+    manager.progress_listeners.push(progress);
+    manager.last_progress = None;
+    drop(manager);
+
+    // 扫描结束丢弃 Sender 后转发线程退出
+    let (scan_tx, scan_rx) = std::sync::mpsc::channel::<(u8, String)>();
+    let forwarder = std::thread::spawn(move || {
+        for update in scan_rx {
+            if let Ok(mut manager) = APP_SCAN_MANAGER.0.lock() {
+                manager
+                    .progress_listeners
+                    .retain(|listener| listener.send(update.clone()).is_ok());
+                manager.last_progress = Some(update);
+            }
+        }
+    });
+
+    // 扫描 panic 时同样要清除进行中标志，否则后续调用会一直等待
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| scan(scan_tx)))
+        .unwrap_or_else(|_| Err("应用扫描异常终止".to_string()));
+    let _ = forwarder.join();
+    if let Ok(apps) = &result {
+        if let Ok(mut cache_guard) = APP_CACHE.lock() {
+            *cache_guard = Some(apps.clone());
+        }
+        let _ = app_search::windows::save_cache(app_data_dir, apps);
+    }
+
+    let mut manager = lock.lock().map_err(|e| e.to_string())?;
+    manager.in_progress = false;
+    manager.generation += 1;
+    manager.last_error = result.as_ref().err().cloned();
+    manager.progress_listeners.clear();
+    manager.last_progress = None;
+    finished.notify_all();
+    result
+}
+
 // 搜索任务管理器：管理 Everything 搜索的取消标志
 // 每次新搜索会将旧搜索的取消标志设为 true，从而让旧任务尽快退出
 struct SearchTaskManager {
@@ -91,17 +223,19 @@ pub fn get_app_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
 }
 
 #[tauri::command]
-pub fn get_recording_status() -> Result<bool, String> {
+pub fn get_recording_status() -> Result<bool, AppError> {
     let state = RECORDING_STATE.clone();
     let state_guard = state.lock().map_err(|e| e.to_string())?;
     Ok(state_guard.is_recording)
 }
 
 #[tauri::command]
-pub fn start_recording() -> Result<(), String> {
+pub fn start_recording() -> Result<(), AppError> {
     #[cfg(not(target_os = "windows"))]
     {
-        return Err("Recording is only supported on Windows".to_string());
+        return Err(AppError::PlatformUnsupported(
+            "Recording is only supported on Windows".to_string(),
+        ));
     }
 
     let state = RECORDING_STATE.clone();
@@ -129,17 +263,19 @@ pub fn start_recording() -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn stop_recording(app: tauri::AppHandle) -> Result<String, String> {
+pub fn stop_recording(app: tauri::AppHandle) -> Result<String, AppError> {
     #[cfg(not(target_os = "windows"))]
     {
-        return Err("Recording is only supported on Windows".to_string());
+        return Err(AppError::PlatformUnsupported(
+            "Recording is only supported on Windows".to_string(),
+        ));
     }
 
     let state = RECORDING_STATE.clone();
     let mut state_guard = state.lock().map_err(|e| e.to_string())?;
 
     if !state_guard.is_recording {
-        return Err("Not currently recording".to_string());
+        return Err(AppError::RecordingError("Not currently recording".to_string()));
     }
 
     // Get events before stopping
@@ -158,7 +294,7 @@ pub fn stop_recording(app: tauri::AppHandle) -> Result<String, String> {
 
     // Create recordings directory if it doesn't exist
     fs::create_dir_all(&recordings_dir)
-        .map_err(|e| format!("Failed to create recordings directory: {}", e))?;
+        .map_err(|e| AppError::Io(format!("Failed to create recordings directory: {}", e)))?;
 
     // Generate filename with timestamp
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
@@ -170,27 +306,28 @@ pub fn stop_recording(app: tauri::AppHandle) -> Result<String, String> {
         "events": events,
         "duration_ms": duration_ms,
         "created_at": chrono::Local::now().to_rfc3339(),
+        "capture_env": CaptureEnv::current(),
     });
 
     // Write to file
     let json_string = serde_json::to_string_pretty(&recording_data)
-        .map_err(|e| format!("Failed to serialize recording data: {}", e))?;
+        .map_err(|e| AppError::Parse(format!("Failed to serialize recording data: {}", e)))?;
     fs::write(&file_path, json_string)
-        .map_err(|e| format!("Failed to write recording file: {}", e))?;
+        .map_err(|e| AppError::Io(format!("Failed to write recording file: {}", e)))?;
 
     // Return relative path for display
     Ok(format!("recordings/{}", filename))
 }
 
 #[tauri::command]
-pub fn list_recordings(app: tauri::AppHandle) -> Result<Vec<RecordingMeta>, String> {
+pub fn list_recordings(app: tauri::AppHandle) -> Result<Vec<RecordingMeta>, AppError> {
     let app_data_dir = get_app_data_dir(&app)?;
     let recordings_dir = app_data_dir.join("recordings");
 
     // Create directory if it doesn't exist
     if !recordings_dir.exists() {
         fs::create_dir_all(&recordings_dir)
-            .map_err(|e| format!("Failed to create recordings directory: {}", e))?;
+            .map_err(|e| AppError::Io(format!("Failed to create recordings directory: {}", e)))?;
         return Ok(vec![]);
     }
 
@@ -198,10 +335,10 @@ pub fn list_recordings(app: tauri::AppHandle) -> Result<Vec<RecordingMeta>, Stri
 
     // Read directory entries
     let entries = fs::read_dir(&recordings_dir)
-        .map_err(|e| format!("Failed to read recordings directory: {}", e))?;
+        .map_err(|e| AppError::Io(format!("Failed to read recordings directory: {}", e)))?;
 
     for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let entry = entry.map_err(|e| AppError::Io(format!("Failed to read directory entry: {}", e)))?;
         let path = entry.path();
 
         // Only process JSON files
@@ -219,7 +356,7 @@ pub fn list_recordings(app: tauri::AppHandle) -> Result<Vec<RecordingMeta>, Stri
 }
 
 #[tauri::command]
-pub fn delete_recording(app: tauri::AppHandle, path: String) -> Result<(), String> {
+pub fn delete_recording(app: tauri::AppHandle, path: String) -> Result<(), AppError> {
     let app_data_dir = get_app_data_dir(&app)?;
     let recordings_dir = app_data_dir.join("recordings");
 
@@ -235,20 +372,116 @@ pub fn delete_recording(app: tauri::AppHandle, path: String) -> Result<(), Strin
 
     // Validate that the file exists and is within the recordings directory
     if !file_path.exists() {
-        return Err(format!("Recording file not found: {}", path));
+        return Err(AppError::NotFound(format!("Recording file not found: {}", path)));
     }
 
     // Ensure the file is actually within the recordings directory (security check)
-    if !file_path.starts_with(&recordings_dir) {
-        return Err("Invalid file path: outside recordings directory".to_string());
+    // 规范化后再比较，避免 `..` 绕过
+    let file_path = file_path.canonicalize()?;
+    if !file_path.starts_with(recordings_dir.canonicalize()?) {
+        return Err(AppError::PermissionDenied(
+            "Invalid file path: outside recordings directory".to_string(),
+        ));
     }
 
-    // Delete the file
-    fs::remove_file(&file_path).map_err(|e| format!("Failed to delete recording file: {}", e))?;
+    // Delete the file (moved to trash unless disabled in settings)
+    trash::remove_file(&file_path, use_trash_for_deletes(&app_data_dir))
+        .map_err(|e| AppError::Io(format!("Failed to delete recording file: {}", e)))?;
 
     Ok(())
 }
 
+/// 用户触发的文件删除是否移到回收站（读取设置失败时默认移到回收站）
+fn use_trash_for_deletes(app_data_dir: &Path) -> bool {
+    settings::load_settings(app_data_dir)
+        .map(|s| s.delete_to_trash)
+        .unwrap_or(true)
+}
+
+/// 将文件或文件夹移到回收站
+#[tauri::command]
+pub fn move_to_trash(path: String) -> Result<(), String> {
+    let target = trash::validate_trash_target(&path)?;
+    trash::move_to_trash(&target)
+}
+
+/// 将录制导出为同目录下的同名 CSV 文件，返回 CSV 的完整路径
+#[tauri::command]
+pub fn export_recording_csv(app: tauri::AppHandle, path: String) -> Result<String, AppError> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let recordings_dir = app_data_dir.join("recordings");
+    let relative = Path::new(path.strip_prefix("recordings/").unwrap_or(&path));
+
+    // 拒绝绝对路径和 ..，防止导出到录制目录之外
+    if !relative
+        .components()
+        .all(|c| matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err(AppError::PermissionDenied(
+            "Invalid file path: outside recordings directory".to_string(),
+        ));
+    }
+    let file_path = recordings_dir.join(relative);
+    if !file_path.is_file() {
+        return Err(AppError::NotFound(format!("Recording file not found: {}", path)));
+    }
+
+    let content = fs::read_to_string(&file_path)?;
+    let json: serde_json::Value = serde_json::from_str(&content)?;
+    let events: Vec<crate::recording::RecordedEvent> = serde_json::from_value(json["events"].clone())?;
+
+    let csv_path = file_path.with_extension("csv");
+    fs::write(&csv_path, crate::recording::events_to_csv(&events))
+        .map_err(|e| AppError::Io(format!("Failed to write CSV file: {}", e)))?;
+
+    Ok(csv_path.to_string_lossy().to_string())
+}
+
+/// 从 CSV 生成新的录制文件 recordings/<name>.json，返回相对路径
+#[tauri::command]
+pub fn import_recording_csv(
+    app: tauri::AppHandle,
+    path: String,
+    name: String,
+) -> Result<String, AppError> {
+    let content = fs::read_to_string(&path)?;
+    let events = crate::recording::events_from_csv(&content).map_err(AppError::Parse)?;
+
+    // 文件名只保留字母数字（含中文）、- 和 _
+    let name: String = name
+        .trim()
+        .trim_end_matches(".json")
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if name.is_empty() {
+        return Err(AppError::Other("Recording name cannot be empty".to_string()));
+    }
+
+    let app_data_dir = get_app_data_dir(&app)?;
+    let recordings_dir = app_data_dir.join("recordings");
+    fs::create_dir_all(&recordings_dir)
+        .map_err(|e| AppError::Io(format!("Failed to create recordings directory: {}", e)))?;
+
+    let filename = format!("{}.json", name);
+    let file_path = recordings_dir.join(&filename);
+    if file_path.exists() {
+        return Err(AppError::Other(format!("Recording already exists: {}", filename)));
+    }
+
+    let recording_data = serde_json::json!({
+        "duration_ms": events.last().map(|e| e.time_offset_ms).unwrap_or(0),
+        "events": events,
+        "created_at": chrono::Local::now().to_rfc3339(),
+    });
+    let json_string = serde_json::to_string_pretty(&recording_data)
+        .map_err(|e| AppError::Parse(format!("Failed to serialize recording data: {}", e)))?;
+    fs::write(&file_path, json_string)
+        .map_err(|e| AppError::Io(format!("Failed to write recording file: {}", e)))?;
+
+    Ok(format!("recordings/{}", filename))
+}
+
 fn extract_recording_meta(
     file_path: &Path,
     recordings_dir: &Path,
@@ -273,6 +506,8 @@ fn extract_recording_meta(
         .ok_or_else(|| format!("Missing or invalid created_at in {}", file_path.display()))?
         .to_string();
 
+    let capture_env = serde_json::from_value(json["capture_env"].clone()).unwrap_or(None);
+
     // Get file name and relative path
     let file_name = file_path
         .file_name()
@@ -293,20 +528,60 @@ fn extract_recording_meta(
         duration_ms,
         event_count,
         created_at,
+        capture_env,
     })
 }
 
+/// 当前屏幕环境，前端回放前与录制时的 capture_env 比较
+#[tauri::command]
+pub fn get_capture_env() -> Option<CaptureEnv> {
+    CaptureEnv::current()
+}
+
+/// Esc 当前是否按下，用于中断回放
+fn is_escape_pressed() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
+        const VK_ESCAPE: i32 = 0x1B;
+        unsafe {
+            // GetAsyncKeyState returns negative value if key is currently pressed
+            // The high bit (0x8000) indicates the key is currently down
+            let key_state = GetAsyncKeyState(VK_ESCAPE) as u16;
+            key_state & 0x8000 != 0
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        false
+    }
+}
+
+/// 回放开始前最长等待时间
+const MAX_PLAYBACK_START_DELAY_MS: u64 = 60_000;
+
+/// scale_to_current_resolution 为 true 时，按当前主显示器分辨率线性缩放录制的鼠标坐标
+/// start_delay_ms 为开始前的倒计时，期间每秒发送 `playback-countdown` 事件
 #[tauri::command]
-pub fn play_recording(app: tauri::AppHandle, path: String, speed: f32) -> Result<(), String> {
+pub fn play_recording(
+    app: tauri::AppHandle,
+    path: String,
+    speed: f32,
+    scale_to_current_resolution: Option<bool>,
+    start_delay_ms: Option<u64>,
+) -> Result<(), AppError> {
     #[cfg(not(target_os = "windows"))]
     {
-        return Err("Replay is only supported on Windows".to_string());
+        return Err(AppError::PlatformUnsupported(
+            "Replay is only supported on Windows".to_string(),
+        ));
     }
 
     let mut state = REPLAY_STATE.lock().map_err(|e| e.to_string())?;
 
     if state.is_playing {
-        return Err("Already playing".to_string());
+        return Err(AppError::ReplayError("Already playing".to_string()));
     }
 
     // Convert relative path to absolute path
@@ -325,22 +600,58 @@ pub fn play_recording(app: tauri::AppHandle, path: String, speed: f32) -> Result
 
     // Validate speed - limit to reasonable range to prevent system overload
     if speed <= 0.0 || speed > 10.0 {
-        return Err("Speed must be between 0.1 and 10.0".to_string());
+        return Err(AppError::ReplayError(
+            "Speed must be between 0.1 and 10.0".to_string(),
+        ));
+    }
+
+    let start_delay_ms = start_delay_ms.unwrap_or(0);
+    if start_delay_ms > MAX_PLAYBACK_START_DELAY_MS {
+        return Err(AppError::ReplayError(format!(
+            "Start delay must not exceed {} ms",
+            MAX_PLAYBACK_START_DELAY_MS
+        )));
     }
 
     state.load_recording(&file_path)?;
 
+    let scale_to_current_resolution = scale_to_current_resolution.unwrap_or(false);
+    let mut coordinate_scale = None;
+    match (&state.capture_env, CaptureEnv::current()) {
+        (Some(recorded), Some(current)) if *recorded != current => {
+            if scale_to_current_resolution {
+                coordinate_scale = Some(recorded.scale_factors_to(&current));
+            } else {
+                log_warn!(
+                    "Replay",
+                    "Recording captured at {}x{} ({}x scale), current screen is {}x{} ({}x scale); clicks may land in the wrong place",
+                    recorded.width, recorded.height, recorded.scale,
+                    current.width, current.height, current.scale
+                );
+            }
+        }
+        (None, _) if scale_to_current_resolution => {
+            log_warn!(
+                "Replay",
+                "Recording has no capture_env data, coordinate scaling skipped"
+            );
+        }
+        _ => {}
+    }
+
     // Check if there are any events
     if state.current_events.is_empty() {
-        return Err("Recording file contains no events".to_string());
+        return Err(AppError::ReplayError(
+            "Recording file contains no events".to_string(),
+        ));
     }
 
     // Limit the number of events to prevent system overload
     if state.current_events.len() > 100000 {
-        return Err(format!(
+        return Err(AppError::ReplayError(format!(
             "Too many events ({}). Maximum allowed is 100000.",
             state.current_events.len()
-        ));
+        )));
     }
 
     state.start(speed);
@@ -349,8 +660,48 @@ pub fn play_recording(app: tauri::AppHandle, path: String, speed: f32) -> Result
     // should be done in a blocking context
     let replay_state = Arc::clone(&REPLAY_STATE);
     let speed_multiplier = speed.max(0.1).min(10.0); // Ensure speed is between 0.1 and 10.0
+    let app_handle = app.clone();
 
     std::thread::spawn(move || {
+        // 开始前倒计时：每秒通知前端一次，按 Esc 或调用 stop_playback 可取消
+        if start_delay_ms > 0 {
+            let countdown_start = std::time::Instant::now();
+            let mut last_emitted = None;
+            loop {
+                let elapsed = countdown_start.elapsed().as_millis() as u64;
+                let remaining_ms = start_delay_ms.saturating_sub(elapsed);
+                let remaining_seconds = remaining_ms.div_ceil(1000);
+                if last_emitted != Some(remaining_seconds) {
+                    let _ = app_handle.emit(
+                        "playback-countdown",
+                        events::PlaybackCountdown {
+                            remaining_seconds,
+                            remaining_ms,
+                        },
+                    );
+                    last_emitted = Some(remaining_seconds);
+                }
+                if remaining_ms == 0 {
+                    break;
+                }
+
+                if is_escape_pressed() {
+                    log_debug!(
+                        "Replay",
+                        "Esc key pressed, playback cancelled during countdown"
+                    );
+                    if let Ok(mut state) = replay_state.lock() {
+                        state.stop();
+                    }
+                    return;
+                }
+                if !replay_state.lock().map(|s| s.is_playing).unwrap_or(false) {
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(remaining_ms.min(50)));
+            }
+        }
+
         let mut last_time = 0u64;
         let mut last_mouse_move_time = 0u64;
         let mut event_count = 0u64;
@@ -361,22 +712,12 @@ pub fn play_recording(app: tauri::AppHandle, path: String, speed: f32) -> Result
 
         loop {
             // Check if Esc key is pressed to stop playback
-            #[cfg(target_os = "windows")]
-            {
-                use windows_sys::Win32::UI::Input::KeyboardAndMouse::GetAsyncKeyState;
-                const VK_ESCAPE: i32 = 0x1B;
-                unsafe {
-                    // GetAsyncKeyState returns negative value if key is currently pressed
-                    // The high bit (0x8000) indicates the key is currently down
-                    let key_state = GetAsyncKeyState(VK_ESCAPE) as u16;
-                    if key_state & 0x8000 != 0 {
-                        eprintln!("Esc key pressed, stopping playback");
-                        if let Ok(mut state) = replay_state.lock() {
-                            state.stop();
-                        }
-                        break;
-                    }
+            if is_escape_pressed() {
+                log_debug!("Replay", "Esc key pressed, stopping playback");
+                if let Ok(mut state) = replay_state.lock() {
+                    state.stop();
                 }
+                break;
             }
 
             // Safety check: prevent infinite loops
@@ -440,6 +781,11 @@ pub fn play_recording(app: tauri::AppHandle, path: String, speed: f32) -> Result
                     std::thread::sleep(Duration::from_millis(delay_ms));
                 }
 
+                let event = match coordinate_scale {
+                    Some(factors) => event.scaled(factors),
+                    None => event,
+                };
+
                 // Execute the event with error handling
                 match crate::replay::ReplayState::execute_event(&event) {
                     Ok(_) => {}
@@ -464,11 +810,11 @@ pub fn play_recording(app: tauri::AppHandle, path: String, speed: f32) -> Result
 }
 
 #[tauri::command]
-pub fn stop_playback() -> Result<(), String> {
+pub fn stop_playback() -> Result<(), AppError> {
     let mut state = REPLAY_STATE.lock().map_err(|e| e.to_string())?;
 
     if !state.is_playing {
-        return Err("Not currently playing".to_string());
+        return Err(AppError::ReplayError("Not currently playing".to_string()));
     }
 
     state.stop();
@@ -476,53 +822,99 @@ pub fn stop_playback() -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn get_playback_status() -> Result<bool, String> {
+pub fn get_playback_status() -> Result<bool, AppError> {
     let state = REPLAY_STATE.lock().map_err(|e| e.to_string())?;
     Ok(state.is_playing)
 }
 
 #[tauri::command]
-pub fn get_playback_progress() -> Result<f32, String> {
+pub fn get_playback_progress() -> Result<f32, AppError> {
     let state = REPLAY_STATE.lock().map_err(|e| e.to_string())?;
     Ok(state.get_progress())
 }
 
-#[tauri::command]
-pub async fn scan_applications(app: tauri::AppHandle) -> Result<Vec<app_search::AppInfo>, String> {
-    let app_clone = app.clone();
-    async_runtime::spawn_blocking(move || {
-        let cache = APP_CACHE.clone();
-        let mut cache_guard = cache.lock().map_err(|e| e.to_string())?;
+/// 需要接收应用扫描进度的窗口，应用中心可能在启动器窗口或独立窗口中
+fn app_scan_windows(app: &tauri::AppHandle) -> Vec<tauri::WebviewWindow> {
+    ["launcher", "plugin-list-window", "main"]
+        .into_iter()
+        .filter_map(|label| app.get_webview_window(label))
+        .collect()
+}
 
-        let mut apps = if let Some(ref cached_apps) = *cache_guard {
-            // Return cached apps if available
-            cached_apps.clone()
-        } else {
-            // Try to load from disk cache first
-            let app_data_dir = get_app_data_dir(&app_clone)?;
-            if let Ok(disk_cache) = app_search::windows::load_cache(&app_data_dir) {
-                if !disk_cache.is_empty() {
-                    disk_cache
-                } else {
-                    // Scan applications (potentially slow) on background thread
-                    app_search::windows::scan_start_menu(None)?
-                }
-            } else {
-                // Scan applications (potentially slow) on background thread
-                app_search::windows::scan_start_menu(None)?
+/// 把 scan_start_menu 的进度转发为窗口事件（载荷为 RescanProgress）；
+/// 进度到 100 或扫描结束丢弃 Sender 后转发线程退出
+fn forward_scan_progress(
+    windows: Vec<tauri::WebviewWindow>,
+    event: &'static str,
+) -> std::sync::mpsc::Sender<(u8, String)> {
+    let (tx, rx) = std::sync::mpsc::channel::<(u8, String)>();
+    std::thread::spawn(move || {
+        for (progress, message) in rx {
+            let event_data = events::RescanProgress { progress, message };
+            for window in &windows {
+                let _ = window.emit(event, &event_data);
             }
-        };
+            if progress >= 100 {
+                break;
+            }
+        }
+    });
+    tx
+}
 
+/// 按设置构建应用扫描的忽略规则，设置读取失败时只使用内置规则
+fn load_app_scan_ignore(app_data_dir: &Path) -> app_search::AppScanIgnore {
+    let user_patterns = settings::load_settings(app_data_dir)
+        .map(|settings| settings.app_scan_ignore)
+        .unwrap_or_default();
+    app_search::AppScanIgnore::new(&user_patterns)
+}
+
+/// 完整扫描应用（开始菜单、桌面、商店应用），并加入用户添加的网址快捷方式
+fn scan_all_apps(
+    tx: std::sync::mpsc::Sender<(u8, String)>,
+    app_data_dir: &Path,
+) -> Result<Vec<app_search::AppInfo>, String> {
+    let mut apps =
+        app_search::windows::scan_start_menu(Some(tx), &load_app_scan_ignore(app_data_dir))?;
+    match url_shortcuts::load_url_shortcuts(app_data_dir) {
+        Ok(shortcuts) => {
+            for shortcut in shortcuts {
+                if !apps.iter().any(|app| app.path == shortcut.url) {
+                    apps.push(shortcut.to_app_info());
+                }
+            }
+        }
+        Err(e) => log_warn!("AppSearch", "加载网址快捷方式失败: {}", e),
+    }
+    Ok(apps)
+}
 
-        // Update cache with apps including builtin apps
-        *cache_guard = Some(apps.clone());
+#[tauri::command]
+pub async fn scan_applications(app: tauri::AppHandle) -> Result<Vec<app_search::AppInfo>, String> {
+    let app_clone = app.clone();
+    async_runtime::spawn_blocking(move || {
+        // Return cached apps if available
+        if let Some(cached_apps) = APP_CACHE.lock().map_err(|e| e.to_string())?.clone() {
+            return Ok(cached_apps);
+        }
 
-        // Save to disk cache (including builtin apps)
+        // Try to load from disk cache first
         let app_data_dir = get_app_data_dir(&app_clone)?;
-        let _ = app_search::windows::save_cache(&app_data_dir, &apps);
+        if let Ok(disk_cache) = app_search::windows::load_cache(&app_data_dir) {
+            if !disk_cache.is_empty() {
+                let mut cache_guard = APP_CACHE.lock().map_err(|e| e.to_string())?;
+                // 读取磁盘期间可能已有扫描写入了更新的结果
+                return Ok(cache_guard.get_or_insert(disk_cache).clone());
+            }
+        }
 
+        // 首次运行没有缓存，完整扫描较慢，通过 app-scan-progress 事件通知前端显示进度
         // No background icon extraction - icons will be extracted on-demand during search
-        Ok(apps)
+        let progress = forward_scan_progress(app_scan_windows(&app_clone), "app-scan-progress");
+        run_app_scan(&app_data_dir, progress, |tx| {
+            scan_all_apps(tx, &app_data_dir)
+        })
     })
     .await
     .map_err(|e| format!("scan_applications join error: {}", e))?
@@ -530,16 +922,7 @@ pub async fn scan_applications(app: tauri::AppHandle) -> Result<Vec<app_search::
 
 #[tauri::command]
 pub async fn rescan_applications(app: tauri::AppHandle) -> Result<(), String> {
-    // 获取所有可能的窗口，应用中心可能在启动器窗口或独立窗口中
-    let windows_to_notify: Vec<_> = vec![
-        app.get_webview_window("launcher"),
-        app.get_webview_window("plugin-list-window"),
-        app.get_webview_window("main"),
-    ]
-    .into_iter()
-    .flatten()
-    .collect();
-    
+    let windows_to_notify = app_scan_windows(&app);
     if windows_to_notify.is_empty() {
         return Err("无法获取窗口".to_string());
     }
@@ -548,62 +931,23 @@ pub async fn rescan_applications(app: tauri::AppHandle) -> Result<(), String> {
     
     // 立即返回，在后台执行扫描
     async_runtime::spawn(async move {
-        // 创建通道用于传递进度信息（使用标准库通道，因为需要在阻塞线程中使用）
-        let (tx, rx) = std::sync::mpsc::channel::<(u8, String)>();
-        
-        // 启动进度监听任务，向所有可能的窗口发送事件
-        let windows_for_progress: Vec<_> = windows_to_notify.iter().map(|w| w.clone()).collect();
-        async_runtime::spawn(async move {
-            // 在异步上下文中轮询接收进度信息
-            loop {
-                match rx.recv_timeout(Duration::from_millis(100)) {
-                    Ok((progress, message)) => {
-                        let event_data = serde_json::json!({
-                            "progress": progress,
-                            "message": message
-                        });
-                        // 向所有可能的窗口发送进度事件
-                        for window in &windows_for_progress {
-                            let _ = window.emit("app-rescan-progress", &event_data);
-                        }
-                        if progress >= 100 {
-                            break;
-                        }
-                    }
-                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
-                        // 超时，继续等待
-                        continue;
-                    }
-                    Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
-                        // 发送端已关闭
-                        break;
-                    }
-                }
-            }
-        });
+        // 向所有可能的窗口发送进度事件
+        let tx = forward_scan_progress(windows_to_notify.clone(), "app-rescan-progress");
         
         let scan_result = async_runtime::spawn_blocking(move || -> Result<Vec<app_search::AppInfo>, String> {
-            let cache = APP_CACHE.clone();
-            let mut cache_guard = cache.lock().map_err(|e| format!("锁定缓存失败: {}", e))?;
-
-            // Clear memory cache
-            *cache_guard = None;
-
-            // Clear disk cache
             let app_data_dir = get_app_data_dir(&app_clone).map_err(|e| format!("获取应用数据目录失败: {}", e))?;
-            let cache_file = app_search::windows::get_cache_file_path(&app_data_dir);
-            let _ = fs::remove_file(&cache_file); // Ignore errors if file doesn't exist
 
-            // Force rescan with progress callback
-            let apps = app_search::windows::scan_start_menu(Some(tx))?;
-
-            // Cache the results
-            *cache_guard = Some(apps.clone());
-
-            // Save to disk cache
-            let _ = app_search::windows::save_cache(&app_data_dir, &apps);
+            // 扫描完成前保留内存缓存，搜索继续使用旧结果；正在进行的扫描会被复用，
+            // 它的进度同样通过 app-rescan-progress 发给这些窗口
+            run_app_scan(&app_data_dir, tx, |tx| {
+                // Clear disk cache
+                let cache_file = app_search::windows::get_cache_file_path(&app_data_dir);
+                let _ = fs::remove_file(&cache_file); // Ignore errors if file doesn't exist
 
-            Ok(apps)
+                // Force rescan with progress callback
+                // 忽略规则每次重新读取设置，修改后重新扫描即可生效
+                scan_all_apps(tx, &app_data_dir)
+            })
         })
         .await;
         
@@ -611,25 +955,21 @@ pub async fn rescan_applications(app: tauri::AppHandle) -> Result<(), String> {
         let windows_for_result: Vec<_> = windows_to_notify.iter().map(|w| w.clone()).collect();
         match scan_result {
             Ok(Ok(apps)) => {
-                let event_data = serde_json::json!({
-                    "apps": apps
-                });
+                let event_data = events::RescanComplete { apps };
                 for window in &windows_for_result {
                     let _ = window.emit("app-rescan-complete", &event_data);
                 }
             }
             Ok(Err(e)) => {
-                let event_data = serde_json::json!({
-                    "error": e
-                });
+                let event_data = events::RescanError { error: e };
                 for window in &windows_for_result {
                     let _ = window.emit("app-rescan-error", &event_data);
                 }
             }
             Err(e) => {
-                let event_data = serde_json::json!({
-                    "error": format!("扫描任务失败: {}", e)
-                });
+                let event_data = events::RescanError {
+                    error: format!("扫描任务失败: {}", e),
+                };
                 for window in &windows_for_result {
                     let _ = window.emit("app-rescan-error", &event_data);
                 }
@@ -640,11 +980,33 @@ pub async fn rescan_applications(app: tauri::AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// 同一时间只有最新一次调用会完成，之前尚未完成的调用返回 query_guard::SUPERSEDED_ERROR
 #[tauri::command]
 pub async fn search_applications(
     query: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
     app: tauri::AppHandle,
-) -> Result<Vec<app_search::AppInfo>, String> {
+) -> Result<app_search::AppSearchPage, String> {
+    let ticket = query_guard::begin(query_guard::APPS);
+    search_applications_page(query, limit, offset, ticket, app).await
+}
+
+async fn search_applications_page(
+    query: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    ticket: query_guard::QueryTicket,
+    app: tauri::AppHandle,
+) -> Result<app_search::AppSearchPage, String> {
+    let limit = limit.unwrap_or_else(|| {
+        let max_app_results = get_app_data_dir(&app)
+            .and_then(|dir| settings::load_settings_cached(&dir))
+            .map(|settings| settings.max_app_results)
+            .unwrap_or(app_search::DEFAULT_APP_SEARCH_LIMIT);
+        app_search::default_app_search_limit(&query, max_app_results)
+    });
+    let offset = offset.unwrap_or(0);
     let cache = APP_CACHE.clone();
     let app_handle_clone = app.clone();
     let query_clone = query.clone();
@@ -654,8 +1016,13 @@ pub async fn search_applications(
     // 需要提前克隆 cache，因为闭包会移动它
     let cache_for_search = cache.clone();
     let app_handle_for_scan = app_handle_clone.clone();
-    let results = async_runtime::spawn_blocking(move || {
+    let ticket_for_search = ticket.clone();
+    let page = async_runtime::spawn_blocking(move || {
+        let ticket = ticket_for_search;
+        ticket.ensure_current()?;
         let mut cache_guard = cache_for_search.lock().map_err(|e| e.to_string())?;
+        // 等待锁期间可能已有更新的查询
+        ticket.ensure_current()?;
 
         // 如果缓存为空，尝试加载磁盘缓存
         if cache_guard.is_none() {
@@ -683,6 +1050,7 @@ pub async fn search_applications(
             let builtin_calculator = app_search::AppInfo {
                 name: "计算器".to_string(),
                 path: "shell:AppsFolder\\Microsoft.WindowsCalculator_8wekyb3d8bbwe!App".to_string(),
+                is_url: false,
                 icon: None,
                 description: Some("Windows 计算器".to_string()),
                 name_pinyin: Some("jisuanqi".to_string()),
@@ -693,11 +1061,15 @@ pub async fn search_applications(
 
         // Perform search while holding the lock (search is fast, lock is held briefly)
         // The search function only reads from the apps list, so this is safe
-        let mut results = app_search::windows::search_apps(&query_clone, &apps_with_builtin);
+        let mut page =
+            app_search::windows::search_apps(&query_clone, &apps_with_builtin, offset, limit);
         
         // 如果搜索结果为空，检查特定路径是否存在匹配的应用
-        if results.is_empty() && !query_clone.trim().is_empty() {
+        if page.total_count == 0 && !query_clone.trim().is_empty() {
+            // 回退扫描开销较大，过期查询不再执行
+            ticket.ensure_current()?;
             let query_lower = query_clone.to_lowercase();
+            let mut results: Vec<app_search::AppInfo> = Vec::new();
             
             // 检查常见的应用安装路径
             let potential_paths: Vec<std::path::PathBuf> = vec![
@@ -716,11 +1088,16 @@ pub async fn search_applications(
             .into_iter()
             .flatten()
             .collect();
+            let ignore = get_app_data_dir(&app_handle_for_scan)
+                .map(|dir| load_app_scan_ignore(&dir))
+                .unwrap_or_else(|_| app_search::AppScanIgnore::new(&[]));
             
             for dir_path in potential_paths {
                 if dir_path.exists() {
                     // 扫描这个目录
-                    if let Ok(mut dir_apps) = app_search::windows::scan_specific_path(&dir_path) {
+                    if let Ok(mut dir_apps) =
+                        app_search::windows::scan_specific_path(&dir_path, &ignore)
+                    {
                         // 在扫描到的应用中查找匹配的
                         for app in &dir_apps {
                             let name_lower = app.name.to_lowercase();
@@ -757,22 +1134,27 @@ pub async fn search_applications(
                     }
                 }
             }
+
+            page.total_count = results.len();
+            page.items = results.into_iter().skip(offset).take(limit).collect();
         }
         
         // Lock is released here when cache_guard goes out of scope
-        Ok::<Vec<app_search::AppInfo>, String>(results)
+        Ok::<app_search::AppSearchPage, String>(page)
     })
     .await
     .map_err(|e| format!("搜索任务失败: {}", e))??;
+    ticket.ensure_current()?;
 
     // 在后台异步提取图标，提取完成后通过事件通知前端刷新
     let cache_clone = cache.clone();
     let app_handle_for_emit = app_handle_clone.clone();
     let app_handle_for_save = app_handle_clone.clone();
     
-    let results_paths: Vec<String> = results
+    let results_paths: Vec<String> = page
+        .items
         .iter()
-        .filter(|r| r.icon.is_none())
+        .filter(|r| r.icon.is_none() && !r.is_url)
         .map(|r| r.path.clone())
         .collect();
     
@@ -837,7 +1219,7 @@ pub async fn search_applications(
         });
     }
 
-    Ok(results)
+    Ok(page)
 }
 
 /// Populate icons for cached applications (best-effort, limited to avoid long blocks).
@@ -910,9 +1292,59 @@ pub async fn populate_app_icons(
     .map_err(|e| format!("populate_app_icons join error: {}", e))?
 }
 
+/// 网址快捷方式通过 open_url 打开，并记录到 open_history
+#[tauri::command]
+pub fn launch_application(
+    app: app_search::AppInfo,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    if !app.is_url {
+        return app_search::windows::launch_app(&app);
+    }
+    open_url(app.path.clone())?;
+    let app_data_dir = get_app_data_dir(&app_handle)?;
+    if let Err(e) = open_history::record_open(app.path, &app_data_dir) {
+        log_warn!("AppSearch", "记录网址快捷方式使用失败: {}", e);
+    }
+    Ok(())
+}
+
+/// 获取网址对应站点的图标，返回 PNG data URL（与应用图标格式一致）；
+/// 设置中关闭了联网获取图标、网址不是 http(s) 或获取失败时返回 None
+#[tauri::command]
+pub async fn fetch_favicon(url: String, app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    if !settings::load_settings(&app_data_dir)?.fetch_favicons {
+        return Ok(None);
+    }
+    Ok(favicon::fetch_favicon(&url, &app_data_dir).await)
+}
+
+/// 添加自定义网址快捷方式并立即加入应用索引，同一网址已存在时更新名称和图标
 #[tauri::command]
-pub fn launch_application(app: app_search::AppInfo) -> Result<(), String> {
-    app_search::windows::launch_app(&app)
+pub async fn add_url_shortcut(
+    name: String,
+    url: String,
+    icon: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<app_search::AppInfo, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    async_runtime::spawn_blocking(move || {
+        let shortcut = url_shortcuts::add_url_shortcut(&app_data_dir, name, url, icon)?;
+        let info = shortcut.to_app_info();
+
+        let mut cache_guard = APP_CACHE.lock().map_err(|e| format!("锁定缓存失败: {}", e))?;
+        if let Some(apps) = cache_guard.as_mut() {
+            match apps.iter_mut().find(|a| a.path == info.path) {
+                Some(existing) => *existing = info.clone(),
+                None => apps.push(info.clone()),
+            }
+            let _ = app_search::windows::save_cache(&app_data_dir, apps);
+        }
+        Ok(info)
+    })
+    .await
+    .map_err(|e| format!("添加网址快捷方式任务失败: {}", e))?
 }
 
 /// 从应用索引中删除指定的应用
@@ -1053,12 +1485,75 @@ pub async fn extract_icon_from_path(file_path: String) -> Result<Option<String>,
     .map_err(|e| format!("extract_icon_from_path join error: {}", e))?
 }
 
+/// 通过 WS_EX_LAYERED + SetLayeredWindowAttributes 设置整个窗口的不透明度
+#[cfg(target_os = "windows")]
+fn set_window_opacity(window: &tauri::WebviewWindow, opacity: f64) -> Result<(), String> {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE, LWA_ALPHA,
+        WS_EX_LAYERED,
+    };
+
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| format!("获取窗口句柄失败: {}", e))?
+        .0 as isize;
+    let alpha = (opacity * 255.0).round() as u8;
+    unsafe {
+        let ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        if ex_style & WS_EX_LAYERED as isize == 0 {
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, ex_style | WS_EX_LAYERED as isize);
+        }
+        if SetLayeredWindowAttributes(hwnd, 0, alpha, LWA_ALPHA) == 0 {
+            return Err(format!(
+                "设置窗口透明度失败 (错误码 {})",
+                windows_sys::Win32::Foundation::GetLastError()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn set_window_opacity(_window: &tauri::WebviewWindow, _opacity: f64) -> Result<(), String> {
+    Err("窗口透明度仅在 Windows 上支持".to_string())
+}
+
+/// 应用保存的启动器不透明度；完全不透明时不改动窗口样式
+pub(crate) fn apply_launcher_opacity(window: &tauri::WebviewWindow, app_data_dir: &std::path::Path) {
+    let Ok(settings) = settings::load_settings(app_data_dir) else {
+        return;
+    };
+    let opacity = settings::clamp_launcher_opacity(settings.launcher_opacity);
+    if opacity < 1.0 {
+        if let Err(e) = set_window_opacity(window, opacity) {
+            log_warn!("Window", "Failed to apply launcher opacity: {}", e);
+        }
+    }
+}
+
+/// 设置并保存启动器不透明度（自动限制在 0.3–1.0），返回实际生效的值
+#[tauri::command]
+pub fn set_launcher_opacity(app: tauri::AppHandle, opacity: f64) -> Result<f64, String> {
+    let opacity = settings::clamp_launcher_opacity(opacity);
+    let app_data_dir = get_app_data_dir(&app)?;
+    let mut settings = settings::load_settings(&app_data_dir)?;
+    settings.launcher_opacity = opacity;
+    settings::save_settings(&app_data_dir, &settings)?;
+
+    if let Some(window) = app.get_webview_window("launcher") {
+        set_window_opacity(&window, opacity)?;
+    }
+    Ok(opacity)
+}
+
 /// 设置 launcher 窗口位置（居中但稍微偏上）
 /// 优先使用保存的位置，如果没有保存的位置则计算默认位置
 fn set_launcher_window_position(window: &tauri::WebviewWindow, app_data_dir: &std::path::Path) {
     use tauri::PhysicalPosition;
     use crate::window_config;
     
+    apply_launcher_opacity(window, app_data_dir);
+
     // 首先尝试加载保存的位置
     if let Some(saved_pos) = window_config::get_launcher_position(app_data_dir) {
         // 验证保存的位置是否仍然有效（在屏幕范围内）
@@ -1144,7 +1639,7 @@ pub fn hide_launcher(app: tauri::AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn add_file_to_history(path: String, app: tauri::AppHandle) -> Result<(), String> {
+pub fn add_file_to_history(path: String, app: tauri::AppHandle) -> Result<(), AppError> {
     let app_data_dir = get_app_data_dir(&app)?;
 
     // Load history first to ensure it's up to date
@@ -1155,13 +1650,37 @@ pub fn add_file_to_history(path: String, app: tauri::AppHandle) -> Result<(), St
     Ok(())
 }
 
+/// 批量加入文件历史（拖入多个文件时），只加载和保存一次，返回记录成功的条数
+#[tauri::command]
+pub fn add_files_to_history(paths: Vec<String>, app: tauri::AppHandle) -> Result<usize, AppError> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    file_history::add_file_paths(paths, &app_data_dir)
+}
+
+/// action 为 "open" / "reveal" 时只返回最近一次以该方式使用的记录；
+/// 与 search_applications 相同，被更新的查询取代时返回 query_guard::SUPERSEDED_ERROR
 #[tauri::command]
-pub fn search_file_history(
+pub async fn search_file_history(
     query: String,
+    action: Option<String>,
     app: tauri::AppHandle,
 ) -> Result<Vec<file_history::FileHistoryItem>, String> {
+    let ticket = query_guard::begin(query_guard::HISTORY);
     let app_data_dir = get_app_data_dir(&app)?;
-    file_history::search_file_history(&query, &app_data_dir)
+    async_runtime::spawn_blocking(move || {
+        ticket.ensure_current()?;
+        let limit = settings::load_settings_cached(&app_data_dir)?.max_history_results;
+        let items = file_history::search_file_history(
+            &query,
+            action.as_deref(),
+            Some(limit),
+            &app_data_dir,
+        )?;
+        ticket.ensure_current()?;
+        Ok(items)
+    })
+    .await
+    .map_err(|e| format!("搜索任务失败: {}", e))?
 }
 
 
@@ -1169,17 +1688,18 @@ pub fn search_file_history(
 pub fn get_all_file_history(
     app: tauri::AppHandle,
 ) -> Result<Vec<file_history::FileHistoryItem>, String> {
-    println!("[后端] get_all_file_history: START");
+    log_debug!("FileHistory", "get_all_file_history: START");
     let start_time = std::time::Instant::now();
 
     let app_data_dir = match get_app_data_dir(&app) {
         Ok(dir) => {
-            println!("[后端] get_all_file_history: App data dir = {:?}", dir);
+            log_debug!("FileHistory", "get_all_file_history: App data dir = {:?}", dir);
             dir
         }
         Err(e) => {
-            println!(
-                "[后端] get_all_file_history: ERROR getting app data dir: {}",
+            log_error!(
+                "FileHistory",
+                "get_all_file_history: ERROR getting app data dir: {}",
                 e
             );
             return Err(e);
@@ -1188,49 +1708,62 @@ pub fn get_all_file_history(
 
     // CRITICAL: Lock only once, then do all operations within the lock
     // This prevents nested locking and potential deadlocks
-    println!("[后端] get_all_file_history: Acquiring lock...");
+    log_debug!("FileHistory", "get_all_file_history: Acquiring lock...");
     let mut state = match file_history::lock_history() {
         Ok(guard) => {
-            println!("[后端] get_all_file_history: Lock acquired successfully");
+            log_debug!("FileHistory", "get_all_file_history: Lock acquired successfully");
             guard
         }
         Err(e) => {
-            println!("[后端] get_all_file_history: ERROR acquiring lock: {}", e);
+            log_error!("FileHistory", "get_all_file_history: ERROR acquiring lock: {}", e);
             return Err(e);
         }
     };
 
     // Load history into the locked state (no additional locking)
-    println!("[后端] get_all_file_history: Loading history from disk...");
+    log_debug!("FileHistory", "get_all_file_history: Loading history from disk...");
     match file_history::load_history_into(&mut state, &app_data_dir) {
         Ok(_) => {
-            println!(
-                "[后端] get_all_file_history: History loaded successfully, {} items in memory",
+            log_debug!(
+                "FileHistory",
+                "get_all_file_history: History loaded successfully, {} items in memory",
                 state.len()
             );
         }
         Err(e) => {
-            println!("[后端] get_all_file_history: ERROR loading history: {}", e);
+            log_error!("FileHistory", "get_all_file_history: ERROR loading history: {}", e);
             return Err(e);
         }
     }
 
     // Search within the locked state (no additional locking)
-    println!("[后端] get_all_file_history: Searching history (empty query = all items)...");
-    let result = file_history::search_in_history(&state, "");
-    println!(
-        "[后端] get_all_file_history: Search completed, {} items found",
+    log_debug!("FileHistory", "get_all_file_history: Searching history (empty query = all items)...");
+    let result = file_history::search_in_history(&state, "", None, None);
+    log_debug!(
+        "FileHistory",
+        "get_all_file_history: Search completed, {} items found",
         result.len()
     );
 
     // Lock is automatically released when state goes out of scope
     let elapsed = start_time.elapsed();
-    println!("[后端] get_all_file_history: END (took {:?})", elapsed);
+    log_debug!("FileHistory", "get_all_file_history: END (took {:?})", elapsed);
     Ok(result)
 }
 
+/// 系统最近使用的文档（Windows 的 Recent 文件夹），按最近打开时间倒序；非 Windows 平台返回空列表
+#[tauri::command]
+pub async fn get_recent_documents(
+    limit: Option<usize>,
+) -> Result<Vec<file_history::FileHistoryItem>, String> {
+    let limit = limit.unwrap_or(recent_documents::DEFAULT_RECENT_DOCUMENTS_LIMIT);
+    async_runtime::spawn_blocking(move || recent_documents::get_recent_documents(limit))
+        .await
+        .map_err(|e| format!("读取最近文档任务失败: {}", e))
+}
+
 #[tauri::command]
-pub fn delete_file_history(path: String, app: tauri::AppHandle) -> Result<(), String> {
+pub fn delete_file_history(path: String, app: tauri::AppHandle) -> Result<(), AppError> {
     let app_data_dir = get_app_data_dir(&app)?;
     file_history::load_history(&app_data_dir)?;
     file_history::delete_file_history(path, &app_data_dir)
@@ -1241,7 +1774,7 @@ pub fn update_file_history_name(
     path: String,
     new_name: String,
     app: tauri::AppHandle,
-) -> Result<file_history::FileHistoryItem, String> {
+) -> Result<file_history::FileHistoryItem, AppError> {
     let app_data_dir = get_app_data_dir(&app)?;
     file_history::load_history(&app_data_dir)?;
     file_history::update_file_history_name(path, new_name, &app_data_dir)
@@ -1303,14 +1836,40 @@ pub struct EverythingSearchOptions {
     pub match_folder_name_only: Option<bool>,
     #[serde(rename = "chunkSize")]
     pub chunk_size: Option<usize>,
+    /// 只搜索该文件夹（含子文件夹）下的结果
+    #[serde(rename = "scopePath", default)]
+    pub scope_path: Option<String>,
 }
 
-fn build_everything_query(base: &str, options: &Option<EverythingSearchOptions>) -> (String, usize) {
-    let mut parts: Vec<String> = Vec::new();
-    let mut base_query = base.trim().to_string();
-    let mut use_regex = false;
-
-    let mut max_results = 50usize;
+/// 把文件夹转换为 Everything 的 path: 过滤，如 `path:"C:\My Projects\"`；
+/// 末尾补上分隔符，避免 C:\foo 同时匹配 C:\foobar
+fn everything_scope_filter(scope: &str) -> Option<String> {
+    let expanded = path_utils::expand_path(scope.trim());
+    if expanded.is_empty() {
+        return None;
+    }
+    let mut folder = expanded.replace('/', "\\");
+    if !folder.ends_with('\\') {
+        folder.push('\\');
+    }
+    Some(if folder.contains(char::is_whitespace) {
+        format!("path:\"{}\"", folder)
+    } else {
+        format!("path:{}", folder)
+    })
+}
+
+/// default_max_results 为 options 未指定 maxResults 时返回的结果数（来自设置）
+fn build_everything_query(
+    base: &str,
+    options: &Option<EverythingSearchOptions>,
+    default_max_results: usize,
+) -> (String, usize) {
+    let mut parts: Vec<String> = Vec::new();
+    let mut base_query = base.trim().to_string();
+    let mut use_regex = false;
+
+    let mut max_results = default_max_results;
 
     // 检测用户是否已经使用了 Everything 原生语法
     // Everything 支持的语法前缀：regex:, path:, parent:, file:, folder:, ext:, !ext:, case:
@@ -1329,6 +1888,11 @@ fn build_everything_query(base: &str, options: &Option<EverythingSearchOptions>)
                 || word.starts_with("case:")
         });
     
+    // 用户已输入 path: / parent: 时不再添加 scope_path 的路径过滤
+    let has_path_filter = original_query
+        .split_whitespace()
+        .any(|word| word.starts_with("path:") || word.starts_with("parent:"));
+
     // 检测是否包含扩展名过滤（在原始查询中检测）
     let has_ext_filter = original_query
         .split_whitespace()
@@ -1403,6 +1967,12 @@ fn build_everything_query(base: &str, options: &Option<EverythingSearchOptions>)
                 max_results = mr;
             }
         }
+
+        if !has_path_filter {
+            if let Some(filter) = opts.scope_path.as_deref().and_then(everything_scope_filter) {
+                parts.insert(0, filter);
+            }
+        }
     }
     // 当没有传递 options（如启动器中的简化调用）时，也应保留用户输入的基础查询，
     // 否则会导致 combined_query 为空，后端直接返回 0 结果
@@ -1416,15 +1986,149 @@ fn build_everything_query(base: &str, options: &Option<EverythingSearchOptions>)
     (combined_query, max_results)
 }
 
+/// 正在自动启动 Everything 时为 true，并发的搜索只等待而不重复启动进程
+#[cfg(target_os = "windows")]
+static EVERYTHING_AUTO_STARTING: AtomicBool = AtomicBool::new(false);
+
+/// 自动启动后等待 Everything IPC 窗口就绪的最长时间
+#[cfg(target_os = "windows")]
+const EVERYTHING_AUTO_START_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 开启了 auto_start_everything 且 Everything 已安装但未运行时启动它，
+/// 异步等待至多 EVERYTHING_AUTO_START_TIMEOUT；返回 Everything 当前是否可用
+/// 每次搜索最多调用一次，Everything 在使用中途退出后下一次搜索会再次启动它
+async fn start_everything_if_enabled(app: &tauri::AppHandle) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+
+        if is_everything_available() {
+            return true;
+        }
+        let enabled = get_app_data_dir(app)
+            .and_then(|dir| settings::load_settings_cached(&dir))
+            .is_ok_and(|settings| settings.auto_start_everything);
+        if !enabled {
+            return false;
+        }
+        let Some(everything_exe) = everything_search::windows::find_everything_main_exe() else {
+            return false;
+        };
+
+        let starting_here = !EVERYTHING_AUTO_STARTING.swap(true, Ordering::SeqCst);
+        if starting_here {
+            log_debug!(
+                "Everything",
+                "Everything is installed but not running, starting it"
+            );
+            if let Err(e) = std::process::Command::new(&everything_exe)
+                .creation_flags(0x08000000) // CREATE_NO_WINDOW - 不显示控制台窗口
+                .spawn()
+            {
+                EVERYTHING_AUTO_STARTING.store(false, Ordering::SeqCst);
+                log_warn!("Everything", "Failed to auto-start Everything: {}", e);
+                return false;
+            }
+        }
+
+        let deadline = tokio::time::Instant::now() + EVERYTHING_AUTO_START_TIMEOUT;
+        let mut available = false;
+        while !available && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            available = is_everything_available();
+        }
+        if starting_here {
+            EVERYTHING_AUTO_STARTING.store(false, Ordering::SeqCst);
+            if !available {
+                log_warn!(
+                    "Everything",
+                    "Everything was started but not ready within {} seconds",
+                    EVERYTHING_AUTO_START_TIMEOUT.as_secs()
+                );
+            }
+        }
+        available
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = app;
+        false
+    }
+}
+
+/// 启动器检查 Everything 状态时调用，见 start_everything_if_enabled
+#[tauri::command]
+pub async fn auto_start_everything(app: tauri::AppHandle) -> bool {
+    start_everything_if_enabled(&app).await
+}
+
+/// 启动器前端注册好 open-path-requested 监听后调用，取走启动时命令行传入的路径（只返回一次）
+#[tauri::command]
+pub fn take_startup_paths() -> Vec<String> {
+    single_instance::take_startup_paths()
+}
+
 #[tauri::command]
 pub async fn search_everything(
     query: String,
     options: Option<EverythingSearchOptions>,
     app: tauri::AppHandle,
-) -> Result<everything_search::EverythingSearchResponse, String> {
+) -> Result<everything_search::EverythingSearchResponse, AppError> {
+    let settings = get_app_data_dir(&app).and_then(|dir| settings::load_settings_cached(&dir));
+    let default_max_results = settings
+        .as_ref()
+        .map(|settings| settings.max_everything_results)
+        .unwrap_or_else(|_| settings::default_max_everything_results());
+
+    // Everything 不可用时按设置先尝试启动并等待就绪（每次搜索一次），仍不可用再回退到文件系统遍历
+    if !is_everything_available() && !start_everything_if_enabled(&app).await {
+        let fallback = settings?.everything_fallback;
+        if fallback.allow_fallback {
+            // 文件系统遍历不识别 path: 过滤，改为从 scope_path 开始遍历
+            let scope = options
+                .as_ref()
+                .and_then(|opts| opts.scope_path.as_deref())
+                .map(|scope| path_utils::expand_path(scope.trim()))
+                .filter(|scope| !scope.is_empty());
+            let fallback_options = options.clone().map(|opts| EverythingSearchOptions {
+                scope_path: None,
+                ..opts
+            });
+            let (combined_query, max_results) =
+                build_everything_query(&query, &fallback_options, default_max_results);
+            if combined_query.trim().is_empty() {
+                return Ok(everything_search::EverythingSearchResponse {
+                    results: vec![],
+                    total_count: 0,
+                });
+            }
+            let roots: Vec<PathBuf> = if let Some(scope) = scope {
+                vec![PathBuf::from(scope)]
+            } else if fallback.roots.is_empty() {
+                file_search_fallback::default_roots()
+            } else {
+                fallback.roots.iter().map(PathBuf::from).collect()
+            };
+            // 新的输入会使仍在遍历的旧查询提前结束
+            let ticket = query_guard::begin(query_guard::EVERYTHING_FALLBACK);
+            return async_runtime::spawn_blocking(move || {
+                file_search_fallback::search(
+                    &combined_query,
+                    &roots,
+                    fallback.max_depth,
+                    max_results,
+                    &ticket,
+                )
+            })
+            .await
+            .map_err(|e| AppError::Other(format!("搜索任务失败: {}", e)));
+        }
+    }
+
     #[cfg(target_os = "windows")]
     {
-        let (combined_query, max_results) = build_everything_query(&query, &options);
+        let (combined_query, max_results) =
+            build_everything_query(&query, &options, default_max_results);
         let chunk_size = options
             .as_ref()
             .and_then(|opts| opts.chunk_size)
@@ -1434,8 +2138,9 @@ pub async fn search_everything(
         // 前置兜底：若最终查询字符串为空，直接返回空结果，避免前端误触发“查询字符串不能为空”错误
         // 典型场景：仅设置过滤器但未输入关键词，或异步竞态导致空串落到后端
         if combined_query.trim().is_empty() {
-            eprintln!(
-                "[RUST] search_everything: combined query is empty, return empty result (raw='{}')",
+            log_debug!(
+                "Everything",
+                "search_everything: combined query is empty, return empty result (raw='{}')",
                 query
             );
             return Ok(everything_search::EverythingSearchResponse {
@@ -1454,8 +2159,11 @@ pub async fn search_everything(
             if let Some(ref current_query) = manager.current_query {
                 if current_query == &combined_query {
                     // query 相同，说明是重复搜索，返回错误
-                    eprintln!("[RUST] Duplicate search detected for query: {}, skipping", combined_query);
-                    return Err(format!("搜索 '{}' 正在进行中，跳过重复调用", combined_query));
+                    log_debug!("Everything", "Duplicate search detected for query: {}, skipping", combined_query);
+                    return Err(AppError::Other(format!(
+                        "搜索 '{}' 正在进行中，跳过重复调用",
+                        combined_query
+                    )));
                 }
             }
 
@@ -1464,11 +2172,11 @@ pub async fn search_everything(
             if let Some(old_flag) = &manager.cancel_flag {
                 // 只有当 query 不同时才取消
                 if manager.current_query.as_ref() != Some(&combined_query) {
-                    eprintln!("[RUST] Cancelling previous search (query: {:?}) for new search (query: {})", 
+                    log_debug!("Everything", "Cancelling previous search (query: {:?}) for new search (query: {})", 
                         manager.current_query, combined_query);
                     old_flag.store(true, Ordering::Relaxed);
                 } else {
-                    eprintln!("[RUST] Same query detected, not cancelling previous search: {}", combined_query);
+                    log_debug!("Everything", "Same query detected, not cancelling previous search: {}", combined_query);
                 }
             }
 
@@ -1479,7 +2187,7 @@ pub async fn search_everything(
             // 验证新标志的初始值
             let initial_flag_value = new_flag.load(Ordering::Relaxed);
             if initial_flag_value {
-                eprintln!("[RUST] ERROR: New flag initial value is true! This should never happen!");
+                log_error!("Everything", "ERROR: New flag initial value is true! This should never happen!");
             }
             
             // 先更新 current_query，再更新 cancel_flag，确保状态一致性
@@ -1490,12 +2198,12 @@ pub async fn search_everything(
             
             // 再次验证新标志的值，确保在更新过程中没有被修改
             let flag_value_after_update = new_flag.load(Ordering::Relaxed);
-            eprintln!("[RUST] Created new search flag for query: {} (old query: {:?}, flag value: {})", 
+            log_debug!("Everything", "Created new search flag for query: {} (old query: {:?}, flag value: {})", 
                 combined_query, old_query, flag_value_after_update);
             
             // 如果标志值不是 false，说明有问题
             if flag_value_after_update {
-                eprintln!("[RUST] CRITICAL ERROR: New flag is true after update! This indicates a serious bug!");
+                log_error!("Everything", "CRITICAL ERROR: New flag is true after update! This indicates a serious bug!");
             }
             
             new_flag
@@ -1523,11 +2231,11 @@ pub async fn search_everything(
                 
                 // 使用运行时句柄在阻塞线程中发送异步事件
                 handle.spawn(async move {
-                    let event_data = serde_json::json!({
-                        "results": batch_results,
-                        "total_count": total_count,
-                        "current_count": current_count,
-                    });
+                    let event_data = events::SearchBatch {
+                        results: batch_results,
+                        total_count,
+                        current_count,
+                    };
 
                     if let Some(win) = launcher {
                         if let Err(e) = win.emit("everything-search-batch", &event_data) {
@@ -1562,11 +2270,12 @@ pub async fn search_everything(
                 }
             }
 
-            let resp = result.map_err(|e| e.to_string())?;
+            let resp = result.map_err(AppError::from)?;
 
             // 调试：确认后端实际返回了多少条结果
-            eprintln!(
-                "[RUST] search_everything: search_files returned {} results (total_count={})",
+            log_debug!(
+                "Everything",
+                "search_everything: search_files returned {} results (total_count={})",
                 resp.results.len(),
                 resp.total_count
             );
@@ -1575,11 +2284,13 @@ pub async fn search_everything(
             Ok(resp)
         })
         .await
-        .map_err(|e| format!("搜索任务失败: {}", e))?
+        .map_err(|e| AppError::Other(format!("搜索任务失败: {}", e)))?
     }
     #[cfg(not(target_os = "windows"))]
     {
-        Err("Everything search is only available on Windows".to_string())
+        Err(AppError::PlatformUnsupported(
+            "Everything search is only available on Windows".to_string(),
+        ))
     }
 }
 
@@ -1608,6 +2319,17 @@ pub fn cancel_everything_search() -> Result<(), String> {
     }
 }
 
+/// 校验 Everything 查询（目前检查 regex: 语法），供前端在输入时提示错误
+#[tauri::command]
+pub fn validate_everything_query(
+    query: String,
+    options: Option<EverythingSearchOptions>,
+) -> Result<(), String> {
+    let (combined_query, _) =
+        build_everything_query(&query, &options, settings::default_max_everything_results());
+    everything_search::validate_query(&combined_query).map_err(|e| e.to_string())
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct EverythingSearchSessionOptions {
     pub extensions: Option<Vec<String>>,
@@ -1619,6 +2341,21 @@ pub struct EverythingSearchSessionOptions {
     pub sort_order: Option<String>, // "asc" | "desc"
     #[serde(rename = "matchFolderNameOnly")]
     pub match_folder_name_only: Option<bool>,
+    #[serde(rename = "scopePath", default)]
+    pub scope_path: Option<String>,
+}
+
+impl From<everything_filters::SavedSearchOptions> for EverythingSearchSessionOptions {
+    fn from(options: everything_filters::SavedSearchOptions) -> Self {
+        Self {
+            extensions: options.extensions,
+            max_results: options.max_results,
+            sort_key: options.sort_key,
+            sort_order: options.sort_order,
+            match_folder_name_only: options.match_folder_name_only,
+            scope_path: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -1638,6 +2375,44 @@ pub struct EverythingSearchRangeResponse {
     pub total_count: Option<u32>,
 }
 
+/// 按父目录分组的搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct EverythingResultGroup {
+    pub folder: String,
+    pub items: Vec<everything_search::EverythingResult>,
+}
+
+/// 结果路径的父目录；路径中没有分隔符时为空字符串
+fn result_parent_folder(path: &str) -> &str {
+    let trimmed = path.trim_end_matches(['\\', '/']);
+    match trimmed.rfind(['\\', '/']) {
+        // 保留盘符根目录的分隔符，如 C:\
+        Some(index) if trimmed[..index].ends_with(':') => &trimmed[..=index],
+        Some(index) => &trimmed[..index],
+        None => "",
+    }
+}
+
+/// 将已排序的结果按父目录分组：组按目录名排序（不区分大小写），组内保持原有顺序
+fn group_results_by_folder(
+    results: &[everything_search::EverythingResult],
+) -> Vec<EverythingResultGroup> {
+    let mut groups: std::collections::BTreeMap<String, EverythingResultGroup> =
+        std::collections::BTreeMap::new();
+    for result in results {
+        let folder = result_parent_folder(&result.path);
+        groups
+            .entry(folder.to_lowercase())
+            .or_insert_with(|| EverythingResultGroup {
+                folder: folder.to_string(),
+                items: Vec::new(),
+            })
+            .items
+            .push(result.clone());
+    }
+    groups.into_values().collect()
+}
+
 /// 开启 Everything 搜索会话
 #[tauri::command]
 pub async fn start_everything_search_session(
@@ -1669,9 +2444,14 @@ pub async fn start_everything_search_session(
             max_results: Some(max_results),
             match_folder_name_only: Some(match_folder_name_only),
             chunk_size: Some(5000),
+            scope_path: opts.and_then(|o| o.scope_path.clone()),
         };
         
-        let (combined_query, _) = build_everything_query(&search_query, &Some(search_opts));
+        let (combined_query, _) = build_everything_query(
+            &search_query,
+            &Some(search_opts),
+            settings::default_max_everything_results(),
+        );
         
         // 在移动之前克隆 combined_query，用于后续生成会话 ID
         let combined_query_for_session = combined_query.clone();
@@ -1694,11 +2474,11 @@ pub async fn start_everything_search_session(
                 
                 // 使用运行时句柄在阻塞线程中发送异步事件
                 handle.spawn(async move {
-                    let event_data = serde_json::json!({
-                        "results": batch_results,
-                        "total_count": total_count,
-                        "current_count": current_count,
-                    });
+                    let event_data = events::SearchBatch {
+                        results: batch_results,
+                        total_count,
+                        current_count,
+                    };
 
                     if let Some(win) = everything_win {
                         if let Err(e) = win.emit("everything-search-batch", &event_data) {
@@ -1840,6 +2620,21 @@ pub fn get_everything_search_range(
     })
 }
 
+/// 获取搜索会话的全部结果，按父目录分组
+#[tauri::command]
+pub fn get_everything_search_groups(session_id: String) -> Result<Vec<EverythingResultGroup>, String> {
+    let manager = SEARCH_SESSION_MANAGER
+        .lock()
+        .map_err(|e| format!("锁定会话管理器失败: {}", e))?;
+
+    let session = manager
+        .sessions
+        .get(&session_id)
+        .ok_or_else(|| "会话不存在或已过期".to_string())?;
+
+    Ok(group_results_by_folder(&session.results))
+}
+
 /// 关闭搜索会话
 #[tauri::command]
 pub fn close_everything_search_session(session_id: String) -> Result<(), String> {
@@ -1877,6 +2672,117 @@ pub fn get_everything_status() -> (bool, Option<String>) {
     }
 }
 
+/// everything_health 探测查询的超时，超时后通过取消标志中止查询
+#[cfg(target_os = "windows")]
+const EVERYTHING_HEALTH_TIMEOUT: Duration = Duration::from_secs(3);
+/// 探测查询耗时超过该值时认为 Everything 仍在建立索引
+#[cfg(target_os = "windows")]
+const EVERYTHING_HEALTH_SLOW_MS: u64 = 1000;
+
+/// Everything IPC 健康检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EverythingHealth {
+    pub available: bool,
+    /// 与 get_everything_status 相同的错误代码，或探测查询的错误
+    pub error_code: Option<String>,
+    /// 探测查询的往返耗时；超时时为超时上限
+    pub latency_ms: Option<u64>,
+    pub has_results: bool,
+    /// 启发式判断：探测查询很慢或超时
+    pub indexing: bool,
+}
+
+/// 用单条结果的简单查询测量 Everything 的响应速度，判断其是否已可正常使用
+#[tauri::command]
+pub async fn everything_health() -> Result<EverythingHealth, String> {
+    #[cfg(target_os = "windows")]
+    {
+        let (available, error_code) = everything_search::windows::check_everything_status();
+        if !available {
+            return Ok(EverythingHealth {
+                available,
+                error_code,
+                latency_ms: None,
+                has_results: false,
+                indexing: false,
+            });
+        }
+
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let task_cancel_flag = cancel_flag.clone();
+        let started = std::time::Instant::now();
+        let probe = tokio::task::spawn_blocking(move || {
+            everything_search::windows::search_files(
+                "explorer.exe",
+                1,
+                1,
+                Some(&task_cancel_flag),
+                None::<fn(&[everything_search::EverythingResult], u32, u32)>,
+            )
+        });
+
+        let health = match tokio::time::timeout(EVERYTHING_HEALTH_TIMEOUT, probe).await {
+            Ok(joined) => {
+                let latency_ms = started.elapsed().as_millis() as u64;
+                match joined.map_err(|e| format!("健康检查任务失败: {}", e))? {
+                    Ok(response) => EverythingHealth {
+                        available: true,
+                        error_code: None,
+                        latency_ms: Some(latency_ms),
+                        has_results: !response.results.is_empty(),
+                        indexing: latency_ms > EVERYTHING_HEALTH_SLOW_MS,
+                    },
+                    Err(everything_search::EverythingError::Timeout) => EverythingHealth {
+                        available: true,
+                        error_code: Some("TIMEOUT".to_string()),
+                        latency_ms: Some(latency_ms),
+                        has_results: false,
+                        indexing: true,
+                    },
+                    Err(e) => EverythingHealth {
+                        available: false,
+                        error_code: Some(e.to_string()),
+                        latency_ms: Some(latency_ms),
+                        has_results: false,
+                        indexing: false,
+                    },
+                }
+            }
+            Err(_) => {
+                // 中止仍在等待回复的查询，阻塞线程随后自行退出
+                cancel_flag.store(true, Ordering::Relaxed);
+                EverythingHealth {
+                    available: true,
+                    error_code: Some("TIMEOUT".to_string()),
+                    latency_ms: Some(EVERYTHING_HEALTH_TIMEOUT.as_millis() as u64),
+                    has_results: false,
+                    indexing: true,
+                }
+            }
+        };
+
+        log_debug!(
+            "Everything",
+            "健康检查: latency={:?}ms, results={}, indexing={}",
+            health.latency_ms,
+            health.has_results,
+            health.indexing
+        );
+        Ok(health)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Ok(EverythingHealth {
+            available: false,
+            error_code: Some("NOT_WINDOWS".to_string()),
+            latency_ms: None,
+            has_results: false,
+            indexing: false,
+        })
+    }
+}
+
 #[tauri::command]
 pub fn get_everything_path() -> Result<Option<String>, String> {
     #[cfg(target_os = "windows")]
@@ -1921,11 +2827,44 @@ pub fn get_everything_log_file_path() -> Result<Option<String>, String> {
     }
 }
 
+/// 文件历史统计：按扩展名分组、时间范围和使用最多的 top 条（默认 10，最多 100）
+#[tauri::command]
+pub fn get_file_history_stats(
+    app: tauri::AppHandle,
+    top: Option<usize>,
+) -> Result<file_history::FileHistoryStats, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    file_history::get_history_stats(&app_data_dir, top.unwrap_or(10).clamp(1, 100))
+}
+
+/// 一次清理的记录数达到该值时自动 VACUUM，回收数据库文件空间
+const PURGE_VACUUM_THRESHOLD: usize = 500;
+
 #[tauri::command]
 pub fn purge_file_history(days: Option<u64>, app: tauri::AppHandle) -> Result<usize, String> {
     let app_data_dir = get_app_data_dir(&app)?;
     let days = days.unwrap_or(30).max(1);
-    file_history::purge_history_older_than(days, &app_data_dir)
+    let removed = file_history::purge_history_older_than(days, &app_data_dir)?;
+    if removed >= PURGE_VACUUM_THRESHOLD {
+        match db::vacuum(&app_data_dir) {
+            Ok(result) => log_info!(
+                "Database",
+                "清理 {} 条历史后压缩数据库: {} -> {} 字节",
+                removed,
+                result.size_before,
+                result.size_after
+            ),
+            Err(e) => log_warn!("Database", "清理历史后压缩数据库失败: {}", e),
+        }
+    }
+    Ok(removed)
+}
+
+/// 压缩数据库（VACUUM），返回压缩前后的文件大小
+#[tauri::command]
+pub fn vacuum_database(app: tauri::AppHandle) -> Result<db::VacuumResult, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    db::vacuum(&app_data_dir)
 }
 
 #[tauri::command]
@@ -1957,6 +2896,11 @@ pub struct FilePreview {
     pub image_data_url: Option<String>,
     pub truncated: bool,
     pub metadata: Option<FilePreviewMetadata>,
+    pub line_count: Option<usize>,
+    pub encoding: Option<String>,
+    /// `image_data_url` 是缩放后的缩略图而非原始文件
+    pub thumbnail: bool,
+    pub folder: Option<folder_stats::FolderPreview>,
     pub error: Option<String>,
 }
 
@@ -1987,6 +2931,8 @@ fn guess_mime_from_extension(ext: Option<&str>) -> Option<&'static str> {
         Some("js") => Some("application/javascript"),
         Some("ts") | Some("tsx") => Some("text/typescript"),
         Some("rs") => Some("text/plain"),
+        Some("pdf") => Some("application/pdf"),
+        Some("docx") => Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document"),
         _ => None,
     }
 }
@@ -2048,9 +2994,214 @@ fn is_probably_binary(buffer: &[u8]) -> bool {
     buffer.iter().take(2048).any(|&b| b == 0)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Gbk,
+}
+
+impl TextEncoding {
+    fn label(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 BOM",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::Gbk => "GBK",
+        }
+    }
+}
+
+/// 通过 BOM 判断编码，无 BOM 时返回 None
+fn detect_bom(buffer: &[u8]) -> Option<TextEncoding> {
+    if buffer.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        Some(TextEncoding::Utf8Bom)
+    } else if buffer.starts_with(&[0xFF, 0xFE]) {
+        Some(TextEncoding::Utf16Le)
+    } else if buffer.starts_with(&[0xFE, 0xFF]) {
+        Some(TextEncoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// GBK 双字节序列中落在 GB2312 常用区（首字节 A1–F7、尾字节 A1–FE）的比例下限；
+/// 仅"能按 GBK 解码"不够，Latin-1 文本和二进制数据也常常能解码
+const GBK_MIN_COMMON_RATIO: f64 = 0.8;
+
+/// 非 ASCII 字节全部组成 GBK 双字节序列，且大部分是 GB2312 常用汉字/符号时认为是 GBK
+fn looks_like_gbk(buffer: &[u8]) -> bool {
+    let mut pairs = 0usize;
+    let mut common = 0usize;
+    let mut i = 0;
+    while i < buffer.len() {
+        let lead = buffer[i];
+        if lead < 0x80 {
+            i += 1;
+            continue;
+        }
+        let Some(&trail) = buffer.get(i + 1) else {
+            return false;
+        };
+        if !(0x81..=0xFE).contains(&lead) || !(0x40..=0xFE).contains(&trail) || trail == 0x7F {
+            return false;
+        }
+        pairs += 1;
+        if (0xA1..=0xF7).contains(&lead) && (0xA1..=0xFE).contains(&trail) {
+            common += 1;
+        }
+        i += 2;
+    }
+    pairs > 0 && common as f64 >= pairs as f64 * GBK_MIN_COMMON_RATIO
+}
+
+/// 按双字节配对后末尾落单的首字节属于被截断的字符，去掉它；截断恰好落在两个字符之间时原样返回
+fn without_unpaired_gbk_tail(buffer: &[u8]) -> &[u8] {
+    let mut i = 0;
+    while i < buffer.len() {
+        if buffer[i] < 0x80 {
+            i += 1;
+        } else if i + 1 < buffer.len() {
+            i += 2;
+        } else {
+            return &buffer[..i];
+        }
+    }
+    buffer
+}
+
+/// 无 BOM 时的编码推断：合法 UTF-8 优先，否则尝试 GBK
+/// `truncated` 为 true 时允许末尾存在被截断的不完整字符
+fn guess_encoding_without_bom(buffer: &[u8], truncated: bool) -> TextEncoding {
+    match std::str::from_utf8(buffer) {
+        Ok(_) => return TextEncoding::Utf8,
+        Err(e) if e.error_len().is_none() && truncated => return TextEncoding::Utf8,
+        Err(_) => {}
+    }
+
+    let candidate = if truncated {
+        without_unpaired_gbk_tail(buffer)
+    } else {
+        buffer
+    };
+    if looks_like_gbk(candidate)
+        && encoding_rs::GBK
+            .decode_without_bom_handling_and_without_replacement(candidate)
+            .is_some()
+    {
+        TextEncoding::Gbk
+    } else {
+        TextEncoding::Utf8
+    }
+}
+
+/// 按编码解码预览缓冲区（去掉 BOM）
+fn decode_text_buffer(buffer: &[u8], encoding: TextEncoding) -> String {
+    match encoding {
+        TextEncoding::Utf8 => String::from_utf8_lossy(buffer).to_string(),
+        TextEncoding::Utf8Bom => String::from_utf8_lossy(&buffer[3..]).to_string(),
+        TextEncoding::Utf16Le => encoding_rs::UTF_16LE
+            .decode_without_bom_handling(&buffer[2..])
+            .0
+            .into_owned(),
+        TextEncoding::Utf16Be => encoding_rs::UTF_16BE
+            .decode_without_bom_handling(&buffer[2..])
+            .0
+            .into_owned(),
+        TextEncoding::Gbk => encoding_rs::GBK
+            .decode_without_bom_handling(buffer)
+            .0
+            .into_owned(),
+    }
+}
+
+/// 统计整个文件的行数（超过大小上限时返回 None）
+fn count_file_lines(path: &Path, encoding: TextEncoding) -> Option<usize> {
+    const MAX_LINE_COUNT_BYTES: u64 = 64 * 1024 * 1024;
+    let file = fs::File::open(path).ok()?;
+    if file.metadata().ok()?.len() > MAX_LINE_COUNT_BYTES {
+        return None;
+    }
+
+    let mut reader = std::io::BufReader::new(file);
+    let mut chunk = vec![0u8; 64 * 1024];
+    let mut newlines = 0usize;
+    let mut total = 0u64;
+    let mut last_is_newline = false;
+    // UTF-16 需要按 2 字节单元统计，保留跨块的奇数字节
+    let mut pending: Option<u8> = None;
+    loop {
+        let n = reader.read(&mut chunk).ok()?;
+        if n == 0 {
+            break;
+        }
+        total += n as u64;
+        match encoding {
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+                let mut bytes: Vec<u8> = pending.take().into_iter().collect();
+                bytes.extend_from_slice(&chunk[..n]);
+                if bytes.len() % 2 == 1 {
+                    pending = bytes.pop();
+                }
+                for pair in bytes.chunks_exact(2) {
+                    let unit = if encoding == TextEncoding::Utf16Le {
+                        u16::from_le_bytes([pair[0], pair[1]])
+                    } else {
+                        u16::from_be_bytes([pair[0], pair[1]])
+                    };
+                    last_is_newline = unit == 0x0A;
+                    if last_is_newline {
+                        newlines += 1;
+                    }
+                }
+            }
+            _ => {
+                newlines += chunk[..n].iter().filter(|&&b| b == b'\n').count();
+                last_is_newline = chunk[n - 1] == b'\n';
+            }
+        }
+    }
+
+    if total == 0 {
+        Some(0)
+    } else if last_is_newline {
+        Some(newlines)
+    } else {
+        Some(newlines + 1)
+    }
+}
+
+/// 预览需要读取和解析文件（文档文本、大图缩略图、文件夹大小等），在后台线程执行，避免阻塞主线程
 #[tauri::command]
-pub fn get_file_preview(path: String) -> Result<FilePreview, String> {
-    let path_ref = Path::new(&path);
+pub async fn get_file_preview(
+    path: String,
+    compute_folder_size: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<FilePreview, String> {
+    let app_data_dir = get_app_data_dir(&app);
+    async_runtime::spawn_blocking(move || {
+        let max_preview_bytes = app_data_dir
+            .and_then(|dir| settings::load_settings_cached(&dir))
+            .map(|settings| settings.preview_max_bytes)
+            .unwrap_or_else(|_| settings::default_preview_max_bytes());
+        build_file_preview(
+            Path::new(&path),
+            compute_folder_size.unwrap_or(false),
+            max_preview_bytes,
+        )
+    })
+    .await
+    .map_err(|e| format!("生成预览任务失败: {}", e))?
+}
+
+fn build_file_preview(
+    path_ref: &Path,
+    compute_folder_size: bool,
+    max_preview_bytes: u64,
+) -> Result<FilePreview, String> {
     let metadata =
         fs::metadata(path_ref).map_err(|e| format!("无法读取文件信息: {}", e.to_string()))?;
 
@@ -2072,6 +3223,7 @@ pub fn get_file_preview(path: String) -> Result<FilePreview, String> {
     };
 
     if metadata.is_dir() {
+        let folder = folder_stats::build_folder_preview(path_ref, compute_folder_size).ok();
         return Ok(FilePreview {
             kind: "folder".to_string(),
             size,
@@ -2082,19 +3234,64 @@ pub fn get_file_preview(path: String) -> Result<FilePreview, String> {
             image_data_url: None,
             truncated: false,
             metadata: None,
+            line_count: None,
+            encoding: None,
+            thumbnail: false,
+            folder,
             error: None,
         });
     }
 
     if let Some(ext_ref) = extension.as_deref() {
         if is_image_extension(ext_ref) {
-            let max_preview_bytes: u64 = 200 * 1024;
+            // 小图直接内联原始字节；大图解码后生成缩略图，避免截断导致图片损坏
+            let max_raw_bytes: u64 = 200 * 1024;
+            let max_decode_bytes: u64 = 30 * 1024 * 1024;
+            let file_size = size.unwrap_or(0);
+            let header_dimensions = media_info::image_dimensions(path_ref, ext_ref);
+            let fits_raw = file_size <= max_raw_bytes
+                && header_dimensions
+                    .is_none_or(|(w, h)| w.max(h) <= media_info::THUMBNAIL_MAX_EDGE);
+
+            if !fits_raw && ext_ref != "svg" && file_size <= max_decode_bytes {
+                let thumbnail = fs::read(path_ref).ok().and_then(|bytes| {
+                    media_info::generate_thumbnail(&bytes, ext_ref, media_info::THUMBNAIL_MAX_EDGE)
+                });
+                if let Some(thumb) = thumbnail {
+                    let data_url = format!(
+                        "data:{};base64,{}",
+                        thumb.mime,
+                        general_purpose::STANDARD.encode(&thumb.bytes)
+                    );
+                    return Ok(FilePreview {
+                        kind: "image".to_string(),
+                        size,
+                        modified,
+                        extension,
+                        mime,
+                        content: None,
+                        image_data_url: Some(data_url),
+                        truncated: false,
+                        metadata: Some(FilePreviewMetadata {
+                            duration_ms: None,
+                            width: Some(thumb.original_width),
+                            height: Some(thumb.original_height),
+                        }),
+                        line_count: None,
+                        encoding: None,
+                        thumbnail: true,
+                        folder: None,
+                        error: None,
+                    });
+                }
+            }
+
             let mut file =
                 fs::File::open(path_ref).map_err(|e| format!("无法打开文件: {}", e.to_string()))?;
             let mut buffer: Vec<u8> = Vec::new();
             let read_bytes = file
                 .by_ref()
-                .take(max_preview_bytes)
+                .take(max_raw_bytes)
                 .read_to_end(&mut buffer)
                 .map_err(|e| format!("读取文件失败: {}", e.to_string()))?;
             let truncated = size.map_or(false, |s| s > read_bytes as u64);
@@ -2115,13 +3312,44 @@ pub fn get_file_preview(path: String) -> Result<FilePreview, String> {
                 content: None,
                 image_data_url: Some(data_url),
                 truncated,
-                metadata: None,
+                metadata: header_dimensions.map(|(width, height)| FilePreviewMetadata {
+                    duration_ms: None,
+                    width: Some(width),
+                    height: Some(height),
+                }),
+                line_count: None,
+                encoding: None,
+                thumbnail: false,
+                folder: None,
                 error: None,
             });
         }
     }
 
-    let max_preview_bytes: u64 = 32 * 1024;
+    // PDF / Office 文档：尽力提取文本，失败时回退为 binary
+    if let Some(ext_ref) = extension.as_deref() {
+        if document_text::is_extractable_extension(ext_ref) {
+            if let Some(extracted) = document_text::extract_text(path_ref, ext_ref, 32 * 1024) {
+                return Ok(FilePreview {
+                    kind: "text".to_string(),
+                    size,
+                    modified,
+                    extension,
+                    mime,
+                    content: Some(extracted.content),
+                    image_data_url: None,
+                    truncated: extracted.truncated,
+                    metadata: None,
+                    line_count: None,
+                    encoding: None,
+                    thumbnail: false,
+                    folder: None,
+                    error: None,
+                });
+            }
+        }
+    }
+
     let mut file = fs::File::open(path_ref)
         .map_err(|e| format!("无法打开文件: {}", e.to_string()))?;
     let mut buffer: Vec<u8> = Vec::new();
@@ -2132,14 +3360,25 @@ pub fn get_file_preview(path: String) -> Result<FilePreview, String> {
         .map_err(|e| format!("读取文件失败: {}", e.to_string()))?;
     let truncated = size.map_or(false, |s| s > read_bytes as u64);
 
-    let is_text = extension
-        .as_deref()
-        .map(is_text_extension)
-        .unwrap_or(false)
+    // BOM 优先判断，UTF-16 文本包含 0 字节，不能走二进制检测
+    let bom = detect_bom(&buffer);
+    let is_text = bom.is_some()
+        || extension
+            .as_deref()
+            .map(is_text_extension)
+            .unwrap_or(false)
         || !is_probably_binary(&buffer);
 
     if is_text {
-        let content = String::from_utf8_lossy(&buffer).to_string();
+        let encoding = bom.unwrap_or_else(|| guess_encoding_without_bom(&buffer, truncated));
+        let content = decode_text_buffer(&buffer, encoding);
+        let line_count = if truncated {
+            count_file_lines(path_ref, encoding)
+        } else if content.is_empty() {
+            Some(0)
+        } else {
+            Some(content.lines().count())
+        };
         return Ok(FilePreview {
             kind: "text".to_string(),
             size,
@@ -2150,12 +3389,17 @@ pub fn get_file_preview(path: String) -> Result<FilePreview, String> {
             image_data_url: None,
             truncated,
             metadata: None,
+            line_count,
+            encoding: Some(encoding.label().to_string()),
+            thumbnail: false,
+            folder: None,
             error: None,
         });
     }
 
     if let Some(ext_ref) = extension.as_deref() {
         if is_media_extension(ext_ref) {
+            let info = media_info::probe_media(path_ref, ext_ref);
             return Ok(FilePreview {
                 kind: "media".to_string(),
                 size,
@@ -2166,10 +3410,14 @@ pub fn get_file_preview(path: String) -> Result<FilePreview, String> {
                 image_data_url: None,
                 truncated,
                 metadata: Some(FilePreviewMetadata {
-                    duration_ms: None,
-                    width: None,
-                    height: None,
+                    duration_ms: info.duration_ms,
+                    width: info.width,
+                    height: info.height,
                 }),
+                line_count: None,
+                encoding: None,
+                thumbnail: false,
+                folder: None,
                 error: None,
             });
         }
@@ -2185,6 +3433,10 @@ pub fn get_file_preview(path: String) -> Result<FilePreview, String> {
         image_data_url: None,
         truncated,
         metadata: None,
+        line_count: None,
+        encoding: None,
+        thumbnail: false,
+        folder: None,
         error: None,
     })
 }
@@ -2224,6 +3476,9 @@ pub struct DatabaseBackupInfo {
     pub path: String,
     pub size: u64,
     pub modified: Option<u64>,
+    pub compressed: bool,
+    /// 压缩备份解压后的大小（读取 gzip 尾部的 ISIZE，超过 4GB 时不准确）
+    pub uncompressed_size: Option<u64>,
 }
 
 #[derive(Serialize)]
@@ -2249,25 +3504,89 @@ fn ensure_backup_path(path: &str, app_data_dir: &Path) -> Result<std::path::Path
     Ok(target)
 }
 
+fn is_compressed_backup(path: &Path) -> bool {
+    path.to_string_lossy().to_lowercase().ends_with(".db.gz")
+}
+
+/// 读取 gzip 尾部记录的原始大小（模 2^32）
+fn gzip_uncompressed_size(path: &Path) -> Option<u64> {
+    use std::io::{Seek, SeekFrom};
+
+    let mut file = fs::File::open(path).ok()?;
+    file.seek(SeekFrom::End(-4)).ok()?;
+    let mut buf = [0u8; 4];
+    file.read_exact(&mut buf).ok()?;
+    Some(u32::from_le_bytes(buf) as u64)
+}
+
+fn compress_file(src: &Path, dest: &Path) -> Result<(), String> {
+    let mut input =
+        fs::File::open(src).map_err(|e| format!("Failed to open database: {}", e))?;
+    let output =
+        fs::File::create(dest).map_err(|e| format!("Failed to create backup file: {}", e))?;
+    let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+    std::io::copy(&mut input, &mut encoder)
+        .map_err(|e| format!("Failed to compress database: {}", e))?;
+    encoder
+        .finish()
+        .map_err(|e| format!("Failed to finish compressed backup: {}", e))?;
+    Ok(())
+}
+
+fn decompress_file(src: &Path, dest: &Path) -> Result<(), String> {
+    let input = fs::File::open(src).map_err(|e| format!("Failed to open backup: {}", e))?;
+    let mut output =
+        fs::File::create(dest).map_err(|e| format!("Failed to create temp file: {}", e))?;
+    let mut decoder = flate2::read::GzDecoder::new(input);
+    std::io::copy(&mut decoder, &mut output)
+        .map_err(|e| format!("Failed to decompress backup: {}", e))?;
+    Ok(())
+}
+
+/// 把数据库连同 -wal 中尚未写回的内容写入 backup_path，compress 为 true 时先生成快照再压缩
+fn write_backup(db_path: &Path, backup_path: &Path, compress: bool) -> Result<(), String> {
+    if !compress {
+        return db::snapshot(db_path, backup_path);
+    }
+    let snapshot_path = backup_path.with_extension("snapshot-tmp");
+    let result = db::snapshot(db_path, &snapshot_path)
+        .and_then(|_| compress_file(&snapshot_path, backup_path));
+    let _ = fs::remove_file(&snapshot_path);
+    db::remove_wal_files(&snapshot_path);
+    if result.is_err() {
+        let _ = fs::remove_file(backup_path);
+    }
+    result
+}
+
 /// 备份数据库到 app_data_dir/backups/re-fast-backup_yyyyMMdd_HHmmss.db
+/// compress 为 true 时写入 .db.gz，未指定时使用设置中的 compress_backups
 #[tauri::command]
-pub fn backup_database(app: tauri::AppHandle) -> Result<String, String> {
+pub fn backup_database(app: tauri::AppHandle, compress: Option<bool>) -> Result<String, String> {
     let app_data_dir = get_app_data_dir(&app)?;
     let db_path = db::get_db_path(&app_data_dir);
     if !db_path.exists() {
         return Err("Database file not found".to_string());
     }
 
+    let compress = match compress {
+        Some(value) => value,
+        None => settings::load_settings(&app_data_dir)?.compress_backups,
+    };
+
     let backup_dir = app_data_dir.join("backups");
     fs::create_dir_all(&backup_dir)
         .map_err(|e| format!("Failed to create backup directory: {}", e))?;
 
     let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let backup_name = format!("re-fast-backup_{}.db", timestamp);
+    let backup_name = if compress {
+        format!("re-fast-backup_{}.db.gz", timestamp)
+    } else {
+        format!("re-fast-backup_{}.db", timestamp)
+    };
     let backup_path = backup_dir.join(backup_name);
 
-    fs::copy(&db_path, &backup_path)
-        .map_err(|e| format!("Failed to copy database: {}", e))?;
+    write_backup(&db_path, &backup_path, compress)?;
 
     Ok(backup_path
         .to_string_lossy()
@@ -2284,10 +3603,12 @@ pub fn delete_backup(app: tauri::AppHandle, path: String) -> Result<(), String>
         return Err("Backup file not found".to_string());
     }
 
-    fs::remove_file(&target).map_err(|e| format!("Failed to delete backup: {}", e))
+    trash::remove_file(&target, use_trash_for_deletes(&app_data_dir))
+        .map_err(|e| format!("Failed to delete backup: {}", e))
 }
 
-/// 用指定的备份覆盖当前数据库
+/// 用指定的备份覆盖当前数据库（.db.gz 备份会先解压）
+/// 覆盖前校验备份完整性，并把当前数据库另存为 re-fast-pre-restore_yyyyMMdd_HHmmss.db
 #[tauri::command]
 pub fn restore_backup(app: tauri::AppHandle, path: String) -> Result<String, String> {
     let app_data_dir = get_app_data_dir(&app)?;
@@ -2297,14 +3618,57 @@ pub fn restore_backup(app: tauri::AppHandle, path: String) -> Result<String, Str
         return Err("Backup file not found".to_string());
     }
 
-    let db_path = db::get_db_path(&app_data_dir);
+    let decompressed = verified_backup_source(&target)?;
+    let source = decompressed.as_deref().unwrap_or(&target);
+    let result = replace_database(&app_data_dir, source);
+    if let Some(temp_path) = &decompressed {
+        let _ = fs::remove_file(temp_path);
+    }
+    result
+}
+
+/// 校验备份；压缩备份解压到临时文件，校验和覆盖都基于解压后的数据
+/// 返回解压出的临时文件（用完后由调用方删除），未压缩的备份返回 None
+fn verified_backup_source(target: &Path) -> Result<Option<std::path::PathBuf>, String> {
+    if is_compressed_backup(target) {
+        let temp_path = target.with_extension("restore-tmp");
+        if let Err(e) = decompress_file(target, &temp_path)
+            .and_then(|_| db::verify_backup(&temp_path))
+        {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
+        Ok(Some(temp_path))
+    } else {
+        db::verify_backup(target)?;
+        Ok(None)
+    }
+}
+
+/// 恢复前自动保存的当前数据库的文件名前缀
+const PRE_RESTORE_PREFIX: &str = "re-fast-pre-restore_";
+
+fn replace_database(app_data_dir: &Path, source: &Path) -> Result<String, String> {
+    let db_path = db::get_db_path(app_data_dir);
     if let Some(parent) = db_path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("Failed to create database directory: {}", e))?;
     }
 
-    fs::copy(&target, &db_path)
+    if db_path.exists() {
+        let backup_dir = app_data_dir.join("backups");
+        fs::create_dir_all(&backup_dir)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let snapshot_path = backup_dir.join(format!("{}{}.db", PRE_RESTORE_PREFIX, timestamp));
+        db::snapshot(&db_path, &snapshot_path)?;
+    }
+
+    fs::copy(source, &db_path)
         .map_err(|e| format!("Failed to restore database: {}", e))?;
+    // 旧的 -wal 属于被替换的数据库，留着会在下次打开时被应用到恢复后的文件上
+    db::remove_wal_files(&db_path);
+    settings::invalidate_settings_cache();
 
     Ok(db_path
         .to_string_lossy()
@@ -2315,6 +3679,10 @@ pub fn restore_backup(app: tauri::AppHandle, path: String) -> Result<String, Str
 #[tauri::command]
 pub fn list_backups(app: tauri::AppHandle) -> Result<DatabaseBackupList, String> {
     let app_data_dir = get_app_data_dir(&app)?;
+    read_backup_list(&app_data_dir)
+}
+
+fn read_backup_list(app_data_dir: &Path) -> Result<DatabaseBackupList, String> {
     let backup_dir = app_data_dir.join("backups");
 
     if !backup_dir.exists() {
@@ -2333,11 +3701,14 @@ pub fn list_backups(app: tauri::AppHandle) -> Result<DatabaseBackupList, String>
         if !path.is_file() {
             continue;
         }
-        // 仅保留 .db 备份文件
-        if let Some(ext) = path.extension() {
-            if ext.to_string_lossy().to_lowercase() != "db" {
-                continue;
-            }
+        // 仅保留 .db 和 .db.gz 备份文件
+        let compressed = is_compressed_backup(&path);
+        let is_db = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().eq_ignore_ascii_case("db"))
+            .unwrap_or(false);
+        if !compressed && !is_db {
+            continue;
         }
 
         let metadata = entry
@@ -2354,6 +3725,12 @@ pub fn list_backups(app: tauri::AppHandle) -> Result<DatabaseBackupList, String>
             path: path.to_string_lossy().to_string(),
             size: metadata.len(),
             modified,
+            compressed,
+            uncompressed_size: if compressed {
+                gzip_uncompressed_size(&path)
+            } else {
+                Some(metadata.len())
+            },
         });
     }
 
@@ -2366,9 +3743,146 @@ pub fn list_backups(app: tauri::AppHandle) -> Result<DatabaseBackupList, String>
     })
 }
 
-/// 聚合索引状态，便于前端一次性获取
+/// 检查数据库完整性（PRAGMA integrity_check）
 #[tauri::command]
-pub fn get_index_status(app: tauri::AppHandle) -> Result<IndexStatus, String> {
+pub fn check_database_integrity(app: tauri::AppHandle) -> Result<db::IntegrityReport, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let db_path = db::get_db_path(&app_data_dir);
+    if !db_path.exists() {
+        return Err("Database file not found".to_string());
+    }
+    Ok(db::check_integrity(&db_path))
+}
+
+#[derive(Serialize)]
+pub struct DatabaseRepairResult {
+    /// "none"：数据库完好，无需修复；"rebuilt"：逐表导出后重建；"restored_backup"：恢复了最新的可用备份
+    pub method: String,
+    pub before: db::IntegrityReport,
+    pub after: db::IntegrityReport,
+    /// 重建时未能恢复的表和索引
+    pub skipped: Vec<String>,
+    pub restored_from: Option<String>,
+}
+
+/// 修复损坏的数据库：先尝试逐表导出重建；有数据无法读出时改为恢复最新的可用备份，
+/// 没有可用备份时仍使用部分恢复的结果。原数据库会另存为 re-fast-pre-restore_*.db
+#[tauri::command]
+pub fn repair_database(app: tauri::AppHandle) -> Result<DatabaseRepairResult, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let db_path = db::get_db_path(&app_data_dir);
+    if !db_path.exists() {
+        return Err("Database file not found".to_string());
+    }
+
+    let before = db::check_integrity(&db_path);
+    if before.ok {
+        return Ok(DatabaseRepairResult {
+            method: "none".to_string(),
+            after: before.clone(),
+            before,
+            skipped: vec![],
+            restored_from: None,
+        });
+    }
+
+    let rebuilt_path = app_data_dir.join("re-fast.repair-tmp.db");
+    let rebuilt = db::rebuild_into(&db_path, &rebuilt_path).and_then(|skipped| {
+        let report = db::check_integrity(&rebuilt_path);
+        if report.ok {
+            Ok(skipped)
+        } else {
+            Err(format!("重建后的数据库未通过校验: {}", report.problems.join("; ")))
+        }
+    });
+
+    let outcome = match rebuilt {
+        Ok(skipped) if skipped.is_empty() => {
+            replace_database(&app_data_dir, &rebuilt_path).map(|_| ("rebuilt", skipped, None))
+        }
+        rebuilt => match restore_newest_backup(&app_data_dir) {
+            Ok(Some(backup)) => Ok(("restored_backup", vec![], Some(backup))),
+            Ok(None) => match rebuilt {
+                Ok(skipped) => replace_database(&app_data_dir, &rebuilt_path)
+                    .map(|_| ("rebuilt", skipped, None)),
+                Err(e) => Err(format!("修复数据库失败，且没有可用的备份: {}", e)),
+            },
+            Err(e) => Err(e),
+        },
+    };
+    let _ = fs::remove_file(&rebuilt_path);
+    let (method, skipped, restored_from) = outcome?;
+
+    // 清空内存中的文件历史，下次访问时从修复后的数据库重新加载
+    file_history::lock_history()?.clear();
+
+    Ok(DatabaseRepairResult {
+        method: method.to_string(),
+        before,
+        after: db::check_integrity(&db_path),
+        skipped,
+        restored_from,
+    })
+}
+
+/// 从新到旧尝试备份，恢复第一个通过校验的并返回其路径；没有可用备份时返回 None
+/// 恢复前自动保存的 re-fast-pre-restore_*.db 是被替换掉的状态（可能已损坏），不参与
+fn restore_newest_backup(app_data_dir: &Path) -> Result<Option<String>, String> {
+    let backups = read_backup_list(app_data_dir)?.items;
+    for item in backups
+        .into_iter()
+        .filter(|item| !item.name.starts_with(PRE_RESTORE_PREFIX))
+    {
+        let path = std::path::PathBuf::from(&item.path);
+        let Ok(decompressed) = verified_backup_source(&path) else {
+            continue;
+        };
+        let result = replace_database(app_data_dir, decompressed.as_deref().unwrap_or(&path));
+        if let Some(temp_path) = &decompressed {
+            let _ = fs::remove_file(temp_path);
+        }
+        result?;
+        return Ok(Some(item.path));
+    }
+    Ok(None)
+}
+
+/// 导出全部应用数据（数据库、设置、录制等）到一个 zip 文件
+#[tauri::command]
+pub async fn export_all_data(app: tauri::AppHandle, dest: String) -> Result<String, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let dest_path = PathBuf::from(&dest);
+    async_runtime::spawn_blocking(move || data_transfer::export_all(&app_data_dir, &dest_path))
+        .await
+        .map_err(|e| format!("导出任务失败: {}", e))??;
+    Ok(dest)
+}
+
+/// 从导出的 zip 恢复全部应用数据，返回导入前自动备份的路径
+#[tauri::command]
+pub async fn import_all_data(
+    app: tauri::AppHandle,
+    src: String,
+) -> Result<Option<String>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let pre_import = async_runtime::spawn_blocking(move || {
+        data_transfer::import_all(&app_data_dir, &PathBuf::from(src))
+    })
+    .await
+    .map_err(|e| format!("导入任务失败: {}", e))??;
+
+    // 应用缓存可能已被替换，清空内存缓存以便下次从磁盘重新加载
+    if let Ok(mut cache) = APP_CACHE.lock() {
+        *cache = None;
+    }
+    settings::invalidate_settings_cache();
+
+    Ok(pre_import.map(|p| p.to_string_lossy().to_string()))
+}
+
+/// 聚合索引状态，便于前端一次性获取
+#[tauri::command]
+pub fn get_index_status(app: tauri::AppHandle) -> Result<IndexStatus, String> {
     #[cfg(target_os = "windows")]
     {
         let app_data_dir = get_app_data_dir(&app)?;
@@ -2501,7 +4015,7 @@ fn find_everything_installation_dir() -> Option<std::path::PathBuf> {
 }
 
 #[tauri::command]
-pub async fn start_everything() -> Result<(), String> {
+pub async fn start_everything() -> Result<(), AppError> {
     #[cfg(target_os = "windows")]
     {
         use std::os::windows::process::CommandExt;
@@ -2510,61 +4024,329 @@ pub async fn start_everything() -> Result<(), String> {
         tokio::task::spawn_blocking(move || {
             // 查找 Everything.exe
             let everything_exe = everything_search::windows::find_everything_main_exe()
-                .ok_or_else(|| "Everything.exe 未找到，请确保 Everything 已安装".to_string())?;
+                .ok_or_else(|| {
+                    AppError::NotFound("Everything.exe 未找到，请确保 Everything 已安装".to_string())
+                })?;
 
             // 启动 Everything.exe
             // 如果 Everything 已配置后台运行，启动后会自动最小化到托盘
             std::process::Command::new(&everything_exe)
                 .creation_flags(0x08000000) // CREATE_NO_WINDOW - 不显示控制台窗口
                 .spawn()
-                .map_err(|e| format!("无法启动 Everything: {}", e))?;
+                .map_err(|e| AppError::Io(format!("无法启动 Everything: {}", e)))?;
 
             // 等待 Everything 启动并初始化服务（通常需要 1-2 秒）
             std::thread::sleep(std::time::Duration::from_millis(2000));
 
-            Ok::<(), String>(())
+            Ok::<(), AppError>(())
         })
         .await
-        .map_err(|e| format!("启动任务失败: {}", e))?
+        .map_err(|e| AppError::Other(format!("启动任务失败: {}", e)))?
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(AppError::PlatformUnsupported(
+            "Everything 仅在 Windows 上可用".to_string(),
+        ))
+    }
+}
+
+/// 让 Everything 重建索引（文件变化后索引过期时使用），请求发出后立即返回
+#[tauri::command]
+pub fn refresh_everything_index() -> Result<(), AppError> {
+    #[cfg(target_os = "windows")]
+    {
+        everything_search::windows::rebuild_index().map_err(AppError::from)
     }
     #[cfg(not(target_os = "windows"))]
     {
-        Err("Everything 仅在 Windows 上可用".to_string())
+        Err(AppError::PlatformUnsupported(
+            "Everything 仅在 Windows 上可用".to_string(),
+        ))
+    }
+}
+
+/// Everything 安装包下载信息
+#[cfg(target_os = "windows")]
+struct EverythingInstaller {
+    url: &'static str,
+    /// voidtools 公布的 SHA-256；未登记时不下载，安装包必须通过校验才能使用
+    sha256: Option<&'static str>,
+}
+
+/// 本机 CPU 架构（与 std::env::consts::ARCH 相同的命名）
+/// x64 构建在 ARM64 Windows 上模拟运行时 consts::ARCH 仍为 x86_64，需用 IsWow64Process2 取本机架构；
+/// 该函数在 Windows 10 1709 之前不存在，因此动态查找，失败时退回进程架构
+#[cfg(target_os = "windows")]
+fn host_arch() -> &'static str {
+    use std::ffi::c_void;
+    use windows_sys::Win32::System::Threading::GetCurrentProcess;
+
+    // GetModuleHandleW / GetProcAddress 需要未启用的 Win32_System_LibraryLoader 特性
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetModuleHandleW(module_name: *const u16) -> isize;
+        fn GetProcAddress(module: isize, proc_name: *const std::ffi::c_char) -> *const c_void;
+    }
+    type IsWow64Process2Fn = unsafe extern "system" fn(isize, *mut u16, *mut u16) -> i32;
+
+    const IMAGE_FILE_MACHINE_I386: u16 = 0x014C;
+    const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+    const IMAGE_FILE_MACHINE_ARM64: u16 = 0xAA64;
+
+    let native_machine = unsafe {
+        let kernel32: Vec<u16> = "kernel32.dll".encode_utf16().chain([0]).collect();
+        let module = GetModuleHandleW(kernel32.as_ptr());
+        let proc = if module == 0 {
+            std::ptr::null()
+        } else {
+            GetProcAddress(module, c"IsWow64Process2".as_ptr())
+        };
+        if proc.is_null() {
+            None
+        } else {
+            let is_wow64_process2 = std::mem::transmute::<*const c_void, IsWow64Process2Fn>(proc);
+            let mut process_machine = 0u16;
+            let mut native_machine = 0u16;
+            (is_wow64_process2(GetCurrentProcess(), &mut process_machine, &mut native_machine) != 0)
+                .then_some(native_machine)
+        }
+    };
+
+    match native_machine {
+        Some(IMAGE_FILE_MACHINE_AMD64) => "x86_64",
+        Some(IMAGE_FILE_MACHINE_ARM64) => "aarch64",
+        Some(IMAGE_FILE_MACHINE_I386) => "x86",
+        _ => std::env::consts::ARCH,
+    }
+}
+
+/// 按架构选择安装包（x64 / x86 / ARM64）
+#[cfg(target_os = "windows")]
+fn everything_installer_for_arch(arch: &str) -> Result<EverythingInstaller, String> {
+    match arch {
+        "x86_64" => Ok(EverythingInstaller {
+            url: "https://www.voidtools.com/Everything-1.4.1.1024.x64-Setup.exe",
+            sha256: None,
+        }),
+        "x86" => Ok(EverythingInstaller {
+            url: "https://www.voidtools.com/Everything-1.4.1.1024.x86-Setup.exe",
+            sha256: None,
+        }),
+        "aarch64" => Ok(EverythingInstaller {
+            url: "https://www.voidtools.com/Everything-1.4.1.1024.ARM64-Setup.exe",
+            sha256: None,
+        }),
+        other => Err(format!("Unsupported architecture for Everything installer: {}", other)),
+    }
+}
+
+/// 安装包在临时目录中的位置：按下载地址的文件名区分架构和版本，
+/// 避免续传时把另一架构或旧版本的残留文件当作已下载部分
+#[cfg(target_os = "windows")]
+fn everything_installer_path(installer: &EverythingInstaller) -> PathBuf {
+    let file_name = installer
+        .url
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("Everything-Setup.exe");
+    std::env::temp_dir().join(file_name)
+}
+
+/// 与部分下载文件一起保存的校验值（ETag 或 Last-Modified），续传时作为 If-Range 发送
+#[cfg(target_os = "windows")]
+fn resume_validator_path(installer_path: &Path) -> PathBuf {
+    let mut path = installer_path.as_os_str().to_owned();
+    path.push(".validator");
+    PathBuf::from(path)
+}
+
+/// If-Range 只接受强校验值：弱 ETag（W/ 开头）不可用，此时退回 Last-Modified
+#[cfg(target_os = "windows")]
+fn resume_validator(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let etag = headers
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"));
+    etag.or_else(|| {
+        headers
+            .get(reqwest::header::LAST_MODIFIED)
+            .and_then(|value| value.to_str().ok())
+    })
+    .map(str::to_string)
+}
+
+/// 解析 `Content-Range: bytes <start>-<end>/<total>` 的起始位置
+#[cfg(target_os = "windows")]
+fn content_range_start(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// 校验下载结果：完整性（大小）、可执行文件格式和登记的 SHA-256
+#[cfg(target_os = "windows")]
+fn verify_everything_installer(
+    installer: &EverythingInstaller,
+    path: &Path,
+    downloaded: u64,
+    expected_size: u64,
+    sha256: &str,
+) -> Result<(), String> {
+    if downloaded != expected_size {
+        return Err(format!(
+            "Installer download incomplete: {} of {} bytes",
+            downloaded, expected_size
+        ));
+    }
+
+    // 检查 MZ 头和 PE 签名，避免把错误页面等非安装包当作安装程序
+    let content = fs::read(path).map_err(|e| format!("Failed to read installer: {}", e))?;
+    let pe_offset = content
+        .get(0x3C..0x40)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize);
+    let is_pe = content.starts_with(b"MZ")
+        && pe_offset
+            .and_then(|offset| content.get(offset..offset + 4))
+            .map(|sig| sig == b"PE\0\0")
+            .unwrap_or(false);
+    if !is_pe {
+        return Err("Downloaded file is not a valid Windows installer".to_string());
+    }
+
+    let expected = installer
+        .sha256
+        .ok_or_else(|| format!("No known SHA-256 for {}, got {}", installer.url, sha256))?;
+    if !expected.eq_ignore_ascii_case(sha256) {
+        return Err(format!(
+            "Installer checksum mismatch: expected {}, got {}",
+            expected, sha256
+        ));
     }
+    Ok(())
+}
+
+/// 校验未通过时删除安装包，避免之后被当作已下载的部分续传或被运行
+#[cfg(target_os = "windows")]
+fn keep_verified_installer(
+    installer: &EverythingInstaller,
+    path: &Path,
+    downloaded: u64,
+    expected_size: u64,
+    sha256: &str,
+) -> Result<(), String> {
+    let result = verify_everything_installer(installer, path, downloaded, expected_size, sha256);
+    if result.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    result
+}
+
+/// 运行前重新校验安装包（路径来自前端，文件也可能在下载后被替换）；不删除文件
+#[cfg(target_os = "windows")]
+fn verify_installer_file(installer: &EverythingInstaller, path: &Path) -> Result<(), String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("Failed to read installer: {}", e))?
+        .len();
+    let digest = hash_file::<sha2::Sha256>(path)?;
+    verify_everything_installer(installer, path, size, size, &digest)
 }
 
 #[tauri::command]
 pub async fn download_everything(app: tauri::AppHandle) -> Result<String, String> {
     #[cfg(target_os = "windows")]
     {
+        use sha2::{Digest, Sha256};
         use std::fs::File;
         use std::io::Write;
 
-        // Get temp directory
-        let temp_dir = std::env::temp_dir();
-        let installer_path = temp_dir.join("Everything-Setup.exe");
-
-        // Determine download URL based on system architecture
-        // For now, use 64-bit version (most common)
-        let download_url = "https://www.voidtools.com/Everything-1.4.1.1024.x64-Setup.exe";
+        // Determine download URL based on the host architecture
+        let installer = everything_installer_for_arch(host_arch())?;
+        if installer.sha256.is_none() {
+            return Err(format!(
+                "No known SHA-256 for {}, the installer cannot be verified",
+                installer.url
+            ));
+        }
+        let download_url = installer.url;
+        let installer_path = everything_installer_path(&installer);
+        let validator_path = resume_validator_path(&installer_path);
 
         // Create HTTP client
         let client = reqwest::Client::new();
-        let response = client
-            .get(download_url)
+
+        // 临时目录中残留的部分文件：带 If-Range 的 Range 请求只下载剩余部分，
+        // 服务器上的文件已变化时服务器返回 200 和完整内容
+        let existing_size = fs::metadata(&installer_path).map(|m| m.len()).unwrap_or(0);
+        let stored_validator = fs::read_to_string(&validator_path)
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        let mut request = client.get(download_url);
+        if let (true, Some(validator)) = (existing_size > 0, &stored_validator) {
+            request = request
+                .header(reqwest::header::RANGE, format!("bytes={}-", existing_size))
+                .header(reqwest::header::IF_RANGE, validator.as_str());
+        }
+        let mut response = request
             .send()
             .await
             .map_err(|e| format!("Failed to start download: {}", e))?;
+        let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT
+            && content_range_start(&response) == Some(existing_size);
+        if !resumed && response.status() != reqwest::StatusCode::OK {
+            // 416 或起始位置不符：丢弃部分文件，重新完整下载
+            response = client
+                .get(download_url)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to start download: {}", e))?;
+        }
+        if !response.status().is_success() {
+            return Err(format!("Failed to download installer: HTTP {}", response.status()));
+        }
+        if !resumed {
+            match resume_validator(response.headers()) {
+                Some(validator) => {
+                    let _ = fs::write(&validator_path, validator);
+                }
+                None => {
+                    let _ = fs::remove_file(&validator_path);
+                }
+            }
+        }
 
-        let total_size = response
+        let remaining_size = response
             .content_length()
             .ok_or_else(|| "Failed to get content length".to_string())?;
 
-        // Create file
-        let mut file =
-            File::create(&installer_path).map_err(|e| format!("Failed to create file: {}", e))?;
+        // 续传时追加写入，并把已有内容计入哈希和进度
+        let mut hasher = Sha256::new();
+        let (mut file, mut downloaded) = if resumed {
+            let existing = fs::read(&installer_path)
+                .map_err(|e| format!("Failed to read partial download: {}", e))?;
+            hasher.update(&existing);
+            let file = fs::OpenOptions::new()
+                .append(true)
+                .open(&installer_path)
+                .map_err(|e| format!("Failed to open file: {}", e))?;
+            (file, existing_size)
+        } else {
+            let file = File::create(&installer_path)
+                .map_err(|e| format!("Failed to create file: {}", e))?;
+            (file, 0)
+        };
+        let total_size = downloaded + remaining_size;
 
-        let mut downloaded: u64 = 0;
         let mut stream = response.bytes_stream();
 
         // Use tokio stream to read chunks
@@ -2573,6 +4355,7 @@ pub async fn download_everything(app: tauri::AppHandle) -> Result<String, String
             let chunk = item.map_err(|e| format!("Failed to read chunk: {}", e))?;
             file.write_all(&chunk)
                 .map_err(|e| format!("Failed to write chunk: {}", e))?;
+            hasher.update(&chunk);
 
             downloaded += chunk.len() as u64;
 
@@ -2582,6 +4365,16 @@ pub async fn download_everything(app: tauri::AppHandle) -> Result<String, String
                 let _ = window.emit("everything-download-progress", progress);
             }
         }
+        drop(file);
+
+        let digest: String = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        // 下载已结束（成功或校验失败），不再需要续传
+        let _ = fs::remove_file(&validator_path);
+        keep_verified_installer(&installer, &installer_path, downloaded, total_size, &digest)?;
 
         let path_str = installer_path.to_string_lossy().to_string();
         Ok(path_str)
@@ -2592,16 +4385,242 @@ pub async fn download_everything(app: tauri::AppHandle) -> Result<String, String
     }
 }
 
+/// voidtools 安装程序的静默安装参数：安装 Everything 服务（普通用户也能使用索引）并随系统启动
+#[cfg(target_os = "windows")]
+const EVERYTHING_SILENT_INSTALL_ARGS: &[&str] = &[
+    "/S",
+    "-install-options",
+    "-install-service -install-run-on-system-startup",
+];
+
+/// 安装程序退出后等待 Everything 服务就绪的最长时间
+#[cfg(target_os = "windows")]
+const EVERYTHING_INSTALL_READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 运行安装程序并等待其退出，返回退出码
+/// 安装程序清单要求管理员权限时 CreateProcess 返回 ERROR_ELEVATION_REQUIRED，此时改用 runas 触发 UAC
+#[cfg(target_os = "windows")]
+fn run_installer(installer: &Path, args: &[&str], silent: bool) -> Result<u32, AppError> {
+    use std::os::windows::process::CommandExt;
+
+    const ERROR_ELEVATION_REQUIRED: i32 = 740;
+
+    match std::process::Command::new(installer)
+        .args(args)
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW - 不显示控制台窗口
+        .spawn()
+    {
+        Ok(mut child) => {
+            let status = child
+                .wait()
+                .map_err(|e| AppError::Io(format!("等待安装程序退出失败: {}", e)))?;
+            Ok(status.code().unwrap_or(-1) as u32)
+        }
+        Err(e) if e.raw_os_error() == Some(ERROR_ELEVATION_REQUIRED) => {
+            run_installer_elevated(installer, args, silent)
+        }
+        Err(e) => Err(AppError::Io(format!("无法启动安装程序: {}", e))),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn run_installer_elevated(installer: &Path, args: &[&str], silent: bool) -> Result<u32, AppError> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{CloseHandle, GetLastError};
+    use windows_sys::Win32::System::Threading::{GetExitCodeProcess, WaitForSingleObject, INFINITE};
+    use windows_sys::Win32::UI::Shell::{
+        ShellExecuteExW, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW, SHELLEXECUTEINFOW_0,
+    };
+
+    const ERROR_CANCELLED: u32 = 1223;
+
+    let to_wide = |s: &std::ffi::OsStr| -> Vec<u16> { s.encode_wide().chain(Some(0)).collect() };
+    let parameters = args
+        .iter()
+        .map(|arg| {
+            if arg.contains(' ') {
+                format!("\"{}\"", arg)
+            } else {
+                arg.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let verb = to_wide("runas".as_ref());
+    let file = to_wide(installer.as_os_str());
+    let parameters = to_wide(parameters.as_ref());
+
+    let mut exec_info = SHELLEXECUTEINFOW {
+        cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: SEE_MASK_NOCLOSEPROCESS,
+        hwnd: 0,
+        lpVerb: verb.as_ptr(),
+        lpFile: file.as_ptr(),
+        lpParameters: parameters.as_ptr(),
+        lpDirectory: std::ptr::null(),
+        nShow: if silent { 0 } else { 1 }, // SW_HIDE / SW_SHOWNORMAL
+        hInstApp: 0,
+        lpIDList: std::ptr::null_mut(),
+        lpClass: std::ptr::null(),
+        hkeyClass: 0,
+        dwHotKey: 0,
+        Anonymous: SHELLEXECUTEINFOW_0 { hIcon: 0 },
+        hProcess: 0,
+    };
+
+    if unsafe { ShellExecuteExW(&mut exec_info) } == 0 {
+        let error_code = unsafe { GetLastError() };
+        if error_code == ERROR_CANCELLED {
+            return Err(AppError::PermissionDenied(
+                "已取消管理员权限请求，Everything 未安装".to_string(),
+            ));
+        }
+        return Err(AppError::Other(format!(
+            "以管理员身份启动安装程序失败 (错误码 {})",
+            error_code
+        )));
+    }
+    if exec_info.hProcess == 0 {
+        return Err(AppError::Other("未能获取安装程序进程句柄".to_string()));
+    }
+
+    let mut exit_code = 0u32;
+    let ok = unsafe {
+        WaitForSingleObject(exec_info.hProcess, INFINITE);
+        let ok = GetExitCodeProcess(exec_info.hProcess, &mut exit_code);
+        CloseHandle(exec_info.hProcess);
+        ok
+    };
+    if ok == 0 {
+        return Err(AppError::Other("无法获取安装程序退出码".to_string()));
+    }
+    Ok(exit_code)
+}
+
+/// 运行 download_everything 下载的安装程序，等待安装结束并确认 Everything 服务可用
+/// silent 为 true 时使用静默安装参数；过程通过 everything-install-status 事件通知前端
+#[tauri::command]
+pub async fn install_everything(
+    app: tauri::AppHandle,
+    installer_path: String,
+    silent: bool,
+) -> Result<(), AppError> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+
+        let emit_status = |stage: &'static str, message: String| {
+            let _ = app.emit(
+                "everything-install-status",
+                events::EverythingInstallStatus { stage, message },
+            );
+        };
+        let fail = |error: AppError| {
+            emit_status("failed", error.to_string());
+            log_error!("Everything", "安装 Everything 失败: {}", error);
+            error
+        };
+
+        let installer = PathBuf::from(installer_path.trim());
+        if !installer.is_file() {
+            return Err(fail(AppError::NotFound(format!(
+                "安装程序不存在: {}",
+                installer.display()
+            ))));
+        }
+
+        everything_installer_for_arch(host_arch())
+            .and_then(|expected| verify_installer_file(&expected, &installer))
+            .map_err(|e| fail(AppError::Other(format!("安装程序未通过校验: {}", e))))?;
+
+        emit_status("launching", installer.display().to_string());
+        log_info!(
+            "Everything",
+            "运行安装程序: {} (silent={})",
+            installer.display(),
+            silent
+        );
+        let args: &'static [&'static str] = if silent { EVERYTHING_SILENT_INSTALL_ARGS } else { &[] };
+        let installer_for_task = installer.clone();
+        let installing = tokio::task::spawn_blocking(move || {
+            run_installer(&installer_for_task, args, silent)
+        });
+        emit_status("installing", String::new());
+        let exit_code = installing
+            .await
+            .map_err(|e| AppError::Other(format!("安装任务失败: {}", e)))
+            .and_then(|result| result)
+            .map_err(fail)?;
+        if exit_code != 0 {
+            return Err(fail(AppError::Other(format!(
+                "安装程序退出码 {}，安装可能已取消或失败",
+                exit_code
+            ))));
+        }
+
+        // 静默安装注册了服务但不一定启动了 Everything 主程序
+        if !everything_search::windows::is_everything_available() {
+            emit_status("starting", String::new());
+            match everything_search::windows::find_everything_main_exe() {
+                Some(everything_exe) => {
+                    if let Err(e) = std::process::Command::new(&everything_exe)
+                        .creation_flags(0x08000000) // CREATE_NO_WINDOW - 不显示控制台窗口
+                        .spawn()
+                    {
+                        log_warn!("Everything", "安装后启动 Everything 失败: {}", e);
+                    }
+                }
+                None => log_warn!("Everything", "安装结束但未找到 Everything.exe"),
+            }
+        }
+
+        emit_status("waiting", String::new());
+        let deadline = std::time::Instant::now() + EVERYTHING_INSTALL_READY_TIMEOUT;
+        loop {
+            let (available, status) = everything_search::windows::check_everything_status();
+            if available {
+                break;
+            }
+            if std::time::Instant::now() >= deadline {
+                return Err(fail(AppError::EverythingUnavailable(format!(
+                    "{}: 安装已完成，但 Everything 在 {} 秒内未就绪",
+                    status.unwrap_or_else(|| "SERVICE_NOT_RUNNING".to_string()),
+                    EVERYTHING_INSTALL_READY_TIMEOUT.as_secs()
+                ))));
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+
+        emit_status("ready", String::new());
+        log_info!("Everything", "Everything 安装完成并已就绪");
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, installer_path, silent);
+        Err(AppError::PlatformUnsupported(
+            "Everything 仅在 Windows 上可用".to_string(),
+        ))
+    }
+}
+
+/// 解析 .lnk 快捷方式的目标、参数、工作目录和图标位置，供界面显示“指向”和定位目标
+#[tauri::command]
+pub async fn resolve_shortcut(path: String) -> Result<app_search::ShortcutInfo, String> {
+    async_runtime::spawn_blocking(move || app_search::resolve_shortcut(&path))
+        .await
+        .map_err(|e| format!("解析快捷方式任务失败: {}", e))?
+}
+
 #[tauri::command]
 pub fn check_path_exists(path: String, app: tauri::AppHandle) -> Result<Option<file_history::FileHistoryItem>, String> {
-    use std::path::Path;
     use std::time::{SystemTime, UNIX_EPOCH};
 
     let app_data_dir = get_app_data_dir(&app)?;
 
-    // Normalize path: trim whitespace and remove trailing backslashes/slashes
-    let trimmed = path.trim();
-    let trimmed = trimmed.trim_end_matches(|c| c == '\\' || c == '/');
+    // Normalize path: expand %VAR% / ~, trim whitespace and remove trailing backslashes/slashes
+    let expanded = path_utils::expand_path(path.trim());
+    let trimmed = expanded.trim_end_matches(['\\', '/']);
 
     // Normalize path (convert to absolute if relative)
     let path_buf = PathBuf::from(trimmed);
@@ -2615,13 +4634,18 @@ pub fn check_path_exists(path: String, app: tauri::AppHandle) -> Result<Option<f
 
     let normalized_path_str = normalized_path.to_string_lossy().to_string();
 
-    // Check if path exists (file or directory)
-    if !Path::new(&normalized_path_str).exists() {
+    // 用 symlink_metadata 判断存在性，悬空链接也会返回
+    let Ok(link_metadata) = fs::symlink_metadata(&normalized_path) else {
         return Ok(None);
-    }
+    };
+    let link = link_info(&normalized_path, &link_metadata);
 
     // Check if path is a directory
-    let is_folder = normalized_path.is_dir();
+    let is_folder = match fs::metadata(&normalized_path) {
+        Ok(metadata) => metadata.is_dir(),
+        // 悬空链接按链接本身的类型判断
+        Err(_) => is_dir_link(&link_metadata),
+    };
 
     // Get name (file name or directory name)
     let name = normalized_path
@@ -2635,16 +4659,16 @@ pub fn check_path_exists(path: String, app: tauri::AppHandle) -> Result<Option<f
         .map_err(|e| format!("Failed to get timestamp: {}", e))?
         .as_secs();
 
-    // Try to get use_count from history
-    let use_count = {
+    // Try to get use_count and last_action from history
+    let (use_count, last_action) = {
         let mut state = file_history::lock_history()?;
         if state.is_empty() {
             file_history::load_history_into(&mut state, &app_data_dir).ok();
         }
         // Get use_count from history if exists, otherwise use 0
         state.get(&normalized_path_str)
-            .map(|item| item.use_count)
-            .unwrap_or(0)
+            .map(|item| (item.use_count, item.last_action.clone()))
+            .unwrap_or_default()
     };
 
     Ok(Some(file_history::FileHistoryItem {
@@ -2653,9 +4677,42 @@ pub fn check_path_exists(path: String, app: tauri::AppHandle) -> Result<Option<f
         last_used: timestamp,
         use_count,
         is_folder: Some(is_folder),
+        last_action,
+        is_symlink: Some(link.is_some()),
+        target_exists: link.as_ref().map(|(_, exists)| *exists),
+        target: link.map(|(target, _)| target),
     }))
 }
 
+/// 符号链接（Windows 上包括目录联接）的目标路径及目标是否存在，不是链接时返回 None
+fn link_info(path: &Path, link_metadata: &fs::Metadata) -> Option<(String, bool)> {
+    if !link_metadata.file_type().is_symlink() {
+        return None;
+    }
+    let target = fs::read_link(path).ok()?;
+    // 相对链接相对于链接所在目录
+    let target = match path.parent() {
+        Some(parent) if target.is_relative() => parent.join(target),
+        _ => target,
+    };
+    let exists = fs::metadata(path).is_ok();
+    // 联接的目标形如 \\?\C:\...，去掉前缀便于展示
+    Some((display_path(&target.to_string_lossy()), exists))
+}
+
+/// 链接本身是否为目录链接（目标不存在时也能判断）
+fn is_dir_link(link_metadata: &fs::Metadata) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::fs::FileTypeExt;
+        link_metadata.file_type().is_symlink_dir()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        link_metadata.is_dir()
+    }
+}
+
 #[tauri::command]
 pub fn get_clipboard_file_path() -> Result<Option<String>, String> {
     #[cfg(target_os = "windows")]
@@ -2805,43 +4862,327 @@ pub fn get_clipboard_text() -> Result<Option<String>, String> {
     }
 }
 
+/// 写入剪贴板文本；空字符串会清空剪贴板
 #[tauri::command]
-pub fn save_clipboard_image(image_data: Vec<u8>, extension: String) -> Result<String, String> {
-    use std::fs;
-    use std::io::Write;
-    
-    // 获取临时目录
-    let temp_dir = std::env::temp_dir();
-    
-    // 生成唯一的文件名
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-    let filename = format!("pasted_image_{}.{}", timestamp, extension);
-    let file_path = temp_dir.join(&filename);
-    
-    // 写入文件
-    let mut file = fs::File::create(&file_path)
-        .map_err(|e| format!("Failed to create temp file: {}", e))?;
-    
-    file.write_all(&image_data)
-        .map_err(|e| format!("Failed to write image data: {}", e))?;
-    
-    // 返回文件路径
-    file_path.to_str()
-        .ok_or_else(|| "Failed to convert path to string".to_string())
-        .map(|s| s.to_string())
-}
+pub fn set_clipboard_text(text: String) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::ffi::OsStr;
+        use std::os::windows::ffi::OsStrExt;
+        use windows_sys::Win32::Foundation::GlobalFree;
+        use windows_sys::Win32::System::DataExchange::*;
+        use windows_sys::Win32::System::Memory::*;
 
-#[tauri::command]
-pub fn write_debug_log(message: String) -> Result<(), String> {
-    use std::fs::OpenOptions;
-    use std::io::Write;
-    let result = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(r"d:\project\re-fast\.cursor\debug.log")
+        const CF_UNICODETEXT: u32 = 13; // Clipboard format for Unicode text
+
+        unsafe {
+            if OpenClipboard(0) == 0 {
+                return Err("Failed to open clipboard".to_string());
+            }
+
+            let result = (|| -> Result<(), String> {
+                if EmptyClipboard() == 0 {
+                    return Err("Failed to empty clipboard".to_string());
+                }
+                if text.is_empty() {
+                    return Ok(());
+                }
+
+                // UTF-16 + 结尾的 0
+                let wide: Vec<u16> = OsStr::new(&text)
+                    .encode_wide()
+                    .chain(std::iter::once(0))
+                    .collect();
+                let size = wide.len() * std::mem::size_of::<u16>();
+
+                let hmem = GlobalAlloc(GMEM_MOVEABLE, size);
+                if hmem.is_null() {
+                    return Err("Failed to allocate clipboard memory".to_string());
+                }
+
+                let ptr = GlobalLock(hmem);
+                if ptr.is_null() {
+                    GlobalFree(hmem);
+                    return Err("Failed to lock clipboard memory".to_string());
+                }
+                std::ptr::copy_nonoverlapping(wide.as_ptr(), ptr as *mut u16, wide.len());
+                GlobalUnlock(hmem);
+
+                // 成功后内存归剪贴板所有，失败时需要自行释放
+                if SetClipboardData(CF_UNICODETEXT, hmem as isize) == 0 {
+                    GlobalFree(hmem);
+                    return Err("Failed to set clipboard data".to_string());
+                }
+                Ok(())
+            })();
+
+            CloseClipboard();
+            result
+        }
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        #[cfg(target_os = "macos")]
+        let mut command = Command::new("pbcopy");
+        #[cfg(target_os = "linux")]
+        let mut command = {
+            let mut command = Command::new("xclip");
+            command.arg("-selection").arg("clipboard");
+            command
+        };
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(text.as_bytes())
+                .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+        }
+        let status = child
+            .wait()
+            .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("Failed to write clipboard: exit status {}", status))
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        Err("Clipboard text writing is not supported on this platform".to_string())
+    }
+}
+
+/// 路径的展示形式：去掉 canonicalize 产生的 \\?\ 前缀，Windows 上统一使用反斜杠
+fn display_path(path: &str) -> String {
+    let path = path.trim();
+    let path = if let Some(unc) = path.strip_prefix("\\\\?\\UNC\\") {
+        format!("\\\\{}", unc)
+    } else {
+        path.strip_prefix("\\\\?\\").unwrap_or(path).to_string()
+    };
+    if cfg!(target_os = "windows") {
+        path.replace('/', "\\")
+    } else {
+        path
+    }
+}
+
+/// 复制文件路径到剪贴板（搜索结果右键菜单使用）
+#[tauri::command]
+pub fn copy_path_to_clipboard(path: String) -> Result<(), String> {
+    let path = display_path(&path);
+    if path.is_empty() {
+        return Err("路径不能为空".to_string());
+    }
+    set_clipboard_text(path)
+}
+
+/// 复制多个路径到剪贴板，每行一个；空路径被忽略
+#[tauri::command]
+pub fn copy_paths_to_clipboard(paths: Vec<String>) -> Result<(), String> {
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|path| display_path(path))
+        .filter(|path| !path.is_empty())
+        .collect();
+    if paths.is_empty() {
+        return Err("路径不能为空".to_string());
+    }
+    let separator = if cfg!(target_os = "windows") { "\r\n" } else { "\n" };
+    set_clipboard_text(paths.join(separator))
+}
+
+/// 将剪贴板中的 DIB（BITMAPINFOHEADER + 调色板/掩码 + 像素）补上 BMP 文件头后转换为 PNG
+fn dib_to_png(dib: &[u8]) -> Result<Vec<u8>, String> {
+    const BI_BITFIELDS: u32 = 3;
+    const BI_ALPHABITFIELDS: u32 = 6;
+
+    let read_u16 = |offset: usize| -> Option<u16> {
+        dib.get(offset..offset + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    };
+    let read_u32 = |offset: usize| -> Option<u32> {
+        dib.get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    let invalid = || "Invalid clipboard bitmap data".to_string();
+
+    let header_size = read_u32(0).ok_or_else(invalid)?;
+    let (bit_count, masks_size, palette_entries, entry_size) = if header_size == 12 {
+        // BITMAPCOREHEADER
+        let bit_count = read_u16(10).ok_or_else(invalid)?;
+        let entries = if bit_count <= 8 { 1u32 << bit_count } else { 0 };
+        (bit_count, 0, entries, 3)
+    } else if header_size >= 40 {
+        let bit_count = read_u16(14).ok_or_else(invalid)?;
+        let compression = read_u32(16).ok_or_else(invalid)?;
+        let clr_used = read_u32(32).ok_or_else(invalid)?;
+        // 仅 BITMAPINFOHEADER 的颜色掩码跟在头之后，V4/V5 头内已包含掩码
+        let masks_size = match compression {
+            BI_BITFIELDS if header_size == 40 => 12,
+            BI_ALPHABITFIELDS if header_size == 40 => 16,
+            _ => 0,
+        };
+        let entries = if clr_used > 0 {
+            clr_used
+        } else if bit_count <= 8 {
+            1u32 << bit_count
+        } else {
+            0
+        };
+        (bit_count, masks_size, entries, 4)
+    } else {
+        return Err(invalid());
+    };
+    if bit_count == 0 {
+        return Err(invalid());
+    }
+
+    let pixel_offset = 14 + header_size + masks_size + palette_entries * entry_size;
+    let mut bmp = Vec::with_capacity(14 + dib.len());
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&((14 + dib.len()) as u32).to_le_bytes());
+    bmp.extend_from_slice(&0u32.to_le_bytes());
+    bmp.extend_from_slice(&pixel_offset.to_le_bytes());
+    bmp.extend_from_slice(dib);
+
+    let img = image::load_from_memory_with_format(&bmp, image::ImageFormat::Bmp)
+        .map_err(|e| format!("Failed to decode clipboard bitmap: {}", e))?;
+    let mut png_data = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut png_data, image::ImageOutputFormat::Png)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(png_data.into_inner())
+}
+
+/// 读取剪贴板图片并转换为 PNG，剪贴板中没有图片时返回 None
+fn read_clipboard_png() -> Result<Option<Vec<u8>>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::System::DataExchange::*;
+        use windows_sys::Win32::System::Memory::*;
+
+        // CF_BITMAP 放入剪贴板时系统会自动合成 CF_DIB
+        const CF_DIB: u32 = 8;
+
+        unsafe {
+            if IsClipboardFormatAvailable(CF_DIB) == 0 {
+                return Ok(None);
+            }
+
+            if OpenClipboard(0) == 0 {
+                return Err("Failed to open clipboard".to_string());
+            }
+
+            let result = (|| -> Result<Option<Vec<u8>>, String> {
+                let hmem = GetClipboardData(CF_DIB) as isize;
+                if hmem == 0 {
+                    return Ok(None);
+                }
+
+                let ptr = GlobalLock(hmem as *mut _);
+                if ptr.is_null() {
+                    return Ok(None);
+                }
+                let size = GlobalSize(hmem as *mut _);
+                let dib = std::slice::from_raw_parts(ptr as *const u8, size).to_vec();
+                GlobalUnlock(hmem as *mut _);
+
+                dib_to_png(&dib).map(Some)
+            })();
+
+            CloseClipboard();
+            result
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("Clipboard image reading is not supported on this platform".to_string())
+    }
+}
+
+/// 获取剪贴板图片，返回 PNG 的 base64 data URL
+#[tauri::command]
+pub fn get_clipboard_image() -> Result<Option<String>, String> {
+    Ok(read_clipboard_png()?
+        .map(|png| format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(png))))
+}
+
+/// 将剪贴板图片保存为 PNG 文件，返回保存路径
+#[tauri::command]
+pub fn save_clipboard_image_to_file(dest: String) -> Result<String, String> {
+    let png = read_clipboard_png()?.ok_or_else(|| "剪贴板中没有图片".to_string())?;
+    let dest_path = PathBuf::from(&dest);
+    if let Some(parent) = dest_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    fs::write(&dest_path, png).map_err(|e| format!("Failed to write image: {}", e))?;
+    Ok(dest)
+}
+
+/// 识别剪贴板图片中的文字，lang 为语言标识（如 "zh-Hans"、"en-US"；外部命令使用 tesseract 的 "chi_sim" 等）
+#[tauri::command]
+pub async fn ocr_clipboard_image(
+    app: tauri::AppHandle,
+    lang: Option<String>,
+) -> Result<String, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let settings = settings::load_settings(&app_data_dir)?;
+    // 先检查后端，未配置时不必读取剪贴板
+    ocr::select_backend(&settings.ocr)?;
+    let png = read_clipboard_png()?.ok_or_else(|| "剪贴板中没有图片".to_string())?;
+    async_runtime::spawn_blocking(move || {
+        ocr::recognize_png(&settings.ocr, &png, lang.as_deref())
+    })
+    .await
+    .map_err(|e| format!("文字识别任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub fn save_clipboard_image(image_data: Vec<u8>, extension: String) -> Result<String, String> {
+    use std::fs;
+    use std::io::Write;
+    
+    // 获取临时目录
+    let temp_dir = std::env::temp_dir();
+    
+    // 生成唯一的文件名
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let filename = format!("pasted_image_{}.{}", timestamp, extension);
+    let file_path = temp_dir.join(&filename);
+    
+    // 写入文件
+    let mut file = fs::File::create(&file_path)
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    
+    file.write_all(&image_data)
+        .map_err(|e| format!("Failed to write image data: {}", e))?;
+    
+    // 返回文件路径
+    file_path.to_str()
+        .ok_or_else(|| "Failed to convert path to string".to_string())
+        .map(|s| s.to_string())
+}
+
+#[tauri::command]
+pub fn write_debug_log(message: String) -> Result<(), String> {
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(r"d:\project\re-fast\.cursor\debug.log")
         .and_then(|mut f| {
             writeln!(f, r#"{{"timestamp":{},"location":"frontend","message":"{}","sessionId":"debug-session","runId":"run1"}}"#, 
                 std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis(),
@@ -3149,16 +5490,105 @@ pub fn copy_file_to_downloads(source_path: String) -> Result<String, String> {
 }
 
 #[tauri::command]
-pub fn launch_file(path: String, app: tauri::AppHandle) -> Result<(), String> {
+pub fn launch_file(path: String, app: tauri::AppHandle) -> Result<(), AppError> {
+    // 快捷方式等可以保存 %USERPROFILE%\Projects 这样的路径，历史中记录展开后的真实路径
+    let path = path_utils::expand_path(&path);
     // Add to history when launched
     let app_data_dir = get_app_data_dir(&app)?;
     file_history::load_history(&app_data_dir).ok(); // Ignore errors
-    file_history::add_file_path(path.clone(), &app_data_dir).ok(); // Ignore errors
+    file_history::add_file_path_with_action(path.clone(), file_history::ACTION_OPEN, &app_data_dir)
+        .ok(); // Ignore errors
 
     // Launch the file
     file_history::launch_file(&path)
 }
 
+/// 使用指定应用打开文件（打开方式），同样记录到文件历史
+#[tauri::command(rename_all = "camelCase")]
+pub fn launch_file_with(
+    path: String,
+    app_path: String,
+    app: tauri::AppHandle,
+) -> Result<(), AppError> {
+    let path = path_utils::expand_path(&path);
+    let app_data_dir = get_app_data_dir(&app)?;
+    file_history::load_history(&app_data_dir).ok(); // Ignore errors
+    file_history::add_file_path_with_action(path.clone(), file_history::ACTION_OPEN, &app_data_dir)
+        .ok(); // Ignore errors
+
+    file_history::launch_file_with(&path, &app_path)
+}
+
+/// 弹出系统“打开方式”对话框，由用户选择打开文件的程序
+///
+/// Windows 使用 shell32 的 `SHOpenWithDialog`（OPENASINFO + OAIF_EXEC，选择后立即打开），
+/// 失败时退回 `rundll32 shell32.dll,OpenAs_RunDLL`；对话框是模态的，因此在后台线程中等待关闭
+#[tauri::command]
+pub async fn open_with_dialog(path: String) -> Result<(), AppError> {
+    let trimmed = path.trim().to_string();
+    let target = PathBuf::from(&trimmed);
+    if !target.exists() {
+        return Err(AppError::NotFound(format!("文件不存在: {}", trimmed)));
+    }
+    if target.is_dir() {
+        return Err(AppError::Other(format!("文件夹不支持打开方式: {}", trimmed)));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        async_runtime::spawn_blocking(move || -> Result<(), AppError> {
+            use std::ffi::OsStr;
+            use std::os::windows::ffi::OsStrExt;
+            use windows_sys::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
+            use windows_sys::Win32::UI::Shell::{
+                SHOpenWithDialog, OAIF_ALLOW_REGISTRATION, OAIF_EXEC, OAIF_REGISTER_EXT, OPENASINFO,
+            };
+
+            // HRESULT_FROM_WIN32(ERROR_CANCELLED)：用户关闭了对话框
+            const HRESULT_CANCELLED: i32 = 0x800704C7u32 as i32;
+
+            let wide: Vec<u16> = OsStr::new(&trimmed)
+                .encode_wide()
+                .chain(std::iter::once(0))
+                .collect();
+            let info = OPENASINFO {
+                pcszFile: wide.as_ptr(),
+                pcszClass: std::ptr::null(),
+                oaifInFlags: OAIF_ALLOW_REGISTRATION | OAIF_REGISTER_EXT | OAIF_EXEC,
+            };
+
+            let hr = unsafe {
+                let init = CoInitializeEx(std::ptr::null_mut(), COINIT_APARTMENTTHREADED as u32);
+                let hr = SHOpenWithDialog(0, &info);
+                if init >= 0 {
+                    CoUninitialize();
+                }
+                hr
+            };
+
+            if hr >= 0 || hr == HRESULT_CANCELLED {
+                return Ok(());
+            }
+
+            std::process::Command::new("rundll32.exe")
+                .arg("shell32.dll,OpenAs_RunDLL")
+                .arg(&trimmed)
+                .spawn()
+                .map_err(|e| AppError::Other(format!("打开“打开方式”对话框失败: {}", e)))?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| AppError::Other(format!("打开方式任务失败: {}", e)))?
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(AppError::PlatformUnsupported(
+            "“打开方式”对话框仅在 Windows 上可用，请使用 launch_file_with 指定程序".to_string(),
+        ))
+    }
+}
+
 #[tauri::command]
 pub fn get_all_shortcuts(app: tauri::AppHandle) -> Result<Vec<shortcuts::ShortcutItem>, String> {
     let app_data_dir = get_app_data_dir(&app)?;
@@ -3254,149 +5684,146 @@ pub fn open_url(url: String) -> Result<(), String> {
     }
 }
 
+/// 在文件夹中定位，并以 "reveal" 记录到文件历史
 #[tauri::command]
-pub fn reveal_in_folder(path: String) -> Result<(), String> {
-    use std::path::PathBuf;
-    use std::process::Command;
+pub fn reveal_in_folder(path: String, app: tauri::AppHandle) -> Result<(), String> {
+    if let Ok(app_data_dir) = get_app_data_dir(&app) {
+        file_history::load_history(&app_data_dir).ok(); // Ignore errors
+        file_history::add_file_path_with_action(
+            path.clone(),
+            file_history::ACTION_REVEAL,
+            &app_data_dir,
+        )
+        .ok(); // Ignore errors
+    }
+    reveal_path_in_folder(path)
+}
 
-    // Normalize path
-    let trimmed = path.trim();
-    let trimmed = trimmed.trim_end_matches(|c| c == '\\' || c == '/');
-    let path_buf = PathBuf::from(trimmed);
+fn reveal_path_in_folder(path: String) -> Result<(), String> {
+    reveal_paths_in_folder(&[path])
+}
 
-    // Get the absolute path (even if file doesn't exist, we can still open parent folder)
-    let absolute_path = if path_buf.is_absolute() {
-        path_buf.clone()
+/// 定位用的路径：去掉末尾分隔符和 \\?\ 前缀（UNC 路径还原为 \\server\share\...），相对路径基于当前目录
+fn reveal_target(path: &str) -> Result<PathBuf, String> {
+    let normalized = display_path(&path_utils::expand_path(path.trim()));
+    let trimmed = normalized.trim_end_matches(['\\', '/']);
+    // "C:\" 去掉分隔符后变成 "C:"，会被当作驱动器当前目录
+    let trimmed = if trimmed.ends_with(':') {
+        normalized.as_str()
     } else {
-        std::env::current_dir()
-            .map_err(|e| format!("Failed to get current directory: {}", e))?
-            .join(&path_buf)
+        trimmed
     };
-
-    #[cfg(target_os = "windows")]
-    {
-        // Check if the path is in the Recycle Bin
-        let path_str_lower = trimmed.to_lowercase();
-        let is_recycle_bin = path_str_lower.contains("$recycle.bin");
-        
-        if is_recycle_bin {
-            // For Recycle Bin files, open the Recycle Bin folder directly
-            // Use shell:RecycleBinFolder to open the Recycle Bin
-            Command::new("explorer")
-                .arg("shell:RecycleBinFolder")
-                .spawn()
-                .map_err(|e| format!("Failed to open Recycle Bin: {}", e))?;
-            return Ok(());
-        }
-
-        // Get parent directory from the path string itself (more reliable)
-        // This works even if the file doesn't exist
-        let parent_dir = if absolute_path.exists() {
-            // If path exists, get canonical parent
-            let canonical_path = absolute_path
-                .canonicalize()
-                .map_err(|e| format!("Failed to canonicalize path: {}", e))?;
-            canonical_path
-                .parent()
-                .ok_or_else(|| "File has no parent directory".to_string())?
-                .to_path_buf()
-        } else {
-            // If path doesn't exist, construct parent from path components
-            absolute_path
-                .parent()
-                .ok_or_else(|| "File has no parent directory".to_string())?
-                .to_path_buf()
+    let path = PathBuf::from(trimmed);
+    if path.is_absolute() {
+        return Ok(path);
+    }
+    Ok(std::env::current_dir()
+        .map_err(|e| format!("Failed to get current directory: {}", e))?
+        .join(path))
+}
+
+/// 在文件管理器中定位路径，所有定位操作都经过这里：
+/// 存在的文件/目录交给 opener 插件在所在目录中选中（Windows 为 SHOpenFolderAndSelectItems，
+/// 同一目录下的多个路径在一个窗口中选中，UNC 路径同样适用）；
+/// 不存在的路径打开其父目录；回收站中的文件打开回收站
+fn reveal_paths_in_folder(paths: &[String]) -> Result<(), String> {
+    let mut existing = Vec::new();
+    let mut folders: Vec<PathBuf> = Vec::new();
+    let mut errors = Vec::new();
+    let mut open_recycle_bin = false;
+
+    for raw in paths.iter().filter(|p| !p.trim().is_empty()) {
+        if cfg!(target_os = "windows") && raw.to_lowercase().contains("$recycle.bin") {
+            open_recycle_bin = true;
+            continue;
+        }
+        let path = match reveal_target(raw) {
+            Ok(path) => path,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
         };
-
-        // Try to canonicalize parent directory to ensure it exists
-        let parent_dir = if parent_dir.exists() {
-            parent_dir
-                .canonicalize()
-                .map_err(|e| format!("Failed to canonicalize parent directory: {}", e))?
+        if path.exists() {
+            existing.push(path);
         } else {
-            // If parent doesn't exist, return error
-            return Err(format!("Parent directory does not exist: {}", parent_dir.display()));
-        };
-
-        // Convert parent directory to string and normalize
-        let mut parent_str = parent_dir.to_string_lossy().to_string();
-        if parent_str.starts_with("\\\\?\\") {
-            parent_str = parent_str[4..].to_string();
+            match path.parent().filter(|parent| parent.is_dir()) {
+                Some(parent) => folders.push(parent.to_path_buf()),
+                None => errors.push(format!(
+                    "Parent directory does not exist: {}",
+                    path.display()
+                )),
+            }
         }
-        parent_str = parent_str.replace("/", "\\");
+    }
 
-        // If file exists and is a file, use explorer /select to open folder and select file
-        // Otherwise, just open the parent folder
-        if absolute_path.exists() && absolute_path.is_file() {
-            let mut file_path = if let Ok(canonical) = absolute_path.canonicalize() {
-                canonical
-            } else {
-                absolute_path
-            };
-            
-            let mut path_str = file_path.to_string_lossy().to_string();
-            if path_str.starts_with("\\\\?\\") {
-                path_str = path_str[4..].to_string();
-            }
-            path_str = path_str.replace("/", "\\");
-            
-            // Escape quotes in path
-            let escaped_path = path_str.replace("\"", "\"\"");
-            let explorer_arg = format!("/select,\"{}\"", escaped_path);
-            
-            // Use explorer /select to open folder and select file
-            Command::new("explorer")
-                .arg(&explorer_arg)
-                .spawn()
-                .map_err(|e| format!("Failed to execute explorer command: {}", e))?;
-        } else {
-            // File doesn't exist or is a directory, just open the parent folder
-            Command::new("explorer")
-                .arg(&parent_str)
-                .spawn()
-                .map_err(|e| format!("Failed to open folder: {}", e))?;
+    if open_recycle_bin {
+        if let Err(e) = tauri_plugin_opener::open_url("shell:RecycleBinFolder", None::<&str>) {
+            errors.push(format!("Failed to open Recycle Bin: {}", e));
         }
     }
 
-    #[cfg(target_os = "macos")]
-    {
-        // On macOS, use open with -R flag to reveal in Finder
-        Command::new("open")
-            .args(&["-R", trimmed])
-            .spawn()
-            .map_err(|e| format!("Failed to reveal in folder: {}", e))?;
+    if !existing.is_empty() {
+        if let Err(e) = tauri_plugin_opener::reveal_items_in_dir(&existing) {
+            log_warn!("Reveal", "定位失败，改为打开所在目录: {}", e);
+            // 如共享根目录 \\server\share 没有父目录，直接打开它本身
+            folders.extend(existing.iter().map(|path| match path.parent() {
+                Some(parent) if !path.is_dir() => parent.to_path_buf(),
+                _ => path.clone(),
+            }));
+        }
     }
 
-    #[cfg(target_os = "linux")]
-    {
-        // On Linux, try to open the parent directory
-        if let Some(parent) = path_buf.parent() {
-            Command::new("xdg-open")
-                .arg(parent)
-                .spawn()
-                .map_err(|e| format!("Failed to reveal in folder: {}", e))?;
-        } else {
-            return Err("No parent directory found".to_string());
+    folders.sort();
+    folders.dedup();
+    for folder in folders {
+        if let Err(e) = tauri_plugin_opener::open_path(&folder, None::<&str>) {
+            errors.push(format!("Failed to open folder {}: {}", folder.display(), e));
         }
     }
 
-    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-    {
-        return Err("Reveal in folder is not supported on this platform".to_string());
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
     }
+}
 
-    Ok(())
+/// 一次定位多个路径：同一目录下的文件在一个窗口中同时选中
+#[tauri::command]
+pub fn reveal_in_folder_many(paths: Vec<String>) -> Result<(), String> {
+    reveal_paths_in_folder(&paths)
+}
+
+/// 在终端中打开目录；传入文件时使用其所在目录
+#[tauri::command]
+pub fn open_terminal_here(path: String, app: tauri::AppHandle) -> Result<(), String> {
+    let target = reveal_target(&path)?;
+    let metadata = fs::metadata(&target)
+        .map_err(|_| format!("路径不存在: {}", target.display()))?;
+    let dir = if metadata.is_dir() {
+        target
+    } else {
+        target
+            .parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| format!("无法确定所在目录: {}", target.display()))?
+    };
+
+    let app_data_dir = get_app_data_dir(&app)?;
+    let settings = settings::load_settings(&app_data_dir)?;
+    process_utils::open_terminal(&dir, &settings.terminal)
 }
 
 #[tauri::command]
 pub async fn show_shortcuts_config(app: tauri::AppHandle) -> Result<(), String> {
     use tauri::Manager;
 
-    println!("[后端] show_shortcuts_config: START");
+    log_debug!("Window", "show_shortcuts_config: START");
 
     // 1. 尝试获取现有窗口
     if let Some(window) = app.get_webview_window("shortcuts-config") {
-        println!("[后端] show_shortcuts_config: 窗口已存在，执行显示操作");
+        log_debug!("Window", "show_shortcuts_config: 窗口已存在，执行显示操作");
         window.show().map_err(|e| e.to_string())?;
         window.set_focus().map_err(|e| e.to_string())?;
         // 设置窗口始终在最前面，确保在主程序窗口前面
@@ -3408,18 +5835,19 @@ pub async fn show_shortcuts_config(app: tauri::AppHandle) -> Result<(), String>
             tokio::time::sleep(std::time::Duration::from_millis(150)).await;
             match window_clone.emit("shortcuts-config:refresh", ()) {
                 Ok(_) => {
-                    println!("[后端] show_shortcuts_config: Refresh event emitted successfully");
+                    log_debug!("Window", "show_shortcuts_config: Refresh event emitted successfully");
                 }
                 Err(e) => {
-                    println!(
-                        "[后端] show_shortcuts_config: ERROR emitting refresh event: {}",
+                    log_error!(
+                        "Window",
+                        "show_shortcuts_config: ERROR emitting refresh event: {}",
                         e
                     );
                 }
             }
         });
     } else {
-        println!("[后端] show_shortcuts_config: 窗口不存在，开始动态创建");
+        log_debug!("Window", "show_shortcuts_config: 窗口不存在，开始动态创建");
 
         // 2. 动态创建窗口
         // 注意：这里 URL 设为 index.html，React 会根据 window label 路由到正确的组件
@@ -3435,8 +5863,9 @@ pub async fn show_shortcuts_config(app: tauri::AppHandle) -> Result<(), String>
         .center()
         .build()
         .map_err(|e| format!("创建窗口失败: {}", e))?;
+        attach_window_geometry(&app, &window);
 
-        println!("[后端] show_shortcuts_config: 窗口创建成功");
+        log_debug!("Window", "show_shortcuts_config: 窗口创建成功");
 
         // 新窗口创建后，前端组件挂载会自动 loadData，不需要 emit refresh
         // 但为了保险，可以保留 emit，前端防抖即可
@@ -3445,11 +5874,12 @@ pub async fn show_shortcuts_config(app: tauri::AppHandle) -> Result<(), String>
             tokio::time::sleep(std::time::Duration::from_millis(200)).await;
             match window_clone.emit("shortcuts-config:refresh", ()) {
                 Ok(_) => {
-                    println!("[后端] show_shortcuts_config: Refresh event emitted for new window");
+                    log_debug!("Window", "show_shortcuts_config: Refresh event emitted for new window");
                 }
                 Err(e) => {
-                    println!(
-                        "[后端] show_shortcuts_config: ERROR emitting refresh event: {}",
+                    log_error!(
+                        "Window",
+                        "show_shortcuts_config: ERROR emitting refresh event: {}",
                         e
                     );
                 }
@@ -3457,7 +5887,8 @@ pub async fn show_shortcuts_config(app: tauri::AppHandle) -> Result<(), String>
         });
     }
 
-    println!("[后端] show_shortcuts_config: END");
+    log_debug!("Window", "show_shortcuts_config: END");
+    remember_tool_window("shortcuts-config");
     Ok(())
 }
 
@@ -3483,8 +5914,20 @@ pub fn get_open_history(app: tauri::AppHandle) -> Result<std::collections::HashM
 }
 
 #[tauri::command]
-pub fn record_plugin_usage(
-    plugin_id: String,
+pub fn clear_open_history(app: tauri::AppHandle) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    open_history::clear_history(&app_data_dir)
+}
+
+#[tauri::command]
+pub fn forget_open_history(key: String, app: tauri::AppHandle) -> Result<bool, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    open_history::forget_key(&key, &app_data_dir)
+}
+
+#[tauri::command]
+pub fn record_plugin_usage(
+    plugin_id: String,
     name: Option<String>,
     app: tauri::AppHandle,
 ) -> Result<plugin_usage::PluginUsage, String> {
@@ -3493,9 +5936,98 @@ pub fn record_plugin_usage(
 }
 
 #[tauri::command]
-pub fn get_plugin_usage(app: tauri::AppHandle) -> Result<Vec<plugin_usage::PluginUsage>, String> {
+pub fn get_plugin_usage(
+    half_life_secs: Option<u64>,
+    app: tauri::AppHandle,
+) -> Result<Vec<plugin_usage::PluginUsage>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    plugin_usage::list_plugin_usage(&app_data_dir, half_life_secs)
+}
+
+#[tauri::command]
+pub fn clear_plugin_usage(
+    plugin_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<usize, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    plugin_usage::clear_plugin_usage(plugin_id, &app_data_dir)
+}
+
+/// 保存一条计算器历史
+#[tauri::command]
+pub fn add_calc_entry(
+    expression: String,
+    result: String,
+    app: tauri::AppHandle,
+) -> Result<calc_history::CalcEntry, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    calc_history::add_entry(&app_data_dir, &expression, &result)
+}
+
+/// 获取计算器历史，最新的在前
+#[tauri::command]
+pub fn get_calc_history(
+    limit: Option<usize>,
+    app: tauri::AppHandle,
+) -> Result<Vec<calc_history::CalcEntry>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    calc_history::list_entries(&app_data_dir, limit)
+}
+
+#[tauri::command]
+pub fn clear_calc_history(app: tauri::AppHandle) -> Result<usize, String> {
     let app_data_dir = get_app_data_dir(&app)?;
-    plugin_usage::list_plugin_usage(&app_data_dir)
+    calc_history::clear_entries(&app_data_dir)
+}
+
+/// 为新建的工具窗口恢复上次的位置和大小，并在关闭时保存
+fn attach_window_geometry(app: &tauri::AppHandle, window: &tauri::WebviewWindow) {
+    let Ok(app_data_dir) = get_app_data_dir(app) else {
+        return;
+    };
+    window_config::restore_window_geometry(window, &app_data_dir);
+    window_config::remember_window_geometry(window, &app_data_dir);
+}
+
+// 最近显示的工具窗口，供 reopen_last_window 重新打开
+#[derive(Default)]
+struct ToolWindowHistory {
+    last_shown: Option<&'static str>,
+}
+
+static TOOL_WINDOW_HISTORY: LazyLock<Mutex<ToolWindowHistory>> =
+    LazyLock::new(|| Mutex::new(ToolWindowHistory::default()));
+
+/// 由各 show_* 窗口命令在显示成功后调用
+fn remember_tool_window(label: &'static str) {
+    if let Ok(mut history) = TOOL_WINDOW_HISTORY.lock() {
+        history.last_shown = Some(label);
+    }
+}
+
+/// 重新打开最近显示过的工具窗口（如刚关闭的备忘录、JSON 格式化窗口），返回窗口 label
+#[tauri::command]
+pub async fn reopen_last_window(app: tauri::AppHandle) -> Result<String, String> {
+    let label = TOOL_WINDOW_HISTORY
+        .lock()
+        .map_err(|e| format!("读取窗口记录失败: {}", e))?
+        .last_shown
+        .ok_or_else(|| "还没有打开过工具窗口".to_string())?;
+
+    match label {
+        "shortcuts-config" => show_shortcuts_config(app).await?,
+        "memo-window" => show_memo_window(app).await?,
+        "plugin-list-window" => show_plugin_list_window(app).await?,
+        "json-formatter-window" => show_json_formatter_window(app).await?,
+        "translation-window" => show_translation_window(app).await?,
+        "file-toolbox-window" => show_file_toolbox_window(app).await?,
+        "calculator-pad-window" => show_calculator_pad_window(app).await?,
+        "everything-search-window" => show_everything_search_window(app).await?,
+        "settings" => show_settings_window(app).await?,
+        "hotkey-settings" => show_hotkey_settings(app).await?,
+        other => return Err(format!("未知的工具窗口: {}", other)),
+    }
+    Ok(label.to_string())
 }
 
 #[tauri::command]
@@ -3520,8 +6052,10 @@ pub async fn show_memo_window(app: tauri::AppHandle) -> Result<(), String> {
         .center()
         .build()
         .map_err(|e| format!("创建备忘录窗口失败: {}", e))?;
+        attach_window_geometry(&app, &window);
     }
 
+    remember_tool_window("memo-window");
     Ok(())
 }
 
@@ -3547,8 +6081,10 @@ pub async fn show_plugin_list_window(app: tauri::AppHandle) -> Result<(), String
         .center()
         .build()
         .map_err(|e| format!("创建应用中心窗口失败: {}", e))?;
+        attach_window_geometry(&app, &window);
     }
 
+    remember_tool_window("plugin-list-window");
     Ok(())
 }
 
@@ -3574,11 +6110,64 @@ pub async fn show_json_formatter_window(app: tauri::AppHandle) -> Result<(), Str
         .center()
         .build()
         .map_err(|e| format!("创建 JSON 格式化窗口失败: {}", e))?;
+        attach_window_geometry(&app, &window);
     }
 
+    remember_tool_window("json-formatter-window");
     Ok(())
 }
 
+/// 格式化 JSON：indent 为缩进空格数（0 表示压缩），sort_keys 为 true 时递归按键名排序；
+/// 解析失败时返回 valid=false 及错误所在的行列
+#[tauri::command]
+pub async fn format_json(
+    input: String,
+    indent: usize,
+    sort_keys: bool,
+) -> Result<json_tools::JsonFormatResult, String> {
+    async_runtime::spawn_blocking(move || json_tools::format_json(&input, indent, sort_keys))
+        .await
+        .map_err(|e| format!("格式化 JSON 任务失败: {}", e))?
+}
+
+/// 校验 JSON 语法，无效时返回错误所在的行列
+#[tauri::command]
+pub async fn validate_json(input: String) -> Result<json_tools::JsonValidationResult, String> {
+    async_runtime::spawn_blocking(move || json_tools::validate_json(&input))
+        .await
+        .map_err(|e| format!("校验 JSON 任务失败: {}", e))?
+}
+
+/// 将 JSON 转换为 yaml / toml / csv（csv 仅支持扁平对象数组）
+#[tauri::command]
+pub fn convert_json(input: String, to: String) -> Result<String, String> {
+    let target = json_tools::ConvertFormat::parse(&to)
+        .ok_or_else(|| format!("不支持的目标格式: {}（可选 yaml、toml、csv）", to))?;
+    json_tools::convert_json(&input, target)
+}
+
+/// 用 JSONPath 子集（如 "$.users[0].name"、"$.items[*].id"）查询 JSON，返回格式化后的结果
+#[tauri::command]
+pub async fn query_json(input: String, path: String) -> Result<String, String> {
+    async_runtime::spawn_blocking(move || json_path::query_json(&input, &path))
+        .await
+        .map_err(|e| format!("查询 JSON 任务失败: {}", e))?
+}
+
+/// 生成二维码，返回 PNG 的 base64 data URL
+#[tauri::command]
+pub async fn generate_qr_code(text: String, size: u32) -> Result<String, String> {
+    async_runtime::spawn_blocking(move || qr_code::generate_qr_code(&text, size))
+        .await
+        .map_err(|e| format!("生成二维码任务失败: {}", e))?
+}
+
+/// 颜色格式转换，返回 hex / rgb / hsl 表示及建议的文字颜色
+#[tauri::command]
+pub fn convert_color(input: String) -> Result<color_convert::ColorConversion, String> {
+    color_convert::convert_color(&input)
+}
+
 #[tauri::command]
 pub async fn show_translation_window(app: tauri::AppHandle) -> Result<(), String> {
     use tauri::Manager;
@@ -3601,11 +6190,37 @@ pub async fn show_translation_window(app: tauri::AppHandle) -> Result<(), String
         .center()
         .build()
         .map_err(|e| format!("创建翻译窗口失败: {}", e))?;
+        attach_window_geometry(&app, &window);
     }
 
+    remember_tool_window("translation-window");
     Ok(())
 }
 
+/// 通过设置中配置的翻译服务翻译文本；from 为空或 "auto" 时自动识别源语言
+#[tauri::command]
+pub async fn translate_text(
+    app: tauri::AppHandle,
+    text: String,
+    from: String,
+    to: String,
+) -> Result<translation::TranslationResult, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let settings = settings::load_settings(&app_data_dir)?;
+    translation::translate(&settings.translation, &text, &from, &to)
+        .await
+        .inspect_err(|e| log_warn!("Translation", "翻译失败: {}", e))
+}
+
+/// 保存翻译服务的 API Key；只写不读，get_settings 不会把它返回给前端
+#[tauri::command]
+pub fn set_translation_api_key(app: tauri::AppHandle, api_key: String) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let mut settings = settings::load_settings(&app_data_dir)?;
+    settings.translation.api_key = api_key.trim().to_string();
+    settings::save_settings(&app_data_dir, &settings)
+}
+
 #[tauri::command]
 pub async fn show_file_toolbox_window(app: tauri::AppHandle) -> Result<(), String> {
     use tauri::Manager;
@@ -3628,8 +6243,10 @@ pub async fn show_file_toolbox_window(app: tauri::AppHandle) -> Result<(), Strin
         .center()
         .build()
         .map_err(|e| format!("创建文件工具箱窗口失败: {}", e))?;
+        attach_window_geometry(&app, &window);
     }
 
+    remember_tool_window("file-toolbox-window");
     Ok(())
 }
 
@@ -3655,8 +6272,10 @@ pub async fn show_calculator_pad_window(app: tauri::AppHandle) -> Result<(), Str
         .center()
         .build()
         .map_err(|e| format!("创建计算稿纸窗口失败: {}", e))?;
+        attach_window_geometry(&app, &window);
     }
 
+    remember_tool_window("calculator-pad-window");
     Ok(())
 }
 
@@ -3682,8 +6301,10 @@ pub async fn show_everything_search_window(app: tauri::AppHandle) -> Result<(),
         .center()
         .build()
         .map_err(|e| format!("创建 Everything 搜索窗口失败: {}", e))?;
+        attach_window_geometry(&app, &window);
     }
 
+    remember_tool_window("everything-search-window");
     Ok(())
 }
 
@@ -3698,6 +6319,143 @@ pub struct FileReplaceParams {
     case_sensitive: bool,
     backup_folder: bool,
     replace_file_name: bool,
+    /// 只处理匹配任一模式的文件（相对于目标文件夹），为空时不限制
+    #[serde(default)]
+    include_globs: Vec<String>,
+    /// 跳过匹配任一模式的文件和文件夹，命中的文件夹不再递归
+    #[serde(default)]
+    exclude_globs: Vec<String>,
+    /// 按字节查找替换（不要求 UTF-8），search/replace 以 `hex:` 开头时按十六进制解析
+    #[serde(default)]
+    binary_mode: bool,
+}
+
+/// 构建查找替换使用的正则；非正则模式下按字面量转义
+fn build_replace_pattern(search: &str, use_regex: bool, case_sensitive: bool) -> Result<Regex, String> {
+    let flags = if case_sensitive { "" } else { "(?i)" };
+    if use_regex {
+        Regex::new(&format!("{}{}", flags, search)).map_err(|e| format!("正则表达式错误: {}", e))
+    } else {
+        Regex::new(&format!("{}{}", flags, regex::escape(search)))
+            .map_err(|e| format!("构建匹配模式失败: {}", e))
+    }
+}
+
+/// 按模式替换文件或文件夹名称，未命中时返回 None
+fn replace_in_name(pattern: &Regex, name: &str, replacement: &str) -> Option<String> {
+    pattern
+        .is_match(name)
+        .then(|| pattern.replace_all(name, replacement).into_owned())
+}
+
+/// 二进制模式的字节模式与替换内容
+struct BinaryReplace {
+    pattern: regex::bytes::Regex,
+    replacement: Vec<u8>,
+}
+
+/// 解析二进制模式的输入："hex:DE AD BE EF" 按十六进制解析，其他按 UTF-8 字节
+fn parse_byte_literal(text: &str) -> Result<Vec<u8>, String> {
+    let trimmed = text.trim();
+    let Some(hex) = trimmed
+        .get(..4)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("hex:"))
+        .map(|_| &trimmed[4..])
+    else {
+        return Ok(text.as_bytes().to_vec());
+    };
+
+    let digits: String = hex
+        .split_whitespace()
+        .map(|part| part.trim_start_matches("0x").trim_start_matches("0X"))
+        .collect();
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("无效的十六进制内容: {}", hex.trim()));
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err(format!("十六进制字节数不完整: {}", hex.trim()));
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|_| format!("无效的十六进制字节: {}", &digits[i..i + 2]))
+        })
+        .collect()
+}
+
+impl BinaryReplace {
+    fn new(params: &FileReplaceParams) -> Result<Self, String> {
+        if params.use_regex {
+            return Err("二进制模式不支持正则表达式，请使用字面量".to_string());
+        }
+        let search = parse_byte_literal(&params.search_text)?;
+        if search.is_empty() {
+            return Err("查找内容不能为空".to_string());
+        }
+        let replacement = parse_byte_literal(&params.replace_text)?;
+
+        // 逐字节转义，(?-u) 下 (?i) 只做 ASCII 大小写折叠
+        let escaped: String = search.iter().map(|b| format!("\\x{:02X}", b)).collect();
+        let flags = if params.case_sensitive { "(?-u)" } else { "(?i-u)" };
+        let pattern = regex::bytes::Regex::new(&format!("{}{}", flags, escaped))
+            .map_err(|e| format!("构建匹配模式失败: {}", e))?;
+        Ok(BinaryReplace {
+            pattern,
+            replacement,
+        })
+    }
+}
+
+/// 替换时使用的 glob 规则，路径统一为 `/` 分隔的相对路径
+/// 不含 `/` 的模式（如 `*.ts`）只匹配名称
+struct ReplaceGlob {
+    pattern: glob::Pattern,
+    name_only: bool,
+    /// 以 `/**` 结尾的模式去掉后缀后的部分，用于整个目录的排除判断
+    dir_prefix: Option<glob::Pattern>,
+}
+
+const REPLACE_GLOB_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+impl ReplaceGlob {
+    fn compile(patterns: &[String]) -> Result<Vec<ReplaceGlob>, String> {
+        patterns
+            .iter()
+            .map(|p| p.trim().replace('\\', "/"))
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                let pattern = glob::Pattern::new(&p)
+                    .map_err(|e| format!("无效的 glob 模式 \"{}\": {}", p, e))?;
+                let dir_prefix = p
+                    .strip_suffix("/**")
+                    .and_then(|prefix| glob::Pattern::new(prefix).ok());
+                Ok(ReplaceGlob {
+                    pattern,
+                    name_only: !p.contains('/'),
+                    dir_prefix,
+                })
+            })
+            .collect()
+    }
+
+    fn matches(&self, relative: &str, name: &str) -> bool {
+        let target = if self.name_only { name } else { relative };
+        self.pattern.matches_with(target, REPLACE_GLOB_OPTIONS)
+    }
+
+    /// 目录本身命中，或目录是 `xxx/**` 模式的前缀时，整个目录被排除
+    fn excludes_dir(&self, relative: &str, name: &str) -> bool {
+        self.matches(relative, name)
+            || self
+                .dir_prefix
+                .as_ref()
+                .is_some_and(|prefix| prefix.matches_with(relative, REPLACE_GLOB_OPTIONS))
+    }
 }
 
 #[derive(serde::Serialize)]
@@ -3707,141 +6465,177 @@ pub struct FileReplaceResult {
     matches: usize,
     success: bool,
     error: Option<String>,
+    /// 仅预览时提供：前几处匹配所在行的 (替换前, 替换后)
+    preview_diff: Option<PreviewDiff>,
 }
 
+/// 替换预览片段：(替换前, 替换后)
+type PreviewDiff = Vec<(String, String)>;
+
+/// 每个文件最多返回的预览片段数
+const MAX_PREVIEW_DIFFS_PER_FILE: usize = 5;
+/// 单个预览片段的最大字符数
+const MAX_PREVIEW_DIFF_CHARS: usize = 300;
+
 #[derive(serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileReplaceResponse {
     results: Vec<FileReplaceResult>,
     total_matches: usize,
     total_files: usize,
+    /// 执行模式下生成的撤销记录 ID，可传给 undo_file_replace
+    journal_id: Option<String>,
+    /// 是否被取消（结果为取消前已处理的部分）
+    cancelled: bool,
 }
 
-fn process_file_replace(
-    params: &FileReplaceParams,
+// 当前带进度的替换预览任务的取消标志
+static FILE_REPLACE_CANCEL_FLAG: LazyLock<Mutex<Option<Arc<AtomicBool>>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// 替换进度回调：(已扫描文件数, 当前累计匹配数, 当前文件)
+type ReplaceProgressFn<'a> = dyn FnMut(usize, usize, &Path) + 'a;
+
+/// 文件替换遍历的取消与进度控制
+#[derive(Default)]
+struct ReplaceControl<'a> {
+    cancel_flag: Option<&'a AtomicBool>,
+    on_progress: Option<&'a mut ReplaceProgressFn<'a>>,
+}
+
+/// 递归遍历文件夹时共享的参数与累计结果
+struct ReplaceWalker<'a, 'c> {
+    pattern: &'a Regex,
+    replace_text: &'a str,
+    file_extensions: &'a [String],
     execute: bool,
-) -> Result<FileReplaceResponse, String> {
-    use std::path::Path;
-    use regex::Regex;
+    replace_file_name: bool,
+    journal: Option<&'a mut ReplaceJournal>,
+    control: ReplaceControl<'c>,
+    /// glob 匹配使用的根目录（目标文件夹重命名后的实际路径）
+    root: PathBuf,
+    include_globs: Vec<ReplaceGlob>,
+    exclude_globs: Vec<ReplaceGlob>,
+    /// 启用二进制模式时按字节处理文件内容
+    binary: Option<BinaryReplace>,
+    results: Vec<FileReplaceResult>,
+    total_matches: usize,
+    total_files: usize,
+    scanned_files: usize,
+    cancelled: bool,
+}
 
-    let folder_path = Path::new(&params.folder_path);
-    if !folder_path.exists() || !folder_path.is_dir() {
-        return Err("文件夹不存在或不是有效目录".to_string());
+impl ReplaceWalker<'_, '_> {
+    fn check_cancelled(&mut self) -> bool {
+        if !self.cancelled {
+            self.cancelled = self
+                .control
+                .cancel_flag
+                .is_some_and(|flag| flag.load(Ordering::Relaxed));
+        }
+        self.cancelled
     }
 
-    // 如果需要执行替换且需要备份，先备份文件夹
-    if execute && params.backup_folder {
-        backup_folder(folder_path)?;
+    /// 返回 (相对根目录的 `/` 分隔路径, 名称)
+    fn relative_parts(&self, path: &Path) -> (String, String) {
+        let relative = path
+            .strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        (relative, name)
     }
 
-    let mut results = Vec::new();
-    let mut total_matches = 0;
-    let mut total_files = 0;
+    fn is_dir_excluded(&self, path: &Path) -> bool {
+        if self.exclude_globs.is_empty() {
+            return false;
+        }
+        let (relative, name) = self.relative_parts(path);
+        self.exclude_globs
+            .iter()
+            .any(|g| g.excludes_dir(&relative, &name))
+    }
 
-    // 构建正则表达式或普通字符串匹配
-    let pattern = if params.use_regex {
-        let flags = if params.case_sensitive { "" } else { "(?i)" };
-        Regex::new(&format!("{}{}", flags, params.search_text))
-            .map_err(|e| format!("正则表达式错误: {}", e))?
-    } else {
-        // 对于普通字符串，转义特殊字符
-        let escaped = regex::escape(&params.search_text);
-        let flags = if params.case_sensitive { "" } else { "(?i)" };
-        Regex::new(&format!("{}{}", flags, escaped))
-            .map_err(|e| format!("构建匹配模式失败: {}", e))?
-    };
+    fn is_file_selected(&self, path: &Path) -> bool {
+        if self.include_globs.is_empty() && self.exclude_globs.is_empty() {
+            return true;
+        }
+        let (relative, name) = self.relative_parts(path);
+        (self.include_globs.is_empty()
+            || self.include_globs.iter().any(|g| g.matches(&relative, &name)))
+            && !self.exclude_globs.iter().any(|g| g.matches(&relative, &name))
+    }
 
-    // 处理目标文件夹本身的名字（如果启用替换文件名）
-    let mut actual_folder_path = folder_path.to_path_buf();
-    if params.replace_file_name {
-        if let Some(folder_name) = folder_path.file_name().and_then(|n| n.to_str()) {
-            if pattern.is_match(folder_name) {
-                let new_folder_name = pattern.replace_all(folder_name, &params.replace_text).to_string();
-                let parent = folder_path.parent().ok_or_else(|| "无法获取文件夹父目录".to_string())?;
-                let new_folder_path = parent.join(&new_folder_name);
-                
-                if execute {
-                    // 执行模式：如果新文件夹名与旧文件夹名不同，执行重命名
-                    if new_folder_path != folder_path {
-                        std::fs::rename(folder_path, &new_folder_path)
-                            .map_err(|e| format!("重命名目标文件夹失败: {}", e))?;
-                        actual_folder_path = new_folder_path.clone();
-                        total_matches += 1;
-                        results.push(FileReplaceResult {
-                            file_path: new_folder_path.to_string_lossy().to_string(),
-                            matches: 1,
-                            success: true,
-                            error: None,
-                        });
-                    }
-                } else {
-                    // 预览模式：记录文件夹名匹配
-                    total_matches += 1;
-                    results.push(FileReplaceResult {
-                        file_path: new_folder_path.to_string_lossy().to_string(),
-                        matches: 1,
-                        success: true,
-                        error: None,
-                    });
-                }
-            }
+    fn report_progress(&mut self, path: &Path) {
+        if let Some(on_progress) = self.control.on_progress.as_mut() {
+            on_progress(self.scanned_files, self.total_matches, path);
         }
     }
 
-    // 递归遍历文件夹
-    fn walk_dir(
-        dir: &Path,
-        pattern: &Regex,
-        replace_text: &str,
-        file_extensions: &[String],
-        execute: bool,
-        replace_file_name: bool,
-        results: &mut Vec<FileReplaceResult>,
-        total_matches: &mut usize,
-        total_files: &mut usize,
-    ) -> Result<(), String> {
+    // 递归遍历文件夹；取消后立即返回，保留已收集的结果
+    fn walk_dir(&mut self, dir: &Path) -> Result<(), String> {
         use std::fs;
 
+        let pattern = self.pattern;
+        let replace_text = self.replace_text;
+
         for entry in fs::read_dir(dir).map_err(|e| format!("读取目录失败: {}", e))? {
+            if self.check_cancelled() {
+                return Ok(());
+            }
+
             let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
             let path = entry.path();
 
             if path.is_dir() {
+                // 命中排除规则的文件夹不重命名也不递归
+                if self.is_dir_excluded(&path) {
+                    continue;
+                }
+
                 // 处理文件夹名替换
                 let mut final_dir_path = path.clone();
                 let mut dir_name_matches = 0;
                 let mut content_dir_path = path.clone(); // 用于递归遍历的路径
                 
-                if replace_file_name {
+                if self.replace_file_name {
                     if let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) {
-                        if pattern.is_match(dir_name) {
+                        if let Some(new_dir_name) = replace_in_name(pattern, dir_name, replace_text) {
                             dir_name_matches = 1;
-                            let new_dir_name = pattern.replace_all(dir_name, replace_text).to_string();
                             let parent = path.parent().ok_or_else(|| "无法获取文件夹父目录".to_string())?;
                             final_dir_path = parent.join(&new_dir_name);
                             
-                            if execute {
+                            if self.execute {
                                 // 执行模式：如果新文件夹名与旧文件夹名不同，执行重命名
                                 if final_dir_path != path {
                                     fs::rename(&path, &final_dir_path)
                                         .map_err(|e| format!("重命名文件夹失败: {}", e))?;
+                                    if let Some(journal) = self.journal.as_deref_mut() {
+                                        journal.record_rename(&path, &final_dir_path);
+                                    }
                                     content_dir_path = final_dir_path.clone(); // 重命名后使用新路径
-                                    *total_matches += dir_name_matches;
-                                    results.push(FileReplaceResult {
+                                    self.total_matches += dir_name_matches;
+                                    self.results.push(FileReplaceResult {
                                         file_path: final_dir_path.to_string_lossy().to_string(),
                                         matches: dir_name_matches,
                                         success: true,
                                         error: None,
+                                        preview_diff: None,
                                     });
                                 }
                             } else {
                                 // 预览模式：记录文件夹名匹配
-                                *total_matches += dir_name_matches;
-                                results.push(FileReplaceResult {
+                                self.total_matches += dir_name_matches;
+                                self.results.push(FileReplaceResult {
                                     file_path: final_dir_path.to_string_lossy().to_string(),
                                     matches: dir_name_matches,
                                     success: true,
                                     error: None,
+                                    preview_diff: None,
                                 });
                             }
                         }
@@ -3849,26 +6643,20 @@ fn process_file_replace(
                 }
                 
                 // 递归处理子目录（使用实际存在的路径）
-                walk_dir(
-                    &content_dir_path,
-                    pattern,
-                    replace_text,
-                    file_extensions,
-                    execute,
-                    replace_file_name,
-                    results,
-                    total_matches,
-                    total_files,
-                )?;
+                self.walk_dir(&content_dir_path)?;
             } else if path.is_file() {
-                // 检查文件扩展名
-                let should_process = if file_extensions.is_empty() {
+                self.scanned_files += 1;
+
+                // 检查 glob 规则和文件扩展名
+                let should_process = if !self.is_file_selected(&path) {
+                    false
+                } else if self.file_extensions.is_empty() {
                     true
                 } else {
                     path.extension()
                         .and_then(|ext| ext.to_str())
                         .map(|ext| {
-                            file_extensions
+                            self.file_extensions
                                 .iter()
                                 .any(|allowed| ext.eq_ignore_ascii_case(allowed.trim()))
                         })
@@ -3876,26 +6664,28 @@ fn process_file_replace(
                 };
 
                 if should_process {
-                    *total_files += 1;
+                    self.total_files += 1;
                     
                     // 处理文件名替换
                     let mut final_path = path.clone();
                     let mut file_name_matches = 0;
                     let mut content_path = path.clone(); // 用于读取文件内容的路径
                     
-                    if replace_file_name {
+                    if self.replace_file_name {
                         if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                            if pattern.is_match(file_name) {
+                            if let Some(new_file_name) = replace_in_name(pattern, file_name, replace_text) {
                                 file_name_matches = 1;
-                                let new_file_name = pattern.replace_all(file_name, replace_text).to_string();
                                 let parent = path.parent().ok_or_else(|| "无法获取文件父目录".to_string())?;
                                 final_path = parent.join(&new_file_name);
                                 
-                                if execute {
+                                if self.execute {
                                     // 执行模式：如果新文件名与旧文件名不同，执行重命名
                                     if final_path != path {
                                         fs::rename(&path, &final_path)
                                             .map_err(|e| format!("重命名文件失败: {}", e))?;
+                                        if let Some(journal) = self.journal.as_deref_mut() {
+                                            journal.record_rename(&path, &final_path);
+                                        }
                                         content_path = final_path.clone(); // 重命名后使用新路径
                                     }
                                 }
@@ -3905,16 +6695,32 @@ fn process_file_replace(
                     }
                     
                     // 处理文件内容替换（使用实际存在的文件路径）
-                    match process_single_file(&content_path, pattern, replace_text, execute) {
-                        Ok(content_matches) => {
+                    let content_result = match &self.binary {
+                        Some(binary) => process_single_file_bytes(
+                            &content_path,
+                            binary,
+                            self.execute,
+                            self.journal.as_deref_mut(),
+                        ),
+                        None => process_single_file(
+                            &content_path,
+                            pattern,
+                            replace_text,
+                            self.execute,
+                            self.journal.as_deref_mut(),
+                        ),
+                    };
+                    match content_result {
+                        Ok((content_matches, preview_diff)) => {
                             let total_file_matches = content_matches + file_name_matches;
                             if total_file_matches > 0 {
-                                *total_matches += total_file_matches;
-                                results.push(FileReplaceResult {
+                                self.total_matches += total_file_matches;
+                                self.results.push(FileReplaceResult {
                                     file_path: final_path.to_string_lossy().to_string(),
                                     matches: total_file_matches,
                                     success: true,
                                     error: None,
+                                    preview_diff,
                                 });
                             }
                         }
@@ -3922,12 +6728,13 @@ fn process_file_replace(
                             // 如果文件名被替换了，即使内容无法处理（如二进制文件），也显示为成功
                             // 因为文件名替换已经成功了
                             if file_name_matches > 0 {
-                                *total_matches += file_name_matches;
-                                results.push(FileReplaceResult {
+                                self.total_matches += file_name_matches;
+                                self.results.push(FileReplaceResult {
                                     file_path: final_path.to_string_lossy().to_string(),
                                     matches: file_name_matches,
                                     success: true,
                                     error: None,
+                                    preview_diff: None,
                                 });
                             } else {
                                 // 如果文件名没有被替换，且内容无法处理，静默跳过（不显示错误）
@@ -3936,31 +6743,152 @@ fn process_file_replace(
                         }
                     }
                 }
+
+                self.report_progress(&path);
             }
         }
 
         Ok(())
     }
+}
+
+fn process_file_replace(
+    params: &FileReplaceParams,
+    execute: bool,
+    journal: Option<&mut ReplaceJournal>,
+    control: ReplaceControl,
+) -> Result<FileReplaceResponse, String> {
+    use std::path::Path;
+
+    let folder_path = Path::new(&params.folder_path);
+    if !folder_path.exists() || !folder_path.is_dir() {
+        return Err("文件夹不存在或不是有效目录".to_string());
+    }
+
+    // 如果需要执行替换且需要备份，先备份文件夹
+    if execute && params.backup_folder {
+        backup_folder(folder_path)?;
+    }
+
+    let pattern = build_replace_pattern(&params.search_text, params.use_regex, params.case_sensitive)?;
 
-    walk_dir(
-        &actual_folder_path,
-        &pattern,
-        &params.replace_text,
-        &params.file_extensions,
+    let binary = if params.binary_mode {
+        Some(BinaryReplace::new(params)?)
+    } else {
+        None
+    };
+    let include_globs = ReplaceGlob::compile(&params.include_globs)?;
+    let exclude_globs = ReplaceGlob::compile(&params.exclude_globs)?;
+
+    let mut walker = ReplaceWalker {
+        pattern: &pattern,
+        replace_text: &params.replace_text,
+        file_extensions: &params.file_extensions,
         execute,
-        params.replace_file_name,
-        &mut results,
-        &mut total_matches,
-        &mut total_files,
-    )?;
+        replace_file_name: params.replace_file_name,
+        journal,
+        control,
+        root: folder_path.to_path_buf(),
+        include_globs,
+        exclude_globs,
+        binary,
+        results: Vec::new(),
+        total_matches: 0,
+        total_files: 0,
+        scanned_files: 0,
+        cancelled: false,
+    };
+
+    // 处理目标文件夹本身的名字（如果启用替换文件名）
+    let mut actual_folder_path = folder_path.to_path_buf();
+    if params.replace_file_name {
+        if let Some(folder_name) = folder_path.file_name().and_then(|n| n.to_str()) {
+            if let Some(new_folder_name) = replace_in_name(&pattern, folder_name, &params.replace_text) {
+                let parent = folder_path.parent().ok_or_else(|| "无法获取文件夹父目录".to_string())?;
+                let new_folder_path = parent.join(&new_folder_name);
+                
+                if execute {
+                    // 执行模式：如果新文件夹名与旧文件夹名不同，执行重命名
+                    if new_folder_path != folder_path {
+                        std::fs::rename(folder_path, &new_folder_path)
+                            .map_err(|e| format!("重命名目标文件夹失败: {}", e))?;
+                        if let Some(journal) = walker.journal.as_deref_mut() {
+                            journal.record_rename(folder_path, &new_folder_path);
+                        }
+                        actual_folder_path = new_folder_path.clone();
+                        walker.total_matches += 1;
+                        walker.results.push(FileReplaceResult {
+                            file_path: new_folder_path.to_string_lossy().to_string(),
+                            matches: 1,
+                            success: true,
+                            error: None,
+                            preview_diff: None,
+                        });
+                    }
+                } else {
+                    // 预览模式：记录文件夹名匹配
+                    walker.total_matches += 1;
+                    walker.results.push(FileReplaceResult {
+                        file_path: new_folder_path.to_string_lossy().to_string(),
+                        matches: 1,
+                        success: true,
+                        error: None,
+                        preview_diff: None,
+                    });
+                }
+            }
+        }
+    }
+
+    walker.root = actual_folder_path.clone();
+    walker.walk_dir(&actual_folder_path)?;
 
     Ok(FileReplaceResponse {
-        results,
-        total_matches,
-        total_files,
+        results: walker.results,
+        total_matches: walker.total_matches,
+        total_files: walker.total_files,
+        journal_id: None,
+        cancelled: walker.cancelled,
     })
 }
 
+/// 估算替换可能改写的字节数上限（所有符合扩展名过滤的文件大小之和）
+fn estimate_replace_bytes(folder_path: &Path, file_extensions: &[String]) -> u64 {
+    let mut total = 0u64;
+    let mut stack = vec![folder_path.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(meta) = fs::symlink_metadata(entry.path()) else {
+                continue;
+            };
+            if folder_stats::is_reparse_point(&meta) {
+                continue;
+            }
+            if meta.is_dir() {
+                stack.push(entry.path());
+                continue;
+            }
+            let matches_ext = file_extensions.is_empty()
+                || entry
+                    .path()
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        file_extensions
+                            .iter()
+                            .any(|allowed| ext.eq_ignore_ascii_case(allowed.trim()))
+                    });
+            if matches_ext {
+                total += meta.len();
+            }
+        }
+    }
+    total
+}
+
 /// 备份文件夹到父目录，备份文件夹名称包含时间戳
 fn backup_folder(folder_path: &Path) -> Result<std::path::PathBuf, String> {
     use std::fs;
@@ -4036,7 +6964,8 @@ fn process_single_file(
     pattern: &Regex,
     replace_text: &str,
     execute: bool,
-) -> Result<usize, String> {
+    journal: Option<&mut ReplaceJournal>,
+) -> Result<(usize, Option<PreviewDiff>), String> {
     use std::fs;
     use std::io::Write;
 
@@ -4057,6 +6986,11 @@ fn process_single_file(
         // 执行替换
         let new_content = pattern.replace_all(&content, replace_text).to_string();
 
+        // 写入前记录原始内容，用于撤销
+        if let Some(journal) = journal {
+            journal.record_content(file_path, content.as_bytes())?;
+        }
+
         // 写回文件
         let mut file = fs::File::create(file_path)
             .map_err(|e| format!("打开文件写入失败: {}", e))?;
@@ -4064,7 +6998,98 @@ fn process_single_file(
             .map_err(|e| format!("写入文件失败: {}", e))?;
     }
 
-    Ok(match_count)
+    // 预览模式下附带前几处匹配的替换前后对比
+    let preview_diff = if match_count > 0 && !execute {
+        Some(build_preview_diff(&content, pattern, replace_text))
+    } else {
+        None
+    };
+
+    Ok((match_count, preview_diff))
+}
+
+/// 二进制模式：按字节查找替换，预览片段为匹配前后若干字节的十六进制
+fn process_single_file_bytes(
+    file_path: &Path,
+    binary: &BinaryReplace,
+    execute: bool,
+    journal: Option<&mut ReplaceJournal>,
+) -> Result<(usize, Option<PreviewDiff>), String> {
+    const CONTEXT_BYTES: usize = 8;
+
+    let content = fs::read(file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let match_count = binary.pattern.find_iter(&content).count();
+    if match_count == 0 {
+        return Ok((0, None));
+    }
+
+    if execute {
+        let new_content = binary
+            .pattern
+            .replace_all(&content, regex::bytes::NoExpand(&binary.replacement));
+        if let Some(journal) = journal {
+            journal.record_content(file_path, &content)?;
+        }
+        fs::write(file_path, new_content.as_ref()).map_err(|e| format!("写入文件失败: {}", e))?;
+        return Ok((match_count, None));
+    }
+
+    let to_hex = |bytes: &[u8]| -> String {
+        bytes
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let diffs = binary
+        .pattern
+        .find_iter(&content)
+        .take(MAX_PREVIEW_DIFFS_PER_FILE)
+        .map(|m| {
+            let start = m.start().saturating_sub(CONTEXT_BYTES);
+            let end = (m.end() + CONTEXT_BYTES).min(content.len());
+            let mut after = content[start..m.start()].to_vec();
+            after.extend_from_slice(&binary.replacement);
+            after.extend_from_slice(&content[m.end()..end]);
+            (to_hex(&content[start..end]), to_hex(&after))
+        })
+        .collect();
+    Ok((match_count, Some(diffs)))
+}
+
+/// 取每处匹配所在的完整行（跨行匹配则取覆盖的所有行），生成 (替换前, 替换后) 片段
+/// 同一行内的多处匹配只生成一个片段
+fn build_preview_diff(content: &str, pattern: &Regex, replace_text: &str) -> PreviewDiff {
+    let truncate = |s: &str| -> String {
+        if s.chars().count() > MAX_PREVIEW_DIFF_CHARS {
+            let mut t: String = s.chars().take(MAX_PREVIEW_DIFF_CHARS).collect();
+            t.push('…');
+            t
+        } else {
+            s.to_string()
+        }
+    };
+
+    let mut diffs = Vec::new();
+    let mut covered_until = 0usize;
+    for m in pattern.find_iter(content) {
+        if m.start() < covered_until {
+            continue;
+        }
+        let line_start = content[..m.start()].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = content[m.end()..]
+            .find('\n')
+            .map_or(content.len(), |i| m.end() + i);
+        covered_until = line_end;
+
+        let before = content[line_start..line_end].trim_end_matches('\r');
+        let after = pattern.replace_all(before, replace_text);
+        diffs.push((truncate(before), truncate(&after)));
+        if diffs.len() >= MAX_PREVIEW_DIFFS_PER_FILE {
+            break;
+        }
+    }
+    diffs
 }
 
 #[tauri::command(rename_all = "camelCase")]
@@ -4077,6 +7102,9 @@ pub fn preview_file_replace(
     case_sensitive: bool,
     backup_folder: bool,
     replace_file_name: bool,
+    include_globs: Option<Vec<String>>,
+    exclude_globs: Option<Vec<String>>,
+    binary_mode: Option<bool>,
 ) -> Result<FileReplaceResponse, String> {
     let params = FileReplaceParams {
         folder_path,
@@ -4087,8 +7115,76 @@ pub fn preview_file_replace(
         case_sensitive,
         backup_folder,
         replace_file_name,
+        include_globs: include_globs.unwrap_or_default(),
+        exclude_globs: exclude_globs.unwrap_or_default(),
+        binary_mode: binary_mode.unwrap_or(false),
     };
-    process_file_replace(&params, false)
+    process_file_replace(&params, false, None, ReplaceControl::default())
+}
+
+/// 带进度的替换预览：在后台线程遍历，定期发送 file-replace-progress 事件，可通过 cancel_file_replace 取消
+#[tauri::command]
+pub async fn preview_file_replace_with_progress(
+    params: FileReplaceParams,
+    window: tauri::Window,
+) -> Result<FileReplaceResponse, String> {
+    // 新任务开始时取消上一个未完成的预览
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    {
+        let mut current = FILE_REPLACE_CANCEL_FLAG
+            .lock()
+            .map_err(|e| format!("锁定替换任务状态失败: {}", e))?;
+        if let Some(old_flag) = current.replace(cancel_flag.clone()) {
+            old_flag.store(true, Ordering::Relaxed);
+        }
+    }
+
+    let task_flag = cancel_flag.clone();
+    let result = async_runtime::spawn_blocking(move || {
+        let mut last_emit = std::time::Instant::now();
+        let mut on_progress = |scanned_files: usize, matches: usize, current: &Path| {
+            if last_emit.elapsed() < Duration::from_millis(100) {
+                return;
+            }
+            last_emit = std::time::Instant::now();
+            let _ = window.emit(
+                "file-replace-progress",
+                events::FileReplaceProgress {
+                    scanned_files,
+                    matches,
+                    current_path: current.to_string_lossy().to_string(),
+                },
+            );
+        };
+        let control = ReplaceControl {
+            cancel_flag: Some(&task_flag),
+            on_progress: Some(&mut on_progress),
+        };
+        process_file_replace(&params, false, None, control)
+    })
+    .await
+    .map_err(|e| format!("替换预览任务失败: {}", e))?;
+
+    // 仅清理属于本次任务的标志
+    if let Ok(mut current) = FILE_REPLACE_CANCEL_FLAG.lock() {
+        if current.as_ref().is_some_and(|flag| Arc::ptr_eq(flag, &cancel_flag)) {
+            *current = None;
+        }
+    }
+
+    result
+}
+
+/// 取消正在进行的带进度替换预览，已扫描的部分结果仍会返回
+#[tauri::command]
+pub fn cancel_file_replace() -> Result<(), String> {
+    let mut current = FILE_REPLACE_CANCEL_FLAG
+        .lock()
+        .map_err(|e| format!("锁定替换任务状态失败: {}", e))?;
+    if let Some(flag) = current.take() {
+        flag.store(true, Ordering::Relaxed);
+    }
+    Ok(())
 }
 
 #[tauri::command(rename_all = "camelCase")]
@@ -4101,6 +7197,10 @@ pub fn execute_file_replace(
     case_sensitive: bool,
     backup_folder: bool,
     replace_file_name: bool,
+    include_globs: Option<Vec<String>>,
+    exclude_globs: Option<Vec<String>>,
+    binary_mode: Option<bool>,
+    app: tauri::AppHandle,
 ) -> Result<FileReplaceResponse, String> {
     let params = FileReplaceParams {
         folder_path,
@@ -4111,8 +7211,286 @@ pub fn execute_file_replace(
         case_sensitive,
         backup_folder,
         replace_file_name,
+        include_globs: include_globs.unwrap_or_default(),
+        exclude_globs: exclude_globs.unwrap_or_default(),
+        binary_mode: binary_mode.unwrap_or(false),
     };
-    process_file_replace(&params, true)
+
+    // 涉及内容较小时记录轻量撤销信息；过大时必须依赖完整文件夹备份
+    let folder = Path::new(&params.folder_path);
+    let estimated_bytes = estimate_replace_bytes(folder, &params.file_extensions);
+    if estimated_bytes > replace_journal::MAX_JOURNAL_BYTES {
+        if !params.backup_folder {
+            return Err(format!(
+                "涉及文件过大（约 {} MB），无法记录撤销信息，请启用备份文件夹后再执行",
+                estimated_bytes / 1024 / 1024
+            ));
+        }
+        return process_file_replace(&params, true, None, ReplaceControl::default());
+    }
+
+    let app_data_dir = get_app_data_dir(&app)?;
+    let mut journal = ReplaceJournal::create(&app_data_dir, folder)?;
+    let result = process_file_replace(
+        &params,
+        true,
+        Some(&mut journal),
+        ReplaceControl::default(),
+    );
+    // 即使中途失败也保存已完成部分的撤销记录
+    let journal_id = journal.finish()?;
+    match result {
+        Ok(mut response) => {
+            response.journal_id = journal_id;
+            Ok(response)
+        }
+        Err(e) => match journal_id {
+            Some(id) => Err(format!("{}（已完成的修改可通过撤销记录 {} 恢复）", e, id)),
+            None => Err(e),
+        },
+    }
+}
+
+/// 撤销一次文件替换：按相反顺序恢复文件内容和名称
+#[tauri::command(rename_all = "camelCase")]
+pub fn undo_file_replace(
+    journal_id: String,
+    app: tauri::AppHandle,
+) -> Result<replace_journal::UndoReport, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    replace_journal::undo(&app_data_dir, &journal_id)
+}
+
+/// 批量重命名中的一项
+#[derive(serde::Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkRenameEntry {
+    old_path: String,
+    new_path: String,
+    /// 冲突原因或执行时的错误
+    error: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkRenameResponse {
+    /// 计划（预览）或已执行的重命名
+    renames: Vec<BulkRenameEntry>,
+    /// 无法重命名的项；存在冲突时不执行任何重命名
+    conflicts: Vec<BulkRenameEntry>,
+    renamed: usize,
+    /// 执行模式下生成的撤销记录 ID，可传给 undo_file_replace
+    journal_id: Option<String>,
+}
+
+/// Windows 文件名中不允许出现的字符
+const INVALID_FILE_NAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// 收集要重命名的文件（不含文件夹），按路径排序
+fn collect_rename_files(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("读取文件夹失败 {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_file() {
+            files.push(entry.path());
+        } else if recursive && file_type.is_dir() {
+            collect_rename_files(&entry.path(), recursive, files)?;
+        }
+    }
+    files.sort();
+    Ok(())
+}
+
+/// 计算重命名计划，分为可执行的重命名和冲突项
+/// 冲突：新名称为空或含非法字符、多个文件得到同一名称（不区分大小写）、目标已存在（仅大小写不同的自身除外）
+fn plan_bulk_rename(
+    files: &[PathBuf],
+    pattern: &Regex,
+    replacement: &str,
+) -> (Vec<BulkRenameEntry>, Vec<BulkRenameEntry>) {
+    let planned: Vec<(&PathBuf, PathBuf, String)> = files
+        .iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            let new_name = replace_in_name(pattern, name, replacement)?;
+            (new_name != name).then(|| (path, path.with_file_name(&new_name), new_name))
+        })
+        .collect();
+
+    let mut target_counts: std::collections::HashMap<String, usize> =
+        std::collections::HashMap::new();
+    for (_, to, _) in &planned {
+        *target_counts
+            .entry(to.to_string_lossy().to_lowercase())
+            .or_default() += 1;
+    }
+
+    let mut renames = Vec::new();
+    let mut conflicts = Vec::new();
+    for (from, to, new_name) in planned {
+        let to_key = to.to_string_lossy().to_lowercase();
+        let error = if new_name.trim().is_empty() {
+            Some("新文件名为空")
+        } else if new_name.contains(INVALID_FILE_NAME_CHARS) {
+            Some("新文件名包含非法字符")
+        } else if target_counts.get(&to_key).copied().unwrap_or(0) > 1 {
+            Some("多个文件将被重命名为同一名称")
+        } else if to.exists() && from.to_string_lossy().to_lowercase() != to_key {
+            Some("目标文件已存在")
+        } else {
+            None
+        };
+        let entry = BulkRenameEntry {
+            old_path: from.to_string_lossy().to_string(),
+            new_path: to.to_string_lossy().to_string(),
+            error: error.map(str::to_string),
+        };
+        if entry.error.is_some() {
+            conflicts.push(entry);
+        } else {
+            renames.push(entry);
+        }
+    }
+    (renames, conflicts)
+}
+
+/// 按模式批量重命名文件夹中的文件（默认不递归）
+/// preview 为 true 时只返回计划的 (旧路径, 新路径)；存在冲突时拒绝执行，不会覆盖已有文件
+#[tauri::command(rename_all = "camelCase")]
+pub fn bulk_rename(
+    folder: String,
+    pattern: String,
+    replacement: String,
+    use_regex: bool,
+    preview: bool,
+    recursive: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<BulkRenameResponse, String> {
+    let folder_path = Path::new(folder.trim());
+    if !folder_path.is_dir() {
+        return Err("文件夹不存在或不是有效目录".to_string());
+    }
+    if pattern.is_empty() {
+        return Err("查找内容不能为空".to_string());
+    }
+    let regex = build_replace_pattern(&pattern, use_regex, true)?;
+
+    let mut files = Vec::new();
+    collect_rename_files(folder_path, recursive.unwrap_or(false), &mut files)?;
+    let (mut renames, conflicts) = plan_bulk_rename(&files, &regex, &replacement);
+
+    if preview {
+        return Ok(BulkRenameResponse {
+            renames,
+            conflicts,
+            renamed: 0,
+            journal_id: None,
+        });
+    }
+    if !conflicts.is_empty() {
+        return Err(format!("存在 {} 个命名冲突，未执行重命名", conflicts.len()));
+    }
+
+    let app_data_dir = get_app_data_dir(&app)?;
+    let mut journal = ReplaceJournal::create(&app_data_dir, folder_path)?;
+    let mut renamed = 0;
+    for entry in &mut renames {
+        let (from, to) = (Path::new(&entry.old_path), Path::new(&entry.new_path));
+        match fs::rename(from, to) {
+            Ok(()) => {
+                journal.record_rename(from, to);
+                renamed += 1;
+            }
+            Err(e) => entry.error = Some(format!("重命名失败: {}", e)),
+        }
+    }
+    let journal_id = journal.finish()?;
+    log_info!(
+        "FileToolbox",
+        "批量重命名 {}: {}/{} 个文件",
+        folder_path.display(),
+        renamed,
+        renames.len()
+    );
+
+    Ok(BulkRenameResponse {
+        renames,
+        conflicts,
+        renamed,
+        journal_id,
+    })
+}
+
+/// 分析文件夹空间占用（按扩展名统计），扫描过程中发送 folder-analyze-progress 事件
+#[tauri::command]
+pub async fn analyze_folder(
+    root: String,
+    max_depth: Option<usize>,
+    app: tauri::AppHandle,
+) -> Result<folder_stats::FolderAnalysis, String> {
+    let max_depth = max_depth.unwrap_or(32);
+    async_runtime::spawn_blocking(move || {
+        let mut last_emit = std::time::Instant::now();
+        folder_stats::analyze_folder(Path::new(&root), max_depth, |files, bytes, current| {
+            // 节流，避免事件过多阻塞前端
+            if last_emit.elapsed() < Duration::from_millis(100) {
+                return;
+            }
+            last_emit = std::time::Instant::now();
+            let event_data = events::FolderAnalyzeProgress {
+                scanned_files: files,
+                scanned_bytes: bytes,
+                current_path: current.to_string_lossy().to_string(),
+            };
+            let _ = app.emit("folder-analyze-progress", &event_data);
+        })
+    })
+    .await
+    .map_err(|e| format!("分析任务失败: {}", e))?
+}
+
+/// 分块读取文件计算摘要，避免大文件整体读入内存
+fn hash_file<D: sha2::Digest>(path: &Path) -> Result<String, String> {
+    use std::io::Read;
+
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("无法读取文件 {}: {}", path.display(), e))?;
+    let mut hasher = D::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("读取文件失败 {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// 计算文件校验值（md5 / sha1 / sha256），返回小写十六进制摘要
+#[tauri::command]
+pub async fn compute_file_hash(path: String, algo: String) -> Result<String, String> {
+    let path = PathBuf::from(path.trim());
+    if !path.is_file() {
+        return Err(format!("文件不存在: {}", path.display()));
+    }
+    let algo = algo.trim().to_lowercase().replace('-', "");
+    async_runtime::spawn_blocking(move || match algo.as_str() {
+        "md5" => hash_file::<md5::Md5>(&path),
+        "sha1" => hash_file::<sha1::Sha1>(&path),
+        "sha256" => hash_file::<sha2::Sha256>(&path),
+        _ => Err(format!("不支持的哈希算法: {}（可选 md5、sha1、sha256）", algo)),
+    })
+    .await
+    .map_err(|e| format!("计算校验值任务失败: {}", e))?
 }
 
 #[tauri::command]
@@ -4175,26 +7553,76 @@ pub fn get_plugin_directory(app: tauri::AppHandle) -> Result<String, String> {
     Ok(plugin_dir.to_string_lossy().to_string())
 }
 
+/// 扫描到的插件目录及其解析结果；manifest 无效时 error 给出原因
+#[derive(Serialize, Debug)]
+pub struct ScannedPlugin {
+    pub dir: String,
+    pub manifest: Option<PluginManifest>,
+    pub error: Option<String>,
+    pub enabled: bool,
+}
+
+/// 默认只返回目录列表；with_manifests 为 true 时一并返回解析后的 manifest
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub enum PluginScanResult {
+    Dirs(Vec<String>),
+    WithManifests(Vec<ScannedPlugin>),
+}
+
 #[tauri::command]
-pub fn scan_plugin_directory(directory: String) -> Result<Vec<String>, String> {
+pub fn scan_plugin_directory(
+    app: tauri::AppHandle,
+    directory: String,
+    with_manifests: Option<bool>,
+) -> Result<PluginScanResult, String> {
     let path = PathBuf::from(directory);
-    if !path.exists() {
-        return Ok(vec![]);
-    }
-    
     let mut plugin_dirs = Vec::new();
-    for entry in fs::read_dir(&path).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
-        if path.is_dir() {
-            // 检查是否有 manifest.json
-            if path.join("manifest.json").exists() {
-                plugin_dirs.push(path.to_string_lossy().to_string());
+    if path.exists() {
+        for entry in fs::read_dir(&path).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let path = entry.path();
+            if path.is_dir() {
+                // 检查是否有 manifest.json
+                if path.join(plugin_manifest::MANIFEST_FILE).exists() {
+                    plugin_dirs.push(path);
+                }
             }
         }
     }
-    
-    Ok(plugin_dirs)
+
+    if !with_manifests.unwrap_or(false) {
+        return Ok(PluginScanResult::Dirs(
+            plugin_dirs
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+        ));
+    }
+
+    let app_data_dir = get_app_data_dir(&app)?;
+    let settings = settings::load_settings(&app_data_dir)?;
+    let plugins = plugin_dirs
+        .iter()
+        .map(|dir| {
+            let (manifest, error) = match plugin_manifest::load_manifest(dir) {
+                Ok(manifest) => (Some(manifest), None),
+                Err(e) => (None, Some(e)),
+            };
+            // manifest 无效时以目录名作为插件 ID
+            let plugin_id = manifest.as_ref().map_or_else(
+                || dir.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                |m| m.id.clone(),
+            );
+            ScannedPlugin {
+                dir: dir.to_string_lossy().to_string(),
+                enabled: settings.is_plugin_enabled(&plugin_id),
+                manifest,
+                error,
+            }
+        })
+        .collect();
+    Ok(PluginScanResult::WithManifests(plugins))
 }
 
 #[tauri::command]
@@ -4210,46 +7638,445 @@ pub fn read_plugin_manifest(plugin_dir: String) -> Result<String, String> {
     Ok(content)
 }
 
+/// 解析并校验插件 manifest，返回类型化结构或具体的错误原因
+#[tauri::command]
+pub fn validate_plugin_manifest(plugin_dir: String) -> Result<PluginManifest, String> {
+    plugin_manifest::load_manifest(Path::new(&plugin_dir))
+}
+
+/// 从 zip 插件包安装到插件目录，返回安装后的 manifest
+#[tauri::command]
+pub async fn install_plugin(
+    app: tauri::AppHandle,
+    zip_path: String,
+    overwrite: Option<bool>,
+) -> Result<PluginManifest, String> {
+    let plugins_dir = get_app_data_dir(&app)?.join("plugins");
+    let (dir, manifest) = async_runtime::spawn_blocking(move || {
+        plugin_install::install_from_zip(
+            &plugins_dir,
+            Path::new(&zip_path),
+            overwrite.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| format!("安装任务失败: {}", e))??;
+
+    if let Err(e) = app.emit(
+        "plugin-installed",
+        events::PluginInstalled {
+            dir: dir.to_string_lossy().to_string(),
+            manifest: manifest.clone(),
+        },
+    ) {
+        log_error!("Plugin", "Failed to emit plugin-installed event: {}", e);
+    }
+    Ok(manifest)
+}
+
+/// 返回已禁用的插件 ID，未列出的插件均为启用
+#[tauri::command]
+pub fn get_plugin_states(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    Ok(settings::load_settings(&app_data_dir)?.disabled_plugins)
+}
+
+/// 启用/禁用插件：禁用时注销其快捷键，重新启用时恢复已保存的快捷键
+#[tauri::command]
+pub fn set_plugin_enabled(
+    app: tauri::AppHandle,
+    plugin_id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let mut settings = settings::load_settings(&app_data_dir)?;
+    settings.set_plugin_enabled(&plugin_id, enabled);
+    settings::save_settings(&app_data_dir, &settings)?;
+
+    #[cfg(target_os = "windows")]
+    {
+        let result = match settings.plugin_hotkeys.get(&plugin_id) {
+            Some(hotkey) if enabled => {
+                crate::hotkey_handler::windows::register_plugin_hotkey(plugin_id.clone(), hotkey.clone())
+            }
+            Some(_) => crate::hotkey_handler::windows::unregister_plugin_hotkey(&plugin_id),
+            None => Ok(()),
+        };
+        if let Err(e) = result {
+            log_error!(
+                "Plugin",
+                "Failed to update plugin hotkey for {}: {}",
+                plugin_id,
+                e
+            );
+        }
+    }
+
+    if let Err(e) = app.emit(
+        "plugin-state-changed",
+        events::PluginStateChanged { plugin_id, enabled },
+    ) {
+        log_error!("Plugin", "Failed to emit plugin-state-changed event: {}", e);
+    }
+    Ok(())
+}
+
 // ===== Settings commands =====
 
-#[tauri::command]
-pub fn get_settings(app: tauri::AppHandle) -> Result<settings::Settings, String> {
-    let app_data_dir = get_app_data_dir(&app)?;
-    settings::load_settings(&app_data_dir)
-}
+#[tauri::command]
+pub fn get_settings(app: tauri::AppHandle) -> Result<settings::Settings, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    settings::load_settings(&app_data_dir).map(settings::Settings::without_secrets)
+}
+
+#[tauri::command]
+pub fn save_settings(app: tauri::AppHandle, mut settings: settings::Settings) -> Result<(), String> {
+    if !settings::THEMES.contains(&settings.theme.as_str()) {
+        return Err(format!("无效的主题: {}", settings.theme));
+    }
+    let log_level = logger::LogLevel::parse(&settings.log_level)
+        .ok_or_else(|| format!("无效的日志级别: {}", settings.log_level))?;
+    let app_data_dir = get_app_data_dir(&app)?;
+    let previous = settings::load_settings(&app_data_dir).ok();
+    let previous_theme = previous.as_ref().map(|s| s.theme.clone());
+    if let Some(previous) = &previous {
+        settings.keep_managed_fields(previous);
+    }
+    settings::save_settings(&app_data_dir, &settings)?;
+    logger::set_log_level(log_level);
+
+    // 延迟/最小化启动选项变化时更新已登记的开机启动命令
+    let launch_options = settings::StartupLaunchOptions::from_settings(&settings);
+    if previous.as_ref().map(settings::StartupLaunchOptions::from_settings) != Some(launch_options) {
+        if let Err(e) = startup::repair_startup_entry(&launch_options.to_args()) {
+            log_warn!("Startup", "更新开机启动参数失败: {}", e);
+        }
+    }
+
+    // 主题变化时通知所有窗口立即切换
+    if previous_theme.as_deref() != Some(settings.theme.as_str()) {
+        if let Err(e) = app.emit(
+            "theme-changed",
+            events::ThemeChanged {
+                theme: settings.theme.clone(),
+            },
+        ) {
+            log_error!("Settings", "Failed to emit theme-changed event: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// 设置并保存日志级别（"error" | "warn" | "info" | "debug"），立即生效
+#[tauri::command]
+pub fn set_log_level(app: tauri::AppHandle, level: String) -> Result<(), String> {
+    let log_level =
+        logger::LogLevel::parse(&level).ok_or_else(|| format!("无效的日志级别: {}", level))?;
+    let app_data_dir = get_app_data_dir(&app)?;
+    let mut settings = settings::load_settings(&app_data_dir)?;
+    settings.log_level = log_level.as_str().to_string();
+    settings::save_settings(&app_data_dir, &settings)?;
+    logger::set_log_level(log_level);
+    Ok(())
+}
+
+/// 当前日志文件路径（re-fast.log，轮转后的旧文件在同一目录）
+#[tauri::command]
+pub fn get_log_file_path() -> String {
+    logger::get_log_file_path().to_string_lossy().to_string()
+}
+
+// ===== Everything Filters commands =====
+
+#[tauri::command]
+pub fn get_everything_custom_filters(app: tauri::AppHandle) -> Result<Vec<everything_filters::CustomFilter>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    everything_filters::load_custom_filters(&app_data_dir)
+}
+
+#[tauri::command]
+pub fn save_everything_custom_filters(
+    app: tauri::AppHandle,
+    filters: Vec<everything_filters::CustomFilter>,
+) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    everything_filters::save_custom_filters(&app_data_dir, &filters)
+}
+
+#[tauri::command]
+pub fn list_saved_searches(app: tauri::AppHandle) -> Result<Vec<everything_filters::SavedSearch>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    everything_filters::load_saved_searches(&app_data_dir)
+}
+
+#[tauri::command]
+pub fn add_saved_search(
+    app: tauri::AppHandle,
+    name: String,
+    query: String,
+    options: Option<everything_filters::SavedSearchOptions>,
+) -> Result<everything_filters::SavedSearch, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    everything_filters::add_saved_search(&app_data_dir, name, query, options.unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn delete_saved_search(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    everything_filters::delete_saved_search(&app_data_dir, &id)
+}
+
+/// 以保存的查询和选项开启 Everything 搜索会话，并记录使用以便在启动器中按频率展示
+#[tauri::command]
+pub async fn run_saved_search(
+    app: tauri::AppHandle,
+    id: String,
+) -> Result<EverythingSearchSessionResponse, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let search = everything_filters::find_saved_search(&app_data_dir, &id)?;
+
+    let response =
+        start_everything_search_session(search.query, Some(search.options.into()), app).await?;
+
+    if let Err(e) = open_history::record_open(
+        everything_filters::saved_search_history_key(&search.id),
+        &app_data_dir,
+    ) {
+        log_warn!("Everything", "记录保存的搜索使用失败: {}", e);
+    }
+    Ok(response)
+}
+
+#[tauri::command]
+pub fn search_system_folders(
+    query: String,
+    app: tauri::AppHandle,
+) -> Result<Vec<system_folders_search::windows::SystemFolderItem>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let aliases = system_folders_search::load_folder_aliases(&app_data_dir)
+        .unwrap_or_default()
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    Ok(system_folders_search::windows::search_system_folders(&query, aliases))
+}
+
+#[tauri::command]
+pub fn list_system_folder_aliases(
+    app: tauri::AppHandle,
+) -> Result<Vec<system_folders_search::SystemFolderAlias>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    system_folders_search::load_folder_aliases(&app_data_dir)
+}
+
+#[tauri::command]
+pub fn add_system_folder_alias(
+    label: String,
+    path: String,
+    app: tauri::AppHandle,
+) -> Result<Vec<system_folders_search::SystemFolderAlias>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    system_folders_search::add_folder_alias(&app_data_dir, label, path)
+}
+
+#[tauri::command]
+pub fn remove_system_folder_alias(
+    label: String,
+    app: tauri::AppHandle,
+) -> Result<Vec<system_folders_search::SystemFolderAlias>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    system_folders_search::remove_folder_alias(&app_data_dir, &label)
+}
+
+/// 统一搜索中 Everything 来源的结果上限，避免大查询拖慢整体响应
+const UNIFIED_EVERYTHING_CAP: usize = 100;
+
+/// 展开统一搜索中单个来源的结果：超时、任务失败或来源出错时记录警告并跳过该来源
+fn unified_source_items<T, E: std::fmt::Display>(
+    source: &str,
+    outcome: Result<Result<Result<Vec<T>, String>, E>, tokio::time::error::Elapsed>,
+) -> Vec<T> {
+    let error = match outcome {
+        Ok(Ok(Ok(items))) => return items,
+        Ok(Ok(Err(e))) => e,
+        Ok(Err(e)) => format!("任务失败: {}", e),
+        Err(_) => "超时".to_string(),
+    };
+    log_warn!("UnifiedSearch", "来源 {} 已跳过: {}", source, error);
+    Vec::new()
+}
+
+/// 统一搜索：并行查询应用、快捷方式、文件历史、Everything、系统文件夹和备忘录，
+/// 返回带 `kind` 标识、按归一化分数（含使用频率加成）排序的结果列表
+#[tauri::command]
+pub async fn unified_search(
+    query: String,
+    limit: Option<usize>,
+    app: tauri::AppHandle,
+) -> Result<Vec<search_ranking::UnifiedSearchResult>, String> {
+    use search_ranking::UnifiedItem;
+
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let limit = limit.unwrap_or(50).max(1);
+    let app_data_dir = get_app_data_dir(&app)?;
+    // 各来源并行执行，单个来源超时后忽略其结果，整体耗时不超过该值
+    let source_timeout = Duration::from_millis(1500);
+
+    let apps_task = {
+        let query = query.clone();
+        let app = app.clone();
+        // 使用独立的来源，避免与启动器中的应用搜索相互取代
+        let ticket = query_guard::begin(query_guard::UNIFIED_APPS);
+        async_runtime::spawn(async move {
+            search_applications_page(query, Some(limit), None, ticket, app)
+                .await
+                .map(|page| page.items)
+        })
+    };
+
+    let shortcuts_task = {
+        let query = query.clone();
+        let app_data_dir = app_data_dir.clone();
+        async_runtime::spawn_blocking(move || {
+            shortcuts::load_shortcuts(&app_data_dir)?;
+            Ok(search_ranking::filter_shortcuts(&query, shortcuts::get_all_shortcuts()))
+        })
+    };
+
+    let history_task = {
+        let query = query.clone();
+        let app_data_dir = app_data_dir.clone();
+        async_runtime::spawn_blocking(move || {
+            let limit = settings::load_settings_cached(&app_data_dir)?.max_history_results;
+            file_history::search_file_history(&query, None, Some(limit), &app_data_dir)
+        })
+    };
+
+    let folders_task = {
+        let query = query.clone();
+        let app_data_dir = app_data_dir.clone();
+        async_runtime::spawn_blocking(move || {
+            let aliases = system_folders_search::load_folder_aliases(&app_data_dir)
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect();
+            Ok(system_folders_search::windows::search_system_folders(&query, aliases))
+        })
+    };
+
+    let memos_task = {
+        let query = query.clone();
+        let app_data_dir = app_data_dir.clone();
+        async_runtime::spawn_blocking(move || memos::search_memos(&query, &app_data_dir))
+    };
+
+    let everything_cancel = Arc::new(AtomicBool::new(false));
+    let everything_task = {
+        let query = query.clone();
+        let cancel_flag = everything_cancel.clone();
+        let max_results = limit.min(UNIFIED_EVERYTHING_CAP);
+        async_runtime::spawn_blocking(move || {
+            #[cfg(target_os = "windows")]
+            {
+                everything_search::windows::search_files(
+                    &query,
+                    max_results,
+                    max_results,
+                    Some(&cancel_flag),
+                    None::<fn(&[everything_search::EverythingResult], u32, u32)>,
+                )
+                .map(|response| response.results)
+                .map_err(|e| e.to_string())
+            }
+            #[cfg(not(target_os = "windows"))]
+            {
+                let _ = (query, cancel_flag, max_results);
+                Ok(Vec::<everything_search::EverythingResult>::new())
+            }
+        })
+    };
+
+    let usage_task = {
+        let app_data_dir = app_data_dir.clone();
+        async_runtime::spawn_blocking(move || open_history::get_all_history(&app_data_dir))
+    };
 
-#[tauri::command]
-pub fn save_settings(app: tauri::AppHandle, settings: settings::Settings) -> Result<(), String> {
-    let app_data_dir = get_app_data_dir(&app)?;
-    settings::save_settings(&app_data_dir, &settings)
-}
+    let (apps, shortcuts, history, folders, memos, everything, usage) = tokio::join!(
+        tokio::time::timeout(source_timeout, apps_task),
+        tokio::time::timeout(source_timeout, shortcuts_task),
+        tokio::time::timeout(source_timeout, history_task),
+        tokio::time::timeout(source_timeout, folders_task),
+        tokio::time::timeout(source_timeout, memos_task),
+        tokio::time::timeout(source_timeout, everything_task),
+        tokio::time::timeout(source_timeout, usage_task),
+    );
+    // 超时的 Everything 查询仍在阻塞线程中等待回复，通知其尽快退出
+    everything_cancel.store(true, Ordering::Relaxed);
 
-// ===== Everything Filters commands =====
+    let now = std::time::SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let usage = search_ranking::UsageSignals::new(
+        match usage {
+            Ok(Ok(Ok(history))) => history,
+            _ => {
+                log_warn!("UnifiedSearch", "无法读取打开记录，跳过使用频率加成");
+                Default::default()
+            }
+        },
+        now,
+    );
 
-#[tauri::command]
-pub fn get_everything_custom_filters(app: tauri::AppHandle) -> Result<Vec<everything_filters::CustomFilter>, String> {
-    let app_data_dir = get_app_data_dir(&app)?;
-    everything_filters::load_custom_filters(&app_data_dir)
-}
+    let sources = vec![
+        unified_source_items("apps", apps)
+            .into_iter()
+            .take(limit)
+            .map(UnifiedItem::App)
+            .collect(),
+        unified_source_items("shortcuts", shortcuts)
+            .into_iter()
+            .take(limit)
+            .map(UnifiedItem::Shortcut)
+            .collect(),
+        unified_source_items("file_history", history)
+            .into_iter()
+            .take(limit)
+            .map(UnifiedItem::FileHistory)
+            .collect(),
+        unified_source_items("system_folders", folders)
+            .into_iter()
+            .take(limit)
+            .map(UnifiedItem::SystemFolder)
+            .collect(),
+        unified_source_items("memos", memos)
+            .into_iter()
+            .take(limit)
+            .map(UnifiedItem::Memo)
+            .collect(),
+        unified_source_items("everything", everything)
+            .into_iter()
+            .take(limit)
+            .map(UnifiedItem::Everything)
+            .collect(),
+    ];
 
-#[tauri::command]
-pub fn save_everything_custom_filters(
-    app: tauri::AppHandle,
-    filters: Vec<everything_filters::CustomFilter>,
-) -> Result<(), String> {
-    let app_data_dir = get_app_data_dir(&app)?;
-    everything_filters::save_custom_filters(&app_data_dir, &filters)
+    Ok(search_ranking::merge_results(sources, &query, limit, &usage))
 }
 
 #[tauri::command]
 pub async fn show_settings_window(app: tauri::AppHandle) -> Result<(), String> {
     use tauri::Manager;
 
-    println!("[后端] show_settings_window: START");
+    log_debug!("Window", "show_settings_window: START");
 
     // 1. 尝试获取现有窗口
     if let Some(window) = app.get_webview_window("settings") {
-        println!("[后端] show_settings_window: 窗口已存在，执行显示操作");
+        log_debug!("Window", "show_settings_window: 窗口已存在，执行显示操作");
         window.show().map_err(|e| e.to_string())?;
         window.set_focus().map_err(|e| e.to_string())?;
 
@@ -4259,18 +8086,19 @@ pub async fn show_settings_window(app: tauri::AppHandle) -> Result<(), String> {
             tokio::time::sleep(std::time::Duration::from_millis(150)).await;
             match window_clone.emit("settings:refresh", ()) {
                 Ok(_) => {
-                    println!("[后端] show_settings_window: Refresh event emitted successfully");
+                    log_debug!("Window", "show_settings_window: Refresh event emitted successfully");
                 }
                 Err(e) => {
-                    println!(
-                        "[后端] show_settings_window: ERROR emitting refresh event: {}",
+                    log_error!(
+                        "Window",
+                        "show_settings_window: ERROR emitting refresh event: {}",
                         e
                     );
                 }
             }
         });
     } else {
-        println!("[后端] show_settings_window: 窗口不存在，开始动态创建");
+        log_debug!("Window", "show_settings_window: 窗口不存在，开始动态创建");
 
         // 2. 动态创建窗口
         let window = tauri::WebviewWindowBuilder::new(
@@ -4284,8 +8112,9 @@ pub async fn show_settings_window(app: tauri::AppHandle) -> Result<(), String> {
         .center()
         .build()
         .map_err(|e| format!("创建设置窗口失败: {}", e))?;
+        attach_window_geometry(&app, &window);
 
-        println!("[后端] show_settings_window: 窗口创建成功");
+        log_debug!("Window", "show_settings_window: 窗口创建成功");
 
         // 确保新建的设置窗口出现在前台并获得焦点，避免用户误以为无响应
         window
@@ -4299,11 +8128,12 @@ pub async fn show_settings_window(app: tauri::AppHandle) -> Result<(), String> {
             tokio::time::sleep(std::time::Duration::from_millis(200)).await;
             match window_clone.emit("settings:refresh", ()) {
                 Ok(_) => {
-                    println!("[后端] show_settings_window: Refresh event emitted for new window");
+                    log_debug!("Window", "show_settings_window: Refresh event emitted for new window");
                 }
                 Err(e) => {
-                    println!(
-                        "[后端] show_settings_window: ERROR emitting refresh event: {}",
+                    log_error!(
+                        "Window",
+                        "show_settings_window: ERROR emitting refresh event: {}",
                         e
                     );
                 }
@@ -4311,7 +8141,8 @@ pub async fn show_settings_window(app: tauri::AppHandle) -> Result<(), String> {
         });
     }
 
-    println!("[后端] show_settings_window: END");
+    log_debug!("Window", "show_settings_window: END");
+    remember_tool_window("settings");
     Ok(())
 }
 
@@ -4331,6 +8162,10 @@ pub fn save_hotkey_config(
     let mut settings = settings::load_settings(&app_data_dir)?;
     settings.hotkey = Some(config.clone());
     settings::save_settings(&app_data_dir, &settings)?;
+
+    if let Some(item) = app.try_state::<LauncherMenuItem>() {
+        let _ = item.0.set_text(launcher_menu_text(Some(&config)));
+    }
     
     // 更新已注册的快捷键
     #[cfg(target_os = "windows")]
@@ -4355,15 +8190,15 @@ pub fn save_hotkey_config(
 pub async fn show_hotkey_settings(app: tauri::AppHandle) -> Result<(), String> {
     use tauri::Manager;
 
-    println!("[后端] show_hotkey_settings: START");
+    log_debug!("Window", "show_hotkey_settings: START");
 
     // 1. 尝试获取现有窗口
     if let Some(window) = app.get_webview_window("hotkey-settings") {
-        println!("[后端] show_hotkey_settings: 窗口已存在，执行显示操作");
+        log_debug!("Window", "show_hotkey_settings: 窗口已存在，执行显示操作");
         window.show().map_err(|e| e.to_string())?;
         window.set_focus().map_err(|e| e.to_string())?;
     } else {
-        println!("[后端] show_hotkey_settings: 窗口不存在，开始动态创建");
+        log_debug!("Window", "show_hotkey_settings: 窗口不存在，开始动态创建");
 
         // 2. 动态创建窗口
         let window = tauri::WebviewWindowBuilder::new(
@@ -4377,14 +8212,58 @@ pub async fn show_hotkey_settings(app: tauri::AppHandle) -> Result<(), String> {
         .center()
         .build()
         .map_err(|e| format!("创建快捷键设置窗口失败: {}", e))?;
+        attach_window_geometry(&app, &window);
 
-        println!("[后端] show_hotkey_settings: 窗口创建成功");
+        log_debug!("Window", "show_hotkey_settings: 窗口创建成功");
     }
 
-    println!("[后端] show_hotkey_settings: END");
+    log_debug!("Window", "show_hotkey_settings: END");
+    remember_tool_window("hotkey-settings");
+    Ok(())
+}
+
+/// 托盘菜单中的“暂停快捷键”勾选项，切换状态时同步勾选
+pub struct HotkeyPauseMenuItem(pub tauri::menu::CheckMenuItem<tauri::Wry>);
+
+/// 托盘菜单中的“显示启动器 (<快捷键>)”项，快捷键变化时更新文字
+pub struct LauncherMenuItem(pub tauri::menu::MenuItem<tauri::Wry>);
+
+pub(crate) fn launcher_menu_text(hotkey: Option<&settings::HotkeyConfig>) -> String {
+    format!("显示启动器 ({})", settings::launcher_hotkey_label(hotkey))
+}
+
+/// 全局暂停或恢复所有快捷键（不注销注册），并持久化到设置
+pub(crate) fn apply_hotkeys_enabled(app: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    crate::hotkey_handler::windows::set_hotkeys_enabled(enabled);
+
+    let app_data_dir = get_app_data_dir(app)?;
+    let mut settings = settings::load_settings(&app_data_dir)?;
+    settings.hotkeys_enabled = enabled;
+    settings::save_settings(&app_data_dir, &settings)?;
+
+    if let Some(item) = app.try_state::<HotkeyPauseMenuItem>() {
+        let _ = item.0.set_checked(!enabled);
+    }
+    if let Err(e) = app.emit("hotkeys-enabled-changed", enabled) {
+        log_error!(
+            "Hotkey",
+            "Failed to emit hotkeys-enabled-changed event: {}",
+            e
+        );
+    }
     Ok(())
 }
 
+#[tauri::command]
+pub fn set_hotkeys_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    apply_hotkeys_enabled(&app, enabled)
+}
+
+#[tauri::command]
+pub fn get_hotkeys_enabled() -> bool {
+    crate::hotkey_handler::windows::hotkeys_enabled()
+}
+
 #[tauri::command]
 pub fn get_plugin_hotkeys(app: tauri::AppHandle) -> Result<std::collections::HashMap<String, settings::HotkeyConfig>, String> {
     let app_data_dir = get_app_data_dir(&app)?;
@@ -4402,10 +8281,10 @@ pub fn save_plugin_hotkeys(
     settings.plugin_hotkeys = plugin_hotkeys.clone();
     settings::save_settings(&app_data_dir, &settings)?;
     
-    // 更新后端快捷键注册
+    // 更新后端快捷键注册（跳过已禁用的插件）
     #[cfg(target_os = "windows")]
     {
-        if let Err(e) = crate::hotkey_handler::windows::update_plugin_hotkeys(plugin_hotkeys.clone()) {
+        if let Err(e) = crate::hotkey_handler::windows::update_plugin_hotkeys(settings.enabled_plugin_hotkeys()) {
             eprintln!("Failed to update plugin hotkeys: {}", e);
         }
         
@@ -4441,8 +8320,8 @@ pub fn save_plugin_hotkey(
     // 更新后端快捷键注册
     #[cfg(target_os = "windows")]
     {
-        if let Some(hotkey) = config_clone {
-            // 注册新的快捷键
+        if let Some(hotkey) = config_clone.filter(|_| settings.is_plugin_enabled(&plugin_id)) {
+            // 注册新的快捷键（插件已禁用时只保存不注册）
             if let Err(e) = crate::hotkey_handler::windows::register_plugin_hotkey(plugin_id.clone(), hotkey) {
                 eprintln!("Failed to register plugin hotkey: {}", e);
             }
@@ -4670,14 +8549,99 @@ mod startup {
         }
     }
 
-    /// 设置开机启动
-    pub fn enable_startup() -> Result<(), String> {
+    /// 读取 Run 键中登记的启动命令，不存在时返回 None
+    fn read_startup_value() -> Result<Option<String>, String> {
+        let hkey = match open_registry_key(HKEY_CURRENT_USER, REGISTRY_PATH, KEY_QUERY_VALUE) {
+            Ok(key) => key,
+            Err(_) => return Ok(None),
+        };
+        let value_name_wide = to_wide_string(APP_NAME);
+
+        let mut value_data: Vec<u16> = vec![0; 260];
+        let result = loop {
+            let mut value_type: u32 = 0;
+            let mut value_size = (value_data.len() * std::mem::size_of::<u16>()) as u32;
+            let result = unsafe {
+                RegQueryValueExW(
+                    hkey,
+                    value_name_wide.as_ptr(),
+                    std::ptr::null_mut(),
+                    &mut value_type,
+                    value_data.as_mut_ptr() as *mut u8,
+                    &mut value_size,
+                )
+            };
+            // ERROR_MORE_DATA：缓冲区不够，按返回的大小重试
+            if result == 234 {
+                value_data = vec![0; (value_size as usize).div_ceil(2)];
+                continue;
+            }
+            if result == 0 && value_type != REG_SZ {
+                break Err("Startup registry value is not a string".to_string());
+            }
+            value_data.truncate(value_size as usize / 2);
+            break Ok(result);
+        };
+        unsafe {
+            RegCloseKey(hkey);
+        }
+
+        match result? {
+            0 => {
+                let end = value_data.iter().position(|&c| c == 0).unwrap_or(value_data.len());
+                Ok(Some(String::from_utf16_lossy(&value_data[..end])))
+            }
+            2 => Ok(None), // ERROR_FILE_NOT_FOUND
+            code => Err(format!("Failed to read registry value: error code {}", code)),
+        }
+    }
+
+    /// 写入 Run 键的启动命令：没有参数时只有 exe 路径，有参数时路径加引号
+    fn startup_command(exe_path: &str, args: &str) -> String {
+        if args.is_empty() {
+            exe_path.to_string()
+        } else {
+            format!("\"{}\" {}", exe_path, args)
+        }
+    }
+
+    /// 登记的启动命令是否为 exe_path 加 args：路径去掉引号和 \\?\ 前缀，忽略大小写和分隔符差异
+    pub fn startup_value_matches(registered: &str, exe_path: &str, args: &str) -> bool {
+        fn normalize(path: &str) -> String {
+            let path = path.trim();
+            let path = path.strip_prefix(r"\\?\").unwrap_or(path);
+            path.replace('/', "\\").to_lowercase()
+        }
+        // 带引号时引号内为路径、其后为参数；不带引号时整体视为路径
+        let registered = registered.trim();
+        let (path, registered_args) = match registered.strip_prefix('"') {
+            Some(rest) => rest.split_once('"').unwrap_or((rest, "")),
+            None => (registered, ""),
+        };
+        normalize(path) == normalize(exe_path) && registered_args.trim() == args.trim()
+    }
+
+    /// 已启用开机启动但登记的命令不是当前 exe 加 args（应用被移动、更新或启动选项变化）时重写，
+    /// 返回是否修复
+    pub fn repair_startup_entry(args: &str) -> Result<bool, String> {
+        let Some(registered) = read_startup_value()? else {
+            return Ok(false);
+        };
+        if startup_value_matches(&registered, &get_exe_path()?, args) {
+            return Ok(false);
+        }
+        enable_startup(args)?;
+        Ok(true)
+    }
+
+    /// 设置开机启动，args 为附加的启动参数（延迟、最小化）
+    pub fn enable_startup(args: &str) -> Result<(), String> {
         let exe_path = get_exe_path()?;
         // Run 键应该总是存在的，使用 KEY_ALL_ACCESS 以确保可以写入
         let hkey = open_registry_key(HKEY_CURRENT_USER, REGISTRY_PATH, KEY_ALL_ACCESS)?;
 
         let value_name_wide = to_wide_string(APP_NAME);
-        let value_data_wide = to_wide_string(&exe_path);
+        let value_data_wide = to_wide_string(&startup_command(&exe_path, args));
 
         unsafe {
             let result = RegSetValueExW(
@@ -4729,13 +8693,17 @@ mod startup {
         Err("Startup is only supported on Windows".to_string())
     }
 
-    pub fn enable_startup() -> Result<(), String> {
+    pub fn enable_startup(_args: &str) -> Result<(), String> {
         Err("Startup is only supported on Windows".to_string())
     }
 
     pub fn disable_startup() -> Result<(), String> {
         Err("Startup is only supported on Windows".to_string())
     }
+
+    pub fn repair_startup_entry(_args: &str) -> Result<bool, String> {
+        Err("Startup is only supported on Windows".to_string())
+    }
 }
 
 /// 检查是否已设置开机启动
@@ -4744,25 +8712,42 @@ pub fn is_startup_enabled() -> Result<bool, String> {
     startup::is_startup_enabled()
 }
 
+/// 当前设置对应的开机启动参数
+fn startup_args(app: &tauri::AppHandle) -> String {
+    get_app_data_dir(app)
+        .and_then(|dir| settings::load_settings(&dir))
+        .map(|s| settings::StartupLaunchOptions::from_settings(&s).to_args())
+        .unwrap_or_default()
+}
+
 /// 设置开机启动
 #[tauri::command]
-pub fn set_startup_enabled(enabled: bool) -> Result<(), String> {
+pub fn set_startup_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
     if enabled {
-        startup::enable_startup()
+        startup::enable_startup(&startup_args(&app))
     } else {
         startup::disable_startup()
     }
 }
 
-/// 同步开机启动设置（内部使用）
-pub fn sync_startup_setting(startup_enabled: bool) -> Result<(), String> {
+/// 开机启动项登记的命令与当前 exe 和启动选项不一致（应用被移动或更新）时重写，返回是否修复
+#[tauri::command]
+pub fn repair_startup_entry(app: tauri::AppHandle) -> Result<bool, String> {
+    startup::repair_startup_entry(&startup_args(&app))
+}
+
+/// 同步开机启动设置（内部使用）；已启用时顺带修复失效的启动路径和参数
+pub fn sync_startup_setting(settings: &settings::Settings) -> Result<(), String> {
+    let args = settings::StartupLaunchOptions::from_settings(settings).to_args();
     let current = startup::is_startup_enabled().unwrap_or(false);
-    if current != startup_enabled {
-        if startup_enabled {
-            startup::enable_startup()?;
+    if current != settings.startup_enabled {
+        if settings.startup_enabled {
+            startup::enable_startup(&args)?;
         } else {
             startup::disable_startup()?;
         }
+    } else if settings.startup_enabled && startup::repair_startup_entry(&args)? {
+        log_info!("Startup", "开机启动项已更新为当前程序位置和启动选项");
     }
     Ok(())
 }
@@ -4771,4 +8756,959 @@ pub fn sync_startup_setting(startup_enabled: bool) -> Result<(), String> {
 #[tauri::command]
 pub fn get_app_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
-}
\ No newline at end of file
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    pub generated_at: String,
+    pub app_version: String,
+    pub os: diagnostics::OsInfo,
+    pub index_status: diagnostics::Probe<IndexStatus>,
+    pub log_dir: String,
+    pub hotkey_log_path: diagnostics::Probe<String>,
+    /// 文件历史数据库（SQLite）的大小，路径见 index_status.file_history
+    pub history_db_size: diagnostics::Probe<u64>,
+    pub plugin_count: diagnostics::Probe<usize>,
+    pub startup_enabled: diagnostics::Probe<bool>,
+    pub lock_file_path: String,
+    /// 已脱敏的设置
+    pub settings: diagnostics::Probe<serde_json::Value>,
+}
+
+fn build_diagnostics(app: &tauri::AppHandle) -> Diagnostics {
+    #[cfg(target_os = "windows")]
+    let hotkey_log_path = crate::hotkey_handler::windows::init_hotkey_log()
+        .map(|p| p.to_string_lossy().to_string())
+        .ok_or_else(|| "无法初始化快捷键日志".to_string());
+    #[cfg(not(target_os = "windows"))]
+    let hotkey_log_path: Result<String, String> = Err("快捷键日志仅在 Windows 上可用".to_string());
+
+    let settings = get_app_data_dir(app)
+        .and_then(|dir| settings::load_settings(&dir))
+        .and_then(|s| serde_json::to_value(s).map_err(|e| e.to_string()))
+        .map(|mut value| {
+            diagnostics::redact(&mut value);
+            value
+        });
+    let history_db_size = get_app_data_dir(app).and_then(|dir| {
+        fs::metadata(db::get_db_path(&dir))
+            .map(|meta| meta.len())
+            .map_err(|e| format!("读取数据库文件失败: {}", e))
+    });
+    let plugin_count = get_plugin_directory(app.clone())
+        .and_then(|dir| scan_plugin_directory(app.clone(), dir, None))
+        .map(|result| match result {
+            PluginScanResult::Dirs(dirs) => dirs.len(),
+            PluginScanResult::WithManifests(plugins) => plugins.len(),
+        });
+
+    Diagnostics {
+        generated_at: chrono::Local::now().to_rfc3339(),
+        app_version: get_app_version(),
+        os: diagnostics::OsInfo::current(),
+        index_status: get_index_status(app.clone()).into(),
+        log_dir: crate::logger::get_log_dir().to_string_lossy().to_string(),
+        hotkey_log_path: hotkey_log_path.into(),
+        history_db_size: history_db_size.into(),
+        plugin_count: plugin_count.into(),
+        startup_enabled: is_startup_enabled().into(),
+        lock_file_path: crate::get_lock_file_path().to_string_lossy().to_string(),
+        settings: settings.into(),
+    }
+}
+
+/// 汇总版本、系统、Everything 与索引状态、数据库大小、插件数、开机启动、锁文件和日志路径、
+/// 脱敏后的设置；单项探测失败时记录在对应字段中，不影响其他项
+#[tauri::command]
+pub fn get_diagnostics(app: tauri::AppHandle) -> Diagnostics {
+    build_diagnostics(&app)
+}
+
+/// 与 get_diagnostics 内容相同，返回格式化的 JSON，便于粘贴到问题反馈中；
+/// write_to_logs 为 true 时同时写入日志目录
+#[tauri::command]
+pub fn collect_diagnostics(app: tauri::AppHandle, write_to_logs: Option<bool>) -> Result<String, String> {
+    let report = build_diagnostics(&app);
+    let json = serde_json::to_string_pretty(&report)
+        .map_err(|e| format!("序列化诊断信息失败: {}", e))?;
+
+    if write_to_logs.unwrap_or(false) {
+        let log_dir = crate::logger::get_log_dir();
+        fs::create_dir_all(&log_dir).map_err(|e| format!("创建日志目录失败: {}", e))?;
+        let file_name = format!(
+            "diagnostics_{}.json",
+            chrono::Local::now().format("%Y%m%d_%H%M%S")
+        );
+        fs::write(log_dir.join(file_name), &json)
+            .map_err(|e| format!("写入诊断文件失败: {}", e))?;
+    }
+    Ok(json)
+}
+
+/// 单位与汇率换算，例如 "10 km to miles"、"72 f to c"、"100 usd to cny"
+/// 输入不是换算表达式时返回 None
+#[tauri::command]
+pub fn convert_units(
+    input: String,
+    app: tauri::AppHandle,
+) -> Result<Option<unit_convert::ConversionResult>, String> {
+    let app_data_dir = get_app_data_dir(&app)?;
+    let settings = settings::load_settings(&app_data_dir)?;
+    let rates = unit_convert::CurrencyRates::with_overrides(&settings.currency_rates);
+    Ok(unit_convert::convert_expression(&input, &rates))
+}
+
+/// 启动器内联计算，例如 "12*(3+4)"、"200 * 15%"；输入不是算术表达式时返回 None
+#[tauri::command]
+pub fn evaluate_expression(input: String) -> Result<Option<expression::ExpressionResult>, String> {
+    expression::evaluate_expression(&input)
+}
+
+/// 开发者小工具，例如 "0xFF to dec"、"md5 hello"、"base64 -d aGVsbG8="；
+/// 输入不是已知命令时返回 None
+#[tauri::command]
+pub fn dev_tools(input: String) -> Result<Option<crate::dev_tools::DevToolResult>, String> {
+    crate::dev_tools::run(&input)
+}
+
+/// 启动任意程序（可带参数和工作目录），不等待退出，返回进程 ID
+#[tauri::command(rename_all = "camelCase")]
+pub fn run_command(
+    program: String,
+    args: Vec<String>,
+    working_dir: Option<String>,
+) -> Result<u32, String> {
+    process_utils::run_command(&program, &args, working_dir.as_deref())
+}
+
+/// 列出正在运行的进程（名称、PID、内存占用）
+#[tauri::command]
+pub async fn list_processes() -> Result<Vec<process_utils::ProcessInfo>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        async_runtime::spawn_blocking(process_utils::windows::list_processes)
+            .await
+            .map_err(|e| format!("获取进程列表失败: {}", e))?
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("进程管理仅在 Windows 上可用".to_string())
+    }
+}
+
+/// 结束指定 PID 的进程（系统关键进程受保护）
+#[tauri::command]
+pub fn kill_process(pid: u32) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        process_utils::windows::kill_process(pid)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = pid;
+        Err("进程管理仅在 Windows 上可用".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{temp_dir, temp_path};
+
+    #[test]
+    fn detects_bom_encodings() {
+        assert_eq!(detect_bom(b"\xEF\xBB\xBFabc"), Some(TextEncoding::Utf8Bom));
+        assert_eq!(detect_bom(b"\xFF\xFEa\0"), Some(TextEncoding::Utf16Le));
+        assert_eq!(detect_bom(b"\xFE\xFF\0a"), Some(TextEncoding::Utf16Be));
+        assert_eq!(detect_bom(b"abc"), None);
+    }
+
+    #[test]
+    fn prefers_utf8_and_tolerates_truncated_tail() {
+        assert_eq!(
+            guess_encoding_without_bom("中文".as_bytes(), false),
+            TextEncoding::Utf8
+        );
+        let cut = &"中文".as_bytes()[..4];
+        assert_eq!(guess_encoding_without_bom(cut, true), TextEncoding::Utf8);
+    }
+
+    #[test]
+    fn detects_gbk_chinese_text() {
+        let (gbk, _, _) = encoding_rs::GBK.encode("中文测试，这是一段简体中文文本。");
+        assert_eq!(guess_encoding_without_bom(&gbk, false), TextEncoding::Gbk);
+        assert_eq!(
+            decode_text_buffer(&gbk, TextEncoding::Gbk),
+            "中文测试，这是一段简体中文文本。"
+        );
+        // 截断在双字节字符中间
+        assert_eq!(
+            guess_encoding_without_bom(&gbk[..gbk.len() - 1], true),
+            TextEncoding::Gbk
+        );
+        // 截断恰好落在两个字符之间，末尾是完整的尾字节
+        assert_eq!(
+            guess_encoding_without_bom(&gbk[..gbk.len() - 2], true),
+            TextEncoding::Gbk
+        );
+        let (mixed, _, _) = encoding_rs::GBK.encode("ReFast 中文 v2 测试");
+        for cut in 1..=4 {
+            assert_eq!(
+                guess_encoding_without_bom(&mixed[..mixed.len() - cut], true),
+                TextEncoding::Gbk,
+                "cut {}",
+                cut
+            );
+        }
+    }
+
+    #[test]
+    fn latin1_text_is_not_gbk() {
+        let (latin1, _, _) = encoding_rs::WINDOWS_1252.encode("naïve Señora");
+        assert!(encoding_rs::GBK
+            .decode_without_bom_handling_and_without_replacement(&latin1)
+            .is_some());
+        assert_eq!(
+            guess_encoding_without_bom(&latin1, false),
+            TextEncoding::Utf8
+        );
+        assert!(!looks_like_gbk(b"\xE9t\xE9"));
+    }
+
+    #[test]
+    fn decodes_utf16_without_bom_bytes() {
+        let mut le = vec![0xFF, 0xFE];
+        le.extend("a\n中".encode_utf16().flat_map(|u| u.to_le_bytes()));
+        assert_eq!(decode_text_buffer(&le, TextEncoding::Utf16Le), "a\n中");
+    }
+
+    #[test]
+    fn counts_lines_per_encoding() {
+        let path = temp_path("commands", "lines.txt");
+        fs::write(&path, "one\ntwo\nthree").unwrap();
+        assert_eq!(count_file_lines(&path, TextEncoding::Utf8), Some(3));
+        fs::write(&path, "one\ntwo\n").unwrap();
+        assert_eq!(count_file_lines(&path, TextEncoding::Utf8), Some(2));
+
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("一\n二\n三".encode_utf16().flat_map(|u| u.to_le_bytes()));
+        fs::write(&path, &utf16).unwrap();
+        assert_eq!(count_file_lines(&path, TextEncoding::Utf16Le), Some(3));
+
+        fs::write(&path, "").unwrap();
+        assert_eq!(count_file_lines(&path, TextEncoding::Utf8), Some(0));
+        fs::remove_file(&path).ok();
+    }
+
+    fn write_file(root: &Path, relative: &str, content: &[u8]) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn replace_params(folder: &Path, search: &str, replace: &str) -> FileReplaceParams {
+        FileReplaceParams {
+            folder_path: folder.to_string_lossy().to_string(),
+            search_text: search.to_string(),
+            replace_text: replace.to_string(),
+            file_extensions: Vec::new(),
+            use_regex: false,
+            case_sensitive: true,
+            backup_folder: false,
+            replace_file_name: false,
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+            binary_mode: false,
+        }
+    }
+
+    fn owned_pairs(pairs: &[(&str, &str)]) -> PreviewDiff {
+        pairs
+            .iter()
+            .map(|(before, after)| (before.to_string(), after.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn preview_diff_applies_regex_replacement_per_line() {
+        let pattern = build_replace_pattern(r"(\w+)@example\.com", true, true).unwrap();
+        let content = "first line\ncontact: alice@example.com, bob@example.com\r\nlast";
+        assert_eq!(
+            build_preview_diff(content, &pattern, "$1@test.org"),
+            owned_pairs(&[(
+                "contact: alice@example.com, bob@example.com",
+                "contact: alice@test.org, bob@test.org"
+            )])
+        );
+    }
+
+    #[test]
+    fn preview_diff_matches_case_insensitively() {
+        let pattern = build_replace_pattern("hello", false, false).unwrap();
+        assert_eq!(
+            build_preview_diff("Say HELLO\nhello, Hello", &pattern, "bye"),
+            owned_pairs(&[("Say HELLO", "Say bye"), ("hello, Hello", "bye, bye")])
+        );
+    }
+
+    #[test]
+    fn preview_diff_is_capped_and_only_returned_when_previewing() {
+        let pattern = build_replace_pattern("x", false, true).unwrap();
+        let diffs = build_preview_diff(&"x\n".repeat(20), &pattern, "y");
+        assert_eq!(diffs.len(), MAX_PREVIEW_DIFFS_PER_FILE);
+
+        let root = temp_dir("commands", "preview-diff");
+        write_file(&root, "content.txt", b"old value");
+        write_file(&root, "old_name.bin", b"nothing here");
+        let mut params = replace_params(&root, "old", "new");
+        params.replace_file_name = true;
+
+        let preview =
+            process_file_replace(&params, false, None, ReplaceControl::default()).unwrap();
+        assert_eq!(preview.total_matches, 2);
+        for result in &preview.results {
+            if result.file_path.ends_with("content.txt") {
+                assert_eq!(
+                    result.preview_diff,
+                    Some(owned_pairs(&[("old value", "new value")]))
+                );
+            } else {
+                // 仅文件名命中时没有内容对比
+                assert!(result.file_path.ends_with("new_name.bin"));
+                assert_eq!(result.preview_diff, None);
+            }
+        }
+
+        let executed =
+            process_file_replace(&params, true, None, ReplaceControl::default()).unwrap();
+        assert!(executed.results.iter().all(|r| r.preview_diff.is_none()));
+        assert_eq!(
+            fs::read_to_string(root.join("content.txt")).unwrap(),
+            "new value"
+        );
+        assert!(root.join("new_name.bin").exists());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn cancel_flag_halts_the_walk_with_partial_results() {
+        let root = temp_dir("commands", "replace-cancel");
+        for i in 0..50 {
+            write_file(&root, &format!("dir{}/file{:02}.txt", i % 5, i), b"needle");
+        }
+        let params = replace_params(&root, "needle", "pin");
+
+        // 开始前已取消：不处理任何文件
+        let cancelled = AtomicBool::new(true);
+        let control = ReplaceControl {
+            cancel_flag: Some(&cancelled),
+            on_progress: None,
+        };
+        let response = process_file_replace(&params, false, None, control).unwrap();
+        assert!(response.cancelled);
+        assert_eq!(response.total_files, 0);
+
+        // 扫描到第 3 个文件时取消：立即停止并返回已有结果
+        let flag = AtomicBool::new(false);
+        let mut on_progress = |scanned: usize, _matches: usize, _current: &Path| {
+            if scanned >= 3 {
+                flag.store(true, Ordering::Relaxed);
+            }
+        };
+        let control = ReplaceControl {
+            cancel_flag: Some(&flag),
+            on_progress: Some(&mut on_progress),
+        };
+        let response = process_file_replace(&params, false, None, control).unwrap();
+        assert!(response.cancelled);
+        assert_eq!(response.total_files, 3);
+        assert_eq!(response.results.len(), 3);
+        assert_eq!(response.total_matches, 3);
+
+        let full = process_file_replace(&params, false, None, ReplaceControl::default()).unwrap();
+        assert!(!full.cancelled);
+        assert_eq!(full.total_files, 50);
+        fs::remove_dir_all(&root).ok();
+    }
+
+    /// 预览替换，返回命中文件相对于 root 的路径（已排序）
+    fn replaced_files(root: &Path, params: &FileReplaceParams) -> Vec<String> {
+        let response =
+            process_file_replace(params, false, None, ReplaceControl::default()).unwrap();
+        let mut files: Vec<String> = response
+            .results
+            .iter()
+            .map(|r| {
+                Path::new(&r.file_path)
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn glob_tree(name: &str) -> PathBuf {
+        let root = temp_dir("commands", name);
+        for file in [
+            "src/a.ts",
+            "src/sub/b.ts",
+            "src/c.js",
+            "node_modules/pkg/d.ts",
+            "e.ts",
+        ] {
+            write_file(&root, file, b"needle");
+        }
+        root
+    }
+
+    #[test]
+    fn include_globs_select_matching_files() {
+        let root = glob_tree("glob-include");
+        let mut params = replace_params(&root, "needle", "pin");
+        params.include_globs = vec!["src/**/*.ts".to_string()];
+        assert_eq!(replaced_files(&root, &params), ["src/a.ts", "src/sub/b.ts"]);
+
+        // 不含 / 的模式只匹配名称
+        params.include_globs = vec!["*.JS".to_string()];
+        assert_eq!(replaced_files(&root, &params), ["src/c.js"]);
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn exclude_globs_skip_whole_directories() {
+        let root = glob_tree("glob-exclude");
+        let mut params = replace_params(&root, "needle", "pin");
+        params.exclude_globs = vec!["**/node_modules/**".to_string()];
+        assert_eq!(
+            replaced_files(&root, &params),
+            ["e.ts", "src/a.ts", "src/c.js", "src/sub/b.ts"]
+        );
+
+        params.replace_file_name = true;
+        params.search_text = "node_modules".to_string();
+        // 被排除的文件夹不会被重命名
+        assert!(replaced_files(&root, &params).is_empty());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn globs_combine_with_extension_filter() {
+        let root = glob_tree("glob-combined");
+        let mut params = replace_params(&root, "needle", "pin");
+        params.include_globs = vec!["src/**".to_string()];
+        params.exclude_globs = vec!["src/sub".to_string()];
+        params.file_extensions = vec!["ts".to_string()];
+        assert_eq!(replaced_files(&root, &params), ["src/a.ts"]);
+
+        params.file_extensions = vec!["js".to_string()];
+        assert_eq!(replaced_files(&root, &params), ["src/c.js"]);
+
+        params.include_globs = vec!["[".to_string()];
+        assert!(process_file_replace(&params, false, None, ReplaceControl::default()).is_err());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn parses_hex_and_text_byte_literals() {
+        assert_eq!(
+            parse_byte_literal("hex:DE AD 0xBE ef").unwrap(),
+            [0xDE, 0xAD, 0xBE, 0xEF]
+        );
+        assert_eq!(parse_byte_literal("HEX:0a0B").unwrap(), [0x0A, 0x0B]);
+        assert_eq!(parse_byte_literal("plain").unwrap(), b"plain");
+        assert!(parse_byte_literal("hex:ABC").is_err());
+        assert!(parse_byte_literal("hex:zz").is_err());
+    }
+
+    #[test]
+    fn binary_mode_replaces_bytes_the_text_path_skips() {
+        let root = temp_dir("commands", "replace-binary");
+        let original = [0xFF, 0xFE, 0x00, b'A', b'B', 0x80, b'a', b'b'];
+        write_file(&root, "blob.dat", &original);
+        let mut params = replace_params(&root, "hex:00 41 42", "hex:01 02");
+
+        // 文本模式：不是 UTF-8，跳过
+        let text = process_file_replace(&params, false, None, ReplaceControl::default()).unwrap();
+        assert_eq!(text.total_matches, 0);
+
+        params.binary_mode = true;
+        let preview =
+            process_file_replace(&params, false, None, ReplaceControl::default()).unwrap();
+        assert_eq!(preview.total_matches, 1);
+        assert_eq!(
+            preview.results[0].preview_diff,
+            Some(owned_pairs(&[(
+                "FF FE 00 41 42 80 61 62",
+                "FF FE 01 02 80 61 62"
+            )]))
+        );
+        assert_eq!(fs::read(root.join("blob.dat")).unwrap(), original);
+
+        process_file_replace(&params, true, None, ReplaceControl::default()).unwrap();
+        assert_eq!(
+            fs::read(root.join("blob.dat")).unwrap(),
+            [0xFF, 0xFE, 0x01, 0x02, 0x80, b'a', b'b']
+        );
+
+        // 不区分大小写时只折叠 ASCII
+        params.search_text = "AB".to_string();
+        params.replace_text = "$0".to_string();
+        params.case_sensitive = false;
+        let folded = process_file_replace(&params, true, None, ReplaceControl::default()).unwrap();
+        assert_eq!(folded.total_matches, 1);
+        assert_eq!(
+            fs::read(root.join("blob.dat")).unwrap(),
+            [0xFF, 0xFE, 0x01, 0x02, 0x80, b'$', b'0']
+        );
+
+        params.use_regex = true;
+        assert!(process_file_replace(&params, false, None, ReplaceControl::default()).is_err());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    fn sample_database(path: &Path) {
+        let conn = rusqlite::Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT);
+             CREATE TABLE shortcuts (id TEXT PRIMARY KEY);
+             CREATE TABLE file_history (path TEXT PRIMARY KEY);
+             INSERT INTO settings VALUES ('settings', '{}');",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn recognizes_compressed_backup_names() {
+        assert!(is_compressed_backup(Path::new("re-fast-backup_1.db.gz")));
+        assert!(is_compressed_backup(Path::new("RE-FAST-BACKUP_1.DB.GZ")));
+        assert!(!is_compressed_backup(Path::new("re-fast-backup_1.db")));
+        assert!(!is_compressed_backup(Path::new("archive.gz")));
+    }
+
+    /// 等待进行中的应用扫描满足 condition
+    #[cfg(target_os = "windows")]
+    fn wait_for_app_scan(condition: impl Fn(&AppScanManager) -> bool) {
+        while !condition(&APP_SCAN_MANAGER.0.lock().unwrap()) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn merged_app_scan_callers_receive_progress() {
+        let dir = temp_dir("commands", "app-scan");
+        let (resume_tx, resume_rx) = std::sync::mpsc::channel::<()>();
+        let (first_tx, first_rx) = std::sync::mpsc::channel();
+        let first_dir = dir.clone();
+        let first = std::thread::spawn(move || {
+            run_app_scan(&first_dir, first_tx, |tx| {
+                tx.send((10, "scanning".to_string())).unwrap();
+                resume_rx.recv().unwrap();
+                tx.send((100, "done".to_string())).unwrap();
+                Ok(Vec::new())
+            })
+        });
+        wait_for_app_scan(|m| m.last_progress.is_some());
+
+        // 扫描进行中再次调用：不启动新扫描，先收到最近一次进度，之后的进度也会转发给它
+        let (second_tx, second_rx) = std::sync::mpsc::channel();
+        let second_dir = dir.clone();
+        let second = std::thread::spawn(move || {
+            run_app_scan(&second_dir, second_tx, |_| {
+                panic!("merged call must not scan")
+            })
+        });
+        wait_for_app_scan(|m| m.progress_listeners.len() == 2);
+        resume_tx.send(()).unwrap();
+
+        assert_eq!(first.join().unwrap().unwrap().len(), 0);
+        assert_eq!(second.join().unwrap().unwrap().len(), 0);
+        let expected = vec![(10, "scanning".to_string()), (100, "done".to_string())];
+        assert_eq!(first_rx.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(second_rx.iter().collect::<Vec<_>>(), expected);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn compressed_backup_round_trips() {
+        let src = temp_path("commands", "gz-src.db");
+        let gz = temp_path("commands", "gz-src.db.gz");
+        let out = temp_path("commands", "gz-out.db");
+        let data: Vec<u8> = (0..100_000u32)
+            .flat_map(|i| (i % 97).to_le_bytes())
+            .collect();
+        fs::write(&src, &data).unwrap();
+
+        compress_file(&src, &gz).unwrap();
+        assert!(fs::metadata(&gz).unwrap().len() < data.len() as u64);
+        assert_eq!(gzip_uncompressed_size(&gz), Some(data.len() as u64));
+        decompress_file(&gz, &out).unwrap();
+        assert_eq!(fs::read(&out).unwrap(), data);
+
+        for path in [&src, &gz, &out] {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn compressed_backup_includes_uncheckpointed_wal_content() {
+        let dir = temp_dir("commands", "backup-wal");
+        let conn = db::get_connection(&dir).unwrap();
+        conn.execute_batch("PRAGMA wal_autocheckpoint = 0;")
+            .unwrap();
+        conn.execute("INSERT INTO settings (key, value) VALUES ('a', '1')", [])
+            .unwrap();
+
+        // 连接仍打开，新写入的行只存在于 -wal 中
+        let gz = dir.join("backup.db.gz");
+        write_backup(&db::get_db_path(&dir), &gz, true).unwrap();
+        drop(conn);
+        assert!(!gz.with_extension("snapshot-tmp").exists());
+
+        let out = dir.join("restored.db");
+        decompress_file(&gz, &out).unwrap();
+        db::verify_backup(&out).unwrap();
+        let count: i64 = rusqlite::Connection::open(&out)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM settings WHERE key = 'a'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verified_backup_source_decompresses_and_checks() {
+        let db_path = temp_path("commands", "verify-src.db");
+        let gz = temp_path("commands", "verify-good.db.gz");
+        let _ = fs::remove_file(&db_path);
+        sample_database(&db_path);
+        compress_file(&db_path, &gz).unwrap();
+
+        assert_eq!(verified_backup_source(&db_path).unwrap(), None);
+        let temp = verified_backup_source(&gz).unwrap().expect("temp file");
+        db::verify_backup(&temp).unwrap();
+        let _ = fs::remove_file(&temp);
+
+        // 解压后不是有效数据库时报错，并清理临时文件
+        let bad_src = temp_path("commands", "verify-bad.txt");
+        let bad_gz = temp_path("commands", "verify-bad.db.gz");
+        fs::write(&bad_src, b"definitely not sqlite").unwrap();
+        compress_file(&bad_src, &bad_gz).unwrap();
+        assert!(verified_backup_source(&bad_gz).is_err());
+        assert!(!bad_gz.with_extension("restore-tmp").exists());
+
+        for path in [&db_path, &gz, &bad_src, &bad_gz] {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn repair_skips_pre_restore_snapshots() {
+        let app_data_dir = temp_dir("commands", "repair-backups");
+        let backup_dir = app_data_dir.join("backups");
+        fs::create_dir_all(&backup_dir).unwrap();
+        let backup = backup_dir.join("re-fast-backup_20240101_000000.db");
+        sample_database(&backup);
+        fs::File::options()
+            .write(true)
+            .open(&backup)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() - Duration::from_secs(3600))
+            .unwrap();
+        // 更新的恢复前快照同样能通过校验，但不应被当作备份恢复
+        let snapshot = backup_dir.join("re-fast-pre-restore_20240102_000000.db");
+        sample_database(&snapshot);
+        let db_path = db::get_db_path(&app_data_dir);
+        fs::write(&db_path, b"corrupt").unwrap();
+
+        let restored = restore_newest_backup(&app_data_dir).unwrap();
+        assert_eq!(restored, Some(backup.to_string_lossy().to_string()));
+        assert_eq!(fs::read(&db_path).unwrap(), fs::read(&backup).unwrap());
+
+        // 只剩恢复前快照时视为没有可用备份
+        fs::remove_file(&backup).unwrap();
+        assert_eq!(restore_newest_backup(&app_data_dir).unwrap(), None);
+        let _ = fs::remove_dir_all(&app_data_dir);
+    }
+
+    #[test]
+    fn reveal_target_trims_separators_and_resolves_relative_paths() {
+        let sep = std::path::MAIN_SEPARATOR;
+        let absolute = std::env::temp_dir().join("refast-reveal");
+        let with_slash = format!("{}{}", absolute.display(), sep);
+        assert_eq!(reveal_target(&with_slash).unwrap(), absolute);
+        assert_eq!(
+            reveal_target("some/relative").unwrap(),
+            std::env::current_dir()
+                .unwrap()
+                .join(display_path("some/relative"))
+        );
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn reveal_target_handles_drive_roots_and_verbatim_prefixes() {
+        assert_eq!(reveal_target(r"C:\").unwrap(), PathBuf::from(r"C:\"));
+        assert_eq!(
+            reveal_target(r"\\?\C:\Windows\").unwrap(),
+            PathBuf::from(r"C:\Windows")
+        );
+        assert_eq!(
+            reveal_target(r"\\?\UNC\server\share\file.txt").unwrap(),
+            PathBuf::from(r"\\server\share\file.txt")
+        );
+        assert_eq!(
+            reveal_target("C:/Users/").unwrap(),
+            PathBuf::from(r"C:\Users")
+        );
+    }
+
+    #[test]
+    fn display_path_strips_verbatim_prefixes() {
+        assert_eq!(
+            display_path("  /tmp/a  "),
+            if cfg!(target_os = "windows") {
+                r"\tmp\a"
+            } else {
+                "/tmp/a"
+            }
+        );
+        assert_eq!(display_path(r"\\?\UNC\server\share"), r"\\server\share");
+        assert_eq!(display_path(r"\\?\C:\x"), r"C:\x");
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn everything_installer_paths_differ_per_arch() {
+        let paths: Vec<PathBuf> = ["x86_64", "x86", "aarch64"]
+            .iter()
+            .map(|arch| everything_installer_path(&everything_installer_for_arch(arch).unwrap()))
+            .collect();
+        assert!(paths.iter().all(|p| p.starts_with(std::env::temp_dir())));
+        assert_ne!(paths[0], paths[1]);
+        assert_ne!(paths[0], paths[2]);
+        assert_ne!(paths[1], paths[2]);
+        assert!(resume_validator_path(&paths[0])
+            .to_string_lossy()
+            .ends_with(".exe.validator"));
+    }
+
+    /// 最小的 PE 文件头：MZ、0x3C 处的偏移和 PE 签名
+    #[cfg(target_os = "windows")]
+    fn write_fake_installer(name: &str) -> (PathBuf, String) {
+        let mut content = b"MZ".to_vec();
+        content.resize(0x3C, 0);
+        content.extend_from_slice(&0x40u32.to_le_bytes());
+        content.extend_from_slice(b"PE\0\0");
+        let path = temp_path("commands", name);
+        fs::write(&path, &content).unwrap();
+        let digest = hash_file::<sha2::Sha256>(&path).unwrap();
+        (path, digest)
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn verified_installer_is_kept() {
+        let (path, digest) = write_fake_installer("installer-ok.exe");
+        let size = fs::metadata(&path).unwrap().len();
+        let installer = EverythingInstaller {
+            url: "https://example.com/setup.exe",
+            sha256: Some(Box::leak(digest.to_uppercase().into_boxed_str())),
+        };
+        keep_verified_installer(&installer, &path, size, size, &digest).unwrap();
+        assert!(path.exists());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn installer_failing_verification_is_deleted() {
+        let pinned = EverythingInstaller {
+            url: "https://example.com/setup.exe",
+            sha256: Some("0000000000000000000000000000000000000000000000000000000000000000"),
+        };
+        let (path, digest) = write_fake_installer("installer-mismatch.exe");
+        let size = fs::metadata(&path).unwrap().len();
+        let error = keep_verified_installer(&pinned, &path, size, size, &digest).unwrap_err();
+        assert!(error.contains("checksum mismatch"), "{}", error);
+        assert!(!path.exists());
+
+        // 没有登记校验值时同样拒绝
+        let unpinned = EverythingInstaller {
+            url: "https://example.com/setup.exe",
+            sha256: None,
+        };
+        let (path, digest) = write_fake_installer("installer-unpinned.exe");
+        assert!(keep_verified_installer(&unpinned, &path, size, size, &digest).is_err());
+        assert!(!path.exists());
+
+        // 下载不完整
+        let (path, digest) = write_fake_installer("installer-short.exe");
+        assert!(keep_verified_installer(&pinned, &path, size - 1, size, &digest).is_err());
+        assert!(!path.exists());
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn installer_is_verified_again_before_running() {
+        let (path, digest) = write_fake_installer("installer-run.exe");
+        let pinned = EverythingInstaller {
+            url: "https://example.com/setup.exe",
+            sha256: Some(Box::leak(digest.into_boxed_str())),
+        };
+        verify_installer_file(&pinned, &path).unwrap();
+
+        // 校验失败时拒绝运行，但不删除前端传入的文件
+        fs::write(&path, b"MZ replaced").unwrap();
+        assert!(verify_installer_file(&pinned, &path).is_err());
+        assert!(path.exists());
+        let _ = fs::remove_file(&path);
+        assert!(verify_installer_file(&pinned, &path).is_err());
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn resume_validator_prefers_strong_etag() {
+        use reqwest::header::{HeaderMap, HeaderValue, ETAG, LAST_MODIFIED};
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(resume_validator(&headers), None);
+        headers.insert(
+            LAST_MODIFIED,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        headers.insert(ETAG, HeaderValue::from_static("W/\"weak\""));
+        assert_eq!(
+            resume_validator(&headers).as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+        headers.insert(ETAG, HeaderValue::from_static("\"strong\""));
+        assert_eq!(resume_validator(&headers).as_deref(), Some("\"strong\""));
+    }
+
+    fn everything_options(json: &str) -> Option<EverythingSearchOptions> {
+        Some(serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn everything_default_limit_follows_settings() {
+        let defaults = settings::Settings::default();
+        assert_eq!(
+            build_everything_query("report", &None, defaults.max_everything_results),
+            (
+                "report".to_string(),
+                settings::default_max_everything_results()
+            )
+        );
+
+        let custom = settings::Settings {
+            max_everything_results: 7,
+            ..settings::Settings::default()
+        };
+        let limit = custom.max_everything_results;
+        assert_eq!(build_everything_query("report", &None, limit).1, 7);
+        assert_eq!(
+            build_everything_query("report", &everything_options("{}"), limit).1,
+            7
+        );
+        // 调用方显式指定的 maxResults 优先，0 视为未指定
+        assert_eq!(
+            build_everything_query("report", &everything_options(r#"{"maxResults": 3}"#), limit).1,
+            3
+        );
+        assert_eq!(
+            build_everything_query("report", &everything_options(r#"{"maxResults": 0}"#), limit).1,
+            7
+        );
+    }
+
+    #[test]
+    fn history_limit_follows_settings() {
+        let dir = temp_path("commands", "history-limit");
+        let _ = fs::remove_dir_all(&dir);
+        settings::save_settings(
+            &dir,
+            &settings::Settings {
+                max_history_results: 2,
+                ..settings::Settings::default()
+            },
+        )
+        .unwrap();
+        let limit = settings::load_settings(&dir).unwrap().max_history_results;
+        assert_eq!(limit, 2);
+
+        let state: std::collections::HashMap<String, file_history::FileHistoryItem> = (0..5u64)
+            .map(|i| {
+                let path = format!("C:\\docs\\report{}.txt", i);
+                let item = file_history::FileHistoryItem {
+                    path: path.clone(),
+                    name: format!("report{}.txt", i),
+                    last_used: i,
+                    use_count: 1,
+                    is_folder: Some(false),
+                    last_action: String::new(),
+                    is_symlink: None,
+                    target: None,
+                    target_exists: None,
+                };
+                (path, item)
+            })
+            .collect();
+        assert_eq!(
+            file_history::search_in_history(&state, "", None, Some(limit)).len(),
+            2
+        );
+        assert_eq!(
+            file_history::search_in_history(&state, "report", None, Some(limit)).len(),
+            2
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn startup_value_matches_quoted_and_unquoted_paths() {
+        let exe = r"C:\Program Files\ReFast\ReFast.exe";
+        assert!(startup::startup_value_matches(exe, exe, ""));
+        assert!(startup::startup_value_matches(&format!("\"{}\"", exe), exe, ""));
+        assert!(startup::startup_value_matches(
+            &format!("\"{}\" --startup-delay=30 --minimized", exe),
+            exe,
+            "--startup-delay=30 --minimized"
+        ));
+        assert!(startup::startup_value_matches(&format!("  {}  ", exe), exe, ""));
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn startup_value_matches_ignores_prefix_case_and_slashes() {
+        let exe = r"C:\Program Files\ReFast\ReFast.exe";
+        assert!(startup::startup_value_matches(r"\\?\C:\Program Files\ReFast\ReFast.exe", exe, ""));
+        assert!(startup::startup_value_matches(r"c:\program files\refast\REFAST.EXE", exe, ""));
+        assert!(startup::startup_value_matches(
+            "\"C:/Program Files/ReFast/ReFast.exe\" --minimized",
+            r"\\?\C:\Program Files\ReFast\ReFast.exe",
+            "--minimized"
+        ));
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn startup_value_mismatches_are_detected() {
+        let exe = r"C:\Program Files\ReFast\ReFast.exe";
+        assert!(!startup::startup_value_matches(r"D:\Old\ReFast.exe", exe, ""));
+        assert!(!startup::startup_value_matches(&format!("\"{}\" --minimized", exe), exe, ""));
+        assert!(!startup::startup_value_matches(exe, exe, "--startup-delay=30"));
+        assert!(!startup::startup_value_matches(
+            &format!("\"{}\" --startup-delay=10", exe),
+            exe,
+            "--startup-delay=30"
+        ));
+    }
+}
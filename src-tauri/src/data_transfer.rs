@@ -0,0 +1,356 @@
+use crate::db;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// 导出包中的清单文件，用于识别 ReFast 数据包
+const MANIFEST_NAME: &str = "refast-export.json";
+
+/// 数据库在导出包中的名称
+const DB_ENTRY: &str = "re-fast.db";
+
+/// 随数据库一起导出的 JSON 文件（旧版数据和应用缓存），不存在时跳过
+const DATA_FILES: &[&str] = &[
+    "settings.json",
+    "app_cache.json",
+    "shortcuts.json",
+    "memos.json",
+    "file_history.json",
+    "open_history.json",
+    "window_config.json",
+];
+
+/// 整目录导出的子目录
+const DATA_DIRS: &[&str] = &["recordings"];
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ExportManifest {
+    app_version: String,
+    created_at: String,
+    entries: Vec<String>,
+}
+
+fn zip_options() -> FileOptions {
+    FileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+fn add_file<W: Write + std::io::Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    path: &Path,
+) -> Result<(), String> {
+    let content = fs::read(path).map_err(|e| format!("读取文件失败 {}: {}", path.display(), e))?;
+    zip.start_file(name, zip_options())
+        .map_err(|e| format!("写入压缩包失败 {}: {}", name, e))?;
+    zip.write_all(&content)
+        .map_err(|e| format!("写入压缩包失败 {}: {}", name, e))
+}
+
+fn add_dir<W: Write + std::io::Seek>(
+    zip: &mut ZipWriter<W>,
+    prefix: &str,
+    dir: &Path,
+) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("读取目录失败 {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        if path.is_dir() {
+            add_dir(zip, &name, &path)?;
+        } else if path.is_file() {
+            add_file(zip, &name, &path)?;
+        }
+    }
+    Ok(())
+}
+
+/// 将数据库、JSON 数据文件和录制目录打包为 zip；锁文件、日志等易变文件不在白名单中，不会导出
+pub fn export_all(app_data_dir: &Path, dest: &Path) -> Result<(), String> {
+    let db_path = db::get_db_path(app_data_dir);
+    if !db_path.exists() {
+        return Err("Database file not found".to_string());
+    }
+
+    // VACUUM INTO 生成一致的快照，包含尚未写回主文件的 WAL 内容
+    let snapshot = app_data_dir.join("re-fast.db.export-tmp");
+    let _ = fs::remove_file(&snapshot);
+    db::get_connection(app_data_dir)?
+        .execute("VACUUM INTO ?1", [snapshot.to_string_lossy().to_string()])
+        .map_err(|e| format!("生成数据库快照失败: {}", e))?;
+
+    let result = write_archive(app_data_dir, dest, &snapshot);
+    let _ = fs::remove_file(&snapshot);
+    if result.is_err() {
+        let _ = fs::remove_file(dest);
+    }
+    result
+}
+
+fn write_archive(app_data_dir: &Path, dest: &Path, db_snapshot: &Path) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目标目录失败: {}", e))?;
+    }
+    let file = fs::File::create(dest).map_err(|e| format!("创建导出文件失败: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+    let mut entries = vec![DB_ENTRY.to_string()];
+
+    add_file(&mut zip, DB_ENTRY, db_snapshot)?;
+    for name in DATA_FILES {
+        let path = app_data_dir.join(name);
+        if path.is_file() {
+            add_file(&mut zip, name, &path)?;
+            entries.push(name.to_string());
+        }
+    }
+    for name in DATA_DIRS {
+        let path = app_data_dir.join(name);
+        if path.is_dir() {
+            add_dir(&mut zip, name, &path)?;
+            entries.push(format!("{}/", name));
+        }
+    }
+
+    let manifest = ExportManifest {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+        entries,
+    };
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("序列化导出清单失败: {}", e))?;
+    zip.start_file(MANIFEST_NAME, zip_options())
+        .map_err(|e| format!("写入导出清单失败: {}", e))?;
+    zip.write_all(manifest_json.as_bytes())
+        .map_err(|e| format!("写入导出清单失败: {}", e))?;
+
+    zip.finish()
+        .map_err(|e| format!("完成导出文件失败: {}", e))?;
+    Ok(())
+}
+
+/// 校验压缩包条目名：必须是相对路径，且位于允许的顶层条目之下
+fn entry_target(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    let top = path.components().next()?.as_os_str().to_str()?;
+    let allowed = top == DB_ENTRY
+        || top == MANIFEST_NAME
+        || DATA_FILES.contains(&top)
+        || (DATA_DIRS.contains(&top) && path.components().count() > 1);
+    allowed.then(|| path.to_path_buf())
+}
+
+/// 从导出包恢复数据：先校验整个压缩包，再备份当前数据，最后覆盖
+/// 返回导入前备份文件的路径（当前没有数据库时不备份）
+pub fn import_all(app_data_dir: &Path, src: &Path) -> Result<Option<PathBuf>, String> {
+    let file = fs::File::open(src).map_err(|e| format!("打开导入文件失败: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("不是有效的 zip 文件: {}", e))?;
+
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    if !names.iter().any(|n| n == MANIFEST_NAME) || !names.iter().any(|n| n == DB_ENTRY) {
+        return Err(format!(
+            "导入文件不是 ReFast 数据包（缺少 {} 或 {}）",
+            MANIFEST_NAME, DB_ENTRY
+        ));
+    }
+    if let Some(bad) = names
+        .iter()
+        .find(|n| !n.ends_with('/') && entry_target(n).is_none())
+    {
+        return Err(format!("导入文件包含不支持的条目: {}", bad));
+    }
+
+    let mut manifest_json = String::new();
+    archive
+        .by_name(MANIFEST_NAME)
+        .map_err(|e| format!("读取导出清单失败: {}", e))?
+        .read_to_string(&mut manifest_json)
+        .map_err(|e| format!("读取导出清单失败: {}", e))?;
+    serde_json::from_str::<ExportManifest>(&manifest_json)
+        .map_err(|e| format!("导出清单格式无效: {}", e))?;
+
+    fs::create_dir_all(app_data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+
+    // 先解压数据库到临时文件并校验，失败时不改动任何现有数据
+    let db_temp = app_data_dir.join("re-fast.db.import-tmp");
+    let extracted =
+        extract_entry(&mut archive, DB_ENTRY, &db_temp).and_then(|_| db::verify_backup(&db_temp));
+    if let Err(e) = extracted {
+        let _ = fs::remove_file(&db_temp);
+        return Err(e);
+    }
+
+    let backup_dir = app_data_dir.join("backups");
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let pre_import = db::get_db_path(app_data_dir)
+        .exists()
+        .then(|| backup_dir.join(format!("re-fast-pre-import_{}.zip", timestamp)));
+    let result = pre_import
+        .as_deref()
+        .map_or(Ok(()), |path| export_all(app_data_dir, path))
+        .and_then(|_| restore_entries(app_data_dir, &mut archive, &names, &db_temp));
+    let _ = fs::remove_file(&db_temp);
+    result.map(|_| pre_import)
+}
+
+fn extract_entry<R: Read + std::io::Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+    dest: &Path,
+) -> Result<(), String> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|e| format!("读取压缩包条目失败 {}: {}", name, e))?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let mut out =
+        fs::File::create(dest).map_err(|e| format!("写入文件失败 {}: {}", dest.display(), e))?;
+    std::io::copy(&mut entry, &mut out).map_err(|e| format!("解压失败 {}: {}", name, e))?;
+    Ok(())
+}
+
+fn restore_entries<R: Read + std::io::Seek>(
+    app_data_dir: &Path,
+    archive: &mut ZipArchive<R>,
+    names: &[String],
+    db_temp: &Path,
+) -> Result<(), String> {
+    let db_path = db::get_db_path(app_data_dir);
+    fs::copy(db_temp, &db_path).map_err(|e| format!("恢复数据库失败: {}", e))?;
+    // 旧的 WAL/SHM 文件属于被替换的数据库，保留会损坏新数据
+    db::remove_wal_files(&db_path);
+
+    for name in names {
+        if name == DB_ENTRY || name == MANIFEST_NAME || name.ends_with('/') {
+            continue;
+        }
+        if let Some(relative) = entry_target(name) {
+            extract_entry(archive, name, &app_data_dir.join(relative))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn write_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut zip = ZipWriter::new(fs::File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, zip_options()).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn setting_value(app_data_dir: &Path, key: &str) -> Option<String> {
+        db::get_connection(app_data_dir)
+            .unwrap()
+            .query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .ok()
+    }
+
+    #[test]
+    fn entry_target_allows_only_known_entries() {
+        assert_eq!(
+            entry_target("re-fast.db"),
+            Some(PathBuf::from("re-fast.db"))
+        );
+        assert!(entry_target("memos.json").is_some());
+        assert!(entry_target("recordings/a.json").is_some());
+        assert!(entry_target("recordings").is_none());
+        assert!(entry_target("../re-fast.db").is_none());
+        assert!(entry_target("/etc/passwd").is_none());
+        assert!(entry_target("recordings/../../evil").is_none());
+        assert!(entry_target("logs/app.log").is_none());
+    }
+
+    #[test]
+    fn export_then_import_restores_data() {
+        let source = temp_dir("transfer", "export-src");
+        db::get_connection(&source)
+            .unwrap()
+            .execute(
+                "INSERT INTO settings (key, value) VALUES ('marker', 'exported')",
+                [],
+            )
+            .unwrap();
+        fs::write(source.join("memos.json"), b"[1]").unwrap();
+        fs::create_dir_all(source.join("recordings")).unwrap();
+        fs::write(source.join("recordings/r1.json"), b"{}").unwrap();
+        fs::write(source.join("unlisted.txt"), b"x").unwrap();
+
+        let archive = temp_dir("transfer", "export-out").join("data.zip");
+        export_all(&source, &archive).unwrap();
+
+        let target = temp_dir("transfer", "import-dst");
+        db::get_connection(&target)
+            .unwrap()
+            .execute(
+                "INSERT INTO settings (key, value) VALUES ('marker', 'old')",
+                [],
+            )
+            .unwrap();
+        let pre_import = import_all(&target, &archive)
+            .unwrap()
+            .expect("pre-import backup");
+        assert!(pre_import.is_file());
+        assert_eq!(
+            setting_value(&target, "marker").as_deref(),
+            Some("exported")
+        );
+        assert_eq!(fs::read(target.join("memos.json")).unwrap(), b"[1]");
+        assert!(target.join("recordings/r1.json").is_file());
+        assert!(!target.join("unlisted.txt").exists());
+
+        for dir in [&source, &target, &archive.parent().unwrap().to_path_buf()] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn import_rejects_foreign_or_unsafe_archives() {
+        let dir = temp_dir("transfer", "import-bad");
+        let missing_manifest = dir.join("missing.zip");
+        write_zip(&missing_manifest, &[("re-fast.db", b"x")]);
+        assert!(import_all(&dir, &missing_manifest)
+            .unwrap_err()
+            .contains(MANIFEST_NAME));
+
+        let unsafe_entry = dir.join("unsafe.zip");
+        write_zip(
+            &unsafe_entry,
+            &[
+                (MANIFEST_NAME, b"{}"),
+                ("re-fast.db", b"x"),
+                ("../evil.txt", b"x"),
+            ],
+        );
+        assert!(import_all(&dir, &unsafe_entry)
+            .unwrap_err()
+            .contains("../evil.txt"));
+
+        // 数据库条目校验失败时不改动现有数据
+        let bad_db = dir.join("bad-db.zip");
+        let manifest = br#"{"appVersion":"1","createdAt":"now","entries":[]}"#;
+        write_zip(
+            &bad_db,
+            &[(MANIFEST_NAME, manifest), ("re-fast.db", b"garbage")],
+        );
+        assert!(import_all(&dir, &bad_db).is_err());
+        assert!(!db::get_db_path(&dir).exists());
+        assert!(!dir.join("re-fast.db.import-tmp").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,4 +1,5 @@
 use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -60,6 +61,190 @@ pub fn get_connection(app_data_dir: &Path) -> Result<Connection, String> {
     Ok(conn)
 }
 
+/// 恢复备份前必须存在的核心表
+const REQUIRED_BACKUP_TABLES: &[&str] = &["settings", "shortcuts", "file_history"];
+
+/// 以只读方式打开备份文件，校验 SQLite 完整性并确认核心表存在
+pub fn verify_backup(path: &Path) -> Result<(), String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("无法打开备份文件: {}", e))?;
+
+    let problems =
+        integrity_problems(&conn).map_err(|e| format!("备份文件不是有效的数据库: {}", e))?;
+    if !problems.is_empty() {
+        return Err(format!(
+            "备份文件已损坏: {}",
+            problems.into_iter().take(5).collect::<Vec<_>>().join("; ")
+        ));
+    }
+
+    let missing: Vec<&str> = REQUIRED_BACKUP_TABLES
+        .iter()
+        .copied()
+        .filter(|table| {
+            conn.query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                [table],
+                |_| Ok(()),
+            )
+            .is_err()
+        })
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("备份文件缺少必要的数据表: {}", missing.join(", ")));
+    }
+
+    Ok(())
+}
+
+/// PRAGMA integrity_check 报告的问题，数据库完好时为空
+fn integrity_problems(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows.into_iter().filter(|row| row != "ok").collect())
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    /// integrity_check 的输出（最多 100 条），或无法打开数据库的原因
+    pub problems: Vec<String>,
+}
+
+/// 对数据库文件执行 PRAGMA integrity_check
+/// 只读打开且不运行迁移，损坏的数据库也能得到报告
+pub fn check_integrity(db_path: &Path) -> IntegrityReport {
+    let problems = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .and_then(|conn| integrity_problems(&conn))
+        .unwrap_or_else(|e| vec![format!("无法读取数据库: {}", e)]);
+    IntegrityReport {
+        ok: problems.is_empty(),
+        problems: problems.into_iter().take(100).collect(),
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// 把（可能已损坏的）数据库逐表导出到新文件 dest：先按原结构建表，再复制能读出的数据
+/// 整表读取失败时该表保留为空表，返回这些表以及无法重建的索引名；dest 已存在时会被覆盖
+pub fn rebuild_into(source: &Path, dest: &Path) -> Result<Vec<String>, String> {
+    let _ = fs::remove_file(dest);
+    let conn = Connection::open(dest).map_err(|e| format!("创建新数据库失败: {}", e))?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS damaged",
+        [source.to_string_lossy().to_string()],
+    )
+    .map_err(|e| format!("打开原数据库失败: {}", e))?;
+
+    // 先建表再建索引，避免索引先于表创建
+    let schema: Vec<(String, String, String)> = conn
+        .prepare(
+            "SELECT type, name, sql FROM damaged.sqlite_master
+             WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+             ORDER BY type = 'table' DESC",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| format!("读取数据库结构失败: {}", e))?;
+
+    let mut skipped = Vec::new();
+    for (kind, name, sql) in &schema {
+        if kind != "table" {
+            // 索引等可以缺失，数据导入后建立失败（如唯一约束冲突）时跳过
+            if conn.execute_batch(sql).is_err() {
+                skipped.push(name.clone());
+            }
+            continue;
+        }
+        conn.execute_batch(sql)
+            .map_err(|e| format!("重建表 {} 失败: {}", name, e))?;
+        let copy = format!(
+            "INSERT INTO main.{table} SELECT * FROM damaged.{table}",
+            table = quote_identifier(name)
+        );
+        if conn.execute(&copy, []).is_err() {
+            skipped.push(name.clone());
+        }
+    }
+
+    conn.execute("DETACH DATABASE damaged", [])
+        .map_err(|e| format!("关闭原数据库失败: {}", e))?;
+    Ok(skipped)
+}
+
+#[derive(Serialize, Debug)]
+pub struct VacuumResult {
+    /// 压缩前后的大小（字节，含 -wal 文件）
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+/// 数据库旁的 -wal / -shm 文件路径
+fn sidecar_path(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn database_size(db_path: &Path) -> u64 {
+    [db_path.to_path_buf(), sidecar_path(db_path, "-wal")]
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// 执行 VACUUM 回收已删除数据占用的空间；WAL 模式下随后截断 -wal 文件，使磁盘占用真正减少
+pub fn vacuum(app_data_dir: &Path) -> Result<VacuumResult, String> {
+    let db_path = get_db_path(app_data_dir);
+    let size_before = database_size(&db_path);
+
+    let conn = get_connection(app_data_dir)?;
+    conn.execute_batch("VACUUM;")
+        .map_err(|e| format!("Failed to vacuum database: {}", e))?;
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+        .map_err(|e| format!("Failed to checkpoint database: {}", e))?;
+    drop(conn);
+
+    Ok(VacuumResult {
+        size_before,
+        size_after: database_size(&db_path),
+    })
+}
+
+/// 删除数据库的 -wal / -shm 文件：替换数据库文件后调用，避免旧日志被应用到新文件上
+pub fn remove_wal_files(db_path: &Path) {
+    for suffix in ["-wal", "-shm"] {
+        let _ = fs::remove_file(sidecar_path(db_path, suffix));
+    }
+}
+
+/// 把数据库连同 -wal 中尚未写回的内容保存为单个文件 dest（已存在时覆盖）
+/// 优先使用 VACUUM INTO；数据库损坏无法读取时退回到原样复制数据库文件和 -wal 文件
+pub fn snapshot(db_path: &Path, dest: &Path) -> Result<(), String> {
+    let _ = fs::remove_file(dest);
+    let vacuumed = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+        .and_then(|conn| conn.execute("VACUUM INTO ?1", [dest.to_string_lossy().to_string()]));
+    if vacuumed.is_ok() {
+        return Ok(());
+    }
+
+    let _ = fs::remove_file(dest);
+    fs::copy(db_path, dest).map_err(|e| format!("Failed to snapshot database: {}", e))?;
+    let wal_path = sidecar_path(db_path, "-wal");
+    if wal_path.exists() {
+        fs::copy(&wal_path, sidecar_path(dest, "-wal"))
+            .map_err(|e| format!("Failed to snapshot database WAL: {}", e))?;
+    }
+    Ok(())
+}
+
 fn run_migrations(conn: &Connection) -> Result<(), String> {
     conn.execute_batch(
         r#"
@@ -82,7 +267,8 @@ fn run_migrations(conn: &Connection) -> Result<(), String> {
             name TEXT NOT NULL,
             last_used INTEGER NOT NULL,
             use_count INTEGER NOT NULL,
-            is_folder INTEGER
+            is_folder INTEGER,
+            last_action TEXT
         );
         CREATE INDEX IF NOT EXISTS idx_file_history_last_used ON file_history(last_used);
 
@@ -117,10 +303,110 @@ fn run_migrations(conn: &Connection) -> Result<(), String> {
             last_opened INTEGER NOT NULL
         );
         CREATE INDEX IF NOT EXISTS idx_plugin_usage_last_opened ON plugin_usage(last_opened);
+
+        CREATE TABLE IF NOT EXISTS calc_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            expression TEXT NOT NULL,
+            result TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        );
     "#,
     )
     .map_err(|e| format!("Failed to run database migrations: {}", e))?;
 
+    ensure_column(conn, "file_history", "last_action", "TEXT")?;
+    ensure_column(conn, "window_config", "width", "INTEGER")?;
+    ensure_column(conn, "window_config", "height", "INTEGER")?;
+
     Ok(())
 }
 
+/// 旧数据库的表缺少后来新增的列时补上（CREATE TABLE IF NOT EXISTS 不会修改已有表）
+fn ensure_column(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|e| format!("Failed to read {} schema: {}", table, e))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to read {} schema: {}", table, e))?
+        .flatten()
+        .any(|name| name == column);
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )
+        .map_err(|e| format!("Failed to add column {}.{}: {}", table, column, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn count_settings(path: &Path) -> i64 {
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM settings", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn snapshot_includes_uncheckpointed_wal_content() {
+        let dir = temp_dir("db", "snapshot");
+        let conn = get_connection(&dir).unwrap();
+        conn.execute_batch("PRAGMA wal_autocheckpoint = 0;")
+            .unwrap();
+        conn.execute("INSERT INTO settings (key, value) VALUES ('a', '1')", [])
+            .unwrap();
+        let db_path = get_db_path(&dir);
+        assert!(sidecar_path(&db_path, "-wal").metadata().unwrap().len() > 0);
+
+        // 连接仍打开，新写入的行只存在于 -wal 中
+        let dest = dir.join("snapshot.db");
+        snapshot(&db_path, &dest).unwrap();
+        drop(conn);
+        assert!(!sidecar_path(&dest, "-wal").exists());
+        assert_eq!(count_settings(&dest), 1);
+        verify_backup(&dest).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn remove_wal_files_deletes_sidecars() {
+        let dir = temp_dir("db", "remove-wal");
+        let db_path = get_db_path(&dir);
+        fs::write(&db_path, b"").unwrap();
+        fs::write(sidecar_path(&db_path, "-wal"), b"wal").unwrap();
+        fs::write(sidecar_path(&db_path, "-shm"), b"shm").unwrap();
+        remove_wal_files(&db_path);
+        assert!(db_path.exists());
+        assert!(!sidecar_path(&db_path, "-wal").exists());
+        assert!(!sidecar_path(&db_path, "-shm").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verify_backup_rejects_missing_tables_and_garbage() {
+        let dir = temp_dir("db", "verify");
+        let partial = dir.join("partial.db");
+        Connection::open(&partial)
+            .unwrap()
+            .execute_batch("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT);")
+            .unwrap();
+        let err = verify_backup(&partial).unwrap_err();
+        assert!(err.contains("shortcuts"), "{}", err);
+
+        let garbage = dir.join("garbage.db");
+        fs::write(&garbage, b"not a database at all, just some text").unwrap();
+        assert!(verify_backup(&garbage).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
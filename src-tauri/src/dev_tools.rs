@@ -0,0 +1,283 @@
+// 启动器开发者小工具：进制转换、文本哈希、Base64 编解码
+// 语法："0xFF to dec"、"255 in bin"、"md5 hello"、"sha256 hello"、"base64 hello"、"base64 -d aGVsbG8="
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+
+/// 输入长度上限（字节），避免对超大粘贴内容做哈希或编码
+const MAX_DEV_TOOL_INPUT_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DevToolOperation {
+    ToHex,
+    ToDec,
+    ToBin,
+    ToOct,
+    Md5,
+    Sha1,
+    Sha256,
+    Base64Encode,
+    Base64Decode,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevToolResult {
+    pub operation: DevToolOperation,
+    pub output: String,
+}
+
+/// 解析带前缀的整数：0x / 0b / 0o 或十进制，可带负号和 `_` 分隔符
+pub fn parse_integer(text: &str) -> Option<i128> {
+    let text = text.trim().replace('_', "");
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.as_str()),
+    };
+    let lower = digits.to_ascii_lowercase();
+    let (radix, digits) = if let Some(rest) = lower.strip_prefix("0x") {
+        (16, rest)
+    } else if let Some(rest) = lower.strip_prefix("0b") {
+        (2, rest)
+    } else if let Some(rest) = lower.strip_prefix("0o") {
+        (8, rest)
+    } else {
+        (10, lower.as_str())
+    };
+    if digits.is_empty() || digits.starts_with(['+', '-']) {
+        return None;
+    }
+    let value = i128::from_str_radix(digits, radix).ok()?;
+    Some(if negative { -value } else { value })
+}
+
+/// 按目标进制输出，非十进制带 0x / 0b / 0o 前缀
+pub fn format_integer(value: i128, operation: DevToolOperation) -> Option<String> {
+    let sign = if value < 0 { "-" } else { "" };
+    let abs = value.unsigned_abs();
+    let formatted = match operation {
+        DevToolOperation::ToHex => format!("{}0x{:X}", sign, abs),
+        DevToolOperation::ToDec => value.to_string(),
+        DevToolOperation::ToBin => format!("{}0b{:b}", sign, abs),
+        DevToolOperation::ToOct => format!("{}0o{:o}", sign, abs),
+        _ => return None,
+    };
+    Some(formatted)
+}
+
+fn base_operation(name: &str) -> Option<DevToolOperation> {
+    match name.to_ascii_lowercase().as_str() {
+        "hex" | "hexadecimal" => Some(DevToolOperation::ToHex),
+        "dec" | "decimal" => Some(DevToolOperation::ToDec),
+        "bin" | "binary" => Some(DevToolOperation::ToBin),
+        "oct" | "octal" => Some(DevToolOperation::ToOct),
+        _ => None,
+    }
+}
+
+fn digest_hex<D: sha2::Digest>(text: &str) -> String {
+    D::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 计算文本（UTF-8 字节）的小写十六进制摘要
+pub fn hash_text(text: &str, operation: DevToolOperation) -> Option<String> {
+    match operation {
+        DevToolOperation::Md5 => Some(digest_hex::<md5::Md5>(text)),
+        DevToolOperation::Sha1 => Some(digest_hex::<sha1::Sha1>(text)),
+        DevToolOperation::Sha256 => Some(digest_hex::<sha2::Sha256>(text)),
+        _ => None,
+    }
+}
+
+pub fn base64_encode(text: &str) -> String {
+    general_purpose::STANDARD.encode(text.as_bytes())
+}
+
+/// 解码 Base64（兼容 URL-safe 字母表与缺省的 `=` 填充），结果必须是 UTF-8 文本
+pub fn base64_decode(text: &str) -> Result<String, String> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let normalized = compact.replace('-', "+").replace('_', "/");
+    let bytes = general_purpose::STANDARD_NO_PAD
+        .decode(normalized.trim_end_matches('='))
+        .map_err(|e| format!("无效的 Base64 内容: {}", e))?;
+    String::from_utf8(bytes).map_err(|_| "解码结果不是有效的 UTF-8 文本".to_string())
+}
+
+/// 按首个空白拆分为命令词和其余参数
+fn split_command(input: &str) -> (&str, &str) {
+    match input.split_once(char::is_whitespace) {
+        Some((command, rest)) => (command, rest.trim()),
+        None => (input, ""),
+    }
+}
+
+/// "<数值> to|in <进制>"
+fn try_base_conversion(input: &str) -> Option<DevToolResult> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let [number, keyword, base] = words.as_slice() else {
+        return None;
+    };
+    if !keyword.eq_ignore_ascii_case("to") && !keyword.eq_ignore_ascii_case("in") {
+        return None;
+    }
+    let operation = base_operation(base)?;
+    let value = parse_integer(number)?;
+    Some(DevToolResult {
+        operation,
+        output: format_integer(value, operation)?,
+    })
+}
+
+/// 执行开发者小工具命令；输入不符合任何语法时返回 Ok(None)，
+/// 语法匹配但内容无效（如非法 Base64）时返回 Err
+pub fn run(input: &str) -> Result<Option<DevToolResult>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    if input.len() > MAX_DEV_TOOL_INPUT_BYTES {
+        return Err(format!(
+            "输入过长，最多 {} KB",
+            MAX_DEV_TOOL_INPUT_BYTES / 1024
+        ));
+    }
+    if let Some(result) = try_base_conversion(input) {
+        return Ok(Some(result));
+    }
+
+    let (command, rest) = split_command(input);
+    if rest.is_empty() {
+        return Ok(None);
+    }
+    let (operation, payload) = match command.to_ascii_lowercase().as_str() {
+        "md5" => (DevToolOperation::Md5, rest),
+        "sha1" => (DevToolOperation::Sha1, rest),
+        "sha256" => (DevToolOperation::Sha256, rest),
+        "base64" | "b64" => match split_command(rest) {
+            ("-d" | "--decode" | "decode", payload) if !payload.is_empty() => {
+                (DevToolOperation::Base64Decode, payload)
+            }
+            _ => (DevToolOperation::Base64Encode, rest),
+        },
+        "base64d" | "unbase64" => (DevToolOperation::Base64Decode, rest),
+        _ => return Ok(None),
+    };
+
+    let output = match operation {
+        DevToolOperation::Base64Encode => base64_encode(payload),
+        DevToolOperation::Base64Decode => base64_decode(payload)?,
+        _ => match hash_text(payload, operation) {
+            Some(output) => output,
+            None => return Ok(None),
+        },
+    };
+    Ok(Some(DevToolResult { operation, output }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(input: &str) -> String {
+        run(input)
+            .unwrap()
+            .unwrap_or_else(|| panic!("no result: {}", input))
+            .output
+    }
+
+    #[test]
+    fn parse_integer_prefixes_signs_and_separators() {
+        assert_eq!(parse_integer("255"), Some(255));
+        assert_eq!(parse_integer("0xFF"), Some(255));
+        assert_eq!(parse_integer("0Xff"), Some(255));
+        assert_eq!(parse_integer("0b1010"), Some(10));
+        assert_eq!(parse_integer("0o17"), Some(15));
+        assert_eq!(parse_integer("-0x10"), Some(-16));
+        assert_eq!(parse_integer("1_000_000"), Some(1_000_000));
+        assert_eq!(parse_integer("0b_1111_0000"), Some(0xF0));
+        for bad in ["", "-", "0x", "0x-1", "--1", "+5", "12a", "0b102", "1e3"] {
+            assert_eq!(parse_integer(bad), None, "{}", bad);
+        }
+        // 超出 i128 范围
+        assert_eq!(parse_integer(&"9".repeat(50)), None);
+    }
+
+    #[test]
+    fn format_integer_per_base() {
+        assert_eq!(
+            format_integer(255, DevToolOperation::ToHex).as_deref(),
+            Some("0xFF")
+        );
+        assert_eq!(
+            format_integer(-16, DevToolOperation::ToHex).as_deref(),
+            Some("-0x10")
+        );
+        assert_eq!(
+            format_integer(10, DevToolOperation::ToBin).as_deref(),
+            Some("0b1010")
+        );
+        assert_eq!(
+            format_integer(8, DevToolOperation::ToOct).as_deref(),
+            Some("0o10")
+        );
+        assert_eq!(
+            format_integer(-42, DevToolOperation::ToDec).as_deref(),
+            Some("-42")
+        );
+        assert_eq!(format_integer(1, DevToolOperation::Md5), None);
+    }
+
+    #[test]
+    fn base_conversion_commands() {
+        assert_eq!(output("0xFF to dec"), "255");
+        assert_eq!(output("255 in bin"), "0b11111111");
+        assert_eq!(output("1_024 TO HEX"), "0x400");
+        assert!(run("0xZZ to dec").unwrap().is_none());
+        assert!(run("255 to base7").unwrap().is_none());
+    }
+
+    #[test]
+    fn hash_vectors() {
+        assert_eq!(output("md5 hello"), "5d41402abc4b2a76b9719d911017c592");
+        assert_eq!(
+            output("sha1 hello"),
+            "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d"
+        );
+        assert_eq!(
+            output("sha256 hello"),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(
+            hash_text("", DevToolOperation::Md5).as_deref(),
+            Some("d41d8cd98f00b204e9800998ecf8427e")
+        );
+        assert_eq!(hash_text("x", DevToolOperation::ToHex), None);
+    }
+
+    #[test]
+    fn base64_round_trip_and_lenient_decode() {
+        assert_eq!(output("base64 hello"), "aGVsbG8=");
+        assert_eq!(output("base64 -d aGVsbG8="), "hello");
+        assert_eq!(output("unbase64 aGVsbG8"), "hello");
+        assert_eq!(base64_decode("aGVs\nbG8=").unwrap(), "hello");
+        // URL-safe 字母表以 - 和 _ 代替 + 和 /
+        assert_eq!(base64_encode("~~~???"), "fn5+Pz8/");
+        assert_eq!(base64_decode("fn5-Pz8_").unwrap(), "~~~???");
+        assert_eq!(base64_decode("w6k-Pw").unwrap(), "é>?");
+        assert!(base64_decode("!!!").is_err());
+        // 0xFF 不是有效的 UTF-8
+        assert!(base64_decode("_w").is_err());
+    }
+
+    #[test]
+    fn oversized_and_unknown_input() {
+        let long = format!("md5 {}", "a".repeat(MAX_DEV_TOOL_INPUT_BYTES));
+        assert!(run(&long).is_err());
+        assert!(run("").unwrap().is_none());
+        assert!(run("md5").unwrap().is_none());
+        assert!(run("hello world").unwrap().is_none());
+    }
+}
@@ -0,0 +1,358 @@
+use flate2::read::ZlibDecoder;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// 超过该大小的文档不尝试提取文本，避免预览时卡顿
+pub const MAX_DOCUMENT_BYTES: u64 = 20 * 1024 * 1024;
+/// 解压内容（docx 的 XML、PDF 的压缩流）的总量上限，防止压缩炸弹
+pub const MAX_DECOMPRESSED_BYTES: u64 = 32 * 1024 * 1024;
+
+/// 提取结果：文本内容以及是否被截断
+pub struct ExtractedText {
+    pub content: String,
+    pub truncated: bool,
+}
+
+/// 判断扩展名是否支持文本提取
+pub fn is_extractable_extension(ext: &str) -> bool {
+    matches!(ext, "pdf" | "docx")
+}
+
+/// 按扩展名提取文档文本，失败时返回 None（调用方回退为 binary 预览）
+pub fn extract_text(path: &Path, ext: &str, max_chars: usize) -> Option<ExtractedText> {
+    let size = fs::metadata(path).ok()?.len();
+    if size > MAX_DOCUMENT_BYTES {
+        return None;
+    }
+
+    // 按 UTF-8 最长 4 字节估算，文本超过该长度后不再继续提取
+    let text_limit = max_chars.saturating_mul(4);
+    let raw = match ext {
+        "docx" => extract_docx_text(path, text_limit)?,
+        "pdf" => extract_pdf_text(path, text_limit)?,
+        _ => return None,
+    };
+
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let truncated = trimmed.chars().count() > max_chars;
+    let content = if truncated {
+        trimmed.chars().take(max_chars).collect()
+    } else {
+        trimmed.to_string()
+    };
+
+    Some(ExtractedText { content, truncated })
+}
+
+/// 读取 docx 中 word/document.xml 的文本节点
+fn extract_docx_text(path: &Path, text_limit: usize) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+    let entry = archive.by_name("word/document.xml").ok()?;
+    let mut bytes = Vec::new();
+    entry
+        .take(MAX_DECOMPRESSED_BYTES)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    let xml = String::from_utf8_lossy(&bytes);
+
+    let mut text = String::new();
+    let mut rest = xml.as_ref();
+    while let Some(start) = rest.find('<') {
+        if text.len() > text_limit {
+            break;
+        }
+        let end = match rest[start..].find('>') {
+            Some(offset) => start + offset,
+            None => break,
+        };
+        let tag = &rest[start + 1..end];
+        let after = &rest[end + 1..];
+
+        if tag == "w:t" || tag.starts_with("w:t ") {
+            if let Some(close) = after.find("</w:t>") {
+                text.push_str(&decode_xml_entities(&after[..close]));
+                rest = &after[close + "</w:t>".len()..];
+                continue;
+            }
+        } else if tag == "w:tab/" {
+            text.push('\t');
+        } else if tag == "w:br/" || tag == "/w:p" {
+            text.push('\n');
+        }
+        rest = after;
+    }
+
+    Some(text)
+}
+
+fn decode_xml_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 尽力而为的 PDF 文本提取：解压内容流并读取 Tj/TJ 操作符中的字符串
+fn extract_pdf_text(path: &Path, text_limit: usize) -> Option<String> {
+    let data = fs::read(path).ok()?;
+    if !data.starts_with(b"%PDF") {
+        return None;
+    }
+
+    let mut text = String::new();
+    let mut pos = 0;
+    let mut inflate_budget = MAX_DECOMPRESSED_BYTES;
+    while let Some(offset) = find_bytes(&data[pos..], b"stream") {
+        if text.len() > text_limit || inflate_budget == 0 {
+            break;
+        }
+        let mut start = pos + offset + b"stream".len();
+        // "endstream" 也包含 "stream"，跳过
+        if offset >= 3 && &data[pos + offset - 3..pos + offset] == b"end" {
+            pos = start;
+            continue;
+        }
+        if data.get(start) == Some(&b'\r') {
+            start += 1;
+        }
+        if data.get(start) == Some(&b'\n') {
+            start += 1;
+        }
+        let end = match find_bytes(&data[start..], b"endstream") {
+            Some(len) => start + len,
+            None => break,
+        };
+
+        // 只检查当前对象的字典部分
+        let window = &data[pos..pos + offset];
+        let dict_start = window
+            .windows(3)
+            .rposition(|w| w == b"obj")
+            .unwrap_or(0);
+        let dict = &window[dict_start..];
+        let raw = &data[start..end];
+        let decoded = if find_bytes(dict, b"FlateDecode").is_some() {
+            let mut out = Vec::new();
+            let result = ZlibDecoder::new(raw)
+                .take(inflate_budget)
+                .read_to_end(&mut out);
+            inflate_budget -= out.len() as u64;
+            if result.is_err() && out.is_empty() {
+                pos = end;
+                continue;
+            }
+            out
+        } else {
+            raw.to_vec()
+        };
+
+        collect_pdf_strings(&decoded, &mut text);
+        pos = end + b"endstream".len();
+    }
+
+    Some(text)
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// 解析内容流中的文本块（BT ... ET），提取括号字符串
+fn collect_pdf_strings(stream: &[u8], out: &mut String) {
+    let mut in_text = false;
+    let mut i = 0;
+    while i < stream.len() {
+        let b = stream[i];
+        if !in_text {
+            if stream[i..].starts_with(b"BT") {
+                in_text = true;
+                i += 2;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+
+        match b {
+            b'(' => {
+                let (s, next) = read_pdf_string(stream, i + 1);
+                out.push_str(&s);
+                i = next;
+            }
+            b'E' if stream[i..].starts_with(b"ET") => {
+                in_text = false;
+                out.push('\n');
+                i += 2;
+            }
+            b'T' if stream[i..].starts_with(b"T*") => {
+                out.push('\n');
+                i += 2;
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+/// 读取一个 PDF 字面量字符串（处理转义和嵌套括号），返回内容和下一个位置
+fn read_pdf_string(stream: &[u8], mut i: usize) -> (String, usize) {
+    let mut bytes = Vec::new();
+    let mut depth = 1;
+    while i < stream.len() {
+        let b = stream[i];
+        match b {
+            b'\\' if i + 1 < stream.len() => {
+                let next = stream[i + 1];
+                match next {
+                    b'n' => bytes.push(b'\n'),
+                    b'r' => bytes.push(b'\r'),
+                    b't' => bytes.push(b'\t'),
+                    b'(' | b')' | b'\\' => bytes.push(next),
+                    b'0'..=b'7' => {
+                        let mut value: u32 = 0;
+                        let mut len = 0;
+                        while len < 3 && i + 1 + len < stream.len() {
+                            let d = stream[i + 1 + len];
+                            if !(b'0'..=b'7').contains(&d) {
+                                break;
+                            }
+                            value = value * 8 + (d - b'0') as u32;
+                            len += 1;
+                        }
+                        bytes.push(value as u8);
+                        i += 1 + len;
+                        continue;
+                    }
+                    _ => {}
+                }
+                i += 2;
+            }
+            b'(' => {
+                depth += 1;
+                bytes.push(b);
+                i += 1;
+            }
+            b')' => {
+                depth -= 1;
+                i += 1;
+                if depth == 0 {
+                    break;
+                }
+                bytes.push(b);
+            }
+            _ => {
+                bytes.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    (String::from_utf8_lossy(&bytes).to_string(), i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_path;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = temp_path("doc", name);
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn docx_bytes(document_xml: &str) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer
+            .start_file("word/document.xml", zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(document_xml.as_bytes()).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn pdf_bytes(streams: &[Vec<u8>]) -> Vec<u8> {
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        for (index, content) in streams.iter().enumerate() {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::fast());
+            encoder.write_all(content).unwrap();
+            let compressed = encoder.finish().unwrap();
+            pdf.extend_from_slice(
+                format!(
+                    "{} 0 obj\n<< /Length {} /Filter /FlateDecode >>\nstream\n",
+                    index + 1,
+                    compressed.len()
+                )
+                .as_bytes(),
+            );
+            pdf.extend_from_slice(&compressed);
+            pdf.extend_from_slice(b"\nendstream\nendobj\n");
+        }
+        pdf
+    }
+
+    #[test]
+    fn extracts_docx_paragraphs() {
+        let path = temp_file(
+            "paragraphs.docx",
+            &docx_bytes(
+                r#"<w:document><w:body><w:p><w:r><w:t>Hello &amp; world</w:t></w:r></w:p><w:p><w:r><w:t xml:space="preserve">第二段</w:t></w:r></w:p></w:body></w:document>"#,
+            ),
+        );
+        let extracted = extract_text(&path, "docx", 1000).unwrap();
+        fs::remove_file(&path).ok();
+        assert_eq!(extracted.content, "Hello & world\n第二段");
+        assert!(!extracted.truncated);
+    }
+
+    #[test]
+    fn extracts_pdf_text_operators() {
+        let path = temp_file(
+            "operators.pdf",
+            &pdf_bytes(&[br"BT (Hello \(PDF\)) Tj T* (Line\0402) Tj ET".to_vec()]),
+        );
+        let extracted = extract_text(&path, "pdf", 1000).unwrap();
+        fs::remove_file(&path).ok();
+        assert_eq!(extracted.content, "Hello (PDF)\nLine 2");
+    }
+
+    #[test]
+    fn truncates_to_max_chars() {
+        let paragraph = "<w:p><w:r><w:t>文本</w:t></w:r></w:p>".repeat(100);
+        let path = temp_file("long.docx", &docx_bytes(&paragraph));
+        let extracted = extract_text(&path, "docx", 10).unwrap();
+        fs::remove_file(&path).ok();
+        assert!(extracted.truncated);
+        assert_eq!(extracted.content.chars().count(), 10);
+    }
+
+    #[test]
+    fn stops_inflating_oversized_streams() {
+        // 压缩后只有几十 KB，解压后超过上限
+        let bomb = vec![b' '; MAX_DECOMPRESSED_BYTES as usize + 1024 * 1024];
+        let path = temp_file(
+            "bomb.pdf",
+            &pdf_bytes(&[bomb, b"BT (after) Tj ET".to_vec()]),
+        );
+        let extracted = extract_text(&path, "pdf", 1000);
+        fs::remove_file(&path).ok();
+        // 预算在第一个流中耗尽，不再解压后面的流
+        assert!(extracted.is_none());
+    }
+
+    #[test]
+    fn rejects_non_pdf_data() {
+        let path = temp_file("fake.pdf", b"not a pdf");
+        assert!(extract_text(&path, "pdf", 1000).is_none());
+        fs::remove_file(&path).ok();
+    }
+}
@@ -1,9 +1,27 @@
+use crate::everything_search::EverythingError;
+use serde::Serialize;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum AppError {
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
     #[error("Platform not supported: {0}")]
-    PlatformNotSupported(String),
+    PlatformUnsupported(String),
+
+    #[error("IO error: {0}")]
+    Io(String),
+
+    #[error("Parse error: {0}")]
+    Parse(String),
+
+    /// 消息保留 Everything 原始错误码前缀（如 `NOT_INSTALLED:`），兼容前端现有判断
+    #[error("{0}")]
+    EverythingUnavailable(String),
 
     #[error("Recording error: {0}")]
     RecordingError(String),
@@ -11,21 +29,93 @@ pub enum AppError {
     #[error("Replay error: {0}")]
     ReplayError(String),
 
-    #[error("IO error: {0}")]
-    IoError(#[from] std::io::Error),
-
-    #[error("JSON error: {0}")]
-    JsonError(#[from] serde_json::Error),
-
     #[error("Hotkey error: {0}")]
     HotkeyError(String),
+
+    #[error("{0}")]
+    Other(String),
 }
 
-impl From<AppError> for String {
-    fn from(error: AppError) -> Self {
-        error.to_string()
+/// 返回给前端的错误结构，前端可按 `code` 分支处理
+#[derive(Serialize, Debug, Clone)]
+pub struct FrontendError {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::PermissionDenied(_) => "PERMISSION_DENIED",
+            AppError::PlatformUnsupported(_) => "PLATFORM_UNSUPPORTED",
+            AppError::Io(_) => "IO",
+            AppError::Parse(_) => "PARSE",
+            AppError::EverythingUnavailable(_) => "EVERYTHING_UNAVAILABLE",
+            AppError::RecordingError(_) => "RECORDING",
+            AppError::ReplayError(_) => "REPLAY",
+            AppError::HotkeyError(_) => "HOTKEY",
+            AppError::Other(_) => "OTHER",
+        }
+    }
+
+    pub fn to_frontend(&self) -> FrontendError {
+        FrontendError {
+            code: self.code(),
+            message: self.to_string(),
+        }
     }
 }
 
+// Tauri 命令的错误需要可序列化，统一输出为 { code, message }
+impl Serialize for AppError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.to_frontend().serialize(serializer)
+    }
+}
 
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound(error.to_string()),
+            std::io::ErrorKind::PermissionDenied => AppError::PermissionDenied(error.to_string()),
+            _ => AppError::Io(error.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(error: serde_json::Error) -> Self {
+        AppError::Parse(error.to_string())
+    }
+}
+
+impl From<EverythingError> for AppError {
+    fn from(error: EverythingError) -> Self {
+        match error {
+            EverythingError::NotInstalled | EverythingError::ServiceNotRunning => {
+                AppError::EverythingUnavailable(error.to_string())
+            }
+            EverythingError::InvalidQuery(_) | EverythingError::JsonParseError(_) => {
+                AppError::Parse(error.to_string())
+            }
+            _ => AppError::Other(error.to_string()),
+        }
+    }
+}
 
+// 尚未迁移的内部函数仍返回 String，可直接用 `?` 转换
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,96 @@
+//! 发送给前端的事件载荷定义，字段名即前端约定的事件结构
+
+use crate::app_search::AppInfo;
+use crate::everything_search::EverythingResult;
+use crate::plugin_manifest::PluginManifest;
+use serde::Serialize;
+
+/// `app-rescan-progress` / `app-scan-progress`：应用重新扫描 / 首次扫描的进度
+#[derive(Serialize, Debug, Clone)]
+pub struct RescanProgress {
+    /// 0-100
+    pub progress: u8,
+    pub message: String,
+}
+
+/// `app-rescan-complete`：扫描完成后的应用列表
+#[derive(Serialize, Debug, Clone)]
+pub struct RescanComplete {
+    pub apps: Vec<AppInfo>,
+}
+
+/// `app-rescan-error`：扫描失败
+#[derive(Serialize, Debug, Clone)]
+pub struct RescanError {
+    pub error: String,
+}
+
+/// `everything-search-batch`：Everything 搜索的增量结果
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchBatch {
+    pub results: Vec<EverythingResult>,
+    pub total_count: u32,
+    pub current_count: u32,
+}
+
+/// `file-replace-progress`：带进度的替换预览
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileReplaceProgress {
+    pub scanned_files: usize,
+    pub matches: usize,
+    pub current_path: String,
+}
+
+/// `folder-analyze-progress`：文件夹空间分析进度
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderAnalyzeProgress {
+    pub scanned_files: usize,
+    pub scanned_bytes: u64,
+    pub current_path: String,
+}
+
+/// `playback-countdown`：回放开始前的倒计时，remaining_ms 为 0 时开始回放
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackCountdown {
+    pub remaining_seconds: u64,
+    pub remaining_ms: u64,
+}
+
+/// `plugin-state-changed`：插件被启用或禁用
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginStateChanged {
+    pub plugin_id: String,
+    pub enabled: bool,
+}
+
+/// `plugin-installed`：从 zip 安装插件完成
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInstalled {
+    pub dir: String,
+    pub manifest: PluginManifest,
+}
+
+/// `theme-changed`：保存设置时主题发生变化，所有窗口据此切换
+#[derive(Serialize, Debug, Clone)]
+pub struct ThemeChanged {
+    pub theme: String,
+}
+
+/// `open-path-requested`：通过命令行传入的路径（文件关联、“发送到”），已加入文件历史
+#[derive(Serialize, Debug, Clone)]
+pub struct OpenPathRequested {
+    pub paths: Vec<String>,
+}
+
+/// `everything-install-status`：Everything 安装过程的阶段
+/// stage: "launching" | "installing" | "starting" | "waiting" | "ready" | "failed"
+#[derive(Serialize, Debug, Clone)]
+pub struct EverythingInstallStatus {
+    pub stage: &'static str,
+    pub message: String,
+}
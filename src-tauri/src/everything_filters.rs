@@ -48,3 +48,221 @@ pub fn save_custom_filters(app_data_dir: &Path, filters: &[CustomFilter]) -> Res
     Ok(())
 }
 
+
+/// 保存的搜索中的选项，字段与 EverythingSearchSessionOptions 一致（camelCase）
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearchOptions {
+    pub extensions: Option<Vec<String>>,
+    pub max_results: Option<usize>,
+    pub sort_key: Option<String>,
+    pub sort_order: Option<String>,
+    pub match_folder_name_only: Option<bool>,
+}
+
+/// 收藏的 Everything 搜索
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearch {
+    pub id: String,
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub options: SavedSearchOptions,
+    pub created_at: u64,
+}
+
+/// 加载保存的搜索列表
+pub fn load_saved_searches(app_data_dir: &Path) -> Result<Vec<SavedSearch>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = 'everything_saved_searches' LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to load saved searches from database: {}", e))?;
+
+    if let Some(json) = value {
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse saved searches from database: {}", e))
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+fn save_saved_searches(app_data_dir: &Path, searches: &[SavedSearch]) -> Result<(), String> {
+    let conn = db::get_connection(app_data_dir)?;
+
+    let searches_json = serde_json::to_string(searches)
+        .map_err(|e| format!("Failed to serialize saved searches: {}", e))?;
+
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES ('everything_saved_searches', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![searches_json],
+    )
+    .map_err(|e| format!("Failed to save saved searches to database: {}", e))?;
+
+    Ok(())
+}
+
+/// 保存一个新的搜索，返回带生成 ID 的条目
+pub fn add_saved_search(
+    app_data_dir: &Path,
+    name: String,
+    query: String,
+    options: SavedSearchOptions,
+) -> Result<SavedSearch, String> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let name = name.trim().to_string();
+    let query = query.trim().to_string();
+    if name.is_empty() {
+        return Err("名称不能为空".to_string());
+    }
+    if query.is_empty() {
+        return Err("搜索内容不能为空".to_string());
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("Failed to get timestamp: {}", e))?;
+
+    let mut searches = load_saved_searches(app_data_dir)?;
+    // 毫秒时间戳作为 ID，同一毫秒内重复添加时顺延
+    let mut millis = now.as_millis();
+    while searches
+        .iter()
+        .any(|search| search.id == format!("search_{}", millis))
+    {
+        millis += 1;
+    }
+
+    let search = SavedSearch {
+        id: format!("search_{}", millis),
+        name,
+        query,
+        options,
+        created_at: now.as_secs(),
+    };
+    searches.push(search.clone());
+    save_saved_searches(app_data_dir, &searches)?;
+
+    Ok(search)
+}
+
+/// 删除保存的搜索
+pub fn delete_saved_search(app_data_dir: &Path, id: &str) -> Result<(), String> {
+    let mut searches = load_saved_searches(app_data_dir)?;
+    let before = searches.len();
+    searches.retain(|search| search.id != id);
+    if searches.len() == before {
+        return Err(format!("Saved search not found: {}", id));
+    }
+    save_saved_searches(app_data_dir, &searches)
+}
+
+pub fn find_saved_search(app_data_dir: &Path, id: &str) -> Result<SavedSearch, String> {
+    load_saved_searches(app_data_dir)?
+        .into_iter()
+        .find(|search| search.id == id)
+        .ok_or_else(|| format!("Saved search not found: {}", id))
+}
+
+/// 记录到 open_history 时使用的键
+pub fn saved_search_history_key(id: &str) -> String {
+    format!("saved-search:{}", id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn saved_searches_round_trip() {
+        let dir = temp_dir("everything-filters", "round-trip");
+        assert!(load_saved_searches(&dir).unwrap().is_empty());
+
+        let options = SavedSearchOptions {
+            extensions: Some(vec!["pdf".to_string(), "docx".to_string()]),
+            max_results: Some(200),
+            sort_key: Some("date_modified".to_string()),
+            sort_order: Some("desc".to_string()),
+            match_folder_name_only: Some(false),
+        };
+        let first = add_saved_search(
+            &dir,
+            "  Reports  ".to_string(),
+            " report ".to_string(),
+            options.clone(),
+        )
+        .unwrap();
+        let second = add_saved_search(
+            &dir,
+            "Photos".to_string(),
+            "*.jpg".to_string(),
+            SavedSearchOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(first.name, "Reports");
+        assert_eq!(first.query, "report");
+        assert_eq!(first.options, options);
+        assert_ne!(first.id, second.id);
+        assert_eq!(
+            load_saved_searches(&dir).unwrap(),
+            vec![first.clone(), second.clone()]
+        );
+        assert_eq!(find_saved_search(&dir, &second.id).unwrap(), second);
+
+        delete_saved_search(&dir, &first.id).unwrap();
+        assert_eq!(load_saved_searches(&dir).unwrap(), vec![second]);
+        assert!(find_saved_search(&dir, &first.id).is_err());
+        assert!(delete_saved_search(&dir, &first.id).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn saved_search_requires_name_and_query() {
+        let dir = temp_dir("everything-filters", "validation");
+        assert!(add_saved_search(
+            &dir,
+            "  ".to_string(),
+            "report".to_string(),
+            SavedSearchOptions::default()
+        )
+        .is_err());
+        assert!(add_saved_search(
+            &dir,
+            "Reports".to_string(),
+            "".to_string(),
+            SavedSearchOptions::default()
+        )
+        .is_err());
+        assert!(load_saved_searches(&dir).unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn saved_search_options_use_camel_case_and_default_when_missing() {
+        let search: SavedSearch =
+            serde_json::from_str(r#"{"id":"search_1","name":"Docs","query":"*.md","createdAt":1}"#)
+                .unwrap();
+        assert_eq!(search.options, SavedSearchOptions::default());
+
+        let options = SavedSearchOptions {
+            match_folder_name_only: Some(true),
+            ..SavedSearchOptions::default()
+        };
+        let json = serde_json::to_string(&options).unwrap();
+        assert!(json.contains("\"matchFolderNameOnly\":true"), "{}", json);
+        assert_eq!(
+            saved_search_history_key("search_1"),
+            "saved-search:search_1"
+        );
+    }
+}
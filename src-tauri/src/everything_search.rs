@@ -8,6 +8,12 @@ pub struct EverythingResult {
     pub size: Option<u64>,
     pub date_modified: Option<String>,
     pub is_folder: Option<bool>,
+    /// `name` 中匹配查询词的字符区间 [start, end)
+    #[serde(default)]
+    pub name_highlights: Vec<[usize; 2]>,
+    /// `path` 中匹配查询词的字符区间 [start, end)
+    #[serde(default)]
+    pub path_highlights: Vec<[usize; 2]>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -66,6 +72,138 @@ impl fmt::Display for EverythingError {
     }
 }
 
+/// 从 Everything 查询中提取可高亮的字面量词
+/// 忽略 `ext:` 等修饰符和排除词，通配符/正则按字面片段拆分（尽力而为）
+pub fn highlight_terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for token in query.split_whitespace() {
+        let token = token.trim_matches('"');
+        if token.is_empty() || token.starts_with('!') || token == "|" {
+            continue;
+        }
+
+        let (modifier, value) = match token.split_once(':') {
+            Some((m, v)) if !m.is_empty() && m.chars().all(|c| c.is_ascii_alphabetic()) => {
+                (Some(m.to_lowercase()), v)
+            }
+            _ => (None, token),
+        };
+
+        let literal_source = match modifier.as_deref() {
+            None | Some("regex") | Some("path") | Some("file") | Some("folder") | Some("nopath")
+            | Some("case") | Some("nocase") | Some("wholeword") | Some("ww") => value,
+            // ext:、size:、dm: 等修饰符的值不会出现在名称中
+            Some(_) => continue,
+        };
+
+        let is_regex = modifier.as_deref() == Some("regex");
+        for piece in literal_source.split(|c: char| {
+            if is_regex {
+                !(c.is_alphanumeric() || c == '_')
+            } else {
+                matches!(c, '*' | '?' | '|' | '"' | '<' | '>')
+            }
+        }) {
+            let min_len = if is_regex { 2 } else { 1 };
+            if piece.chars().count() >= min_len {
+                terms.push(piece.to_lowercase());
+            }
+        }
+    }
+    terms.sort();
+    terms.dedup();
+    terms
+}
+
+/// 计算 `text` 中所有查询词（不区分大小写）出现的字符区间，重叠区间会合并
+pub fn compute_highlights(text: &str, terms: &[String]) -> Vec<[usize; 2]> {
+    let haystack: Vec<char> = text
+        .chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect();
+    let mut ranges: Vec<[usize; 2]> = Vec::new();
+
+    for term in terms {
+        let needle: Vec<char> = term.chars().collect();
+        if needle.is_empty() || needle.len() > haystack.len() {
+            continue;
+        }
+        let mut i = 0;
+        while i + needle.len() <= haystack.len() {
+            if haystack[i..i + needle.len()] == needle[..] {
+                ranges.push([i, i + needle.len()]);
+                i += needle.len();
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    ranges.sort();
+    let mut merged: Vec<[usize; 2]> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range[0] <= last[1] => last[1] = last[1].max(range[1]),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// 预检查查询中的 `regex:` 片段，语法错误时返回可读的错误信息，避免 Everything 静默返回 0 结果
+/// Everything 使用 PCRE，Rust regex 不支持的特性（环视、反向引用等）不视为错误
+pub fn validate_query(query: &str) -> Result<(), EverythingError> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for c in query.chars() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+            current.push(c);
+        } else if c.is_whitespace() && !in_quotes {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    for token in tokens {
+        let token = token.trim_start_matches('!');
+        let Some(prefix) = token.get(..6) else {
+            continue;
+        };
+        if !prefix.eq_ignore_ascii_case("regex:") {
+            continue;
+        }
+        let raw = &token[6..];
+        let pattern = raw
+            .strip_prefix('"')
+            .and_then(|p| p.strip_suffix('"'))
+            .unwrap_or(raw);
+        if pattern.is_empty() {
+            return Err(EverythingError::InvalidQuery(
+                "regex: 后缺少正则表达式".to_string(),
+            ));
+        }
+        if let Err(e) = regex::Regex::new(pattern) {
+            let message = e.to_string();
+            if message.contains("not supported") {
+                continue;
+            }
+            return Err(EverythingError::InvalidQuery(format!(
+                "正则表达式 \"{}\" 无效: {}",
+                pattern, message
+            )));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(target_os = "windows")]
 pub mod windows {
     use super::*;
@@ -1179,6 +1317,7 @@ pub mod windows {
                 "查询字符串不能为空".to_string(),
             ));
         }
+        validate_query(query)?;
 
         // 检查 Everything 是否运行（只查找一次）
         log_debug!("[DEBUG] Checking if Everything service is running...");
@@ -1190,6 +1329,9 @@ pub mod windows {
         // 每批次的超时（略放宽，避免大结果集/高负载时过早超时）
         let timeout = Duration::from_secs(12);
 
+        // 结果高亮使用的查询词
+        let highlight_query_terms = highlight_terms(query);
+
         // 归一化参数
         let target_max = max_results.max(1);
         let chunk = chunk_size.max(1);
@@ -1384,6 +1526,8 @@ pub mod windows {
                 };
 
                 batch_results.push(EverythingResult {
+                    name_highlights: compute_highlights(&name, &highlight_query_terms),
+                    path_highlights: compute_highlights(path, &highlight_query_terms),
                     path: path.clone(),
                     name,
                     size,
@@ -1461,6 +1605,24 @@ pub mod windows {
         }
     }
 
+    // everything_ipc.h：通过 EVERYTHING_WM_IPC 发送的命令
+    const EVERYTHING_WM_IPC: u32 = WM_USER;
+    const EVERYTHING_IPC_REBUILD_DB: usize = 405;
+
+    /// 请求 Everything 重建索引；消息投递后即返回，重建在 Everything 中异步进行
+    pub fn rebuild_index() -> Result<(), EverythingError> {
+        let hwnd = find_everything_window().ok_or(EverythingError::ServiceNotRunning)?;
+        let posted = unsafe { PostMessageW(hwnd, EVERYTHING_WM_IPC, EVERYTHING_IPC_REBUILD_DB, 0) };
+        if posted == 0 {
+            let error = unsafe { GetLastError() };
+            return Err(EverythingError::IpcFailed(format!(
+                "发送重建索引请求失败 (错误码 {})",
+                error
+            )));
+        }
+        Ok(())
+    }
+
     /// 获取 Everything 路径（返回 Everything.exe 路径）
     pub fn get_everything_path() -> Option<PathBuf> {
         find_everything_main_exe()
@@ -0,0 +1,357 @@
+// 启动器内联计算：只支持数字、+ - * / % ^、括号和少量常量的算术表达式，不执行任意代码
+
+use crate::unit_convert::format_number;
+use serde::Serialize;
+use std::f64::consts::{E, PI, TAU};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpressionResult {
+    pub value: f64,
+    /// 便于直接展示的结果，如 "84"
+    pub formatted: String,
+}
+
+const CONSTANTS: &[(&str, f64)] = &[("pi", PI), ("π", PI), ("tau", TAU), ("e", E)];
+
+/// 括号、正负号和乘方的最大嵌套层数，超出时不作为表达式处理，避免递归求值栈溢出
+const MAX_NESTING_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Op(char),
+    LParen,
+    RParen,
+}
+
+enum EvalError {
+    /// 输入不是算术表达式
+    Syntax,
+    /// 表达式合法但无法计算，如除以零
+    Math(String),
+}
+
+/// 计算过程中的操作数；`percent` 表示该项是单独的百分数（如 "10%"），
+/// 用于 "100 + 10%" 这类按左侧数值的百分比加减
+#[derive(Clone, Copy)]
+struct Operand {
+    value: f64,
+    percent: bool,
+}
+
+impl Operand {
+    fn plain(value: f64) -> Self {
+        Operand {
+            value,
+            percent: false,
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Option<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // 科学计数法：1e3、2.5E-4；单独的 e 作为常量处理
+                if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                    let digit_at = |j: usize| chars.get(j).is_some_and(|c| c.is_ascii_digit());
+                    let sign = matches!(chars.get(i + 1), Some('+') | Some('-'));
+                    if digit_at(i + 1) || (sign && digit_at(i + 2)) {
+                        i += if sign { 2 } else { 1 };
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(Token::Number(text.parse().ok()?));
+            }
+            '+' | '-' | '/' | '%' | '^' => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            '*' => {
+                // ** 与 ^ 相同
+                if chars.get(i + 1) == Some(&'*') {
+                    tokens.push(Token::Op('^'));
+                    i += 2;
+                } else {
+                    tokens.push(Token::Op('*'));
+                    i += 1;
+                }
+            }
+            '×' => {
+                tokens.push(Token::Op('*'));
+                i += 1;
+            }
+            '÷' => {
+                tokens.push(Token::Op('/'));
+                i += 1;
+            }
+            '(' | '（' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' | '）' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            c if c.is_alphabetic() => {
+                let start = i;
+                while i < chars.len() && chars[i].is_alphabetic() {
+                    i += 1;
+                }
+                let name: String = chars[start..i].iter().collect::<String>().to_lowercase();
+                let (_, value) = CONSTANTS.iter().find(|(constant, _)| *constant == name)?;
+                tokens.push(Token::Number(*value));
+            }
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+/// 递归下降求值，优先级从低到高：+ - → * / %（取模） → 一元正负 → ^（右结合） → 百分号
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next_is_op(&self, ops: &[char]) -> Option<char> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(&op) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn expression(&mut self) -> Result<f64, EvalError> {
+        let mut left = self.term()?.value;
+        while let Some(op) = self.next_is_op(&['+', '-']) {
+            self.pos += 1;
+            let right = self.term()?;
+            // "100 + 10%" 按左侧数值的 10% 计算
+            let delta = if right.percent {
+                left * right.value
+            } else {
+                right.value
+            };
+            left = if op == '+' { left + delta } else { left - delta };
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Operand, EvalError> {
+        let mut left = self.unary()?;
+        while let Some(op) = self.next_is_op(&['*', '/', '%']) {
+            self.pos += 1;
+            let right = self.unary()?.value;
+            let value = match op {
+                '*' => left.value * right,
+                _ if right == 0.0 => return Err(EvalError::Math("除数不能为零".to_string())),
+                '/' => left.value / right,
+                _ => left.value % right,
+            };
+            left = Operand::plain(value);
+        }
+        Ok(left)
+    }
+
+    /// 每层括号、正负号和乘方的递归都经过这里，在此限制嵌套深度
+    fn unary(&mut self) -> Result<Operand, EvalError> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(EvalError::Syntax);
+        }
+        self.depth += 1;
+        let operand = self.signed();
+        self.depth -= 1;
+        operand
+    }
+
+    fn signed(&mut self) -> Result<Operand, EvalError> {
+        match self.next_is_op(&['+', '-']) {
+            Some(op) => {
+                self.pos += 1;
+                let operand = self.unary()?;
+                Ok(Operand {
+                    value: if op == '-' { -operand.value } else { operand.value },
+                    percent: operand.percent,
+                })
+            }
+            None => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Operand, EvalError> {
+        let base = self.postfix()?;
+        if self.next_is_op(&['^']).is_some() {
+            self.pos += 1;
+            let exponent = self.unary()?.value;
+            return Ok(Operand::plain(base.value.powf(exponent)));
+        }
+        Ok(base)
+    }
+
+    /// 数值后的 % 在其后不是数字或左括号时表示百分数，否则交给 term 作为取模
+    fn postfix(&mut self) -> Result<Operand, EvalError> {
+        let mut operand = Operand::plain(self.primary()?);
+        while self.peek() == Some(Token::Op('%'))
+            && !matches!(
+                self.tokens.get(self.pos + 1),
+                Some(Token::Number(_)) | Some(Token::LParen)
+            )
+        {
+            self.pos += 1;
+            operand = Operand {
+                value: operand.value / 100.0,
+                percent: true,
+            };
+        }
+        Ok(operand)
+    }
+
+    fn primary(&mut self) -> Result<f64, EvalError> {
+        match self.peek() {
+            Some(Token::Number(value)) => {
+                self.pos += 1;
+                Ok(value)
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let value = self.expression()?;
+                if self.peek() != Some(Token::RParen) {
+                    return Err(EvalError::Syntax);
+                }
+                self.pos += 1;
+                Ok(value)
+            }
+            _ => Err(EvalError::Syntax),
+        }
+    }
+}
+
+/// 计算算术表达式，如 "12*(3+4)"、"200 * 15%"、"2^10"、"pi * 2"
+/// 输入不是表达式（无法解析，或只是单个数字/常量）时返回 Ok(None)；除以零等错误返回 Err
+pub fn evaluate_expression(input: &str) -> Result<Option<ExpressionResult>, String> {
+    let Some(tokens) = tokenize(input.trim()) else {
+        return Ok(None);
+    };
+    // 至少包含一个非开头的运算符，避免把 "42"、"-5"、"e" 当作计算
+    if !tokens.iter().skip(1).any(|token| matches!(token, Token::Op(_))) {
+        return Ok(None);
+    }
+
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+    let value = match parser.expression() {
+        Ok(_) if parser.pos < parser.tokens.len() => return Ok(None),
+        Ok(value) => value,
+        Err(EvalError::Syntax) => return Ok(None),
+        Err(EvalError::Math(message)) => return Err(message),
+    };
+    if !value.is_finite() {
+        return Err("结果超出可表示的范围".to_string());
+    }
+
+    Ok(Some(ExpressionResult {
+        value,
+        formatted: format_number(value),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> f64 {
+        evaluate_expression(input)
+            .unwrap()
+            .unwrap_or_else(|| panic!("not an expression: {}", input))
+            .value
+    }
+
+    #[test]
+    fn operator_precedence() {
+        assert_eq!(eval("2 + 3 * 4"), 14.0);
+        assert_eq!(eval("(2 + 3) * 4"), 20.0);
+        assert_eq!(eval("12*(3+4)"), 84.0);
+        assert_eq!(eval("10 - 4 - 3"), 3.0);
+        assert_eq!(eval("2 × 3 ÷ 4"), 1.5);
+        assert_eq!(eval("（1+2）*3"), 9.0);
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        assert_eq!(eval("2^3^2"), 512.0);
+        assert_eq!(eval("2**10"), 1024.0);
+        // 一元负号的优先级低于乘方
+        assert_eq!(eval("-2^2"), -4.0);
+        assert_eq!(eval("2^-1"), 0.5);
+    }
+
+    #[test]
+    fn percent_and_modulo() {
+        assert_eq!(eval("200 * 15%"), 30.0);
+        assert_eq!(eval("100 + 10%"), 110.0);
+        assert_eq!(eval("100 - 10%"), 90.0);
+        assert_eq!(eval("10 % 3"), 1.0);
+        assert_eq!(eval("10 % (2 + 1)"), 1.0);
+    }
+
+    #[test]
+    fn division_by_zero_is_an_error() {
+        assert!(evaluate_expression("1 / 0").is_err());
+        assert!(evaluate_expression("5 % 0").is_err());
+        assert!(evaluate_expression("10 ^ 400").is_err());
+    }
+
+    #[test]
+    fn malformed_input_is_not_an_expression() {
+        for input in [
+            "",
+            "42",
+            "-5",
+            "e",
+            "2 +",
+            "(2 + 3",
+            "2 + 3)",
+            "2 ** * 3",
+            "abc + 1",
+            "1.2.3 + 1",
+        ] {
+            assert!(matches!(evaluate_expression(input), Ok(None)), "{}", input);
+        }
+    }
+
+    #[test]
+    fn deep_nesting_is_rejected_without_overflow() {
+        let parens = format!("{}1{}", "(".repeat(10_000), ")".repeat(10_000));
+        assert!(matches!(evaluate_expression(&parens), Ok(None)));
+        let signs = format!("1+{}1", "-".repeat(100_000));
+        assert!(matches!(evaluate_expression(&signs), Ok(None)));
+        let powers = vec!["1"; 10_000].join("^");
+        assert!(matches!(evaluate_expression(&powers), Ok(None)));
+        // 限制以内的嵌套正常计算
+        assert_eq!(
+            eval(&format!("{}1+1{}", "(".repeat(20), ")".repeat(20))),
+            2.0
+        );
+    }
+}
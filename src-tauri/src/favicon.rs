@@ -0,0 +1,274 @@
+// 网址快捷方式的网站图标：依次尝试 /favicon.ico 和页面中 <link rel="icon"> 指向的图标，
+// 缩放为统一大小的 PNG，按主机名缓存在应用数据目录的 favicons 子目录中
+
+use base64::{engine::general_purpose, Engine as _};
+use regex::Regex;
+use reqwest::Url;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime};
+
+/// 与应用图标一致的边长
+pub const FAVICON_SIZE: u32 = 32;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+/// 图标和页面分别允许下载的最大字节数
+const MAX_ICON_BYTES: usize = 1024 * 1024;
+const MAX_PAGE_BYTES: usize = 512 * 1024;
+/// 获取失败的主机在这段时间内不再重试
+const FAILURE_RETRY_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+static LINK_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<link\b[^>]*>").unwrap());
+static TAG_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)([a-z][a-z0-9-]*)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+});
+
+/// 只为 http / https 网址获取图标，返回其站点根地址
+pub fn site_root(url: &str) -> Option<Url> {
+    let url = Url::parse(url.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return None;
+    }
+    url.join("/").ok()
+}
+
+/// 站点默认图标地址，如 "https://example.com/docs?a=1" → "https://example.com/favicon.ico"
+pub fn default_favicon_url(url: &str) -> Option<String> {
+    site_root(url)?.join("/favicon.ico").ok().map(String::from)
+}
+
+/// 缓存文件名：主机名（带非默认端口），如 "example.com.png"、"localhost_8080.png"
+pub fn cache_file_name(url: &str) -> Option<String> {
+    let root = site_root(url)?;
+    let host = root.host_str()?.to_lowercase();
+    Some(match root.port() {
+        Some(port) => format!("{}_{}.png", host, port),
+        None => format!("{}.png", host),
+    })
+}
+
+/// 页面中 rel 含 icon（包括 "shortcut icon"、"apple-touch-icon"）的 <link> 的图标地址，
+/// 按出现顺序，相对地址按 base 解析；SVG 无法解码，跳过
+pub fn parse_icon_links(html: &str, base: &Url) -> Vec<String> {
+    LINK_TAG
+        .find_iter(html)
+        .filter_map(|tag| {
+            let mut rel = None;
+            let mut href = None;
+            let mut mime = None;
+            for attr in TAG_ATTRIBUTE.captures_iter(tag.as_str()) {
+                let value = attr
+                    .get(2)
+                    .or_else(|| attr.get(3))
+                    .or_else(|| attr.get(4))
+                    .map_or("", |m| m.as_str());
+                match attr[1].to_ascii_lowercase().as_str() {
+                    "rel" => rel = Some(value.to_ascii_lowercase()),
+                    "href" => href = Some(value.trim().replace("&amp;", "&")),
+                    "type" => mime = Some(value.to_ascii_lowercase()),
+                    _ => {}
+                }
+            }
+            let is_icon = rel?
+                .split_whitespace()
+                .any(|token| token == "icon" || token == "apple-touch-icon");
+            let href = href.filter(|h| !h.is_empty())?;
+            let is_svg = mime.as_deref() == Some("image/svg+xml")
+                || href
+                    .to_ascii_lowercase()
+                    .split('?')
+                    .next()?
+                    .ends_with(".svg");
+            if !is_icon || is_svg {
+                return None;
+            }
+            base.join(&href).ok().map(String::from)
+        })
+        .collect()
+}
+
+/// 读取响应内容，超过 limit 字节时放弃
+async fn read_limited(mut response: reqwest::Response, limit: usize) -> Option<Vec<u8>> {
+    if !response.status().is_success() {
+        return None;
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len > limit as u64)
+    {
+        return None;
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.ok()? {
+        body.extend_from_slice(&chunk);
+        if body.len() > limit {
+            return None;
+        }
+    }
+    Some(body)
+}
+
+/// 解码 ICO / PNG / JPEG 等格式，缩放为 FAVICON_SIZE 的 PNG
+fn to_png(bytes: &[u8]) -> Option<Vec<u8>> {
+    let image = image::load_from_memory(bytes).ok()?;
+    let resized = image.resize_exact(
+        FAVICON_SIZE,
+        FAVICON_SIZE,
+        image::imageops::FilterType::Lanczos3,
+    );
+    let mut png = std::io::Cursor::new(Vec::new());
+    resized
+        .write_to(&mut png, image::ImageOutputFormat::Png)
+        .ok()?;
+    Some(png.into_inner())
+}
+
+async fn download_icon(client: &reqwest::Client, icon_url: &str) -> Option<Vec<u8>> {
+    let response = client.get(icon_url).send().await.ok()?;
+    to_png(&read_limited(response, MAX_ICON_BYTES).await?)
+}
+
+async fn fetch_png(url: &str) -> Option<Vec<u8>> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .ok()?;
+
+    if let Some(icon) = download_icon(&client, &default_favicon_url(url)?).await {
+        return Some(icon);
+    }
+
+    // 没有 /favicon.ico 时从页面的 <link rel="icon"> 查找，相对地址按跳转后的地址解析
+    let response = client.get(url.trim()).send().await.ok()?;
+    let base = response.url().clone();
+    let page = read_limited(response, MAX_PAGE_BYTES).await?;
+    for icon_url in parse_icon_links(&String::from_utf8_lossy(&page), &base) {
+        if let Some(icon) = download_icon(&client, &icon_url).await {
+            return Some(icon);
+        }
+    }
+    None
+}
+
+fn data_url(png: &[u8]) -> String {
+    format!(
+        "data:image/png;base64,{}",
+        general_purpose::STANDARD.encode(png)
+    )
+}
+
+fn cache_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("favicons")
+}
+
+/// 获取网址对应站点的图标（data URL）；非 http(s) 网址或获取失败时返回 None。
+/// 失败结果以空文件缓存，一天内不再重试
+pub async fn fetch_favicon(url: &str, app_data_dir: &Path) -> Option<String> {
+    let cache_file = cache_dir(app_data_dir).join(cache_file_name(url)?);
+    if let Ok(metadata) = std::fs::metadata(&cache_file) {
+        if metadata.len() > 0 {
+            if let Ok(png) = std::fs::read(&cache_file) {
+                return Some(data_url(&png));
+            }
+        } else {
+            let failed_recently = metadata
+                .modified()
+                .ok()
+                .and_then(|time| SystemTime::now().duration_since(time).ok())
+                .is_some_and(|age| age < FAILURE_RETRY_AFTER);
+            if failed_recently {
+                return None;
+            }
+        }
+    }
+
+    let png = fetch_png(url).await;
+    if let Err(e) = std::fs::create_dir_all(cache_dir(app_data_dir))
+        .and_then(|_| std::fs::write(&cache_file, png.as_deref().unwrap_or_default()))
+    {
+        crate::log_warn!(
+            "Favicon",
+            "写入图标缓存失败 {}: {}",
+            cache_file.display(),
+            e
+        );
+    }
+    if png.is_none() {
+        crate::log_debug!("Favicon", "未能获取网站图标: {}", url);
+    }
+    png.map(|png| data_url(&png))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn site_root_accepts_only_http_urls() {
+        assert_eq!(
+            site_root(" https://example.com/docs/page?a=1#top ").map(String::from),
+            Some("https://example.com/".to_string())
+        );
+        assert_eq!(
+            site_root("http://localhost:8080/app").map(String::from),
+            Some("http://localhost:8080/".to_string())
+        );
+        assert!(site_root("ftp://example.com/file").is_none());
+        assert!(site_root("file:///C:/Users/me/index.html").is_none());
+        assert!(site_root("example.com").is_none());
+        assert!(site_root("").is_none());
+    }
+
+    #[test]
+    fn default_favicon_url_uses_site_root() {
+        assert_eq!(
+            default_favicon_url("https://example.com/docs?a=1").as_deref(),
+            Some("https://example.com/favicon.ico")
+        );
+        assert_eq!(
+            default_favicon_url("http://localhost:8080/app/").as_deref(),
+            Some("http://localhost:8080/favicon.ico")
+        );
+        assert!(default_favicon_url("mailto:me@example.com").is_none());
+    }
+
+    #[test]
+    fn cache_file_name_uses_host_and_port() {
+        assert_eq!(
+            cache_file_name("https://Example.COM/docs").as_deref(),
+            Some("example.com.png")
+        );
+        assert_eq!(
+            cache_file_name("https://example.com:443/").as_deref(),
+            Some("example.com.png")
+        );
+        assert_eq!(
+            cache_file_name("http://localhost:8080/").as_deref(),
+            Some("localhost_8080.png")
+        );
+        assert!(cache_file_name("ftp://example.com/").is_none());
+    }
+
+    #[test]
+    fn parse_icon_links_resolves_relative_and_absolute_hrefs() {
+        let base = Url::parse("https://example.com/docs/page.html").unwrap();
+        let html = r#"
+            <link rel="stylesheet" href="/style.css">
+            <link rel="icon" href="/static/icon.png">
+            <LINK REL='Shortcut Icon' HREF='favicon.ico'>
+            <link href="https://cdn.example.net/touch.png?v=1&amp;s=2" rel=apple-touch-icon>
+            <link rel="icon" type="image/svg+xml" href="/icon-vector">
+            <link rel="icon" href="/logo.svg?v=3">
+            <link rel="icon" href="  ">
+        "#;
+        assert_eq!(
+            parse_icon_links(html, &base),
+            [
+                "https://example.com/static/icon.png",
+                "https://example.com/docs/favicon.ico",
+                "https://cdn.example.net/touch.png?v=1&s=2",
+            ]
+        );
+        assert!(parse_icon_links("<html><head></head></html>", &base).is_empty());
+    }
+}
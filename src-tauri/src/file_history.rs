@@ -1,4 +1,6 @@
 use crate::db;
+use crate::error::AppError;
+use crate::{log_debug, log_error};
 #[cfg(target_os = "windows")]
 use pinyin::ToPinyin;
 use rusqlite::params;
@@ -17,8 +19,26 @@ pub struct FileHistoryItem {
     pub use_count: u64,
     #[serde(default)]
     pub is_folder: Option<bool>, // 是否为文件夹
+    /// 最近一次的操作：ACTION_OPEN / ACTION_REVEAL，旧记录为空
+    #[serde(default)]
+    pub last_action: String,
+    /// 以下三项只在 check_path_exists 返回时填写，不写入历史
+    /// 是否为符号链接（Windows 上包括目录联接）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_symlink: Option<bool>,
+    /// 链接指向的目标路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// 链接目标是否存在，为 false 表示悬空链接
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_exists: Option<bool>,
 }
 
+/// 打开文件
+pub const ACTION_OPEN: &str = "open";
+/// 在文件夹中定位
+pub const ACTION_REVEAL: &str = "reveal";
+
 static FILE_HISTORY: LazyLock<Arc<Mutex<HashMap<String, FileHistoryItem>>>> =
     LazyLock::new(|| Arc::new(Mutex::new(HashMap::new())));
 
@@ -26,6 +46,129 @@ pub fn get_history_file_path(app_data_dir: &Path) -> PathBuf {
     app_data_dir.join("file_history.json")
 }
 
+/// 规范化历史路径：统一分隔符、盘符大写、去掉末尾分隔符，并按字面解析 . 和 ..
+pub fn normalize_history_path(path: &str) -> String {
+    normalize_path_with(path, cfg!(target_os = "windows"))
+}
+
+fn normalize_path_with(path: &str, windows: bool) -> String {
+    let sep = if windows { '\\' } else { '/' };
+    let unified = if windows {
+        path.trim().replace('/', "\\")
+    } else {
+        path.trim().to_string()
+    };
+
+    // 拆出前缀：UNC 路径的 \\server\share，或盘符 C:
+    let bytes = unified.as_bytes();
+    let (prefix, rest) = if windows && unified.starts_with("\\\\") {
+        let mut parts = unified[2..].splitn(3, '\\');
+        let server = parts.next().unwrap_or("");
+        let share = parts.next().unwrap_or("");
+        let remainder = parts.next().unwrap_or("");
+        (format!("\\\\{}\\{}", server, share), format!("\\{}", remainder))
+    } else if windows && bytes.len() >= 2 && bytes[1] == b':' && bytes[0].is_ascii_alphabetic() {
+        (unified[..2].to_ascii_uppercase(), unified[2..].to_string())
+    } else {
+        (String::new(), unified.clone())
+    };
+
+    let absolute = rest.starts_with(sep);
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in rest.split(sep) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                if segments.last().is_some_and(|s| *s != "..") {
+                    segments.pop();
+                } else if !absolute {
+                    segments.push("..");
+                }
+            }
+            s => segments.push(s),
+        }
+    }
+
+    let mut normalized = prefix;
+    if absolute {
+        normalized.push(sep);
+    }
+    normalized.push_str(&segments.join(&sep.to_string()));
+    normalized
+}
+
+/// 去重比较用的键：Windows 路径不区分大小写
+fn history_key(path: &str) -> String {
+    if cfg!(target_os = "windows") {
+        path.to_lowercase()
+    } else {
+        path.to_string()
+    }
+}
+
+/// 查找与 path 指向同一文件的已有条目的键
+fn find_history_key(state: &HashMap<String, FileHistoryItem>, path: &str) -> Option<String> {
+    if state.contains_key(path) {
+        return Some(path.to_string());
+    }
+    let key = history_key(&normalize_history_path(path));
+    state
+        .keys()
+        .find(|existing| history_key(existing) == key)
+        .cloned()
+}
+
+/// 合并路径写法不同但指向同一文件的条目：累加 use_count，其余字段取最近使用的一条
+/// 返回是否有改动
+fn merge_duplicate_paths(state: &mut HashMap<String, FileHistoryItem>) -> bool {
+    let mut merged: HashMap<String, FileHistoryItem> = HashMap::new();
+    let mut changed = false;
+
+    let mut items: Vec<FileHistoryItem> = state.drain().map(|(_, item)| item).collect();
+    // 先处理最近使用的条目，使其名称和路径写法优先保留
+    items.sort_by_key(|item| std::cmp::Reverse(item.last_used));
+    for mut item in items {
+        let normalized = normalize_history_path(&item.path);
+        if normalized != item.path {
+            item.path = normalized;
+            changed = true;
+        }
+        match merged.get_mut(&history_key(&item.path)) {
+            Some(existing) => {
+                existing.use_count += item.use_count;
+                changed = true;
+            }
+            None => {
+                merged.insert(history_key(&item.path), item);
+            }
+        }
+    }
+
+    state.extend(
+        merged
+            .into_values()
+            .map(|item| (item.path.clone(), item)),
+    );
+    changed
+}
+
+/// 与 item_from_row 对应的列
+const ITEM_COLUMNS: &str = "path, name, last_used, use_count, is_folder, last_action";
+
+fn item_from_row(row: &rusqlite::Row) -> rusqlite::Result<FileHistoryItem> {
+    Ok(FileHistoryItem {
+        path: row.get(0)?,
+        name: row.get(1)?,
+        last_used: row.get::<_, i64>(2)? as u64,
+        use_count: row.get::<_, i64>(3)? as u64,
+        is_folder: row.get::<_, Option<bool>>(4)?,
+        last_action: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+        is_symlink: None,
+        target: None,
+        target_exists: None,
+    })
+}
+
 // Load history into an already-locked state (no additional locking)
 pub fn load_history_into(
     state: &mut HashMap<String, FileHistoryItem>,
@@ -34,30 +177,21 @@ pub fn load_history_into(
     let conn = db::get_connection(app_data_dir)?;
     maybe_migrate_from_json(&conn, app_data_dir)?;
 
-    println!(
-        "[后端] file_history.load_history_into: Loading from SQLite at {:?}",
+    log_debug!(
+        "FileHistory",
+        "file_history.load_history_into: Loading from SQLite at {:?}",
         db::get_db_path(app_data_dir)
     );
 
     let mut stmt = conn
-        .prepare(
-            "SELECT path, name, last_used, use_count, is_folder FROM file_history ORDER BY last_used DESC",
-        )
+        .prepare(&format!(
+            "SELECT {} FROM file_history ORDER BY last_used DESC",
+            ITEM_COLUMNS
+        ))
         .map_err(|e| format!("Failed to prepare file_history query: {}", e))?;
 
     let rows = stmt
-        .query_map([], |row| {
-            Ok((
-                row.get::<_, String>(0)?,
-                FileHistoryItem {
-                    path: row.get(0)?,
-                    name: row.get(1)?,
-                    last_used: row.get::<_, i64>(2)? as u64,
-                    use_count: row.get::<_, i64>(3)? as u64,
-                    is_folder: row.get::<_, Option<bool>>(4)?,
-                },
-            ))
-        })
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, item_from_row(row)?)))
         .map_err(|e| format!("Failed to iterate file_history rows: {}", e))?;
 
     state.clear();
@@ -65,9 +199,16 @@ pub fn load_history_into(
         let (key, item) = row.map_err(|e| format!("Failed to read file_history row: {}", e))?;
         state.insert(key, item);
     }
+    drop(stmt);
+
+    // 迁移旧数据：同一文件的不同路径写法合并为一条
+    if merge_duplicate_paths(state) {
+        save_history_internal(state, app_data_dir)?;
+    }
 
-    println!(
-        "[后端] file_history.load_history_into: History loaded into state successfully ({} items)",
+    log_debug!(
+        "FileHistory",
+        "file_history.load_history_into: History loaded into state successfully ({} items)",
         state.len()
     );
 
@@ -95,14 +236,15 @@ fn save_history_internal(
 
     for item in state.values() {
         tx.execute(
-            "INSERT INTO file_history (path, name, last_used, use_count, is_folder)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO file_history (path, name, last_used, use_count, is_folder, last_action)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             params![
                 item.path,
                 item.name,
                 item.last_used as i64,
                 item.use_count as i64,
-                item.is_folder
+                item.is_folder,
+                (!item.last_action.is_empty()).then_some(&item.last_action)
             ],
         )
         .map_err(|e| format!("Failed to insert file_history row: {}", e))?;
@@ -128,14 +270,203 @@ pub fn get_history_count(app_data_dir: &Path) -> Result<usize, String> {
     Ok(count as usize)
 }
 
-pub fn add_file_path(path: String, app_data_dir: &Path) -> Result<(), String> {
-    // Normalize path: trim whitespace and remove trailing backslashes/slashes
-    let trimmed = path.trim();
-    let trimmed = trimmed.trim_end_matches(|c| c == '\\' || c == '/');
+#[derive(Serialize, Debug)]
+pub struct ExtensionStat {
+    /// 小写扩展名（不含点），没有扩展名时为空字符串
+    pub extension: String,
+    pub count: usize,
+    pub use_count: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FileHistoryStats {
+    pub total: usize,
+    pub total_uses: u64,
+    pub folders: usize,
+    pub oldest_last_used: Option<u64>,
+    pub newest_last_used: Option<u64>,
+    /// 文件按扩展名分组（不含文件夹），按条数降序
+    pub by_extension: Vec<ExtensionStat>,
+    /// 使用次数最多的 top 条记录
+    pub most_used: Vec<FileHistoryItem>,
+}
+
+/// 直接从 SQLite 统计文件历史，不经过内存缓存
+pub fn get_history_stats(app_data_dir: &Path, top: usize) -> Result<FileHistoryStats, String> {
+    let conn = db::get_connection(app_data_dir)?;
+
+    let (total, total_uses, oldest_last_used, newest_last_used) = conn
+        .query_row(
+            "SELECT COUNT(*), COALESCE(SUM(use_count), 0), MIN(last_used), MAX(last_used) FROM file_history",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as usize,
+                    row.get::<_, i64>(1)? as u64,
+                    row.get::<_, Option<i64>>(2)?.map(|t| t as u64),
+                    row.get::<_, Option<i64>>(3)?.map(|t| t as u64),
+                ))
+            },
+        )
+        .map_err(|e| format!("Failed to summarize file history: {}", e))?;
+
+    let mut folders = 0;
+    let mut extensions: HashMap<String, ExtensionStat> = HashMap::new();
+    let mut stmt = conn
+        .prepare("SELECT path, use_count, is_folder FROM file_history")
+        .map_err(|e| format!("Failed to prepare file_history query: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)? as u64,
+                row.get::<_, Option<bool>>(2)?.unwrap_or(false),
+            ))
+        })
+        .map_err(|e| format!("Failed to iterate file_history rows: {}", e))?;
+    for row in rows {
+        let (path, use_count, is_folder) =
+            row.map_err(|e| format!("Failed to read file_history row: {}", e))?;
+        if is_folder {
+            folders += 1;
+            continue;
+        }
+        let extension = Path::new(&path)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let stat = extensions
+            .entry(extension.clone())
+            .or_insert_with(|| ExtensionStat {
+                extension,
+                count: 0,
+                use_count: 0,
+            });
+        stat.count += 1;
+        stat.use_count += use_count;
+    }
+    drop(stmt);
+
+    let mut by_extension: Vec<ExtensionStat> = extensions.into_values().collect();
+    by_extension.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.extension.cmp(&b.extension))
+    });
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM file_history ORDER BY use_count DESC, last_used DESC LIMIT ?1",
+            ITEM_COLUMNS
+        ))
+        .map_err(|e| format!("Failed to prepare file_history query: {}", e))?;
+    let most_used = stmt
+        .query_map([top as i64], item_from_row)
+        .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Failed to read most used file history: {}", e))?;
+
+    Ok(FileHistoryStats {
+        total,
+        total_uses,
+        folders,
+        oldest_last_used,
+        newest_last_used,
+        by_extension,
+        most_used,
+    })
+}
+
+pub fn add_file_path(path: String, app_data_dir: &Path) -> Result<(), AppError> {
+    record_file_path(path, None, app_data_dir)
+}
+
+/// 记录一次使用，并记下操作类型（ACTION_OPEN / ACTION_REVEAL）
+pub fn add_file_path_with_action(
+    path: String,
+    action: &str,
+    app_data_dir: &Path,
+) -> Result<(), AppError> {
+    record_file_path(path, Some(action), app_data_dir)
+}
+
+/// action 为 None 时保留原有的 last_action
+fn record_file_path(
+    path: String,
+    action: Option<&str>,
+    app_data_dir: &Path,
+) -> Result<(), AppError> {
+    let (normalized_path_str, name, is_folder) = resolve_history_path(&path)?;
+    let timestamp = current_timestamp()?;
+
+    let mut state = FILE_HISTORY.lock().map_err(|e| e.to_string())?;
+
+    if state.is_empty() {
+        load_history_into(&mut state, app_data_dir)?;
+    }
+
+    upsert_history_item(
+        &mut state,
+        normalized_path_str,
+        name,
+        is_folder,
+        action,
+        timestamp,
+    );
+
+    drop(state);
 
+    // Save to disk
+    save_history(app_data_dir)?;
+
+    Ok(())
+}
+
+/// 批量记录：只加载和保存一次，全部在同一把锁内完成；不存在的路径跳过
+/// 返回记录成功的条数
+pub fn add_file_paths(paths: Vec<String>, app_data_dir: &Path) -> Result<usize, AppError> {
+    let timestamp = current_timestamp()?;
+
+    let mut state = FILE_HISTORY.lock().map_err(|e| e.to_string())?;
+    if state.is_empty() {
+        load_history_into(&mut state, app_data_dir)?;
+    }
+
+    let mut added = 0;
+    for path in paths {
+        match resolve_history_path(&path) {
+            Ok((normalized_path_str, name, is_folder)) => {
+                upsert_history_item(
+                    &mut state,
+                    normalized_path_str,
+                    name,
+                    is_folder,
+                    None,
+                    timestamp,
+                );
+                added += 1;
+            }
+            Err(e) => log_debug!("FileHistory", "add_file_paths: skip {}: {}", path, e),
+        }
+    }
+
+    if added > 0 {
+        save_history_internal(&state, app_data_dir)?;
+    }
+    Ok(added)
+}
+
+fn current_timestamp() -> Result<u64, String> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("Failed to get timestamp: {}", e))?
+        .as_secs())
+}
+
+/// 规范化路径并确认存在，返回 (规范化路径, 名称, 是否为文件夹)
+fn resolve_history_path(path: &str) -> Result<(String, String, bool), AppError> {
     // Normalize path (convert to absolute if relative)
-    let path_buf = PathBuf::from(trimmed);
-    let normalized_path = if path_buf.is_absolute() {
+    let path_buf = PathBuf::from(path.trim());
+    let absolute_path = if path_buf.is_absolute() {
         path_buf
     } else {
         std::env::current_dir()
@@ -143,11 +474,16 @@ pub fn add_file_path(path: String, app_data_dir: &Path) -> Result<(), String> {
             .join(&path_buf)
     };
 
-    let normalized_path_str = normalized_path.to_string_lossy().to_string();
+    // 统一分隔符、盘符大小写、末尾分隔符和 ..，避免同一文件出现多条记录
+    let normalized_path_str = normalize_history_path(&absolute_path.to_string_lossy());
+    let normalized_path = PathBuf::from(&normalized_path_str);
 
     // Check if path exists (file or directory)
     if !Path::new(&normalized_path_str).exists() {
-        return Err(format!("Path not found: {}", normalized_path_str));
+        return Err(AppError::NotFound(format!(
+            "Path not found: {}",
+            normalized_path_str
+        )));
     }
 
     // Check if path is a directory
@@ -160,15 +496,26 @@ pub fn add_file_path(path: String, app_data_dir: &Path) -> Result<(), String> {
         .map(|s| s.to_string())
         .unwrap_or_else(|| normalized_path.to_string_lossy().to_string());
 
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|e| format!("Failed to get timestamp: {}", e))?
-        .as_secs();
-
-    let mut state = FILE_HISTORY.lock().map_err(|e| e.to_string())?;
+    Ok((normalized_path_str, name, is_folder))
+}
 
-    if state.is_empty() {
-        load_history_into(&mut state, app_data_dir)?;
+/// 在已锁定的 state 中记录一次使用
+fn upsert_history_item(
+    state: &mut HashMap<String, FileHistoryItem>,
+    normalized_path_str: String,
+    name: String,
+    is_folder: bool,
+    action: Option<&str>,
+    timestamp: u64,
+) {
+    // 已有同一文件的其他写法时，改用规范化后的路径作为键
+    if let Some(existing_key) = find_history_key(state, &normalized_path_str) {
+        if existing_key != normalized_path_str {
+            if let Some(mut item) = state.remove(&existing_key) {
+                item.path = normalized_path_str.clone();
+                state.insert(normalized_path_str.clone(), item);
+            }
+        }
     }
 
     // Update or create history item
@@ -176,6 +523,9 @@ pub fn add_file_path(path: String, app_data_dir: &Path) -> Result<(), String> {
         item.last_used = timestamp;
         item.use_count += 1;
         item.is_folder = Some(is_folder); // Update is_folder in case it changed
+        if let Some(action) = action {
+            item.last_action = action.to_string();
+        }
     } else {
         state.insert(
             normalized_path_str.clone(),
@@ -185,16 +535,13 @@ pub fn add_file_path(path: String, app_data_dir: &Path) -> Result<(), String> {
                 last_used: timestamp,
                 use_count: 1,
                 is_folder: Some(is_folder),
+                last_action: action.unwrap_or_default().to_string(),
+                is_symlink: None,
+                target: None,
+                target_exists: None,
             },
         );
     }
-
-    drop(state);
-
-    // Save to disk
-    save_history(app_data_dir)?;
-
-    Ok(())
 }
 
 // Convert Chinese characters to pinyin (full pinyin)
@@ -234,15 +581,16 @@ fn contains_chinese(text: &str) -> bool {
 // Get a lock guard - caller must ensure no nested locking
 pub fn lock_history(
 ) -> Result<std::sync::MutexGuard<'static, HashMap<String, FileHistoryItem>>, String> {
-    println!("[后端] file_history.lock_history: Attempting to acquire lock...");
+    log_debug!("FileHistory", "file_history.lock_history: Attempting to acquire lock...");
     match FILE_HISTORY.lock() {
         Ok(guard) => {
-            println!("[后端] file_history.lock_history: Lock acquired successfully");
+            log_debug!("FileHistory", "file_history.lock_history: Lock acquired successfully");
             Ok(guard)
         }
         Err(e) => {
-            println!(
-                "[后端] file_history.lock_history: ERROR acquiring lock: {}",
+            log_error!(
+                "FileHistory",
+                "file_history.lock_history: ERROR acquiring lock: {}",
                 e
             );
             Err(e.to_string())
@@ -251,14 +599,27 @@ pub fn lock_history(
 }
 
 // Search within already-locked history (no additional locking)
+// action 不为 None 时只返回最近一次操作为该类型的记录
+/// limit 为 None 时返回全部匹配项
 pub fn search_in_history(
     state: &HashMap<String, FileHistoryItem>,
     query: &str,
+    action: Option<&str>,
+    limit: Option<usize>,
 ) -> Vec<FileHistoryItem> {
+    let limit = limit.unwrap_or(usize::MAX);
+    let matches_action =
+        |item: &FileHistoryItem| action.is_none_or(|action| item.last_action == action);
+
     if query.is_empty() {
         // Return all items sorted by last_used (most recent first)
-        let mut items: Vec<FileHistoryItem> = state.values().cloned().collect();
-        items.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+        let mut items: Vec<FileHistoryItem> = state
+            .values()
+            .filter(|item| matches_action(item))
+            .cloned()
+            .collect();
+        items.sort_by_key(|item| std::cmp::Reverse(item.last_used));
+        items.truncate(limit);
         return items;
     }
 
@@ -268,6 +629,7 @@ pub fn search_in_history(
 
     let mut results: Vec<(FileHistoryItem, i32)> = state
         .values()
+        .filter(|item| matches_action(item))
         .filter_map(|item| {
             let name_lower = item.name.to_lowercase();
             let path_lower = item.path.to_lowercase();
@@ -326,29 +688,31 @@ pub fn search_in_history(
     // Sort by score (descending)
     results.sort_by(|a, b| b.1.cmp(&a.1));
 
-    results.into_iter().map(|(item, _)| item).collect()
+    results.into_iter().take(limit).map(|(item, _)| item).collect()
 }
 
 // Search helper that ensures data is loaded from SQLite.
 pub fn search_file_history(
     query: &str,
+    action: Option<&str>,
+    limit: Option<usize>,
     app_data_dir: &Path,
 ) -> Result<Vec<FileHistoryItem>, String> {
     let mut state = lock_history()?;
     if state.is_empty() {
         load_history_into(&mut state, app_data_dir)?;
     }
-    Ok(search_in_history(&state, query))
+    Ok(search_in_history(&state, query, action, limit))
 }
 
-pub fn delete_file_history(path: String, app_data_dir: &Path) -> Result<(), String> {
+pub fn delete_file_history(path: String, app_data_dir: &Path) -> Result<(), AppError> {
     // Lock once, do all operations
     let mut state = lock_history()?;
     load_history_into(&mut state, app_data_dir)?;
 
-    state
-        .remove(&path)
-        .ok_or_else(|| format!("File history item not found: {}", path))?;
+    find_history_key(&state, &path)
+        .and_then(|key| state.remove(&key))
+        .ok_or_else(|| AppError::NotFound(format!("File history item not found: {}", path)))?;
 
     // Clone the state for saving (we need to release the lock first)
     let state_clone = state.clone();
@@ -418,7 +782,7 @@ pub fn update_file_history_name(
     path: String,
     new_name: String,
     app_data_dir: &Path,
-) -> Result<FileHistoryItem, String> {
+) -> Result<FileHistoryItem, AppError> {
     use std::time::{SystemTime, UNIX_EPOCH};
 
     let timestamp = SystemTime::now()
@@ -430,9 +794,10 @@ pub fn update_file_history_name(
     let mut state = lock_history()?;
     load_history_into(&mut state, app_data_dir)?;
 
-    let item = state
-        .get_mut(&path)
-        .ok_or_else(|| format!("File history item not found: {}", path))?;
+    let key = find_history_key(&state, &path);
+    let item = key
+        .and_then(|key| state.get_mut(&key))
+        .ok_or_else(|| AppError::NotFound(format!("File history item not found: {}", path)))?;
 
     item.name = new_name;
     item.last_used = timestamp;
@@ -470,7 +835,7 @@ fn maybe_migrate_from_json(
     Ok(())
 }
 
-pub fn launch_file(path: &str) -> Result<(), String> {
+pub fn launch_file(path: &str) -> Result<(), AppError> {
     let trimmed = path.trim();
     
     #[cfg(target_os = "windows")]
@@ -496,6 +861,21 @@ pub fn launch_file(path: &str) -> Result<(), String> {
             return Ok(());
         }
         
+        // shell: 位置（打印机、网络、控制面板项等）交给资源管理器打开
+        if trimmed
+            .get(..6)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("shell:"))
+        {
+            use std::process::Command;
+
+            Command::new("explorer.exe")
+                .arg(trimmed)
+                .spawn()
+                .map_err(|e| format!("Failed to open shell location {}: {}", trimmed, e))?;
+
+            return Ok(());
+        }
+
         // Special handling for ms-settings: URI (Windows 10/11 Settings app)
         if trimmed.starts_with("ms-settings:") {
             use std::process::Command;
@@ -529,7 +909,7 @@ pub fn launch_file(path: &str) -> Result<(), String> {
             // For normal paths, check if they exist
             let path_buf = PathBuf::from(&path_str);
             if !path_buf.exists() {
-                return Err(format!("Path not found: {}", path_str));
+                return Err(AppError::NotFound(format!("Path not found: {}", path_str)));
             }
         }
         
@@ -570,10 +950,10 @@ pub fn launch_file(path: &str) -> Result<(), String> {
             // Get last error for more detailed error message
             use windows_sys::Win32::Foundation::GetLastError;
             let error_code = unsafe { GetLastError() };
-            return Err(format!(
+            return Err(AppError::Other(format!(
                 "Failed to open path: {} (error code: {})",
                 path_str, error_code
-            ));
+            )));
         }
     }
 
@@ -589,3 +969,98 @@ pub fn launch_file(path: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+/// 使用指定的应用程序打开文件（"打开方式"）
+pub fn launch_file_with(path: &str, app_path: &str) -> Result<(), AppError> {
+    let path_str = path.trim();
+    let app_str = app_path.trim();
+
+    if !PathBuf::from(path_str).exists() {
+        return Err(AppError::NotFound(format!("Path not found: {}", path_str)));
+    }
+    if app_str.is_empty() {
+        return Err(AppError::NotFound("Application path is empty".to_string()));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::ffi::OsStr;
+        use std::os::windows::ffi::OsStrExt;
+        use windows_sys::Win32::UI::Shell::ShellExecuteW;
+
+        if !PathBuf::from(app_str).exists() {
+            return Err(AppError::NotFound(format!(
+                "Application not found: {}",
+                app_str
+            )));
+        }
+
+        let app_wide: Vec<u16> = OsStr::new(app_str)
+            .encode_wide()
+            .chain(Some(0))
+            .collect();
+        // 文件路径作为参数传给应用，加引号以支持包含空格的路径
+        let params_wide: Vec<u16> = OsStr::new(&format!("\"{}\"", path_str))
+            .encode_wide()
+            .chain(Some(0))
+            .collect();
+
+        log_debug!(
+            "FileHistory",
+            "launch_file_with: opening '{}' with '{}'",
+            path_str,
+            app_str
+        );
+
+        let result = unsafe {
+            ShellExecuteW(
+                0,                    // hwnd - no parent window
+                std::ptr::null(),     // lpOperation - NULL means "open"
+                app_wide.as_ptr(),    // lpFile - application
+                params_wide.as_ptr(), // lpParameters - file to open
+                std::ptr::null(),     // lpDirectory
+                1,                    // SW_SHOWNORMAL
+            )
+        };
+
+        // ShellExecuteW returns a value greater than 32 on success
+        if result <= 32 {
+            return Err(AppError::Other(format!(
+                "Failed to open {} with {} (error code: {})",
+                path_str, app_str, result
+            )));
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::process::Command;
+        Command::new("open")
+            .args(["-a", app_str, path_str])
+            .spawn()
+            .map_err(|e| format!("Failed to launch file with application: {}", e))?;
+    }
+
+    #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
+    {
+        use std::process::Command;
+        // .desktop 条目交给 gtk-launch，其他情况直接执行应用并传入文件路径
+        let mut command = if let Some(desktop_id) = app_str.strip_suffix(".desktop") {
+            let desktop_id = std::path::Path::new(desktop_id)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| desktop_id.to_string());
+            let mut c = Command::new("gtk-launch");
+            c.arg(desktop_id);
+            c
+        } else {
+            Command::new(app_str)
+        };
+        command
+            .arg(path_str)
+            .spawn()
+            .map_err(|e| format!("Failed to launch file with application: {}", e))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,286 @@
+use crate::everything_search::{
+    compute_highlights, highlight_terms, EverythingResult, EverythingSearchResponse,
+};
+use crate::folder_stats;
+use crate::query_guard::QueryTicket;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 单次回退搜索的最长耗时，超时后返回已找到的结果
+pub const FALLBACK_TIME_LIMIT: Duration = Duration::from_secs(3);
+
+/// 收集的候选结果上限，避免宽泛查询占用过多内存
+const MAX_CANDIDATES: usize = 5000;
+
+/// 从 Everything 风格的查询中提取回退搜索能理解的部分
+#[derive(Debug, Default)]
+struct FallbackQuery {
+    terms: Vec<String>,
+    extensions: Vec<String>,
+    excluded_extensions: Vec<String>,
+    only_files: bool,
+    only_folders: bool,
+}
+
+fn split_extensions(value: &str) -> Vec<String> {
+    value
+        .split([';', ','])
+        .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+        .filter(|e| !e.is_empty())
+        .collect()
+}
+
+fn parse_query(query: &str) -> FallbackQuery {
+    let mut parsed = FallbackQuery::default();
+    for token in query.split_whitespace() {
+        let lower = token.to_lowercase();
+        if let Some(exts) = lower.strip_prefix("!ext:") {
+            parsed.excluded_extensions.extend(split_extensions(exts));
+        } else if let Some(exts) = lower.strip_prefix("ext:") {
+            parsed.extensions.extend(split_extensions(exts));
+        } else if lower == "file:" {
+            parsed.only_files = true;
+        } else if lower == "folder:" {
+            parsed.only_folders = true;
+        } else if let Some((modifier, value)) = lower.split_once(':') {
+            // 其他修饰符（path:、regex: 等）只保留其值作为普通关键字
+            if modifier.chars().all(|c| c.is_ascii_alphabetic()) {
+                if !value.is_empty() {
+                    parsed.terms.push(value.trim_matches('"').to_string());
+                }
+            } else {
+                parsed.terms.push(lower);
+            }
+        } else if !lower.starts_with('!') {
+            parsed.terms.push(lower.trim_matches('"').to_string());
+        }
+    }
+    parsed
+}
+
+/// 按顺序出现即视为模糊匹配
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut chars = haystack.chars();
+    needle.chars().all(|c| chars.any(|h| h == c))
+}
+
+/// 所有关键字都需匹配：子串匹配得分高于模糊匹配，名称越短越靠前
+fn match_score(name: &str, terms: &[String]) -> Option<u32> {
+    let mut score = 0u32;
+    for term in terms {
+        if let Some(pos) = name.find(term.as_str()) {
+            score += if pos == 0 { 300 } else { 200 };
+        } else if is_subsequence(term, name) {
+            score += 50;
+        } else {
+            return None;
+        }
+    }
+    Some(score.saturating_sub(name.chars().count().min(100) as u32))
+}
+
+/// 默认搜索目录：用户的桌面、文档和下载文件夹
+pub fn default_roots() -> Vec<PathBuf> {
+    let home = std::env::var_os("USERPROFILE")
+        .or_else(|| std::env::var_os("HOME"))
+        .map(PathBuf::from);
+    let Some(home) = home else {
+        return Vec::new();
+    };
+    let roots: Vec<PathBuf> = ["Desktop", "Documents", "Downloads"]
+        .iter()
+        .map(|dir| home.join(dir))
+        .filter(|dir| dir.is_dir())
+        .collect();
+    if roots.is_empty() {
+        vec![home]
+    } else {
+        roots
+    }
+}
+
+/// Everything 不可用时的回退搜索：在限定深度内遍历根目录，按名称匹配。
+/// ticket 被更新的查询取代后停止遍历，返回已找到的结果
+pub fn search(
+    query: &str,
+    roots: &[PathBuf],
+    max_depth: usize,
+    max_results: usize,
+    ticket: &QueryTicket,
+) -> EverythingSearchResponse {
+    let parsed = parse_query(query);
+    let highlight = highlight_terms(query);
+    let deadline = Instant::now() + FALLBACK_TIME_LIMIT;
+    let mut matches: Vec<(u32, EverythingResult)> = Vec::new();
+    let mut queue: VecDeque<(PathBuf, usize)> = roots.iter().map(|r| (r.clone(), 0)).collect();
+
+    'walk: while let Some((dir, depth)) = queue.pop_front() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if Instant::now() >= deadline
+                || matches.len() >= MAX_CANDIDATES
+                || !ticket.is_current()
+            {
+                break 'walk;
+            }
+            let path = entry.path();
+            let Ok(meta) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if folder_stats::is_reparse_point(&meta) {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_folder = meta.is_dir();
+
+            // 跳过 .git 等隐藏目录，避免遍历大量无关文件
+            if is_folder && depth < max_depth && !name.starts_with('.') {
+                queue.push_back((path.clone(), depth + 1));
+            }
+
+            if let Some(result) = match_entry(&parsed, &highlight, &path, &name, &meta) {
+                matches.push(result);
+            }
+        }
+    }
+
+    let total_count = matches.len() as u32;
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+    let results = matches
+        .into_iter()
+        .take(max_results)
+        .map(|(_, result)| result)
+        .collect();
+
+    EverythingSearchResponse {
+        results,
+        total_count,
+    }
+}
+
+fn match_entry(
+    parsed: &FallbackQuery,
+    highlight: &[String],
+    path: &Path,
+    name: &str,
+    meta: &fs::Metadata,
+) -> Option<(u32, EverythingResult)> {
+    let is_folder = meta.is_dir();
+    if (parsed.only_files && is_folder) || (parsed.only_folders && !is_folder) {
+        return None;
+    }
+
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !parsed.extensions.is_empty() && (is_folder || !parsed.extensions.contains(&ext)) {
+        return None;
+    }
+    if !is_folder && parsed.excluded_extensions.contains(&ext) {
+        return None;
+    }
+    if parsed.terms.is_empty() && parsed.extensions.is_empty() {
+        return None;
+    }
+
+    let score = match_score(&name.to_lowercase(), &parsed.terms)?;
+    let path_str = path.to_string_lossy().to_string();
+    Some((
+        score,
+        EverythingResult {
+            name_highlights: compute_highlights(name, highlight),
+            path_highlights: compute_highlights(&path_str, highlight),
+            path: path_str,
+            name: name.to_string(),
+            size: if is_folder { None } else { Some(meta.len()) },
+            date_modified: None,
+            is_folder: Some(is_folder),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query_guard::LatestQueryGuard;
+    use crate::test_util::temp_dir;
+
+    fn temp_tree(name: &str) -> PathBuf {
+        let root = temp_dir("fallback", name);
+        fs::create_dir_all(root.join("docs/nested")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("report.txt"), b"a").unwrap();
+        fs::write(root.join("docs/report-final.pdf"), b"b").unwrap();
+        fs::write(root.join("docs/nested/old_report.txt"), b"c").unwrap();
+        fs::write(root.join(".git/report.txt"), b"d").unwrap();
+        root
+    }
+
+    fn names(response: &EverythingSearchResponse) -> Vec<String> {
+        response.results.iter().map(|r| r.name.clone()).collect()
+    }
+
+    #[test]
+    fn parse_query_extracts_modifiers() {
+        let parsed = parse_query("Report ext:txt;.PDF !ext:tmp file: path:docs");
+        assert_eq!(parsed.terms, vec!["report", "docs"]);
+        assert_eq!(parsed.extensions, vec!["txt", "pdf"]);
+        assert_eq!(parsed.excluded_extensions, vec!["tmp"]);
+        assert!(parsed.only_files);
+        assert!(!parsed.only_folders);
+    }
+
+    #[test]
+    fn match_score_prefers_prefix_then_substring_then_fuzzy() {
+        let terms = vec!["rep".to_string()];
+        let prefix = match_score("report.txt", &terms).unwrap();
+        let substring = match_score("old_report.txt", &terms).unwrap();
+        let fuzzy = match_score("readme.py", &terms).unwrap();
+        assert!(prefix > substring);
+        assert!(substring > fuzzy);
+        assert_eq!(match_score("notes.md", &terms), None);
+    }
+
+    #[test]
+    fn search_walks_tree_and_skips_hidden_folders() {
+        let roots = vec![temp_tree("walk")];
+        let ticket = LatestQueryGuard::default().begin("test");
+        let response = search("report", &roots, 6, 10, &ticket);
+        assert_eq!(response.total_count, 3);
+        assert_eq!(
+            names(&response),
+            vec!["report.txt", "report-final.pdf", "old_report.txt"]
+        );
+
+        // 深度限制：只遍历根目录和第一层子目录
+        let shallow = search("report", &roots, 1, 10, &ticket);
+        assert_eq!(names(&shallow), vec!["report.txt", "report-final.pdf"]);
+
+        let txt_only = search("report ext:txt", &roots, 6, 1, &ticket);
+        assert_eq!(txt_only.total_count, 2);
+        assert_eq!(names(&txt_only), vec!["report.txt"]);
+        let _ = fs::remove_dir_all(&roots[0]);
+    }
+
+    #[test]
+    fn superseded_search_stops_walking() {
+        let roots = vec![temp_tree("superseded")];
+        let guard = LatestQueryGuard::default();
+        let ticket = guard.begin("test");
+        guard.begin("test");
+        let response = search("report", &roots, 6, 10, &ticket);
+        assert_eq!(response.total_count, 0);
+        assert!(response.results.is_empty());
+        let _ = fs::remove_dir_all(&roots[0]);
+    }
+
+    #[test]
+    fn fallback_is_disabled_by_default() {
+        assert!(!crate::settings::EverythingFallbackSettings::default().allow_fallback);
+    }
+}
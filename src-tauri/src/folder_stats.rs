@@ -0,0 +1,389 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 文件夹预览中的单个子项
+#[derive(Serialize, Debug, Clone)]
+pub struct FolderChild {
+    pub name: String,
+    pub is_dir: bool,
+    pub size: Option<u64>,
+}
+
+/// 文件夹预览信息
+#[derive(Serialize, Debug, Clone)]
+pub struct FolderPreview {
+    pub children: Vec<FolderChild>,
+    /// 直接子项总数（可能大于 `children.len()`）
+    pub child_count: usize,
+    /// 递归计算的总大小；未请求或超出限制时为 None
+    pub total_size: Option<u64>,
+}
+
+/// 预览中最多列出的子项数量
+pub const MAX_PREVIEW_CHILDREN: usize = 200;
+
+/// 递归统计的上限，超出任一项即放弃，避免大目录卡住界面
+pub struct WalkBudget {
+    pub max_entries: usize,
+    pub deadline: Instant,
+}
+
+impl WalkBudget {
+    pub fn new(max_entries: usize, time_limit: Duration) -> Self {
+        WalkBudget {
+            max_entries,
+            deadline: Instant::now() + time_limit,
+        }
+    }
+}
+
+/// 判断是否为符号链接或 Windows 重解析点（junction 等），遍历时跳过以避免循环
+pub fn is_reparse_point(meta: &fs::Metadata) -> bool {
+    if meta.file_type().is_symlink() {
+        return true;
+    }
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+        if meta.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0 {
+            return true;
+        }
+    }
+    false
+}
+
+/// 列出目录的直接子项（文件夹在前，按名称排序），最多 `cap` 项；
+/// 先对全部子项排序再截断，保证返回的是排序后的前 `cap` 项而不是目录遍历顺序中的任意子集
+pub fn list_children(dir: &Path, cap: usize) -> Result<(Vec<FolderChild>, usize), String> {
+    let mut children = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| format!("读取目录失败: {}", e))? {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let meta = entry.metadata().ok();
+        let is_dir = meta.as_ref().is_some_and(|m| m.is_dir());
+        children.push(FolderChild {
+            name: entry.file_name().to_string_lossy().to_string(),
+            is_dir,
+            size: meta.filter(|m| m.is_file()).map(|m| m.len()),
+        });
+    }
+
+    let count = children.len();
+    let order = |a: &FolderChild, b: &FolderChild| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    };
+    if children.len() > cap {
+        if cap > 0 {
+            children.select_nth_unstable_by(cap - 1, order);
+        }
+        children.truncate(cap);
+    }
+    children.sort_by(order);
+    Ok((children, count))
+}
+
+/// 递归计算目录总大小，跳过重解析点和无权限的条目；超出预算时返回 None
+pub fn compute_dir_size(dir: &Path, budget: &WalkBudget) -> Option<u64> {
+    let mut total = 0u64;
+    let mut visited = 0usize;
+    let mut stack: Vec<PathBuf> = vec![dir.to_path_buf()];
+
+    while let Some(current) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            visited += 1;
+            if visited > budget.max_entries || Instant::now() >= budget.deadline {
+                return None;
+            }
+            let Ok(meta) = fs::symlink_metadata(entry.path()) else {
+                continue;
+            };
+            if is_reparse_point(&meta) {
+                continue;
+            }
+            if meta.is_dir() {
+                stack.push(entry.path());
+            } else {
+                total += meta.len();
+            }
+        }
+    }
+
+    Some(total)
+}
+
+/// 构建文件夹预览；`compute_size` 为 true 时在预算内递归统计总大小
+pub fn build_folder_preview(dir: &Path, compute_size: bool) -> Result<FolderPreview, String> {
+    let (children, child_count) = list_children(dir, MAX_PREVIEW_CHILDREN)?;
+    let total_size = if compute_size {
+        compute_dir_size(dir, &WalkBudget::new(200_000, Duration::from_secs(3)))
+    } else {
+        None
+    };
+
+    Ok(FolderPreview {
+        children,
+        child_count,
+        total_size,
+    })
+}
+
+/// 单个扩展名的占用统计
+#[derive(Serialize, Debug, Clone)]
+pub struct ExtensionStat {
+    /// 小写扩展名，无扩展名的文件为空字符串
+    pub extension: String,
+    pub bytes: u64,
+    pub count: usize,
+}
+
+/// 文件夹空间分析结果
+#[derive(Serialize, Debug, Clone)]
+pub struct FolderAnalysis {
+    pub root: String,
+    pub total_size: u64,
+    pub file_count: usize,
+    pub dir_count: usize,
+    /// 因权限等原因无法读取的条目数
+    pub error_count: usize,
+    /// 是否存在超过 `max_depth` 而未展开的子目录
+    pub depth_limited: bool,
+    /// 按占用字节数降序排列
+    pub extensions: Vec<ExtensionStat>,
+}
+
+/// 分析文件夹空间占用：统计总大小、文件数和按扩展名的分布
+/// 单个条目出错只计数不终止；`on_progress(已扫描文件数, 已统计字节数, 当前目录)` 在每个目录开始时调用
+pub fn analyze_folder<F>(
+    root: &Path,
+    max_depth: usize,
+    mut on_progress: F,
+) -> Result<FolderAnalysis, String>
+where
+    F: FnMut(usize, u64, &Path),
+{
+    if !root.is_dir() {
+        return Err("文件夹不存在或不是有效目录".to_string());
+    }
+
+    let mut by_extension: std::collections::HashMap<String, (u64, usize)> =
+        std::collections::HashMap::new();
+    let mut total_size = 0u64;
+    let mut file_count = 0usize;
+    let mut dir_count = 0usize;
+    let mut error_count = 0usize;
+    let mut depth_limited = false;
+    let mut stack: Vec<(PathBuf, usize)> = vec![(root.to_path_buf(), 0)];
+
+    while let Some((dir, depth)) = stack.pop() {
+        on_progress(file_count, total_size, &dir);
+
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => {
+                error_count += 1;
+                continue;
+            }
+        };
+
+        for entry in entries {
+            let Ok(entry) = entry else {
+                error_count += 1;
+                continue;
+            };
+            let path = entry.path();
+            let Ok(meta) = fs::symlink_metadata(&path) else {
+                error_count += 1;
+                continue;
+            };
+            if is_reparse_point(&meta) {
+                continue;
+            }
+
+            if meta.is_dir() {
+                dir_count += 1;
+                if depth < max_depth {
+                    stack.push((path, depth + 1));
+                } else {
+                    depth_limited = true;
+                }
+            } else {
+                file_count += 1;
+                total_size += meta.len();
+                let ext = path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .map(|e| e.to_lowercase())
+                    .unwrap_or_default();
+                let stat = by_extension.entry(ext).or_insert((0, 0));
+                stat.0 += meta.len();
+                stat.1 += 1;
+            }
+        }
+    }
+
+    let mut extensions: Vec<ExtensionStat> = by_extension
+        .into_iter()
+        .map(|(extension, (bytes, count))| ExtensionStat {
+            extension,
+            bytes,
+            count,
+        })
+        .collect();
+    extensions.sort_by(|a, b| {
+        b.bytes
+            .cmp(&a.bytes)
+            .then_with(|| a.extension.cmp(&b.extension))
+    });
+
+    Ok(FolderAnalysis {
+        root: root.to_string_lossy().to_string(),
+        total_size,
+        file_count,
+        dir_count,
+        error_count,
+        depth_limited,
+        extensions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    /// 测试用临时目录，结束时删除
+    struct TempTree(PathBuf);
+
+    impl TempTree {
+        fn new(name: &str) -> Self {
+            TempTree(temp_dir("folder", name))
+        }
+
+        fn file(&self, relative: &str, size: usize) {
+            let path = self.0.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, vec![b'x'; size]).unwrap();
+        }
+    }
+
+    impl Drop for TempTree {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn lists_folders_first_then_by_name() {
+        let tree = TempTree::new("list");
+        tree.file("b.txt", 3);
+        tree.file("A.txt", 5);
+        tree.file("zdir/inner.txt", 1);
+        fs::create_dir(tree.0.join("Adir")).unwrap();
+
+        let (children, count) = list_children(&tree.0, 10).unwrap();
+        let names: Vec<&str> = children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Adir", "zdir", "A.txt", "b.txt"]);
+        assert_eq!(count, 4);
+        assert_eq!(children[2].size, Some(5));
+        assert_eq!(children[0].size, None);
+    }
+
+    #[test]
+    fn cap_keeps_the_first_entries_in_sorted_order() {
+        let tree = TempTree::new("cap");
+        for i in (0..30).rev() {
+            tree.file(&format!("file{:02}.txt", i), 1);
+        }
+        fs::create_dir(tree.0.join("zz_dir")).unwrap();
+
+        let (children, count) = list_children(&tree.0, 3).unwrap();
+        let names: Vec<&str> = children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["zz_dir", "file00.txt", "file01.txt"]);
+        assert_eq!(count, 31);
+    }
+
+    #[test]
+    fn computes_recursive_size_within_budget() {
+        let tree = TempTree::new("size");
+        tree.file("a.bin", 100);
+        tree.file("sub/b.bin", 20);
+        tree.file("sub/deeper/c.bin", 3);
+
+        let budget = WalkBudget::new(1000, Duration::from_secs(10));
+        assert_eq!(compute_dir_size(&tree.0, &budget), Some(123));
+
+        let tiny = WalkBudget::new(2, Duration::from_secs(10));
+        assert_eq!(compute_dir_size(&tree.0, &tiny), None);
+
+        let preview = build_folder_preview(&tree.0, true).unwrap();
+        assert_eq!(preview.child_count, 2);
+        assert_eq!(preview.total_size, Some(123));
+        assert_eq!(
+            build_folder_preview(&tree.0, false).unwrap().total_size,
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_symlink_loops() {
+        let tree = TempTree::new("loop");
+        tree.file("sub/a.bin", 10);
+        std::os::unix::fs::symlink(&tree.0, tree.0.join("sub/back")).unwrap();
+
+        let budget = WalkBudget::new(1000, Duration::from_secs(10));
+        assert_eq!(compute_dir_size(&tree.0, &budget), Some(10));
+    }
+
+    #[test]
+    fn analyzes_sizes_by_extension() {
+        let tree = TempTree::new("analyze");
+        tree.file("a.LOG", 40);
+        tree.file("b.log", 10);
+        tree.file("c.txt", 30);
+        tree.file("README", 5);
+        tree.file("sub/d.txt", 15);
+        tree.file("sub/deep/e.bin", 1000);
+
+        let mut progress_calls = 0;
+        let analysis = analyze_folder(&tree.0, 8, |_, _, _| progress_calls += 1).unwrap();
+        assert_eq!(analysis.total_size, 1100);
+        assert_eq!(analysis.file_count, 6);
+        assert_eq!(analysis.dir_count, 2);
+        assert!(!analysis.depth_limited);
+        assert_eq!(progress_calls, 3);
+        let breakdown: Vec<(&str, u64, usize)> = analysis
+            .extensions
+            .iter()
+            .map(|s| (s.extension.as_str(), s.bytes, s.count))
+            .collect();
+        assert_eq!(
+            breakdown,
+            [("bin", 1000, 1), ("log", 50, 2), ("txt", 45, 2), ("", 5, 1)]
+        );
+    }
+
+    #[test]
+    fn analyze_respects_max_depth() {
+        let tree = TempTree::new("depth");
+        tree.file("top.txt", 1);
+        tree.file("sub/inner.txt", 2);
+        tree.file("sub/deep/hidden.txt", 4);
+
+        let analysis = analyze_folder(&tree.0, 1, |_, _, _| {}).unwrap();
+        assert_eq!(analysis.total_size, 3);
+        assert_eq!(analysis.dir_count, 2);
+        assert!(analysis.depth_limited);
+
+        assert!(analyze_folder(&tree.0.join("missing"), 4, |_, _, _| {}).is_err());
+    }
+}
@@ -171,6 +171,18 @@ pub mod windows {
 
     static HOTKEY_STATE: Mutex<Option<Arc<Mutex<HotkeyState>>>> = Mutex::new(None);
 
+    // 全局暂停（免打扰）：为 false 时忽略所有快捷键触发，但保留注册
+    static HOTKEYS_ENABLED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(true);
+
+    pub fn set_hotkeys_enabled(enabled: bool) {
+        HOTKEYS_ENABLED.store(enabled, std::sync::atomic::Ordering::SeqCst);
+        log_hotkey!("[Hotkey] Hotkeys {}", if enabled { "enabled" } else { "paused" });
+    }
+
+    pub fn hotkeys_enabled() -> bool {
+        HOTKEYS_ENABLED.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
     // 检查虚拟键码是否匹配目标键（包括左右键变体）
     fn is_target_key(vk_code: u32, target_vk: u32) -> bool {
         if vk_code == target_vk {
@@ -896,7 +908,7 @@ pub mod windows {
                 0
             }
             WM_HOTKEY => {
-                if wparam == HOTKEY_ID as usize {
+                if wparam == HOTKEY_ID as usize && hotkeys_enabled() {
                     // Get sender from window user data
                     let sender_ptr = windows_sys::Win32::UI::WindowsAndMessaging::GetWindowLongPtrW(
                         hwnd,
@@ -1038,6 +1050,11 @@ pub mod windows {
             None => return CallNextHookEx(windows_sys::Win32::UI::WindowsAndMessaging::HHOOK::default(), nCode, wParam, lParam),
         };
         
+        // 快捷键已全局暂停时直接放行按键
+        if !hotkeys_enabled() {
+            return CallNextHookEx(windows_sys::Win32::UI::WindowsAndMessaging::HHOOK::default(), nCode, wParam, lParam);
+        }
+        
         // 检查是否匹配任何已注册的快捷键
         let manager = MULTI_HOTKEY_MANAGER.clone();
         let hotkeys_guard = manager.hotkeys.lock().unwrap();
@@ -1310,4 +1327,14 @@ pub mod windows {
     ) -> Result<(), String> {
         Err("Plugin hotkeys update is only supported on Windows".to_string())
     }
+    
+    static HOTKEYS_ENABLED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(true);
+    
+    pub fn set_hotkeys_enabled(enabled: bool) {
+        HOTKEYS_ENABLED.store(enabled, std::sync::atomic::Ordering::SeqCst);
+    }
+    
+    pub fn hotkeys_enabled() -> bool {
+        HOTKEYS_ENABLED.load(std::sync::atomic::Ordering::SeqCst)
+    }
 }
@@ -0,0 +1,248 @@
+// JSONPath 子集：$ 根、.name / ['name'] 取字段、[0] / [-1] 取数组元素、* / [*] 通配
+
+use crate::json_tools::JsonValue;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(i64),
+    Wildcard,
+}
+
+/// 解析后的路径
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    /// 解析路径，如 "$.users[0].name"、"users[*]['first name']"；开头的 $ 可省略
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let chars: Vec<char> = path.trim().chars().collect();
+        let error = |pos: usize, reason: &str| {
+            format!("无效的 JSONPath（第 {} 个字符）: {}", pos + 1, reason)
+        };
+        let mut segments = Vec::new();
+        let mut i = 0;
+        if chars.first() == Some(&'$') {
+            i = 1;
+        }
+
+        while i < chars.len() {
+            match chars[i] {
+                '.' => {
+                    i += 1;
+                    if chars.get(i) == Some(&'.') {
+                        return Err(error(i, "不支持递归下降 .."));
+                    }
+                    if chars.get(i) == Some(&'*') {
+                        segments.push(Segment::Wildcard);
+                        i += 1;
+                        continue;
+                    }
+                    let start = i;
+                    while i < chars.len() && !matches!(chars[i], '.' | '[') {
+                        i += 1;
+                    }
+                    if start == i {
+                        return Err(error(start, "点号后缺少字段名"));
+                    }
+                    segments.push(Segment::Key(chars[start..i].iter().collect()));
+                }
+                '[' => {
+                    let close = chars[i..]
+                        .iter()
+                        .position(|&c| c == ']')
+                        .map(|offset| i + offset)
+                        .ok_or_else(|| error(i, "缺少 ]"))?;
+                    let inner: String = chars[i + 1..close].iter().collect();
+                    segments.push(
+                        parse_bracket(inner.trim())
+                            .ok_or_else(|| error(i, "方括号中应为数组下标、带引号的字段名或 *"))?,
+                    );
+                    i = close + 1;
+                }
+                // 开头省略 $ 和点号的字段名，如 "users[0]"
+                _ if i == 0 => {
+                    let start = i;
+                    while i < chars.len() && !matches!(chars[i], '.' | '[') {
+                        i += 1;
+                    }
+                    let name: String = chars[start..i].iter().collect();
+                    segments.push(if name == "*" {
+                        Segment::Wildcard
+                    } else {
+                        Segment::Key(name)
+                    });
+                }
+                _ => return Err(error(i, "应为 . 或 [")),
+            }
+        }
+        Ok(JsonPath { segments })
+    }
+
+    /// 不含通配符的路径最多匹配一个值
+    pub fn is_singular(&self) -> bool {
+        !self.segments.contains(&Segment::Wildcard)
+    }
+
+    /// 返回所有匹配的值，按文档顺序排列
+    pub fn select<'a>(&self, root: &'a JsonValue) -> Vec<&'a JsonValue> {
+        let mut current = vec![root];
+        for segment in &self.segments {
+            let mut next = Vec::new();
+            for value in current {
+                match (segment, value) {
+                    (Segment::Key(key), JsonValue::Object(map)) => next.extend(map.get(key)),
+                    (Segment::Index(index), JsonValue::Array(items)) => {
+                        let resolved = if *index < 0 {
+                            items.len().checked_sub(index.unsigned_abs() as usize)
+                        } else {
+                            Some(*index as usize)
+                        };
+                        next.extend(resolved.and_then(|i| items.get(i)));
+                    }
+                    (Segment::Wildcard, JsonValue::Object(map)) => next.extend(map.values()),
+                    (Segment::Wildcard, JsonValue::Array(items)) => next.extend(items.iter()),
+                    _ => {}
+                }
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+/// 方括号内容：*、整数下标、'name' 或 "name"
+fn parse_bracket(inner: &str) -> Option<Segment> {
+    if inner == "*" {
+        return Some(Segment::Wildcard);
+    }
+    if let Ok(index) = inner.parse::<i64>() {
+        return Some(Segment::Index(index));
+    }
+    let quote = inner.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+    let name = inner.strip_prefix(quote)?.strip_suffix(quote)?;
+    Some(Segment::Key(name.to_string()))
+}
+
+/// 在 JSON 文本上执行查询，返回格式化后的结果：
+/// 不含通配符的路径直接返回匹配的值，否则返回所有匹配值组成的数组
+pub fn query_json(input: &str, path: &str) -> Result<String, String> {
+    let path = JsonPath::parse(path)?;
+    let root: JsonValue =
+        serde_json::from_str(input).map_err(|e| format!("JSON 解析失败: {}", e))?;
+    let matches = path.select(&root);
+    if matches.is_empty() {
+        return Err("没有与路径匹配的值".to_string());
+    }
+    let result = if path.is_singular() {
+        serde_json::to_string_pretty(matches[0])
+    } else {
+        serde_json::to_string_pretty(&matches)
+    };
+    result.map_err(|e| format!("序列化 JSON 失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn doc() -> Value {
+        json!({
+            "store": {
+                "name": "ReFast",
+                "users": [
+                    {"name": "a", "first name": "A", "tags": [1, 2]},
+                    {"name": "b", "first name": "B", "tags": [3]},
+                    {"name": "c", "first name": "C", "tags": []}
+                ]
+            }
+        })
+    }
+
+    fn select(path: &str) -> Vec<Value> {
+        let root: JsonValue = serde_json::from_value(doc()).unwrap();
+        JsonPath::parse(path)
+            .unwrap()
+            .select(&root)
+            .into_iter()
+            .map(|value| serde_json::to_value(value).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn nested_object_fields() {
+        assert_eq!(select("$.store.name"), vec![json!("ReFast")]);
+        assert_eq!(select("store.name"), vec![json!("ReFast")]);
+        assert_eq!(select("$"), vec![doc()]);
+        assert!(select("$.store.missing").is_empty());
+        assert!(select("$.store.name.deeper").is_empty());
+    }
+
+    #[test]
+    fn positive_and_negative_indices() {
+        assert_eq!(select("$.store.users[0].name"), vec![json!("a")]);
+        assert_eq!(select("$.store.users[-1].name"), vec![json!("c")]);
+        assert_eq!(select("$.store.users[ -3 ].name"), vec![json!("a")]);
+        assert!(select("$.store.users[3]").is_empty());
+        assert!(select("$.store.users[-4]").is_empty());
+    }
+
+    #[test]
+    fn wildcards() {
+        assert_eq!(
+            select("$.store.users[*].name"),
+            vec![json!("a"), json!("b"), json!("c")]
+        );
+        assert_eq!(select("$.store.users.*.tags[0]"), vec![json!(1), json!(3)]);
+        assert_eq!(select("$.store.*").len(), 2);
+        assert!(!JsonPath::parse("$.a[*]").unwrap().is_singular());
+        assert!(JsonPath::parse("$.a[0]").unwrap().is_singular());
+    }
+
+    #[test]
+    fn quoted_bracket_keys() {
+        assert_eq!(select("$.store.users[1]['first name']"), vec![json!("B")]);
+        assert_eq!(
+            select(r#"$["store"]["users"][2]["name"]"#),
+            vec![json!("c")]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        for path in [
+            "$..name",
+            "$.",
+            "$.a.",
+            "$.a[0",
+            "$.a[name]",
+            "$.a['name\"]",
+            "$.a[]",
+            "$x",
+        ] {
+            assert!(JsonPath::parse(path).is_err(), "{}", path);
+        }
+        let error = JsonPath::parse("$.a[b]").unwrap_err();
+        assert!(error.contains("第 4 个字符"), "{}", error);
+    }
+
+    #[test]
+    fn query_json_formats_results() {
+        let input = doc().to_string();
+        assert_eq!(query_json(&input, "$.store.users[0].tags[1]").unwrap(), "2");
+        assert_eq!(
+            query_json(&input, "$.store.users[*].tags[0]").unwrap(),
+            "[\n  1,\n  3\n]"
+        );
+        // 对象通配按文档中的键顺序返回
+        assert_eq!(
+            query_json(r#"{"b":1,"a":2}"#, "$.*").unwrap(),
+            "[\n  1,\n  2\n]"
+        );
+        assert!(query_json(&input, "$.nothing").is_err());
+        assert!(query_json("{", "$").is_err());
+    }
+}
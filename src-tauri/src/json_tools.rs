@@ -0,0 +1,437 @@
+// JSON 格式化工具：格式化/压缩、按键排序，解析失败时给出精确的行列位置；
+// 以及转换为 YAML / TOML / CSV
+
+use indexmap::IndexMap;
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+/// 缩进空格数上限
+const MAX_INDENT: usize = 8;
+
+/// JSON 语法错误及其位置（行、列均从 1 开始）
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonSyntaxError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonFormatResult {
+    pub valid: bool,
+    /// 格式化后的文本，解析失败时为 None
+    pub output: Option<String>,
+    pub error: Option<JsonSyntaxError>,
+}
+
+/// 输入大小上限，超出时拒绝处理以限制内存占用
+const MAX_JSON_INPUT_BYTES: usize = 64 * 1024 * 1024;
+
+fn syntax_error(e: serde_json::Error) -> JsonSyntaxError {
+    let suffix = format!(" at line {} column {}", e.line(), e.column());
+    let message = e.to_string();
+    JsonSyntaxError {
+        message: message
+            .strip_suffix(&suffix)
+            .unwrap_or(&message)
+            .to_string(),
+        line: e.line(),
+        // 输入为空时 serde_json 报告第 0 列
+        column: e.column().max(1),
+    }
+}
+
+fn check_input_size(input: &str) -> Result<(), String> {
+    if input.len() > MAX_JSON_INPUT_BYTES {
+        return Err(format!(
+            "JSON 内容过大（{} MB），最多支持 {} MB",
+            input.len() / (1024 * 1024),
+            MAX_JSON_INPUT_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(())
+}
+
+/// 保持对象键原有顺序的 JSON 值（serde_json::Value 的对象按键名排序），
+/// 供格式转换和 JSONPath 查询按文档顺序输出
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(IndexMap<String, JsonValue>),
+}
+
+impl<'de> Deserialize<'de> for JsonValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonValueVisitor)
+    }
+}
+
+struct JsonValueVisitor;
+
+impl<'de> Visitor<'de> for JsonValueVisitor {
+    type Value = JsonValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<JsonValue, E> {
+        Ok(JsonValue::Null)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<JsonValue, E> {
+        Ok(JsonValue::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Number(value.into()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<JsonValue, E> {
+        Ok(JsonValue::Number(value.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<JsonValue, E> {
+        serde_json::Number::from_f64(value)
+            .map(JsonValue::Number)
+            .ok_or_else(|| E::custom("JSON 不支持 NaN 或无穷大"))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<JsonValue, E> {
+        Ok(JsonValue::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<JsonValue, E> {
+        Ok(JsonValue::String(value))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsonValue, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(JsonValue::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsonValue, A::Error> {
+        // 重复的键保留首次出现的位置，取最后一次的值
+        let mut object = IndexMap::new();
+        while let Some((key, value)) = map.next_entry::<String, JsonValue>()? {
+            object.insert(key, value);
+        }
+        Ok(JsonValue::Object(object))
+    }
+}
+
+/// 解析 JSON，失败时转换为带位置的错误
+pub fn parse_json(input: &str) -> Result<JsonValue, JsonSyntaxError> {
+    serde_json::from_str(input).map_err(syntax_error)
+}
+
+/// 只校验语法，不构建 Value，内存占用与嵌套深度有关而与内容大小无关
+fn check_syntax(input: &str) -> Result<(), JsonSyntaxError> {
+    serde_json::from_str::<serde::de::IgnoredAny>(input)
+        .map(|_| ())
+        .map_err(syntax_error)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonValidationResult {
+    pub valid: bool,
+    pub error: Option<JsonSyntaxError>,
+}
+
+/// 校验 JSON 语法，无效时返回错误所在的行列
+pub fn validate_json(input: &str) -> Result<JsonValidationResult, String> {
+    check_input_size(input)?;
+    let error = check_syntax(input).err();
+    Ok(JsonValidationResult {
+        valid: error.is_none(),
+        error,
+    })
+}
+
+/// 递归地按键名排序对象
+fn sort_keys(value: JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => {
+            let mut map: IndexMap<String, JsonValue> = map
+                .into_iter()
+                .map(|(key, value)| (key, sort_keys(value)))
+                .collect();
+            map.sort_keys();
+            JsonValue::Object(map)
+        }
+        JsonValue::Array(items) => JsonValue::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+/// 以指定缩进输出，indent 为 0 时输出压缩后的单行 JSON
+fn serialize_with_indent<T: Serialize + ?Sized>(value: &T, indent: usize) -> Result<String, String> {
+    let mut output = Vec::new();
+    if indent == 0 {
+        value
+            .serialize(&mut serde_json::Serializer::new(&mut output))
+            .map_err(|e| format!("序列化 JSON 失败: {}", e))?;
+    } else {
+        let indent = " ".repeat(indent.min(MAX_INDENT));
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+        value
+            .serialize(&mut serde_json::Serializer::with_formatter(&mut output, formatter))
+            .map_err(|e| format!("序列化 JSON 失败: {}", e))?;
+    }
+    String::from_utf8(output).map_err(|e| format!("序列化 JSON 失败: {}", e))
+}
+
+/// 不排序时边解析边输出（serde-transcode），不在内存中构建完整的 Value
+fn transcode_with_indent(input: &str, indent: usize) -> Result<String, String> {
+    let mut deserializer = serde_json::Deserializer::from_str(input);
+    let transcoder = serde_transcode::Transcoder::new(&mut deserializer);
+    let output = serialize_with_indent(&transcoder, indent)?;
+    deserializer
+        .end()
+        .map_err(|e| format!("序列化 JSON 失败: {}", e))?;
+    Ok(output)
+}
+
+/// 格式化 JSON；键默认保持原有顺序，sort 为 true 时递归排序
+pub fn format_json(input: &str, indent: usize, sort: bool) -> Result<JsonFormatResult, String> {
+    check_input_size(input)?;
+    // 先做一遍只校验的解析，得到准确的错误位置（转码过程中的错误不带行列）
+    if let Err(error) = check_syntax(input) {
+        return Ok(JsonFormatResult {
+            valid: false,
+            output: None,
+            error: Some(error),
+        });
+    }
+
+    let output = if sort {
+        let value = parse_json(input).map_err(|e| e.message)?;
+        serialize_with_indent(&sort_keys(value), indent)?
+    } else {
+        transcode_with_indent(input, indent)?
+    };
+
+    Ok(JsonFormatResult {
+        valid: true,
+        output: Some(output),
+        error: None,
+    })
+}
+
+/// JSON 可转换的目标格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertFormat {
+    Yaml,
+    Toml,
+    Csv,
+}
+
+impl ConvertFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Some(ConvertFormat::Yaml),
+            "toml" => Some(ConvertFormat::Toml),
+            "csv" => Some(ConvertFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// 将 JSON 转换为 target 格式的文本，无法映射的结构返回错误
+pub fn convert_json(input: &str, target: ConvertFormat) -> Result<String, String> {
+    check_input_size(input)?;
+    let value = parse_json(input).map_err(|e| {
+        format!(
+            "JSON 解析失败（第 {} 行第 {} 列）: {}",
+            e.line, e.column, e.message
+        )
+    })?;
+    match target {
+        ConvertFormat::Yaml => {
+            serde_yaml::to_string(&value).map_err(|e| format!("转换为 YAML 失败: {}", e))
+        }
+        ConvertFormat::Toml => to_toml(&value),
+        ConvertFormat::Csv => to_csv(&value),
+    }
+}
+
+/// TOML 顶层必须是表，且没有 null
+fn to_toml(value: &JsonValue) -> Result<String, String> {
+    if !matches!(value, JsonValue::Object(_)) {
+        return Err("转换为 TOML 时顶层必须是对象".to_string());
+    }
+    if let Some(path) = find_null(value, "") {
+        return Err(format!("TOML 不支持 null 值（位于 {}）", path));
+    }
+    toml::to_string_pretty(value).map_err(|e| format!("转换为 TOML 失败: {}", e))
+}
+
+/// 返回第一个 null 值的路径，如 "server.ports[1]"
+fn find_null(value: &JsonValue, path: &str) -> Option<String> {
+    match value {
+        JsonValue::Null => Some(if path.is_empty() {
+            "顶层".to_string()
+        } else {
+            path.to_string()
+        }),
+        JsonValue::Object(map) => map.iter().find_map(|(key, value)| {
+            let child = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            find_null(value, &child)
+        }),
+        JsonValue::Array(items) => items
+            .iter()
+            .enumerate()
+            .find_map(|(index, value)| find_null(value, &format!("{}[{}]", path, index))),
+        _ => None,
+    }
+}
+
+/// CSV 只支持由扁平对象组成的数组；列为所有对象键的并集（按首次出现的顺序），缺失的键留空
+fn to_csv(value: &JsonValue) -> Result<String, String> {
+    let JsonValue::Array(rows) = value else {
+        return Err("转换为 CSV 时顶层必须是对象数组".to_string());
+    };
+
+    let mut objects = Vec::with_capacity(rows.len());
+    let mut headers: Vec<&str> = Vec::new();
+    for (index, row) in rows.iter().enumerate() {
+        let JsonValue::Object(object) = row else {
+            return Err(format!("转换为 CSV 失败：第 {} 项不是对象", index + 1));
+        };
+        objects.push(object);
+        for (key, value) in object {
+            if matches!(value, JsonValue::Object(_) | JsonValue::Array(_)) {
+                return Err(format!(
+                    "转换为 CSV 失败：第 {} 项的字段 \"{}\" 是嵌套结构",
+                    index + 1,
+                    key
+                ));
+            }
+            if !headers.contains(&key.as_str()) {
+                headers.push(key);
+            }
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let to_csv_error = |e: csv::Error| format!("转换为 CSV 失败: {}", e);
+    if !headers.is_empty() {
+        writer.write_record(&headers).map_err(to_csv_error)?;
+    }
+    for object in objects {
+        let record = headers.iter().map(|key| match object.get(*key) {
+            None | Some(JsonValue::Null) => String::new(),
+            Some(JsonValue::String(text)) => text.clone(),
+            Some(JsonValue::Bool(value)) => value.to_string(),
+            Some(JsonValue::Number(number)) => number.to_string(),
+            // 嵌套结构已在上面拒绝
+            Some(JsonValue::Object(_) | JsonValue::Array(_)) => String::new(),
+        });
+        writer.write_record(record).map_err(to_csv_error)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| format!("转换为 CSV 失败: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("转换为 CSV 失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatted(input: &str, indent: usize, sort: bool) -> String {
+        let result = format_json(input, indent, sort).unwrap();
+        assert!(result.valid);
+        result.output.unwrap()
+    }
+
+    #[test]
+    fn pretty_prints_with_indent_and_keeps_key_order() {
+        let input = r#"{"b":1,"a":[true,null]}"#;
+        assert_eq!(
+            formatted(input, 2, false),
+            "{\n  \"b\": 1,\n  \"a\": [\n    true,\n    null\n  ]\n}"
+        );
+        assert_eq!(formatted(r#"{"a":1}"#, 4, false), "{\n    \"a\": 1\n}");
+        // 缩进超过上限时按 MAX_INDENT 输出
+        assert_eq!(
+            formatted(r#"{"a":1}"#, 100, false),
+            format!("{{\n{}\"a\": 1\n}}", " ".repeat(MAX_INDENT))
+        );
+    }
+
+    #[test]
+    fn indent_zero_minifies() {
+        let input = "{\n  \"name\" : \"ReFast\",\n  \"tags\" : [ 1, 2 ]\n}\n";
+        assert_eq!(
+            formatted(input, 0, false),
+            r#"{"name":"ReFast","tags":[1,2]}"#
+        );
+    }
+
+    #[test]
+    fn sort_orders_keys_recursively() {
+        let input = r#"{"b":{"d":1,"c":2},"a":[{"z":0,"y":1}]}"#;
+        assert_eq!(
+            formatted(input, 0, true),
+            r#"{"a":[{"y":1,"z":0}],"b":{"c":2,"d":1}}"#
+        );
+    }
+
+    #[test]
+    fn conversions_keep_document_key_order() {
+        let input = r#"[{"b":1,"a":"x"},{"c":true,"a":null}]"#;
+        assert_eq!(
+            convert_json(input, ConvertFormat::Csv).unwrap(),
+            "b,a,c\n1,x,\n,,true\n"
+        );
+        let input = r#"{"zeta":1,"alpha":{"y":2.5,"x":[3]}}"#;
+        assert_eq!(
+            convert_json(input, ConvertFormat::Yaml).unwrap(),
+            "zeta: 1\nalpha:\n  y: 2.5\n  x:\n  - 3\n"
+        );
+        assert_eq!(
+            formatted(input, 0, true),
+            r#"{"alpha":{"x":[3],"y":2.5},"zeta":1}"#
+        );
+    }
+
+    #[test]
+    fn parse_errors_report_line_and_column() {
+        let input = "{\n  \"a\": 1,\n  \"b\": [1 2]\n}";
+        let error = parse_json(input).unwrap_err();
+        assert_eq!((error.line, error.column), (3, 11));
+        assert!(!error.message.contains("at line"), "{}", error.message);
+
+        let validation = validate_json(input).unwrap();
+        assert!(!validation.valid);
+        let error = validation.error.unwrap();
+        assert_eq!((error.line, error.column), (3, 11));
+
+        let result = format_json("[1, 2,]", 2, false).unwrap();
+        assert!(!result.valid);
+        assert!(result.output.is_none());
+        let error = result.error.unwrap();
+        assert_eq!((error.line, error.column), (1, 7));
+    }
+
+    #[test]
+    fn empty_input_reports_first_column() {
+        let error = validate_json("").unwrap().error.unwrap();
+        assert_eq!((error.line, error.column), (1, 1));
+        assert!(validate_json(" {} ").unwrap().valid);
+    }
+}
@@ -1,20 +1,157 @@
 // 通用日志工具模块
-// 复用 everything_search 的日志机制，统一管理日志输出
+// 带时间戳和级别的日志写入 logs 目录下的 re-fast.log，
+// 超过大小上限时轮转为 re-fast.1.log … re-fast.N.log（数字越大越旧）
 
-use std::fs::{File, OpenOptions};
-use std::io::Write;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{LazyLock, Mutex};
 
-// 日志文件状态
-struct LogFileState {
+const LOG_FILE_STEM: &str = "re-fast";
+/// 单个日志文件的大小上限
+const MAX_LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
+/// 保留的历史日志文件数
+const MAX_ROTATED_FILES: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum LogLevel {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 4] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+    ];
+
+    /// 设置中保存的名称
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|level| level.as_str().eq_ignore_ascii_case(name))
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => LogLevel::Error,
+            2 => LogLevel::Warn,
+            4 => LogLevel::Debug,
+            _ => LogLevel::Info,
+        }
+    }
+}
+
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+/// 设置日志级别，低于该级别的日志被丢弃
+pub fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn log_level() -> LogLevel {
+    LogLevel::from_u8(LOG_LEVEL.load(Ordering::Relaxed))
+}
+
+pub fn log_enabled(level: LogLevel) -> bool {
+    level <= log_level()
+}
+
+/// 按大小轮转的日志文件
+struct RotatingFile {
+    dir: PathBuf,
+    max_size: u64,
+    keep: usize,
     file: Option<File>,
-    file_path: PathBuf,
-    date: String, // YYYYMMDD 格式
+    size: u64,
+}
+
+impl RotatingFile {
+    fn new(dir: PathBuf, max_size: u64, keep: usize) -> Self {
+        Self {
+            dir,
+            max_size,
+            keep,
+            file: None,
+            size: 0,
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.join(format!("{}.log", LOG_FILE_STEM))
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{}.{}.log", LOG_FILE_STEM, index))
+    }
+
+    fn open(&mut self) -> io::Result<&mut File> {
+        if self.file.is_none() {
+            fs::create_dir_all(&self.dir)?;
+            let path = self.path();
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            self.size = file.metadata().map(|m| m.len()).unwrap_or(0);
+            self.file = Some(file);
+        }
+        Ok(self.file.as_mut().expect("log file was just opened"))
+    }
+
+    /// re-fast.log → re-fast.1.log，已有的历史文件依次后移，超出 keep 的最旧文件被删除
+    fn rotate(&mut self) -> io::Result<()> {
+        // 先关闭当前文件，Windows 上无法重命名已打开的文件
+        self.file = None;
+        if self.keep == 0 {
+            return fs::remove_file(self.path());
+        }
+        let _ = fs::remove_file(self.rotated_path(self.keep));
+        for index in (1..self.keep).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(self.path(), self.rotated_path(1))?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        self.open()?;
+        let len = line.len() as u64;
+        if self.size > 0 && self.size + len > self.max_size {
+            self.rotate()?;
+        }
+        let file = self.open()?;
+        file.write_all(line.as_bytes())?;
+        file.flush()?;
+        self.size += len;
+        Ok(())
+    }
 }
 
-static LOG_FILE_STATE: OnceLock<Arc<Mutex<LogFileState>>> = OnceLock::new();
+static LOG_FILE: LazyLock<Mutex<RotatingFile>> = LazyLock::new(|| {
+    Mutex::new(RotatingFile::new(
+        get_log_dir(),
+        MAX_LOG_FILE_SIZE,
+        MAX_ROTATED_FILES,
+    ))
+});
 
 /// 获取日志目录路径
 pub fn get_log_dir() -> PathBuf {
@@ -27,163 +164,56 @@ pub fn get_log_dir() -> PathBuf {
     }
 }
 
-fn get_log_file_state() -> Arc<Mutex<LogFileState>> {
-    LOG_FILE_STATE
-        .get_or_init(|| {
-            // 初始化日志文件状态
-            let today = chrono::Local::now().format("%Y%m%d").to_string();
-            let log_dir = get_log_dir();
-            
-            // 确保日志目录存在
-            if let Err(e) = std::fs::create_dir_all(&log_dir) {
-                eprintln!(
-                    "[Logger] ERROR: Failed to create log directory {}: {}",
-                    log_dir.display(),
-                    e
-                );
-            }
-            
-            let log_path = log_dir.join(format!("everything-ipc-{}.log", today));
-
-            let file = match OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&log_path)
-            {
-                Ok(f) => {
-                    eprintln!("[Logger] 日志文件已打开: {}", log_path.display());
-                    Some(f)
-                }
-                Err(e) => {
-                    eprintln!("[Logger] ERROR: 无法打开日志文件 {}: {}", log_path.display(), e);
-                    None
-                }
-            };
-
-            Arc::new(Mutex::new(LogFileState {
-                file,
-                file_path: log_path,
-                date: today,
-            }))
-        })
-        .clone()
-}
-
-/// 确保日志文件是当前日期的文件，如果日期变化了则切换文件
-fn ensure_current_log_file() {
-    let state = get_log_file_state();
-    let today = chrono::Local::now().format("%Y%m%d").to_string();
-
-    let mut state_guard = match state.lock() {
-        Ok(guard) => guard,
-        Err(_) => return,
-    };
-
-    // 如果日期变化了，需要切换到新的日志文件
-    if state_guard.date != today {
-        // 关闭旧文件
-        if let Some(mut old_file) = state_guard.file.take() {
-            let _ = old_file.flush();
-            drop(old_file);
-        }
-
-        // 创建新的日志文件
-        let log_dir = get_log_dir();
-        
-        // 确保日志目录存在
-        let _ = std::fs::create_dir_all(&log_dir);
-        
-        let log_path = log_dir.join(format!("everything-ipc-{}.log", today));
-        let file = match OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_path)
-        {
-            Ok(f) => {
-                eprintln!("[Logger] 切换到新的日志文件: {}", log_path.display());
-                Some(f)
-            }
-            Err(e) => {
-                eprintln!("[Logger] ERROR: 无法打开新日志文件 {}: {}", log_path.display(), e);
-                None
-            }
-        };
-
-        // 更新状态
-        state_guard.file = file;
-        state_guard.file_path = log_path;
-        state_guard.date = today;
+/// 获取当前日志文件路径
+pub fn get_log_file_path() -> PathBuf {
+    match LOG_FILE.lock() {
+        Ok(file) => file.path(),
+        Err(_) => get_log_dir().join(format!("{}.log", LOG_FILE_STEM)),
     }
 }
 
-/// 获取日志文件路径
-pub fn get_log_file_path() -> Option<PathBuf> {
-    let state = get_log_file_state();
-    state.lock().ok().map(|s| s.file_path.clone())
-}
-
 /// 在程序启动时初始化日志文件（确保路径被保存和显示）
 pub fn init_log_file_early() {
-    // 强制初始化日志文件
-    let _ = get_log_file_state();
-    // 写入一条测试日志，确保写入功能正常工作
     write_log("Logger", "日志系统已初始化");
 }
 
-/// 写入日志到文件
-/// 
+/// 以 Info 级别写入日志
+///
 /// # Arguments
 /// * `module` - 模块名称（如 "IconExtract", "Everything", "Hotkey" 等）
 /// * `msg` - 日志消息
 pub fn write_log(module: &str, msg: &str) {
-    // 确保使用当前日期的日志文件（如果日期变化了会自动切换）
-    ensure_current_log_file();
-
-    // 输出到日志文件
-    let state = get_log_file_state();
-    let state_guard_result = state.lock();
-    match state_guard_result {
-        Ok(mut state_guard) => {
-            // 如果文件句柄丢失，尝试重新打开
-            if state_guard.file.is_none() {
-                let log_path = state_guard.file_path.clone();
-                match OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open(&log_path)
-                {
-                    Ok(f) => {
-                        eprintln!("[Logger] 重新打开日志文件: {}", log_path.display());
-                        state_guard.file = Some(f);
-                    }
-                    Err(e) => {
-                        eprintln!("[Logger] ERROR: 无法重新打开日志文件 {}: {}", log_path.display(), e);
-                        return;
-                    }
-                }
-            }
+    write_log_at(LogLevel::Info, module, msg);
+}
+
+/// 写入指定级别的日志；调试构建同时输出到控制台
+pub fn write_log_at(level: LogLevel, module: &str, msg: &str) {
+    if !log_enabled(level) {
+        return;
+    }
+
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+    let line = format!(
+        "[{}] [{}] [{}] {}\n",
+        timestamp,
+        level.as_str().to_uppercase(),
+        module,
+        msg
+    );
+    if cfg!(debug_assertions) {
+        eprint!("{}", line);
+    }
 
-            // 现在文件句柄应该存在了，尝试写入
-            match state_guard.file.as_mut() {
-                Some(file) => {
-                    let timestamp = chrono::Local::now().format("%H:%M:%S%.3f");
-                    let log_msg = format!("[{}] [{}] {}\n", timestamp, module, msg);
-                    match file.write_all(log_msg.as_bytes()) {
-                        Ok(_) => {
-                            if let Err(e) = file.flush() {
-                                eprintln!("[Logger] ERROR: 刷新日志文件失败: {}", e);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("[Logger] ERROR: 写入日志失败: {} (文件路径: {})", e, state_guard.file_path.display());
-                            // 写入失败时，清除文件句柄，下次尝试重新打开
-                            state_guard.file = None;
-                        }
-                    }
-                }
-                None => {
-                    eprintln!("[Logger] ERROR: 文件句柄仍然为 None，无法写入日志");
-                }
+    match LOG_FILE.lock() {
+        Ok(mut file) => {
+            if let Err(e) = file.write_line(&line) {
+                eprintln!(
+                    "[Logger] ERROR: 写入日志失败: {} (文件路径: {})",
+                    e,
+                    file.path().display()
+                );
+                // 写入失败时丢弃文件句柄，下次重新打开
+                file.file = None;
             }
         }
         Err(e) => {
@@ -192,18 +222,112 @@ pub fn write_log(module: &str, msg: &str) {
     }
 }
 
-/// 日志宏，支持格式化字符串
-/// 
+/// 日志宏，支持格式化字符串；`log!` 为 Info 级别
+///
 /// # 使用示例
 /// ```rust
-/// use crate::logger::log;
+/// use crate::{log, log_debug};
 /// log!("IconExtract", "开始提取图标: {}", file_path);
-/// log!("Everything", "搜索查询: {}", query);
+/// log_debug!("Everything", "搜索查询: {}", query);
 /// ```
 #[macro_export]
 macro_rules! log {
     ($module:expr, $($arg:tt)*) => {
-        crate::logger::write_log($module, &format!($($arg)*));
+        $crate::log_info!($module, $($arg)*)
+    };
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($module:expr, $($arg:tt)*) => {
+        $crate::log_at!($crate::logger::LogLevel::Error, $module, $($arg)*)
+    };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($module:expr, $($arg:tt)*) => {
+        $crate::log_at!($crate::logger::LogLevel::Warn, $module, $($arg)*)
+    };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($module:expr, $($arg:tt)*) => {
+        $crate::log_at!($crate::logger::LogLevel::Info, $module, $($arg)*)
+    };
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($module:expr, $($arg:tt)*) => {
+        $crate::log_at!($crate::logger::LogLevel::Debug, $module, $($arg)*)
+    };
+}
+
+/// 级别未启用时不格式化参数
+#[macro_export]
+macro_rules! log_at {
+    ($level:expr, $module:expr, $($arg:tt)*) => {
+        if $crate::logger::log_enabled($level) {
+            $crate::logger::write_log_at($level, $module, &format!($($arg)*));
+        }
     };
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn read(path: PathBuf) -> Option<String> {
+        fs::read_to_string(path).ok()
+    }
+
+    #[test]
+    fn rotates_past_max_size_and_drops_oldest() {
+        let dir = temp_dir("logger", "rotate");
+        let mut log = RotatingFile::new(dir.clone(), 10, 2);
+        for line in ["line1\n", "line2\n", "line3\n", "line4\n"] {
+            log.write_line(line).unwrap();
+        }
+
+        assert_eq!(read(log.path()).as_deref(), Some("line4\n"));
+        assert_eq!(read(log.rotated_path(1)).as_deref(), Some("line3\n"));
+        assert_eq!(read(log.rotated_path(2)).as_deref(), Some("line2\n"));
+        // line1 所在的文件超出 keep，已被删除
+        assert!(!log.rotated_path(3).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn lines_are_appended_until_threshold() {
+        let dir = temp_dir("logger", "append");
+        let mut log = RotatingFile::new(dir.clone(), 12, 2);
+        log.write_line("line1\n").unwrap();
+        log.write_line("line2\n").unwrap();
+        assert_eq!(read(log.path()).as_deref(), Some("line1\nline2\n"));
+        assert!(!log.rotated_path(1).exists());
+
+        // 重新打开时按已有文件大小计算，下一行触发轮转
+        let mut reopened = RotatingFile::new(dir.clone(), 12, 2);
+        reopened.write_line("line3\n").unwrap();
+        assert_eq!(read(reopened.path()).as_deref(), Some("line3\n"));
+        assert_eq!(
+            read(reopened.rotated_path(1)).as_deref(),
+            Some("line1\nline2\n")
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn keep_zero_discards_old_log() {
+        let dir = temp_dir("logger", "keep-zero");
+        let mut log = RotatingFile::new(dir.clone(), 10, 0);
+        log.write_line("line1\n").unwrap();
+        log.write_line("line2\n").unwrap();
+        assert_eq!(read(log.path()).as_deref(), Some("line2\n"));
+        assert!(!log.rotated_path(1).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
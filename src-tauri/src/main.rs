@@ -2,31 +2,63 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod app_search;
+mod calc_history;
+mod color_convert;
 mod commands;
+mod data_transfer;
 mod error;
 mod everything_search;
 mod everything_filters;
+mod favicon;
+mod events;
+mod expression;
 mod file_history;
+mod file_search_fallback;
+mod folder_stats;
 mod hooks;
 mod hotkey;
 mod hotkey_handler;
+mod json_path;
+mod json_tools;
 // mod keyboard_hook; // 已不再需要，hotkey_handler 已支持双击修饰键
 mod db;
+mod dev_tools;
+mod diagnostics;
+mod document_text;
 mod logger;
+mod media_info;
 mod plugin_usage;
+mod process_utils;
+mod qr_code;
+mod query_guard;
+mod recent_documents;
 mod memos;
+mod ocr;
+mod path_utils;
 mod open_history;
+mod plugin_install;
+mod plugin_manifest;
 mod recording;
+mod replace_journal;
 mod replay;
 mod settings;
 mod shortcuts;
+mod single_instance;
+mod search_ranking;
+mod system_folders_search;
+#[cfg(test)]
+mod test_util;
+mod translation;
+mod trash;
+mod unit_convert;
+mod url_shortcuts;
 mod window_config;
 
 use crate::commands::get_app_data_dir;
 use commands::*;
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem},
     Manager,
     Emitter,
 };
@@ -140,7 +172,7 @@ fn check_single_instance() -> bool {
     }
 }
 
-fn get_lock_file_path() -> std::path::PathBuf {
+pub(crate) fn get_lock_file_path() -> std::path::PathBuf {
     use std::env;
     use std::path::PathBuf;
     
@@ -172,6 +204,8 @@ fn cleanup_lock_file() {
 fn set_launcher_window_position(window: &tauri::WebviewWindow, app_data_dir: &std::path::Path) {
     use tauri::PhysicalPosition;
     
+    apply_launcher_opacity(window, app_data_dir);
+
     // 首先尝试加载保存的位置
     if let Some(saved_pos) = window_config::get_launcher_position(app_data_dir) {
         // 验证保存的位置是否仍然有效（在屏幕范围内）
@@ -220,24 +254,85 @@ fn set_launcher_window_position(window: &tauri::WebviewWindow, app_data_dir: &st
     }
 }
 
+/// 处理命令行传入的路径：加入文件历史，显示启动器并通知前端定位到这些条目
+fn open_paths_in_launcher(app: &tauri::AppHandle, app_data_dir: &std::path::Path, paths: Vec<String>) {
+    if let Err(e) = file_history::add_file_paths(paths.clone(), app_data_dir) {
+        log_warn!("FileHistory", "命令行传入的路径加入文件历史失败: {}", e);
+    }
+    if let Some(window) = app.get_webview_window("launcher") {
+        set_launcher_window_position(&window, app_data_dir);
+        let _ = window.show();
+        let _ = window.set_focus();
+        if let Err(e) = window.emit("open-path-requested", events::OpenPathRequested { paths }) {
+            log_error!("Window", "Failed to emit open-path-requested event: {}", e);
+        }
+    }
+}
+
 fn main() {
+    // 开机启动时的延迟启动（--delayed=N），减轻登录时的负载。
+    // 在单实例检查之前等待，等待期间用户手动启动的实例可以正常运行
+    let launch_options = settings::StartupLaunchOptions::from_args(std::env::args().skip(1));
+    if launch_options.delay_seconds > 0 {
+        std::thread::sleep(std::time::Duration::from_secs(launch_options.delay_seconds));
+    }
+
     // 检查单实例
     if !check_single_instance() {
-        // 已有实例在运行，退出
+        // 延迟的开机启动期间程序已被手动打开，不打扰已运行的实例，直接退出
+        if launch_options.delay_seconds > 0 {
+            std::process::exit(0);
+        }
+        // 已有实例在运行：通知它显示启动器（或打开命令行传入的文件）后退出
+        let current_dir = std::env::current_dir().unwrap_or_default();
+        let message =
+            single_instance::InstanceMessage::from_args(std::env::args().skip(1), &current_dir);
+        if let Err(e) = single_instance::send_to_running_instance(&message) {
+            log_warn!("SingleInstance", "Failed to notify running instance: {}", e);
+        }
         std::process::exit(0);
     }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
-        .setup(|app| {
+        .setup(move |app| {
             // Create system tray menu
             let app_center = MenuItem::with_id(app, "app_center", "应用中心", true, None::<&str>)?;
             let open_logs = MenuItem::with_id(app, "open_logs", "打开日志文件夹", true, None::<&str>)?;
             let restart = MenuItem::with_id(app, "restart", "重启程序", true, None::<&str>)?;
+            let tray_settings = get_app_data_dir(app.handle())
+                .ok()
+                .and_then(|dir| settings::load_settings(&dir).ok())
+                .unwrap_or_default();
+            let show_launcher = MenuItem::with_id(
+                app,
+                "show_launcher",
+                launcher_menu_text(tray_settings.hotkey.as_ref()),
+                true,
+                None::<&str>,
+            )?;
+            app.manage(LauncherMenuItem(show_launcher.clone()));
             let quit = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
 
-            let menu = Menu::with_items(app, &[&app_center, &open_logs, &restart, &quit])?;
+            // 恢复上次的快捷键暂停状态，托盘中勾选表示已暂停
+            let hotkeys_enabled = tray_settings.hotkeys_enabled;
+            hotkey_handler::windows::set_hotkeys_enabled(hotkeys_enabled);
+            let pause_hotkeys = CheckMenuItem::with_id(
+                app,
+                "pause_hotkeys",
+                "暂停快捷键",
+                true,
+                !hotkeys_enabled,
+                None::<&str>,
+            )?;
+            app.manage(HotkeyPauseMenuItem(pause_hotkeys.clone()));
+
+            let menu = Menu::with_items(
+                app,
+                &[&show_launcher, &app_center, &pause_hotkeys, &open_logs, &restart, &quit],
+            )?;
 
             // Create tray icon - use default window icon (which loads from tauri.conf.json)
             // 禁用左键点击显示菜单，左键只用于切换启动器窗口
@@ -267,8 +362,25 @@ fn main() {
             let app_data_dir = get_app_data_dir(app.handle())?;
 
             let app_data_dir_clone1 = app_data_dir.clone();
+            let app_data_dir_clone2 = app_data_dir.clone();
             let app_data_dir_clone3 = app_data_dir.clone();
 
+            // 接收后启动实例的消息：显示并聚焦启动器，带文件路径时加入历史并定位
+            let instance_app = app.handle().clone();
+            let instance_app_data_dir = app_data_dir.clone();
+            single_instance::start_server(move |message| match message {
+                single_instance::InstanceMessage::Focus => {
+                    if let Some(window) = instance_app.get_webview_window("launcher") {
+                        set_launcher_window_position(&window, &instance_app_data_dir);
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                    }
+                }
+                single_instance::InstanceMessage::Open(paths) => {
+                    open_paths_in_launcher(&instance_app, &instance_app_data_dir, paths);
+                }
+            });
+
             let _tray = tray_builder
                 .on_tray_icon_event(move |tray, event| {
                     if let TrayIconEvent::Click {
@@ -292,6 +404,13 @@ fn main() {
                     }
                 })
                 .on_menu_event(move |app, event| match event.id.as_ref() {
+                    "show_launcher" => {
+                        if let Some(window) = app.get_webview_window("launcher") {
+                            set_launcher_window_position(&window, &app_data_dir_clone2);
+                            let _ = window.show();
+                            let _ = window.set_focus();
+                        }
+                    }
                     "app_center" => {
                         // 调用应用中心窗口命令
                         let app_handle = app.clone();
@@ -346,6 +465,12 @@ fn main() {
                             }
                         }
                     }
+                    "pause_hotkeys" => {
+                        let enabled = !hotkey_handler::windows::hotkeys_enabled();
+                        if let Err(e) = apply_hotkeys_enabled(app, enabled) {
+                            log_error!("Hotkey", "Failed to toggle hotkeys: {}", e);
+                        }
+                    }
                     "restart" => {
                         // 清理锁文件，以便重启后新实例可以正常启动
                         cleanup_lock_file();
@@ -455,8 +580,12 @@ fn main() {
                                     // 启动应用
                                     use crate::app_search;
                                     if let Ok(apps) = app_search::windows::load_cache(&app_data_dir_hotkey) {
-                                        if let Some(app) = apps.iter().find(|a| a.path == app_path) {
-                                            if let Err(e) = app_search::windows::launch_app(app) {
+                                        if let Some(app) = apps.into_iter().find(|a| a.path == app_path) {
+                                            // 网址快捷方式也走 launch_application，由其调用 open_url
+                                            if let Err(e) = crate::commands::launch_application(
+                                                app,
+                                                app_handle_plugin.clone(),
+                                            ) {
                                                 eprintln!("[Main] Failed to launch app via hotkey: {}", e);
                                             }
                                         }
@@ -475,8 +604,8 @@ fn main() {
                         std::thread::spawn(move || {
                             std::thread::sleep(std::time::Duration::from_millis(500)); // 等待监听器完全启动
                             if let Ok(settings) = settings::load_settings(&app_data_dir_plugin) {
-                                // 注册插件快捷键
-                                let plugin_hotkeys = settings.plugin_hotkeys.clone();
+                                // 注册插件快捷键（已禁用的插件不注册）
+                                let plugin_hotkeys = settings.enabled_plugin_hotkeys();
                                 let plugin_hotkey_count = plugin_hotkeys.len();
                                 if !plugin_hotkeys.is_empty() {
                                     if let Err(e) = hotkey_handler::windows::update_plugin_hotkeys(plugin_hotkeys) {
@@ -523,6 +652,22 @@ fn main() {
             open_history::load_history(&app_data_dir).ok(); // Ignore errors if file doesn't exist
             shortcuts::load_shortcuts(&app_data_dir).ok(); // Ignore errors if file doesn't exist
 
+            // 启动时通过命令行传入的路径（文件关联、“发送到”）：先加入文件历史并显示启动器，
+            // 启动器前端注册好监听后通过 take_startup_paths 取走并定位
+            let current_dir = std::env::current_dir().unwrap_or_default();
+            let startup_paths = single_instance::parse_path_args(std::env::args().skip(1), &current_dir);
+            if !startup_paths.is_empty() {
+                if let Err(e) = file_history::add_file_paths(startup_paths.clone(), &app_data_dir) {
+                    log_warn!("FileHistory", "命令行传入的路径加入文件历史失败: {}", e);
+                }
+                single_instance::set_startup_paths(startup_paths);
+                if let Some(window) = app.get_webview_window("launcher") {
+                    set_launcher_window_position(&window, &app_data_dir);
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+
             // Sync startup setting on Windows
             #[cfg(target_os = "windows")]
             {
@@ -530,7 +675,7 @@ fn main() {
                 use crate::settings;
                 // Load settings and sync startup state
                 if let Ok(settings) = settings::load_settings(&app_data_dir) {
-                    commands::sync_startup_setting(settings.startup_enabled).ok();
+                    commands::sync_startup_setting(&settings).ok();
                 }
             }
 
@@ -544,13 +689,19 @@ fn main() {
             }
 
             // Initialize logger module on startup to ensure log file is created
-            #[cfg(target_os = "windows")]
             {
                 use crate::logger;
-                logger::init_log_file_early();
-                if let Some(log_path) = logger::get_log_file_path() {
-                    eprintln!("[Main] Logger log file: {}", log_path.display());
+                if let Some(level) = settings::load_settings(&app_data_dir)
+                    .ok()
+                    .and_then(|s| logger::LogLevel::parse(&s.log_level))
+                {
+                    logger::set_log_level(level);
                 }
+                logger::init_log_file_early();
+                eprintln!(
+                    "[Main] Logger log file: {}",
+                    logger::get_log_file_path().display()
+                );
             }
 
             // Load app cache on startup and start background scan
@@ -569,23 +720,26 @@ fn main() {
             });
 
             // Show launcher window on startup after a short delay to ensure frontend is loaded
-            let app_handle = app.handle().clone();
-            let app_data_dir_startup = app_data_dir.clone();
-            std::thread::spawn(move || {
-                use std::time::Duration;
-                // Wait for frontend to load (500ms should be enough)
-                std::thread::sleep(Duration::from_millis(500));
-                
-                if let Some(window) = app_handle.get_webview_window("launcher") {
-                    set_launcher_window_position(&window, &app_data_dir_startup);
-                    if let Err(e) = window.show() {
-                        eprintln!("Failed to show launcher window on startup: {}", e);
-                    }
-                    if let Err(e) = window.set_focus() {
-                        eprintln!("Failed to focus launcher window on startup: {}", e);
+            // 以 --minimized 开机启动时只驻留托盘，不显示启动器
+            if launch_options.shows_launcher_on_startup() {
+                let app_handle = app.handle().clone();
+                let app_data_dir_startup = app_data_dir.clone();
+                std::thread::spawn(move || {
+                    use std::time::Duration;
+                    // Wait for frontend to load (500ms should be enough)
+                    std::thread::sleep(Duration::from_millis(500));
+
+                    if let Some(window) = app_handle.get_webview_window("launcher") {
+                        set_launcher_window_position(&window, &app_data_dir_startup);
+                        if let Err(e) = window.show() {
+                            log_error!("Window", "Failed to show launcher window on startup: {}", e);
+                        }
+                        if let Err(e) = window.set_focus() {
+                            log_error!("Window", "Failed to focus launcher window on startup: {}", e);
+                        }
                     }
-                }
-            });
+                });
+            }
 
             Ok(())
         })
@@ -594,7 +748,11 @@ fn main() {
             start_recording,
             stop_recording,
             list_recordings,
+            get_capture_env,
+            export_recording_csv,
+            import_recording_csv,
             delete_recording,
+            move_to_trash,
             play_recording,
             stop_playback,
             get_playback_status,
@@ -604,49 +762,82 @@ fn main() {
             search_applications,
             populate_app_icons,
             launch_application,
+            add_url_shortcut,
+            fetch_favicon,
             remove_app_from_index,
             debug_app_icon,
             extract_icon_from_path,
             toggle_launcher,
+            set_launcher_opacity,
+            set_hotkeys_enabled,
+            get_hotkeys_enabled,
             hide_launcher,
             add_file_to_history,
+            add_files_to_history,
             search_file_history,
             search_everything,
             cancel_everything_search,
+            validate_everything_query,
             start_everything_search_session,
             get_everything_search_range,
+            get_everything_search_groups,
             close_everything_search_session,
             is_everything_available,
             get_everything_status,
+            everything_health,
             get_everything_path,
             get_everything_version,
             get_everything_log_file_path,
             get_file_preview,
             purge_file_history,
+            get_file_history_stats,
+            vacuum_database,
             delete_file_history_by_range,
             backup_database,
             delete_backup,
             restore_backup,
             list_backups,
+            set_log_level,
+            get_log_file_path,
+            check_database_integrity,
+            repair_database,
+            export_all_data,
+            import_all_data,
             get_index_status,
             start_everything,
+            auto_start_everything,
+            take_startup_paths,
+            refresh_everything_index,
             open_everything_download,
             download_everything,
+            install_everything,
             launch_file,
+            launch_file_with,
+            open_with_dialog,
             check_path_exists,
+            resolve_shortcut,
             get_clipboard_file_path,
             get_clipboard_text,
+            set_clipboard_text,
+            copy_path_to_clipboard,
+            copy_paths_to_clipboard,
+            get_clipboard_image,
+            save_clipboard_image_to_file,
+            ocr_clipboard_image,
             save_clipboard_image,
             paste_text_to_cursor,
             write_debug_log,
             get_downloads_folder,
             copy_file_to_downloads,
             reveal_in_folder,
+            reveal_in_folder_many,
+            open_terminal_here,
             get_all_shortcuts,
             add_shortcut,
             update_shortcut,
             delete_shortcut,
             get_all_file_history,
+            get_recent_documents,
             delete_file_history,
             update_file_history_name,
             get_all_memos,
@@ -659,28 +850,63 @@ fn main() {
             open_url,
             record_open_history,
             get_open_history,
+            clear_open_history,
+            forget_open_history,
             record_plugin_usage,
             get_plugin_usage,
+            clear_plugin_usage,
+            add_calc_entry,
+            get_calc_history,
+            clear_calc_history,
             show_memo_window,
             show_plugin_list_window,
             show_json_formatter_window,
+            format_json,
+            validate_json,
+            convert_json,
+            query_json,
+            generate_qr_code,
+            convert_color,
             show_translation_window,
+            translate_text,
+            set_translation_api_key,
             show_file_toolbox_window,
             show_calculator_pad_window,
             show_everything_search_window,
+            reopen_last_window,
             preview_file_replace,
+            preview_file_replace_with_progress,
+            cancel_file_replace,
             execute_file_replace,
+            undo_file_replace,
+            bulk_rename,
+            analyze_folder,
+            compute_file_hash,
             select_folder,
             get_plugin_directory,
             scan_plugin_directory,
             read_plugin_manifest,
+            validate_plugin_manifest,
+            install_plugin,
+            get_plugin_states,
+            set_plugin_enabled,
             get_settings,
             save_settings,
             show_settings_window,
             get_everything_custom_filters,
             save_everything_custom_filters,
+            list_saved_searches,
+            add_saved_search,
+            delete_saved_search,
+            run_saved_search,
+            search_system_folders,
+            list_system_folder_aliases,
+            add_system_folder_alias,
+            remove_system_folder_alias,
+            unified_search,
             is_startup_enabled,
             set_startup_enabled,
+            repair_startup_entry,
             get_hotkey_config,
             save_hotkey_config,
             get_plugin_hotkeys,
@@ -693,6 +919,14 @@ fn main() {
             show_hotkey_settings,
             restart_app,
             get_app_version,
+            collect_diagnostics,
+            get_diagnostics,
+            convert_units,
+            evaluate_expression,
+            dev_tools,
+            run_command,
+            list_processes,
+            kill_process,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
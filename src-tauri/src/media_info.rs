@@ -0,0 +1,706 @@
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// 从文件头解析出的媒体信息，解析失败的字段保持 None
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MediaInfo {
+    pub duration_ms: Option<u64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+/// 读取文件开头最多 `max_bytes` 字节
+fn read_head(path: &Path, max_bytes: u64) -> Option<Vec<u8>> {
+    let file = fs::File::open(path).ok()?;
+    let mut buffer = Vec::new();
+    file.take(max_bytes).read_to_end(&mut buffer).ok()?;
+    Some(buffer)
+}
+
+fn be_u16(b: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(b.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be_u32(b: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn be_u64(b: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(b.get(at..at + 8)?.try_into().ok()?))
+}
+
+fn le_u16(b: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(b.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn le_u32(b: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// 只解析图片文件头获取尺寸，不解码像素
+pub fn image_dimensions(path: &Path, ext: &str) -> Option<(u32, u32)> {
+    // JPEG 的 SOF 段可能位于较大的 EXIF 之后，多读一些
+    let head_size = if matches!(ext, "jpg" | "jpeg") { 512 * 1024 } else { 64 };
+    let head = read_head(path, head_size)?;
+    image_dimensions_from_bytes(&head)
+}
+
+/// 根据魔数识别格式并读取尺寸
+pub fn image_dimensions_from_bytes(b: &[u8]) -> Option<(u32, u32)> {
+    if b.starts_with(b"\x89PNG\r\n\x1a\n") {
+        // IHDR 紧跟在签名之后
+        return Some((be_u32(b, 16)?, be_u32(b, 20)?));
+    }
+    if b.starts_with(b"GIF87a") || b.starts_with(b"GIF89a") {
+        return Some((le_u16(b, 6)? as u32, le_u16(b, 8)? as u32));
+    }
+    if b.starts_with(b"BM") {
+        let width = le_u32(b, 18)? as i32;
+        let height = le_u32(b, 22)? as i32;
+        return Some((width.unsigned_abs(), height.unsigned_abs()));
+    }
+    if b.len() >= 16 && &b[0..4] == b"RIFF" && &b[8..12] == b"WEBP" {
+        return webp_dimensions(b);
+    }
+    if b.starts_with(&[0xFF, 0xD8]) {
+        return jpeg_dimensions(b);
+    }
+    None
+}
+
+fn webp_dimensions(b: &[u8]) -> Option<(u32, u32)> {
+    match b.get(12..16)? {
+        b"VP8 " => Some((
+            (le_u16(b, 26)? & 0x3FFF) as u32,
+            (le_u16(b, 28)? & 0x3FFF) as u32,
+        )),
+        b"VP8L" => {
+            let bits = le_u32(b, 21)?;
+            Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+        }
+        b"VP8X" => {
+            let w = u32::from_le_bytes([*b.get(24)?, *b.get(25)?, *b.get(26)?, 0]);
+            let h = u32::from_le_bytes([*b.get(27)?, *b.get(28)?, *b.get(29)?, 0]);
+            Some((w + 1, h + 1))
+        }
+        _ => None,
+    }
+}
+
+fn jpeg_dimensions(b: &[u8]) -> Option<(u32, u32)> {
+    let mut i = 2;
+    while i + 4 <= b.len() {
+        if b[i] != 0xFF {
+            i += 1;
+            continue;
+        }
+        let marker = b[i + 1];
+        // 填充字节或无长度的标记
+        if marker == 0xFF || marker == 0x01 || (0xD0..=0xD9).contains(&marker) {
+            i += if marker == 0xFF { 1 } else { 2 };
+            continue;
+        }
+        let len = be_u16(b, i + 2)? as usize;
+        // SOF0-SOF15，排除 DHT(C4)、JPG(C8)、DAC(CC)
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let height = be_u16(b, i + 5)? as u32;
+            let width = be_u16(b, i + 7)? as u32;
+            return Some((width, height));
+        }
+        i += 2 + len;
+    }
+    None
+}
+
+/// 解析音视频容器元数据（时长、视频尺寸），尽力而为
+pub fn probe_media(path: &Path, ext: &str) -> MediaInfo {
+    let info = match ext {
+        "mp4" | "mov" | "m4a" | "m4v" => probe_mp4(path),
+        "mkv" | "webm" => probe_matroska(path),
+        "mp3" => probe_mp3(path),
+        "wav" => probe_wav(path),
+        _ => None,
+    };
+    info.unwrap_or_default()
+}
+
+// ---------- MP4 / MOV ----------
+
+fn probe_mp4(path: &Path) -> Option<MediaInfo> {
+    let mut file = fs::File::open(path).ok()?;
+    let file_len = file.metadata().ok()?.len();
+
+    // 遍历顶层 box 找到 moov（可能位于文件末尾）
+    let mut offset = 0u64;
+    while offset + 8 <= file_len {
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        let mut header = [0u8; 16];
+        file.read_exact(&mut header[..8]).ok()?;
+        let mut size = be_u32(&header, 0)? as u64;
+        let kind: [u8; 4] = header[4..8].try_into().ok()?;
+        let mut header_len = 8;
+        if size == 1 {
+            file.read_exact(&mut header[8..16]).ok()?;
+            size = be_u64(&header, 8)?;
+            header_len = 16;
+        } else if size == 0 {
+            size = file_len - offset;
+        }
+        if size < header_len {
+            return None;
+        }
+
+        if &kind == b"moov" {
+            const MAX_MOOV_BYTES: u64 = 16 * 1024 * 1024;
+            let body_len = (size - header_len).min(MAX_MOOV_BYTES);
+            let mut body = vec![0u8; body_len as usize];
+            file.read_exact(&mut body).ok()?;
+            let mut info = MediaInfo::default();
+            parse_mp4_boxes(&body, &mut info, 0);
+            return Some(info);
+        }
+        offset += size;
+    }
+    None
+}
+
+/// box / EBML 元素的最大嵌套层数，实际文件不超过 4 层；构造的深层嵌套文件不再递归，避免栈溢出
+const MAX_NESTING_DEPTH: usize = 8;
+
+fn parse_mp4_boxes(data: &[u8], info: &mut MediaInfo, depth: usize) {
+    if depth > MAX_NESTING_DEPTH {
+        return;
+    }
+    let mut i = 0;
+    while i + 8 <= data.len() {
+        let size = match be_u32(data, i) {
+            Some(s) if s >= 8 => s as usize,
+            _ => return,
+        };
+        let end = (i + size).min(data.len());
+        let kind = &data[i + 4..i + 8];
+        let body = &data[i + 8..end];
+        match kind {
+            b"trak" | b"mdia" => parse_mp4_boxes(body, info, depth + 1),
+            b"mvhd" => {
+                let version = body.first().copied().unwrap_or(0);
+                let (timescale, duration) = if version == 1 {
+                    (be_u32(body, 20), be_u64(body, 24))
+                } else {
+                    (be_u32(body, 12), be_u32(body, 16).map(|d| d as u64))
+                };
+                if let (Some(ts), Some(d)) = (timescale, duration) {
+                    if ts > 0 {
+                        info.duration_ms = d.checked_mul(1000).map(|ms| ms / ts as u64);
+                    }
+                }
+            }
+            b"tkhd" if info.width.is_none() => {
+                let version = body.first().copied().unwrap_or(0);
+                let base = if version == 1 { 88 } else { 76 };
+                // 16.16 定点数，音频轨道为 0
+                let width = be_u32(body, base).map(|w| w >> 16).unwrap_or(0);
+                let height = be_u32(body, base + 4).map(|h| h >> 16).unwrap_or(0);
+                if width > 0 && height > 0 {
+                    info.width = Some(width);
+                    info.height = Some(height);
+                }
+            }
+            _ => {}
+        }
+        i += size;
+    }
+}
+
+// ---------- Matroska / WebM ----------
+
+const EBML_SEGMENT: u32 = 0x1853_8067;
+const EBML_INFO: u32 = 0x1549_A966;
+const EBML_TIMECODE_SCALE: u32 = 0x2A_D7B1;
+const EBML_DURATION: u32 = 0x4489;
+const EBML_TRACKS: u32 = 0x1654_AE6B;
+const EBML_TRACK_ENTRY: u32 = 0xAE;
+const EBML_VIDEO: u32 = 0xE0;
+const EBML_PIXEL_WIDTH: u32 = 0xB0;
+const EBML_PIXEL_HEIGHT: u32 = 0xBA;
+const EBML_CLUSTER: u32 = 0x1F43_B675;
+
+/// 读取 EBML 变长整数，返回 (值, 长度)；`keep_marker` 为 true 时保留长度标记位（用于 ID）
+fn read_vint(b: &[u8], at: usize, keep_marker: bool) -> Option<(u64, usize)> {
+    let first = *b.get(at)?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > 8 {
+        return None;
+    }
+    let mut value = if keep_marker {
+        first as u64
+    } else {
+        (first as u64) & ((1u64 << (8 - len)) - 1)
+    };
+    for k in 1..len {
+        value = (value << 8) | *b.get(at + k)? as u64;
+    }
+    Some((value, len))
+}
+
+fn read_uint(b: &[u8]) -> u64 {
+    b.iter().take(8).fold(0u64, |acc, &x| (acc << 8) | x as u64)
+}
+
+fn probe_matroska(path: &Path) -> Option<MediaInfo> {
+    let data = read_head(path, 4 * 1024 * 1024)?;
+    if !data.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        return None;
+    }
+    let mut info = MediaInfo::default();
+    let mut timecode_scale = 1_000_000u64;
+    let mut duration: Option<f64> = None;
+    parse_ebml(&data, &mut info, &mut timecode_scale, &mut duration, 0);
+    if let Some(d) = duration {
+        info.duration_ms = Some((d * timecode_scale as f64 / 1_000_000.0) as u64);
+    }
+    Some(info)
+}
+
+fn parse_ebml(
+    data: &[u8],
+    info: &mut MediaInfo,
+    timecode_scale: &mut u64,
+    duration: &mut Option<f64>,
+    depth: usize,
+) {
+    if depth > MAX_NESTING_DEPTH {
+        return;
+    }
+    let mut i = 0;
+    while i < data.len() {
+        let Some((id, id_len)) = read_vint(data, i, true) else {
+            return;
+        };
+        let Some((size, size_len)) = read_vint(data, i + id_len, false) else {
+            return;
+        };
+        let start = i + id_len + size_len;
+        // 未知长度（全 1）按剩余数据处理
+        let unknown = size == (1u64 << (7 * size_len)) - 1;
+        let end = if unknown {
+            data.len()
+        } else {
+            start.saturating_add(size as usize).min(data.len())
+        };
+        if start > data.len() {
+            return;
+        }
+        let body = &data[start..end];
+
+        match id as u32 {
+            EBML_SEGMENT | EBML_INFO | EBML_TRACKS | EBML_TRACK_ENTRY | EBML_VIDEO => {
+                parse_ebml(body, info, timecode_scale, duration, depth + 1)
+            }
+            EBML_TIMECODE_SCALE => *timecode_scale = read_uint(body).max(1),
+            EBML_DURATION => {
+                *duration = match body.len() {
+                    4 => Some(f32::from_be_bytes(body.try_into().unwrap_or([0; 4])) as f64),
+                    8 => Some(f64::from_be_bytes(body.try_into().unwrap_or([0; 8]))),
+                    _ => None,
+                }
+            }
+            EBML_PIXEL_WIDTH if info.width.is_none() => info.width = Some(read_uint(body) as u32),
+            EBML_PIXEL_HEIGHT if info.height.is_none() => {
+                info.height = Some(read_uint(body) as u32)
+            }
+            // Cluster 之后只有媒体数据，停止解析
+            EBML_CLUSTER => return,
+            _ => {}
+        }
+        i = end;
+    }
+}
+
+// ---------- MP3 ----------
+
+const MP3_BITRATES_V1_L1: [u32; 15] = [0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448];
+const MP3_BITRATES_V1_L2: [u32; 15] = [0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384];
+const MP3_BITRATES_V1_L3: [u32; 15] = [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320];
+const MP3_BITRATES_V2_L1: [u32; 15] = [0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256];
+const MP3_BITRATES_V2_L23: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+fn probe_mp3(path: &Path) -> Option<MediaInfo> {
+    let file_len = fs::metadata(path).ok()?.len();
+    let data = read_head(path, 256 * 1024)?;
+
+    // 跳过 ID3v2 标签（长度为 syncsafe 整数）
+    let mut start = 0usize;
+    if data.starts_with(b"ID3") && data.len() >= 10 {
+        let tag_size = data[6..10]
+            .iter()
+            .fold(0usize, |acc, &x| (acc << 7) | (x & 0x7F) as usize);
+        start = 10 + tag_size;
+    }
+
+    let mut i = start;
+    while i + 4 <= data.len() {
+        if data[i] == 0xFF && data[i + 1] & 0xE0 == 0xE0 {
+            if let Some(info) = parse_mp3_frame(&data, i, file_len) {
+                return Some(info);
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+fn parse_mp3_frame(data: &[u8], at: usize, file_len: u64) -> Option<MediaInfo> {
+    let h = be_u32(data, at)?;
+    let version = (h >> 19) & 0x3; // 0: 2.5, 2: 2, 3: 1
+    let layer = (h >> 17) & 0x3; // 1: III, 2: II, 3: I
+    let bitrate_index = ((h >> 12) & 0xF) as usize;
+    let sample_index = ((h >> 10) & 0x3) as usize;
+    let channel_mode = (h >> 6) & 0x3;
+    if version == 1 || layer == 0 || bitrate_index == 0 || bitrate_index == 15 || sample_index == 3 {
+        return None;
+    }
+
+    let sample_rate = match version {
+        3 => [44100, 48000, 32000][sample_index],
+        2 => [22050, 24000, 16000][sample_index],
+        _ => [11025, 12000, 8000][sample_index],
+    } as u64;
+    let bitrate_kbps = match (version == 3, layer) {
+        (true, 3) => MP3_BITRATES_V1_L1[bitrate_index],
+        (true, 2) => MP3_BITRATES_V1_L2[bitrate_index],
+        (true, _) => MP3_BITRATES_V1_L3[bitrate_index],
+        (false, 3) => MP3_BITRATES_V2_L1[bitrate_index],
+        (false, _) => MP3_BITRATES_V2_L23[bitrate_index],
+    } as u64;
+    let samples_per_frame: u64 = match (version == 3, layer) {
+        (_, 3) => 384,
+        (_, 2) => 1152,
+        (true, _) => 1152,
+        (false, _) => 576,
+    };
+
+    // VBR 文件的 Xing/Info 头记录了总帧数
+    let side_info = match (version == 3, channel_mode == 3) {
+        (true, false) => 32,
+        (true, true) => 17,
+        (false, false) => 17,
+        (false, true) => 9,
+    };
+    let xing_at = at + 4 + side_info;
+    if let Some(tag) = data.get(xing_at..xing_at + 4) {
+        if tag == b"Xing" || tag == b"Info" {
+            let flags = be_u32(data, xing_at + 4)?;
+            if flags & 0x1 != 0 {
+                let frames = be_u32(data, xing_at + 8)? as u64;
+                return Some(MediaInfo {
+                    duration_ms: Some(frames * samples_per_frame * 1000 / sample_rate),
+                    width: None,
+                    height: None,
+                });
+            }
+        }
+    }
+
+    // CBR：按码率估算
+    let audio_bytes = file_len.saturating_sub(at as u64);
+    Some(MediaInfo {
+        duration_ms: Some(audio_bytes * 8 / bitrate_kbps),
+        width: None,
+        height: None,
+    })
+}
+
+// ---------- WAV ----------
+
+fn probe_wav(path: &Path) -> Option<MediaInfo> {
+    let data = read_head(path, 64 * 1024)?;
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return None;
+    }
+    let mut byte_rate: Option<u32> = None;
+    let mut i = 12;
+    while i + 8 <= data.len() {
+        let id = &data[i..i + 4];
+        let size = le_u32(&data, i + 4)? as usize;
+        if id == b"fmt " {
+            byte_rate = le_u32(&data, i + 16);
+        } else if id == b"data" {
+            let rate = byte_rate.filter(|r| *r > 0)? as u64;
+            return Some(MediaInfo {
+                duration_ms: Some(size as u64 * 1000 / rate),
+                width: None,
+                height: None,
+            });
+        }
+        i += 8 + size + (size & 1);
+    }
+    None
+}
+
+// ---------- 缩略图 ----------
+
+/// 预览缩略图的最长边
+pub const THUMBNAIL_MAX_EDGE: u32 = 512;
+
+/// 解码后生成的缩略图
+pub struct Thumbnail {
+    pub mime: &'static str,
+    pub bytes: Vec<u8>,
+    pub original_width: u32,
+    pub original_height: u32,
+}
+
+/// 按最长边等比缩放，保证结果不小于 1 像素；本身足够小时保持原尺寸
+pub fn thumbnail_size(width: u32, height: u32, max_edge: u32) -> (u32, u32) {
+    let long_edge = width.max(height);
+    if long_edge <= max_edge || long_edge == 0 {
+        return (width, height);
+    }
+    let scale = max_edge as f64 / long_edge as f64;
+    let scaled = |v: u32| ((v as f64 * scale).round() as u32).max(1);
+    (scaled(width), scaled(height))
+}
+
+/// 解码图片并生成缩略图：照片类保持 JPEG，其他格式输出 PNG（保留透明度）
+pub fn generate_thumbnail(bytes: &[u8], ext: &str, max_edge: u32) -> Option<Thumbnail> {
+    let img = image::load_from_memory(bytes).ok()?;
+    let (original_width, original_height) = (img.width(), img.height());
+    let (width, height) = thumbnail_size(original_width, original_height, max_edge);
+    let thumb = if (width, height) == (original_width, original_height) {
+        img
+    } else {
+        img.thumbnail(width, height)
+    };
+
+    let mut out = std::io::Cursor::new(Vec::new());
+    let mime = if matches!(ext, "jpg" | "jpeg") {
+        thumb
+            .to_rgb8()
+            .write_to(&mut out, image::ImageOutputFormat::Jpeg(85))
+            .ok()?;
+        "image/jpeg"
+    } else {
+        thumb.write_to(&mut out, image::ImageOutputFormat::Png).ok()?;
+        "image/png"
+    };
+
+    Some(Thumbnail {
+        mime,
+        bytes: out.into_inner(),
+        original_width,
+        original_height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_path;
+
+    fn mp4_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut out = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(body);
+        out
+    }
+
+    fn ebml(id: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut out = id.to_vec();
+        out.push(0x80 | body.len() as u8);
+        out.extend_from_slice(body);
+        out
+    }
+
+    #[test]
+    fn reads_image_header_dimensions() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+        assert_eq!(image_dimensions_from_bytes(&png), Some((640, 480)));
+
+        let gif = b"GIF89a\x20\x03\x58\x02";
+        assert_eq!(image_dimensions_from_bytes(gif), Some((800, 600)));
+
+        let mut bmp = b"BM".to_vec();
+        bmp.resize(18, 0);
+        bmp.extend_from_slice(&100i32.to_le_bytes());
+        // 负高度表示自上而下存储
+        bmp.extend_from_slice(&(-50i32).to_le_bytes());
+        assert_eq!(image_dimensions_from_bytes(&bmp), Some((100, 50)));
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10];
+        jpeg.extend_from_slice(&[0; 14]);
+        jpeg.extend_from_slice(&[0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0xE0, 0x02, 0x80]);
+        assert_eq!(image_dimensions_from_bytes(&jpeg), Some((640, 480)));
+
+        assert_eq!(image_dimensions_from_bytes(b"not an image"), None);
+    }
+
+    #[test]
+    fn parses_mp4_duration_and_video_track() {
+        let mut mvhd = vec![0u8; 20];
+        mvhd[12..16].copy_from_slice(&1000u32.to_be_bytes());
+        mvhd[16..20].copy_from_slice(&90_500u32.to_be_bytes());
+        let mut tkhd = vec![0u8; 84];
+        tkhd[76..80].copy_from_slice(&(1280u32 << 16).to_be_bytes());
+        tkhd[80..84].copy_from_slice(&(720u32 << 16).to_be_bytes());
+        let mut moov = mp4_box(b"mvhd", &mvhd);
+        moov.extend(mp4_box(b"trak", &mp4_box(b"tkhd", &tkhd)));
+
+        let mut info = MediaInfo::default();
+        parse_mp4_boxes(&moov, &mut info, 0);
+        assert_eq!(info.duration_ms, Some(90_500));
+        assert_eq!((info.width, info.height), (Some(1280), Some(720)));
+    }
+
+    #[test]
+    fn parses_matroska_info_and_tracks() {
+        let mut info_body = ebml(&[0x2A, 0xD7, 0xB1], &[0x0F, 0x42, 0x40]);
+        info_body.extend(ebml(&[0x44, 0x89], &12_345f64.to_be_bytes()));
+        let mut video = ebml(&[0xB0], &1920u16.to_be_bytes());
+        video.extend(ebml(&[0xBA], &1080u16.to_be_bytes()));
+        let tracks = ebml(
+            &[0x16, 0x54, 0xAE, 0x6B],
+            &ebml(&[0xAE], &ebml(&[0xE0], &video)),
+        );
+        let mut segment_body = ebml(&[0x15, 0x49, 0xA9, 0x66], &info_body);
+        segment_body.extend(tracks);
+        let segment = ebml(&[0x18, 0x53, 0x80, 0x67], &segment_body);
+
+        let mut info = MediaInfo::default();
+        let mut timecode_scale = 0;
+        let mut duration = None;
+        parse_ebml(&segment, &mut info, &mut timecode_scale, &mut duration, 0);
+        assert_eq!(timecode_scale, 1_000_000);
+        assert_eq!(duration, Some(12_345.0));
+        assert_eq!((info.width, info.height), (Some(1920), Some(1080)));
+    }
+
+    #[test]
+    fn deeply_nested_mp4_boxes_stop_at_depth_limit() {
+        // 20 万层 trak，每层 8 字节头
+        const DEPTH: usize = 200_000;
+        let mut moov = vec![0u8; DEPTH * 8];
+        for level in 0..DEPTH {
+            let size = ((DEPTH - level) * 8) as u32;
+            moov[level * 8..level * 8 + 4].copy_from_slice(&size.to_be_bytes());
+            moov[level * 8 + 4..level * 8 + 8].copy_from_slice(b"trak");
+        }
+        let mut info = MediaInfo::default();
+        parse_mp4_boxes(&moov, &mut info, 0);
+        assert_eq!(info, MediaInfo::default());
+
+        // 超出深度限制的 mvhd 被忽略
+        let mut mvhd = vec![0u8; 20];
+        mvhd[12..16].copy_from_slice(&1000u32.to_be_bytes());
+        mvhd[16..20].copy_from_slice(&5_000u32.to_be_bytes());
+        let shallow = mp4_box(b"trak", &mp4_box(b"mdia", &mp4_box(b"mvhd", &mvhd)));
+        let mut deep = mp4_box(b"mvhd", &mvhd);
+        for _ in 0..=MAX_NESTING_DEPTH {
+            deep = mp4_box(b"trak", &deep);
+        }
+        let mut info = MediaInfo::default();
+        parse_mp4_boxes(&shallow, &mut info, 0);
+        assert_eq!(info.duration_ms, Some(5_000));
+        let mut info = MediaInfo::default();
+        parse_mp4_boxes(&deep, &mut info, 0);
+        assert_eq!(info.duration_ms, None);
+    }
+
+    #[test]
+    fn mp4_version_1_duration_overflow_is_ignored() {
+        let mut mvhd = vec![0u8; 32];
+        mvhd[0] = 1;
+        mvhd[20..24].copy_from_slice(&1000u32.to_be_bytes());
+        mvhd[24..32].copy_from_slice(&u64::MAX.to_be_bytes());
+        let mut info = MediaInfo::default();
+        parse_mp4_boxes(&mp4_box(b"mvhd", &mvhd), &mut info, 0);
+        assert_eq!(info.duration_ms, None);
+
+        mvhd[24..32].copy_from_slice(&7_200_000u64.to_be_bytes());
+        parse_mp4_boxes(&mp4_box(b"mvhd", &mvhd), &mut info, 0);
+        assert_eq!(info.duration_ms, Some(7_200_000));
+    }
+
+    #[test]
+    fn deeply_nested_ebml_stops_at_depth_limit() {
+        // EBML 头后重复 E0 FF（Video 元素，未知长度）
+        let mut data = vec![0x1A, 0x45, 0xDF, 0xA3, 0x80];
+        for _ in 0..500_000 {
+            data.extend_from_slice(&[0xE0, 0xFF]);
+        }
+        let mut info = MediaInfo::default();
+        let mut timecode_scale = 1_000_000;
+        let mut duration = None;
+        parse_ebml(&data, &mut info, &mut timecode_scale, &mut duration, 0);
+        assert_eq!(info, MediaInfo::default());
+        assert_eq!(duration, None);
+    }
+
+    #[test]
+    fn estimates_mp3_duration() {
+        // MPEG-1 Layer III，128 kbps，44.1 kHz，立体声
+        let mut frame = vec![0xFF, 0xFB, 0x90, 0x00];
+        frame.resize(64, 0);
+        let cbr = parse_mp3_frame(&frame, 0, 160_000).unwrap();
+        assert_eq!(cbr.duration_ms, Some(10_000));
+
+        frame[36..40].copy_from_slice(b"Xing");
+        frame[40..44].copy_from_slice(&1u32.to_be_bytes());
+        frame[44..48].copy_from_slice(&100u32.to_be_bytes());
+        let vbr = parse_mp3_frame(&frame, 0, 160_000).unwrap();
+        assert_eq!(vbr.duration_ms, Some(100 * 1152 * 1000 / 44_100));
+
+        // 保留的版本号
+        assert!(parse_mp3_frame(&[0xFF, 0xEB, 0x90, 0x00], 0, 1000).is_none());
+    }
+
+    #[test]
+    fn reads_wav_duration_from_header() {
+        let mut wav = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&[1, 0, 2, 0]);
+        wav.extend_from_slice(&44_100u32.to_le_bytes());
+        wav.extend_from_slice(&176_400u32.to_le_bytes());
+        wav.extend_from_slice(&[4, 0, 16, 0]);
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(176_400u32 * 3).to_le_bytes());
+
+        let path = temp_path("media", "probe.wav");
+        fs::write(&path, &wav).unwrap();
+        let info = probe_media(&path, "wav");
+        fs::remove_file(&path).ok();
+        assert_eq!(info.duration_ms, Some(3000));
+    }
+
+    #[test]
+    fn scales_thumbnail_by_long_edge() {
+        assert_eq!(thumbnail_size(2048, 1024, 512), (512, 256));
+        assert_eq!(thumbnail_size(1000, 3000, 512), (171, 512));
+        assert_eq!(thumbnail_size(300, 200, 512), (300, 200));
+        // 极端长宽比下短边至少保留 1 像素
+        assert_eq!(thumbnail_size(100_000, 10, 512), (512, 1));
+    }
+
+    #[test]
+    fn generates_png_and_jpeg_thumbnails() {
+        let image = image::RgbaImage::from_pixel(1200, 600, image::Rgba([10, 20, 30, 128]));
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgba8(image)
+            .write_to(&mut png, image::ImageOutputFormat::Png)
+            .unwrap();
+
+        let thumb = generate_thumbnail(png.get_ref(), "png", 512).unwrap();
+        assert_eq!(thumb.mime, "image/png");
+        assert_eq!((thumb.original_width, thumb.original_height), (1200, 600));
+        assert_eq!(image_dimensions_from_bytes(&thumb.bytes), Some((512, 256)));
+
+        let jpeg = generate_thumbnail(png.get_ref(), "jpg", 512).unwrap();
+        assert_eq!(jpeg.mime, "image/jpeg");
+        assert_eq!(image_dimensions_from_bytes(&jpeg.bytes), Some((512, 256)));
+
+        assert!(generate_thumbnail(b"broken", "png", 512).is_none());
+    }
+}
@@ -0,0 +1,199 @@
+// 图片文字识别：后端可插拔，支持外部命令（如 tesseract）和 Windows.Media.Ocr（需启用 ocr-winrt 特性）
+
+use crate::settings::OcrSettings;
+use std::path::Path;
+use std::process::Command;
+
+const OCR_NOT_AVAILABLE: &str =
+    "OCR 不可用：请在设置中配置外部 OCR 命令，或使用启用了 ocr-winrt 特性的版本";
+
+/// 外部命令未指定语言时使用的默认值（tesseract 的英文语言包）
+const DEFAULT_COMMAND_LANG: &str = "eng";
+
+pub trait OcrBackend {
+    fn name(&self) -> &'static str;
+    /// 识别 PNG 图片中的文字；lang 为 None 时使用后端默认语言
+    fn recognize(&self, png: &[u8], lang: Option<&str>) -> Result<String, String>;
+}
+
+/// 调用外部程序识别：图片写入临时文件，程序的标准输出即识别结果
+struct CommandBackend {
+    program: String,
+    args: Vec<String>,
+}
+
+impl OcrBackend for CommandBackend {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
+    fn recognize(&self, png: &[u8], lang: Option<&str>) -> Result<String, String> {
+        let resolved = crate::process_utils::resolve_program(&self.program)
+            .ok_or_else(|| format!("找不到 OCR 程序: {}", self.program))?;
+        let image_path = std::env::temp_dir().join(format!(
+            "re-fast-ocr-{}.png",
+            chrono::Local::now().timestamp_millis()
+        ));
+        std::fs::write(&image_path, png).map_err(|e| format!("写入临时图片失败: {}", e))?;
+
+        let result = self.run(&resolved, &image_path, lang.unwrap_or(DEFAULT_COMMAND_LANG));
+        let _ = std::fs::remove_file(&image_path);
+        result
+    }
+}
+
+impl CommandBackend {
+    fn run(&self, program: &Path, image_path: &Path, lang: &str) -> Result<String, String> {
+        let image = image_path.to_string_lossy();
+        let args: Vec<String> = if self.args.is_empty() {
+            vec![image.to_string()]
+        } else {
+            self.args
+                .iter()
+                .map(|arg| arg.replace("{image}", &image).replace("{lang}", lang))
+                .collect()
+        };
+
+        let mut command = Command::new(program);
+        command.args(&args);
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(0x08000000); // CREATE_NO_WINDOW - 不显示控制台窗口
+        }
+
+        let output = command
+            .output()
+            .map_err(|e| format!("启动 OCR 程序失败 {}: {}", program.display(), e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!(
+                "OCR 程序执行失败（{}）: {}",
+                output.status,
+                stderr.trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+#[cfg(all(target_os = "windows", feature = "ocr-winrt"))]
+struct WindowsBackend;
+
+#[cfg(all(target_os = "windows", feature = "ocr-winrt"))]
+impl OcrBackend for WindowsBackend {
+    fn name(&self) -> &'static str {
+        "windows"
+    }
+
+    fn recognize(&self, png: &[u8], lang: Option<&str>) -> Result<String, String> {
+        use windows::core::HSTRING;
+        use windows::Globalization::Language;
+        use windows::Graphics::Imaging::BitmapDecoder;
+        use windows::Media::Ocr::OcrEngine;
+        use windows::Storage::Streams::{DataWriter, InMemoryRandomAccessStream};
+
+        let winrt_error = |e: windows::core::Error| format!("系统 OCR 调用失败: {}", e.message());
+
+        let engine = match lang {
+            Some(tag) => {
+                let language = Language::CreateLanguage(&HSTRING::from(tag)).map_err(winrt_error)?;
+                if !OcrEngine::IsLanguageSupported(&language).map_err(winrt_error)? {
+                    return Err(format!("系统 OCR 不支持语言 {}，请先在系统设置中安装对应语言包", tag));
+                }
+                OcrEngine::TryCreateFromLanguage(&language)
+            }
+            None => OcrEngine::TryCreateFromUserProfileLanguages(),
+        }
+        .map_err(|_| "无法创建系统 OCR 引擎，请确认已安装支持 OCR 的语言包".to_string())?;
+
+        let stream = InMemoryRandomAccessStream::new().map_err(winrt_error)?;
+        let writer = DataWriter::CreateDataWriter(&stream).map_err(winrt_error)?;
+        writer.WriteBytes(png).map_err(winrt_error)?;
+        writer
+            .StoreAsync()
+            .and_then(|op| op.get())
+            .map_err(winrt_error)?;
+        writer.DetachStream().map_err(winrt_error)?;
+        stream.Seek(0).map_err(winrt_error)?;
+
+        let bitmap = BitmapDecoder::CreateAsync(&stream)
+            .and_then(|op| op.get())
+            .and_then(|decoder| decoder.GetSoftwareBitmapAsync())
+            .and_then(|op| op.get())
+            .map_err(winrt_error)?;
+        let max_dimension = OcrEngine::MaxImageDimension().map_err(winrt_error)? as i32;
+        let (width, height) = (
+            bitmap.PixelWidth().map_err(winrt_error)?,
+            bitmap.PixelHeight().map_err(winrt_error)?,
+        );
+        if width > max_dimension || height > max_dimension {
+            return Err(format!(
+                "图片过大（{}×{}），系统 OCR 最多支持 {} 像素边长",
+                width, height, max_dimension
+            ));
+        }
+
+        let result = engine
+            .RecognizeAsync(&bitmap)
+            .and_then(|op| op.get())
+            .map_err(winrt_error)?;
+        // 按行拼接，Text() 会把所有行合并成一行
+        let lines = result.Lines().map_err(winrt_error)?;
+        let mut text = Vec::new();
+        for line in lines {
+            text.push(line.Text().map_err(winrt_error)?.to_string());
+        }
+        Ok(text.join("\n"))
+    }
+}
+
+fn command_backend(settings: &OcrSettings) -> Option<Box<dyn OcrBackend>> {
+    let program = settings.command.trim();
+    if program.is_empty() {
+        return None;
+    }
+    Some(Box::new(CommandBackend {
+        program: program.to_string(),
+        args: settings.args.clone(),
+    }))
+}
+
+fn windows_backend() -> Option<Box<dyn OcrBackend>> {
+    #[cfg(all(target_os = "windows", feature = "ocr-winrt"))]
+    {
+        Some(Box::new(WindowsBackend))
+    }
+    #[cfg(not(all(target_os = "windows", feature = "ocr-winrt")))]
+    {
+        None
+    }
+}
+
+/// 按设置选择 OCR 后端，均不可用时返回 "OCR 不可用" 错误
+pub fn select_backend(settings: &OcrSettings) -> Result<Box<dyn OcrBackend>, String> {
+    let backend = match settings.backend.trim().to_lowercase().as_str() {
+        "command" => command_backend(settings),
+        "windows" => windows_backend(),
+        _ => command_backend(settings).or_else(windows_backend),
+    };
+    backend.ok_or_else(|| OCR_NOT_AVAILABLE.to_string())
+}
+
+/// 识别 PNG 图片中的文字
+pub fn recognize_png(
+    settings: &OcrSettings,
+    png: &[u8],
+    lang: Option<&str>,
+) -> Result<String, String> {
+    let backend = select_backend(settings)?;
+    let lang = lang.map(str::trim).filter(|l| !l.is_empty());
+    crate::log_info!(
+        "Ocr",
+        "使用 {} 后端识别图片（{} 字节，语言 {}）",
+        backend.name(),
+        png.len(),
+        lang.unwrap_or("默认")
+    );
+    backend.recognize(png, lang)
+}
@@ -7,6 +7,13 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+// Maximum number of keys kept; the least recently opened are evicted first
+const MAX_OPEN_HISTORY_ENTRIES: usize = 500;
+
+// Key in the meta table recording that open_history.json was already imported,
+// so an emptied table (e.g. after clear_history) is not refilled from the legacy file
+const JSON_MIGRATED_META_KEY: &str = "open_history_json_migrated";
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OpenHistoryItem {
     pub key: String,        // path or id that uniquely identifies the item
@@ -101,6 +108,7 @@ pub fn record_open(key: String, app_data_dir: &Path) -> Result<(), String> {
         load_history_into(&mut state, app_data_dir).ok();
     }
     state.insert(key, timestamp);
+    evict_oldest(&mut state, MAX_OPEN_HISTORY_ENTRIES);
     drop(state);
 
     // Save to disk
@@ -109,6 +117,39 @@ pub fn record_open(key: String, app_data_dir: &Path) -> Result<(), String> {
     Ok(())
 }
 
+// Drop the least recently opened keys until the map fits within `max_entries`
+fn evict_oldest(state: &mut HashMap<String, u64>, max_entries: usize) {
+    if state.len() <= max_entries {
+        return;
+    }
+
+    let mut entries: Vec<(String, u64)> = state.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    entries.sort_by_key(|(_, ts)| *ts);
+    let excess = state.len() - max_entries;
+    for (key, _) in entries.into_iter().take(excess) {
+        state.remove(&key);
+    }
+}
+
+pub fn clear_history(app_data_dir: &Path) -> Result<(), String> {
+    let mut state = lock_history()?;
+    // Import the legacy file first so it is marked as migrated and not restored later
+    maybe_migrate_from_json(&mut db::get_connection(app_data_dir)?, app_data_dir)?;
+    state.clear();
+    save_history_internal(&state, app_data_dir)
+}
+
+// Remove a single key; returns whether it was present
+pub fn forget_key(key: &str, app_data_dir: &Path) -> Result<bool, String> {
+    let mut state = lock_history()?;
+    load_history_into(&mut state, app_data_dir).ok();
+    let removed = state.remove(key).is_some();
+    if removed {
+        save_history_internal(&state, app_data_dir)?;
+    }
+    Ok(removed)
+}
+
 pub fn get_last_opened(key: &str) -> Option<u64> {
     let state = lock_history().ok()?;
     state.get(key).copied()
@@ -124,6 +165,17 @@ fn maybe_migrate_from_json(
     conn: &mut rusqlite::Connection,
     app_data_dir: &Path,
 ) -> Result<(), String> {
+    let migrated: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM meta WHERE key = ?1)",
+            params![JSON_MIGRATED_META_KEY],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to read open_history migration flag: {}", e))?;
+    if migrated {
+        return Ok(());
+    }
+
     let count: i64 = conn
         .query_row("SELECT COUNT(*) FROM open_history", [], |row| row.get(0))
         .map_err(|e| format!("Failed to count open_history rows: {}", e))?;
@@ -139,6 +191,73 @@ fn maybe_migrate_from_json(
         }
     }
 
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, '1')",
+        params![JSON_MIGRATED_META_KEY],
+    )
+    .map_err(|e| format!("Failed to record open_history migration: {}", e))?;
+
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn migrates_legacy_json() {
+        let dir = temp_dir("open-history", "migrate");
+        fs::write(
+            get_history_file_path(&dir),
+            r#"{"C:\\a.txt": 10, "app:notepad": 20}"#,
+        )
+        .unwrap();
+
+        let history = get_all_history(&dir).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get("C:\\a.txt"), Some(&10));
+        assert_eq!(history.get("app:notepad"), Some(&20));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cleared_history_is_not_migrated_again() {
+        let dir = temp_dir("open-history", "clear-after-load");
+        fs::write(get_history_file_path(&dir), r#"{"a": 1, "b": 2}"#).unwrap();
+        assert_eq!(get_all_history(&dir).unwrap().len(), 2);
+
+        clear_history(&dir).unwrap();
+        assert!(get_all_history(&dir).unwrap().is_empty());
+        load_history(&dir).unwrap();
+        assert!(get_all_history(&dir).unwrap().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+
+        // Clearing before anything was loaded also keeps the legacy file from coming back
+        let dir = temp_dir("open-history", "clear-before-load");
+        fs::write(get_history_file_path(&dir), r#"{"a": 1}"#).unwrap();
+        clear_history(&dir).unwrap();
+        assert!(get_all_history(&dir).unwrap().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn empty_or_invalid_legacy_file_is_ignored() {
+        for (name, content) in [("empty", ""), ("empty-map", "{}"), ("invalid", "not json")] {
+            let dir = temp_dir("open-history", name);
+            fs::write(get_history_file_path(&dir), content).unwrap();
+            assert!(get_all_history(&dir).unwrap().is_empty(), "{}", name);
+            let _ = fs::remove_dir_all(&dir);
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_opened() {
+        let mut state: HashMap<String, u64> = [("a", 3), ("b", 1), ("c", 2)]
+            .map(|(k, v)| (k.to_string(), v))
+            .into();
+        evict_oldest(&mut state, 2);
+        assert_eq!(state.len(), 2);
+        assert!(!state.contains_key("b"));
+    }
+}
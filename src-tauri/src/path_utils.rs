@@ -0,0 +1,115 @@
+// 路径中的环境变量展开：%NAME% 形式的任意环境变量，非 Windows 平台另外展开开头的 ~
+// 用于快捷方式、检查路径、定位文件等接受用户输入路径的地方，如 "%USERPROFILE%\Projects"
+
+/// 展开 %NAME%，变量值由 lookup 提供；lookup 返回 None 的变量原样保留。
+/// 两个 % 之间为空或含空白时不视为变量（如 "100% of %USERPROFILE%"），后一个 % 仍可作为变量的开头
+pub fn expand_env_vars_with<F>(input: &str, lookup: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('%') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let variable = after.find('%').map(|end| (&after[..end], end)).filter(|(name, _)| {
+            !name.is_empty() && !name.contains('=') && !name.contains(char::is_whitespace)
+        });
+        match variable {
+            Some((name, end)) => {
+                match lookup(name) {
+                    Some(value) => result.push_str(&value),
+                    None => {
+                        result.push('%');
+                        result.push_str(name);
+                        result.push('%');
+                    }
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push('%');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// 把开头的 "~" 或 "~/..." 替换为 home；"~user" 形式不处理
+pub fn expand_home(input: &str, home: Option<&str>) -> String {
+    match (input.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            format!("{}{}", home.trim_end_matches('/'), rest)
+        }
+        _ => input.to_string(),
+    }
+}
+
+/// 使用当前进程的环境变量展开路径，Windows 上变量名不区分大小写
+pub fn expand_path(input: &str) -> String {
+    let expanded = expand_env_vars_with(input, |name| std::env::var(name).ok());
+    if cfg!(target_os = "windows") {
+        expanded
+    } else {
+        expand_home(&expanded, std::env::var("HOME").ok().as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "USERPROFILE" => Some("C:\\Users\\me".to_string()),
+            "APPDATA" => Some("C:\\Users\\me\\AppData\\Roaming".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expands_known_variables() {
+        assert_eq!(
+            expand_env_vars_with("%USERPROFILE%\\Projects", lookup),
+            "C:\\Users\\me\\Projects"
+        );
+        assert_eq!(
+            expand_env_vars_with("%APPDATA%\\re-fast;%USERPROFILE%", lookup),
+            "C:\\Users\\me\\AppData\\Roaming\\re-fast;C:\\Users\\me"
+        );
+        assert_eq!(expand_env_vars_with("a%EMPTY%b", lookup), "ab");
+        assert_eq!(expand_env_vars_with("C:\\plain", lookup), "C:\\plain");
+    }
+
+    #[test]
+    fn unknown_variables_are_kept() {
+        assert_eq!(expand_env_vars_with("%NOPE%\\x", lookup), "%NOPE%\\x");
+        assert_eq!(
+            expand_env_vars_with("%NOPE%%USERPROFILE%", lookup),
+            "%NOPE%C:\\Users\\me"
+        );
+    }
+
+    #[test]
+    fn literal_percent_text() {
+        assert_eq!(
+            expand_env_vars_with("100% of %USERPROFILE%", lookup),
+            "100% of C:\\Users\\me"
+        );
+        assert_eq!(expand_env_vars_with("50%%", lookup), "50%%");
+        assert_eq!(expand_env_vars_with("100%", lookup), "100%");
+        assert_eq!(expand_env_vars_with("%A=B%", lookup), "%A=B%");
+    }
+
+    #[test]
+    fn expands_leading_tilde() {
+        assert_eq!(expand_home("~", Some("/home/me")), "/home/me");
+        assert_eq!(expand_home("~/docs", Some("/home/me/")), "/home/me/docs");
+        assert_eq!(expand_home("~other/docs", Some("/home/me")), "~other/docs");
+        assert_eq!(expand_home("/tmp/~", Some("/home/me")), "/tmp/~");
+        assert_eq!(expand_home("~/docs", None), "~/docs");
+    }
+}
@@ -0,0 +1,218 @@
+use crate::plugin_manifest::{self, PluginManifest, MANIFEST_FILE};
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use zip::ZipArchive;
+
+/// 校验压缩包条目名：只允许普通的相对路径组件，拒绝 ../、绝对路径和盘符（zip slip）
+fn safe_entry_path(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    let mut components = path.components().peekable();
+    (components.peek().is_some() && components.all(|c| matches!(c, Component::Normal(_))))
+        .then(|| path.to_path_buf())
+}
+
+/// 查找已安装的同 ID 插件：同名目录，或 manifest 中 id 相同的目录
+pub fn find_installed(plugins_dir: &Path, plugin_id: &str) -> Option<PathBuf> {
+    let same_name = plugins_dir.join(plugin_id);
+    if same_name.is_dir() {
+        return Some(same_name);
+    }
+    fs::read_dir(plugins_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .find(|path| {
+            plugin_manifest::load_manifest(path).is_ok_and(|manifest| manifest.id == plugin_id)
+        })
+}
+
+/// 从 zip 安装插件到 plugins_dir/<id>：先校验全部条目和 manifest，解压到临时目录后再替换
+/// 已存在同 ID 的插件时，除非 overwrite 为 true，否则拒绝安装
+pub fn install_from_zip(
+    plugins_dir: &Path,
+    zip_path: &Path,
+    overwrite: bool,
+) -> Result<(PathBuf, PluginManifest), String> {
+    let file = fs::File::open(zip_path).map_err(|e| format!("打开插件包失败: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("不是有效的 zip 文件: {}", e))?;
+
+    let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    if let Some(bad) = names.iter().find(|n| safe_entry_path(n).is_none()) {
+        return Err(format!("插件包包含不安全的路径: {}", bad));
+    }
+
+    let mut manifest_json = String::new();
+    archive
+        .by_name(MANIFEST_FILE)
+        .map_err(|_| format!("插件包顶层缺少 {}", MANIFEST_FILE))?
+        .read_to_string(&mut manifest_json)
+        .map_err(|e| format!("读取 {} 失败: {}", MANIFEST_FILE, e))?;
+    let manifest = plugin_manifest::parse_manifest(&manifest_json)?;
+
+    fs::create_dir_all(plugins_dir).map_err(|e| format!("创建插件目录失败: {}", e))?;
+    let existing = find_installed(plugins_dir, &manifest.id);
+    if let Some(existing) = &existing {
+        if !overwrite {
+            return Err(format!(
+                "插件 {} 已安装在 {}",
+                manifest.id,
+                existing.display()
+            ));
+        }
+    }
+
+    let staging = plugins_dir.join(format!(".{}.installing", manifest.id));
+    let _ = fs::remove_dir_all(&staging);
+    if let Err(e) = extract_all(&mut archive, &names, &staging) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    if let Some(existing) = &existing {
+        if let Err(e) = fs::remove_dir_all(existing) {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(format!("删除旧版本插件失败: {}", e));
+        }
+    }
+    let target = plugins_dir.join(&manifest.id);
+    fs::rename(&staging, &target).map_err(|e| {
+        let _ = fs::remove_dir_all(&staging);
+        format!("安装插件失败: {}", e)
+    })?;
+
+    Ok((target, manifest))
+}
+
+fn extract_all<R: Read + std::io::Seek>(
+    archive: &mut ZipArchive<R>,
+    names: &[String],
+    dest: &Path,
+) -> Result<(), String> {
+    for name in names {
+        let Some(relative) = safe_entry_path(name) else {
+            continue;
+        };
+        let out_path = dest.join(relative);
+        if name.ends_with('/') {
+            fs::create_dir_all(&out_path).map_err(|e| format!("创建目录失败: {}", e))?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        let mut entry = archive
+            .by_name(name)
+            .map_err(|e| format!("读取插件包条目失败 {}: {}", name, e))?;
+        let mut out = fs::File::create(&out_path)
+            .map_err(|e| format!("写入文件失败 {}: {}", out_path.display(), e))?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| format!("解压失败 {}: {}", name, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+    use std::io::Write;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    fn manifest_json(id: &str, version: &str) -> String {
+        format!(
+            r#"{{"id": "{}", "name": "Demo", "version": "{}", "entry": "index.js"}}"#,
+            id, version
+        )
+    }
+
+    fn write_zip(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = ZipWriter::new(fs::File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, FileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn safe_entry_path_rejects_zip_slip() {
+        assert!(safe_entry_path("index.js").is_some());
+        assert!(safe_entry_path("dist/index.js").is_some());
+        assert!(safe_entry_path("").is_none());
+        assert!(safe_entry_path("../evil.js").is_none());
+        assert!(safe_entry_path("dist/../../evil.js").is_none());
+        assert!(safe_entry_path("/etc/passwd").is_none());
+        assert!(safe_entry_path("./index.js").is_none());
+    }
+
+    #[test]
+    fn installs_and_overwrites_by_manifest_id() {
+        let dir = temp_dir("plugin-install", "install");
+        let plugins_dir = dir.join("plugins");
+        let v1 = dir.join("v1.zip");
+        write_zip(
+            &v1,
+            &[
+                (MANIFEST_FILE, &manifest_json("demo", "1.0.0")),
+                ("index.js", "v1"),
+                ("assets/icon.svg", "<svg/>"),
+            ],
+        );
+
+        let (target, manifest) = install_from_zip(&plugins_dir, &v1, false).unwrap();
+        assert_eq!(target, plugins_dir.join("demo"));
+        assert_eq!(manifest.version, "1.0.0");
+        assert!(target.join("assets/icon.svg").is_file());
+        assert_eq!(find_installed(&plugins_dir, "demo"), Some(target.clone()));
+
+        let v2 = dir.join("v2.zip");
+        write_zip(
+            &v2,
+            &[
+                (MANIFEST_FILE, &manifest_json("demo", "2.0.0")),
+                ("index.js", "v2"),
+            ],
+        );
+        let err = install_from_zip(&plugins_dir, &v2, false).unwrap_err();
+        assert!(err.contains("已安装"), "{}", err);
+
+        install_from_zip(&plugins_dir, &v2, true).unwrap();
+        assert_eq!(fs::read_to_string(target.join("index.js")).unwrap(), "v2");
+        assert!(!target.join("assets").exists());
+        assert!(!plugins_dir.join(".demo.installing").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_unsafe_or_invalid_packages() {
+        let dir = temp_dir("plugin-install", "reject");
+        let plugins_dir = dir.join("plugins");
+
+        let slip = dir.join("slip.zip");
+        write_zip(
+            &slip,
+            &[
+                (MANIFEST_FILE, &manifest_json("demo", "1.0.0")),
+                ("../evil.js", "x"),
+            ],
+        );
+        assert!(install_from_zip(&plugins_dir, &slip, false)
+            .unwrap_err()
+            .contains("../evil.js"));
+
+        let no_manifest = dir.join("no-manifest.zip");
+        write_zip(&no_manifest, &[("index.js", "x")]);
+        assert!(install_from_zip(&plugins_dir, &no_manifest, false).is_err());
+
+        let bad_version = dir.join("bad-version.zip");
+        write_zip(
+            &bad_version,
+            &[(MANIFEST_FILE, &manifest_json("demo", "1.0"))],
+        );
+        assert!(install_from_zip(&plugins_dir, &bad_version, false).is_err());
+        assert!(find_installed(&plugins_dir, "demo").is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
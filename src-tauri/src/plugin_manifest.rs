@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// 插件目录下 manifest.json 的结构，与前端 `PluginManifest` 对应
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PluginManifest {
+    pub id: String,
+    /// name / version 在旧版插件中可能缺失，只在安装新插件时强制要求
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    /// 相对于插件目录的入口文件路径
+    pub entry: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hotkey: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// 依赖的插件 ID 到版本要求的映射
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<HashMap<String, String>>,
+}
+
+/// 版本号须为 `主.次.修订`，可带 `-预发布` 后缀，例如 1.0.0、2.1.0-beta.1
+fn is_valid_version(version: &str) -> bool {
+    let core = match version.split_once('-') {
+        Some((_, "")) => return false,
+        Some((core, _)) => core,
+        None => version,
+    };
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// 解析并校验 manifest 内容，错误信息指出具体字段；安装新插件时使用
+pub fn parse_manifest(content: &str) -> Result<PluginManifest, String> {
+    let (manifest, warnings) = parse_manifest_lenient(content)?;
+    match warnings.into_iter().next() {
+        Some(problem) => Err(problem),
+        None => Ok(manifest),
+    }
+}
+
+/// 与 parse_manifest 相同，但 name 缺失、version 缺失或格式不规范只作为警告返回，
+/// 已安装的旧版插件因此仍可加载；id 和 entry 的问题仍是错误
+pub fn parse_manifest_lenient(content: &str) -> Result<(PluginManifest, Vec<String>), String> {
+    let mut manifest: PluginManifest =
+        serde_json::from_str(content).map_err(|e| format!("manifest.json 格式错误: {}", e))?;
+
+    for (field, value) in [("id", &manifest.id), ("entry", &manifest.entry)] {
+        if value.trim().is_empty() {
+            return Err(format!("manifest.json 字段 `{}` 不能为空", field));
+        }
+    }
+    // id 会用作安装目录名，不允许以 . 开头（如 ".."）
+    if manifest.id.starts_with('.')
+        || !manifest
+            .id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+    {
+        return Err(format!(
+            "manifest.json 字段 `id` 只能包含字母、数字、_、- 和 .，且不能以 . 开头: {}",
+            manifest.id
+        ));
+    }
+    if Path::new(&manifest.entry).is_absolute() || manifest.entry.contains("..") {
+        return Err(format!(
+            "manifest.json 字段 `entry` 必须是插件目录内的相对路径: {}",
+            manifest.entry
+        ));
+    }
+
+    let mut warnings = Vec::new();
+    if manifest.name.trim().is_empty() {
+        warnings.push("manifest.json 字段 `name` 不能为空".to_string());
+        manifest.name = manifest.id.clone();
+    }
+    if !is_valid_version(&manifest.version) {
+        warnings.push(format!(
+            "manifest.json 字段 `version` 无效（应为 x.y.z）: {}",
+            manifest.version
+        ));
+    }
+
+    Ok((manifest, warnings))
+}
+
+/// 读取并校验已安装插件目录下的 manifest.json，不规范的旧版字段记录警告后照常加载
+pub fn load_manifest(plugin_dir: &Path) -> Result<PluginManifest, String> {
+    let manifest_path = plugin_dir.join(MANIFEST_FILE);
+    if !manifest_path.exists() {
+        return Err("manifest.json not found".to_string());
+    }
+    let content = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Failed to read manifest: {}", e))?;
+    let (manifest, warnings) = parse_manifest_lenient(&content)?;
+    for warning in warnings {
+        crate::log_warn!("Plugin", "{}: {}", plugin_dir.display(), warning);
+    }
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const VALID: &str = r#"{
+        "id": "demo-plugin",
+        "name": "Demo",
+        "version": "1.2.0-beta.1",
+        "entry": "dist/index.js",
+        "keywords": ["demo"],
+        "dependencies": {"json_formatter": "^1.0.0"}
+    }"#;
+
+    #[test]
+    fn parses_valid_manifest_with_dependencies() {
+        let manifest = parse_manifest(VALID).unwrap();
+        assert_eq!(manifest.id, "demo-plugin");
+        assert_eq!(manifest.keywords, vec!["demo"]);
+        assert_eq!(
+            manifest
+                .dependencies
+                .as_ref()
+                .and_then(|deps| deps.get("json_formatter"))
+                .map(String::as_str),
+            Some("^1.0.0")
+        );
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["dependencies"]["json_formatter"], "^1.0.0");
+        assert!(json.get("icon").is_none());
+    }
+
+    #[test]
+    fn version_format() {
+        for ok in ["1.0.0", "10.20.30", "2.1.0-beta.1"] {
+            assert!(is_valid_version(ok), "{}", ok);
+        }
+        for bad in ["", "1.0", "1.0.0.0", "v1.0.0", "1.0.0-", "1..0"] {
+            assert!(!is_valid_version(bad), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn strict_parse_rejects_legacy_fields() {
+        let legacy = r#"{"id": "old", "version": "1.0", "entry": "index.js"}"#;
+        let err = parse_manifest(legacy).unwrap_err();
+        assert!(err.contains("`name`"), "{}", err);
+
+        let bad_version = r#"{"id": "old", "name": "Old", "version": "1.0", "entry": "index.js"}"#;
+        assert!(parse_manifest(bad_version)
+            .unwrap_err()
+            .contains("`version`"));
+    }
+
+    #[test]
+    fn lenient_parse_warns_for_legacy_fields() {
+        let legacy = r#"{"id": "old", "version": "1.0", "entry": "index.js"}"#;
+        let (manifest, warnings) = parse_manifest_lenient(legacy).unwrap();
+        assert_eq!(manifest.name, "old");
+        assert_eq!(manifest.version, "1.0");
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn unsafe_id_and_entry_are_always_errors() {
+        for content in [
+            r#"{"id": "", "name": "x", "version": "1.0.0", "entry": "a.js"}"#,
+            r#"{"id": "..", "name": "x", "version": "1.0.0", "entry": "a.js"}"#,
+            r#"{"id": "a/b", "name": "x", "version": "1.0.0", "entry": "a.js"}"#,
+            r#"{"id": "a", "name": "x", "version": "1.0.0", "entry": "../a.js"}"#,
+            r#"{"id": "a", "name": "x", "version": "1.0.0", "entry": ""}"#,
+            r#"{"id": "a", "name": "x""#,
+        ] {
+            assert!(parse_manifest_lenient(content).is_err(), "{}", content);
+        }
+    }
+
+    #[test]
+    fn load_manifest_reads_plugin_directory() {
+        let dir = temp_dir("manifest", "load");
+        assert!(load_manifest(&dir).is_err());
+        fs::write(
+            dir.join(MANIFEST_FILE),
+            r#"{"id": "legacy", "name": "Legacy", "version": "0.1", "entry": "main.js"}"#,
+        )
+        .unwrap();
+        assert_eq!(load_manifest(&dir).unwrap().id, "legacy");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -63,7 +63,19 @@ fn get_plugin_usage_by_id(
     .map_err(|e| format!("Failed to fetch plugin usage: {}", e))
 }
 
-pub fn list_plugin_usage(app_data_dir: &Path) -> Result<Vec<PluginUsage>, String> {
+/// Decayed usage score: `open_count` halves every `half_life_secs` since `last_opened`.
+fn decayed_score(usage: &PluginUsage, now: u64, half_life_secs: u64) -> f64 {
+    let age = now.saturating_sub(usage.last_opened) as f64;
+    let half_life = half_life_secs.max(1) as f64;
+    usage.open_count as f64 * 0.5_f64.powf(age / half_life)
+}
+
+/// List plugin usage ordered by count, or by time-decayed count when
+/// `half_life_secs` is provided so that recent opens outrank stale ones.
+pub fn list_plugin_usage(
+    app_data_dir: &Path,
+    half_life_secs: Option<u64>,
+) -> Result<Vec<PluginUsage>, String> {
     let mut conn = db::get_connection(app_data_dir)?;
     let mut stmt = conn
         .prepare(
@@ -91,6 +103,29 @@ pub fn list_plugin_usage(app_data_dir: &Path) -> Result<Vec<PluginUsage>, String
         results.push(row.map_err(|e| format!("Failed to read plugin usage row: {}", e))?);
     }
 
+    if let Some(half_life) = half_life_secs {
+        let now = now_ts();
+        results.sort_by(|a, b| {
+            decayed_score(b, now, half_life)
+                .partial_cmp(&decayed_score(a, now, half_life))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b.last_opened.cmp(&a.last_opened))
+        });
+    }
+
     Ok(results)
 }
 
+/// Remove usage data for a single plugin, or for all plugins when `plugin_id` is None.
+/// Returns the number of rows deleted.
+pub fn clear_plugin_usage(plugin_id: Option<String>, app_data_dir: &Path) -> Result<usize, String> {
+    let conn = db::get_connection(app_data_dir)?;
+    let deleted = match plugin_id {
+        Some(id) => conn.execute("DELETE FROM plugin_usage WHERE plugin_id = ?1", params![id]),
+        None => conn.execute("DELETE FROM plugin_usage", []),
+    }
+    .map_err(|e| format!("Failed to clear plugin usage: {}", e))?;
+
+    Ok(deleted)
+}
+
@@ -0,0 +1,300 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 解析可执行文件：带路径时直接检查，否则在 PATH 中查找（Windows 下按 PATHEXT 补全扩展名）
+pub fn resolve_program(program: &str) -> Option<PathBuf> {
+    let program = program.trim().trim_matches('"');
+    if program.is_empty() {
+        return None;
+    }
+
+    let candidate = Path::new(program);
+    if candidate.components().count() > 1 || candidate.is_absolute() {
+        return find_with_extensions(candidate);
+    }
+
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var).find_map(|dir| find_with_extensions(&dir.join(program)))
+}
+
+fn find_with_extensions(base: &Path) -> Option<PathBuf> {
+    if base.is_file() {
+        return Some(base.to_path_buf());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        if base.extension().is_none() {
+            let pathext =
+                std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+            for ext in pathext.split(';').filter(|e| !e.is_empty()) {
+                let mut with_ext = base.as_os_str().to_owned();
+                with_ext.push(ext);
+                let path = PathBuf::from(with_ext);
+                if path.is_file() {
+                    return Some(path);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// 启动外部程序，不等待其退出，返回进程 ID
+pub fn run_command(
+    program: &str,
+    args: &[String],
+    working_dir: Option<&str>,
+) -> Result<u32, String> {
+    let resolved = resolve_program(program)
+        .ok_or_else(|| format!("找不到程序: {}（请检查路径或 PATH 环境变量）", program))?;
+
+    let mut command = Command::new(&resolved);
+    command.args(args);
+
+    if let Some(dir) = working_dir.map(str::trim).filter(|d| !d.is_empty()) {
+        if !Path::new(dir).is_dir() {
+            return Err(format!("工作目录不存在: {}", dir));
+        }
+        command.current_dir(dir);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW - 不显示控制台窗口
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("启动程序失败 {}: {}", resolved.display(), e))?;
+    let pid = child.id();
+
+    // 后台等待子进程退出，避免残留僵尸进程
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+
+    Ok(pid)
+}
+
+/// Linux 下未配置终端时依次尝试的程序
+#[cfg(target_os = "linux")]
+const LINUX_TERMINALS: &[&str] = &[
+    "x-terminal-emulator",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "alacritty",
+    "kitty",
+    "xterm",
+];
+
+/// 在 dir 中打开终端；terminal 为空时自动选择（Windows 优先 Windows Terminal，其次 PowerShell）
+pub fn open_terminal(dir: &Path, terminal: &str) -> Result<(), String> {
+    let terminal = terminal.trim();
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NEW_CONSOLE: u32 = 0x00000010;
+
+        let choice = match terminal.to_lowercase().as_str() {
+            "" | "auto" if resolve_program("wt").is_some() => "wt".to_string(),
+            "" | "auto" => "powershell".to_string(),
+            other => other.to_string(),
+        };
+        let program = resolve_program(&choice)
+            .ok_or_else(|| format!("找不到终端程序: {}", choice))?;
+        let mut command = Command::new(&program);
+        match choice.as_str() {
+            // Windows Terminal 通过 -d 指定起始目录
+            "wt" => {
+                command.arg("-d").arg(dir);
+            }
+            "powershell" | "pwsh" => {
+                command.arg("-NoExit").creation_flags(CREATE_NEW_CONSOLE);
+            }
+            "cmd" => {
+                command.arg("/K").creation_flags(CREATE_NEW_CONSOLE);
+            }
+            _ => {
+                command.creation_flags(CREATE_NEW_CONSOLE);
+            }
+        }
+        command
+    };
+
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let app = if terminal.is_empty() { "Terminal" } else { terminal };
+        let mut command = Command::new("open");
+        command.arg("-a").arg(app).arg(dir);
+        command
+    };
+
+    #[cfg(target_os = "linux")]
+    let mut command = {
+        let program = if terminal.is_empty() {
+            LINUX_TERMINALS
+                .iter()
+                .find_map(|name| resolve_program(name))
+                .ok_or_else(|| "找不到可用的终端程序，请在设置中指定".to_string())?
+        } else {
+            resolve_program(terminal).ok_or_else(|| format!("找不到终端程序: {}", terminal))?
+        };
+        Command::new(program)
+    };
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (dir, terminal);
+        return Err("Opening a terminal is not supported on this platform".to_string());
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+    {
+        // 大多数终端以当前目录作为起始目录
+        command.current_dir(dir);
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("启动终端失败: {}", e))?;
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+        Ok(())
+    }
+}
+
+/// 进程列表中的单项
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    /// 工作集内存（字节），无权限读取时为 None
+    pub memory: Option<u64>,
+}
+
+/// 结束后会导致系统不稳定或蓝屏的关键进程
+const PROTECTED_PROCESS_NAMES: &[&str] = &[
+    "system",
+    "registry",
+    "smss.exe",
+    "csrss.exe",
+    "wininit.exe",
+    "winlogon.exe",
+    "services.exe",
+    "lsass.exe",
+    "lsaiso.exe",
+    "svchost.exe",
+    "dwm.exe",
+    "fontdrvhost.exe",
+    "memory compression",
+];
+
+/// 判断进程是否禁止结束：系统进程（PID 0/4）、关键系统进程和本程序自身
+pub fn is_protected_process(pid: u32, name: &str) -> bool {
+    pid == 0
+        || pid == 4
+        || pid == std::process::id()
+        || PROTECTED_PROCESS_NAMES
+            .iter()
+            .any(|n| n.eq_ignore_ascii_case(name.trim()))
+}
+
+#[cfg(target_os = "windows")]
+pub mod windows {
+    use super::*;
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+    use windows_sys::Win32::System::ProcessStatus::{
+        K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+    };
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, TerminateProcess, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+    };
+
+    fn process_memory(pid: u32) -> Option<u64> {
+        unsafe {
+            let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if handle == 0 {
+                return None;
+            }
+            let mut counters: PROCESS_MEMORY_COUNTERS = std::mem::zeroed();
+            counters.cb = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+            let ok = K32GetProcessMemoryInfo(handle, &mut counters, counters.cb);
+            CloseHandle(handle);
+            if ok != 0 {
+                Some(counters.WorkingSetSize as u64)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// 通过 ToolHelp 快照枚举所有进程，按名称排序
+    pub fn list_processes() -> Result<Vec<ProcessInfo>, String> {
+        let mut processes = Vec::new();
+        unsafe {
+            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+            if snapshot == INVALID_HANDLE_VALUE {
+                return Err("创建进程快照失败".to_string());
+            }
+
+            let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+            entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+            let mut has_entry = Process32FirstW(snapshot, &mut entry) != 0;
+            while has_entry {
+                let len = entry
+                    .szExeFile
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(entry.szExeFile.len());
+                let name = String::from_utf16_lossy(&entry.szExeFile[..len]);
+                processes.push(ProcessInfo {
+                    pid: entry.th32ProcessID,
+                    name,
+                    memory: process_memory(entry.th32ProcessID),
+                });
+                has_entry = Process32NextW(snapshot, &mut entry) != 0;
+            }
+            CloseHandle(snapshot);
+        }
+
+        processes.sort_by_key(|p| (p.name.to_lowercase(), p.pid));
+        Ok(processes)
+    }
+
+    /// 结束指定进程，拒绝结束受保护的系统进程
+    pub fn kill_process(pid: u32) -> Result<(), String> {
+        let name = list_processes()?
+            .into_iter()
+            .find(|p| p.pid == pid)
+            .map(|p| p.name)
+            .ok_or_else(|| format!("进程不存在: {}", pid))?;
+
+        if is_protected_process(pid, &name) {
+            return Err(format!("禁止结束系统关键进程: {} ({})", name, pid));
+        }
+
+        unsafe {
+            let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+            if handle == 0 {
+                return Err(format!(
+                    "无法打开进程 {} ({})，可能需要管理员权限",
+                    name, pid
+                ));
+            }
+            let ok = TerminateProcess(handle, 1);
+            CloseHandle(handle);
+            if ok == 0 {
+                return Err(format!("结束进程失败: {} ({})", name, pid));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+// 二维码生成：用 qrcodegen 编码，png crate 输出灰度 PNG
+
+use base64::{engine::general_purpose, Engine as _};
+use qrcodegen::{QrCode, QrCodeEcc};
+
+/// 输出图片边长的范围（像素）
+const MIN_QR_SIZE: u32 = 64;
+const MAX_QR_SIZE: u32 = 1024;
+/// 二维码四周保留的空白模块数（规范要求至少 4 个）
+const QUIET_ZONE_MODULES: u32 = 4;
+
+/// 将二维码渲染为 size×size 的灰度 PNG；size 不是模块数的整数倍时剩余部分用白边补齐，
+/// 内容过多导致一个模块一个像素也放不下时，图片会大于 size
+fn render_png(qr: &QrCode, size: u32) -> Result<Vec<u8>, String> {
+    let modules = qr.size() as u32 + QUIET_ZONE_MODULES * 2;
+    let scale = (size / modules).max(1);
+    let image_size = size.max(modules * scale);
+    let offset = (image_size - modules * scale) / 2;
+
+    let mut pixels = vec![255u8; (image_size * image_size) as usize];
+    for y in 0..qr.size() {
+        for x in 0..qr.size() {
+            if !qr.get_module(x, y) {
+                continue;
+            }
+            let left = offset + (x as u32 + QUIET_ZONE_MODULES) * scale;
+            let top = offset + (y as u32 + QUIET_ZONE_MODULES) * scale;
+            for row in top..top + scale {
+                let start = (row * image_size + left) as usize;
+                pixels[start..start + scale as usize].fill(0);
+            }
+        }
+    }
+
+    let mut png_data = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(
+            std::io::Cursor::new(&mut png_data),
+            image_size,
+            image_size,
+        );
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("编码二维码图片失败: {}", e))?;
+        writer
+            .write_image_data(&pixels)
+            .map_err(|e| format!("编码二维码图片失败: {}", e))?;
+    }
+    Ok(png_data)
+}
+
+/// 生成二维码，返回 PNG 的 base64 data URL；size 会被限制在 64–1024 像素
+pub fn generate_qr_code(text: &str, size: u32) -> Result<String, String> {
+    if text.is_empty() {
+        return Err("二维码内容不能为空".to_string());
+    }
+    let qr = QrCode::encode_text(text, QrCodeEcc::Medium)
+        .map_err(|_| "内容过长，无法生成二维码".to_string())?;
+    let png = render_png(&qr, size.clamp(MIN_QR_SIZE, MAX_QR_SIZE))?;
+    Ok(format!(
+        "data:image/png;base64,{}",
+        general_purpose::STANDARD.encode(png)
+    ))
+}
@@ -0,0 +1,145 @@
+// 搜索请求的"最新查询优先"守卫：同一来源发起新查询后，旧查询在检查点处放弃剩余工作，
+// 避免快速输入时多个过期搜索同时占用 CPU
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// 启动器应用搜索
+pub const APPS: &str = "apps";
+/// 文件历史搜索
+pub const HISTORY: &str = "history";
+/// 统一搜索中的应用来源
+pub const UNIFIED_APPS: &str = "unified_apps";
+/// Everything 不可用时的文件系统回退搜索
+pub const EVERYTHING_FALLBACK: &str = "everything_fallback";
+
+/// 旧查询被取代时返回的错误
+pub const SUPERSEDED_ERROR: &str = "查询已被更新的输入取代";
+
+/// 按来源记录最新一次查询的序号
+#[derive(Default)]
+pub struct LatestQueryGuard {
+    generations: Mutex<HashMap<&'static str, Arc<AtomicU64>>>,
+}
+
+impl LatestQueryGuard {
+    /// 开始一次查询，同一来源之前的查询随之失效
+    pub fn begin(&self, source: &'static str) -> QueryTicket {
+        let counter = {
+            let mut generations = self
+                .generations
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            generations.entry(source).or_default().clone()
+        };
+        let generation = counter.fetch_add(1, Ordering::SeqCst) + 1;
+        QueryTicket {
+            counter,
+            generation,
+        }
+    }
+}
+
+/// 一次查询的凭据，可跨线程传递
+#[derive(Clone)]
+pub struct QueryTicket {
+    counter: Arc<AtomicU64>,
+    generation: u64,
+}
+
+impl QueryTicket {
+    /// 期间没有同一来源的新查询
+    pub fn is_current(&self) -> bool {
+        self.counter.load(Ordering::SeqCst) == self.generation
+    }
+
+    /// 已被取代时返回 SUPERSEDED_ERROR，供检查点直接用 `?` 退出
+    pub fn ensure_current(&self) -> Result<(), String> {
+        if self.is_current() {
+            Ok(())
+        } else {
+            Err(SUPERSEDED_ERROR.to_string())
+        }
+    }
+}
+
+static SEARCH_GUARD: LazyLock<LatestQueryGuard> = LazyLock::new(LatestQueryGuard::default);
+
+/// 在全局守卫上开始一次查询
+pub fn begin(source: &'static str) -> QueryTicket {
+    SEARCH_GUARD.begin(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::thread;
+
+    #[test]
+    fn newer_query_supersedes_older_one() {
+        let guard = LatestQueryGuard::default();
+        let first = guard.begin(APPS);
+        assert!(first.is_current());
+        assert!(first.ensure_current().is_ok());
+
+        let second = guard.begin(APPS);
+        assert!(!first.is_current());
+        assert_eq!(first.ensure_current(), Err(SUPERSEDED_ERROR.to_string()));
+        assert!(second.is_current());
+        // 克隆的凭据与原凭据一同失效
+        let cloned = second.clone();
+        guard.begin(APPS);
+        assert!(!cloned.is_current());
+    }
+
+    #[test]
+    fn sources_are_independent() {
+        let guard = LatestQueryGuard::default();
+        let apps = guard.begin(APPS);
+        let history = guard.begin(HISTORY);
+        guard.begin(HISTORY);
+        assert!(apps.is_current());
+        assert!(!history.is_current());
+
+        guard.begin(EVERYTHING_FALLBACK);
+        assert!(apps.is_current());
+    }
+
+    #[test]
+    fn overlapping_queries_only_latest_finishes() {
+        let guard = Arc::new(LatestQueryGuard::default());
+        let (started_tx, started_rx) = mpsc::channel();
+        let (resume_tx, resume_rx) = mpsc::channel::<()>();
+
+        // 第一个查询在检查点前暂停，期间第二个查询开始
+        let first = guard.begin(UNIFIED_APPS);
+        let worker = thread::spawn(move || {
+            started_tx.send(()).unwrap();
+            resume_rx.recv().unwrap();
+            first.ensure_current()
+        });
+        started_rx.recv().unwrap();
+        let second = guard.begin(UNIFIED_APPS);
+        resume_tx.send(()).unwrap();
+
+        assert_eq!(worker.join().unwrap(), Err(SUPERSEDED_ERROR.to_string()));
+        assert!(second.ensure_current().is_ok());
+    }
+
+    #[test]
+    fn concurrent_begins_leave_exactly_one_current() {
+        let guard = Arc::new(LatestQueryGuard::default());
+        let tickets: Vec<QueryTicket> = (0..8)
+            .map(|_| {
+                let guard = Arc::clone(&guard);
+                thread::spawn(move || guard.begin(APPS))
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        assert_eq!(tickets.iter().filter(|t| t.is_current()).count(), 1);
+    }
+}
@@ -0,0 +1,83 @@
+// 系统最近使用的文档：读取 %APPDATA%\Microsoft\Windows\Recent 下的 .lnk 快捷方式并解析目标，
+// 与 ReFast 自己的文件历史互为补充
+
+use crate::file_history::FileHistoryItem;
+
+/// 未指定数量时返回的条目数
+pub const DEFAULT_RECENT_DOCUMENTS_LIMIT: usize = 50;
+/// 单次最多返回的条目数
+const MAX_RECENT_DOCUMENTS_LIMIT: usize = 500;
+
+/// 按快捷方式的修改时间（即最近一次打开的时间）倒序返回仍然存在的目标，按目标路径去重
+#[cfg(target_os = "windows")]
+pub fn get_recent_documents(limit: usize) -> Vec<FileHistoryItem> {
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+    use std::time::UNIX_EPOCH;
+
+    let limit = limit.clamp(1, MAX_RECENT_DOCUMENTS_LIMIT);
+    let Some(recent_dir) = std::env::var_os("APPDATA")
+        .map(|appdata| PathBuf::from(appdata).join(r"Microsoft\Windows\Recent"))
+    else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&recent_dir) else {
+        crate::log_warn!("RecentDocuments", "无法读取目录 {}", recent_dir.display());
+        return Vec::new();
+    };
+
+    let mut links: Vec<(u64, PathBuf)> = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .path()
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("lnk"))
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            let timestamp = modified.duration_since(UNIX_EPOCH).ok()?.as_secs();
+            Some((timestamp, entry.path()))
+        })
+        .collect();
+    links.sort_by_key(|(timestamp, _)| std::cmp::Reverse(*timestamp));
+
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+    for (timestamp, link) in links {
+        let Some(target) = crate::app_search::resolve_lnk_target(&link) else {
+            continue;
+        };
+        let Ok(metadata) = std::fs::metadata(&target) else {
+            continue;
+        };
+        let path = target.to_string_lossy().to_string();
+        if !seen.insert(path.to_lowercase()) {
+            continue;
+        }
+        let name = target
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+        items.push(FileHistoryItem {
+            path,
+            name,
+            last_used: timestamp,
+            use_count: 0,
+            is_folder: Some(metadata.is_dir()),
+            last_action: String::new(),
+            is_symlink: None,
+            target: None,
+            target_exists: None,
+        });
+        if items.len() >= limit {
+            break;
+        }
+    }
+    items
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn get_recent_documents(_limit: usize) -> Vec<FileHistoryItem> {
+    Vec::new()
+}
@@ -26,6 +26,15 @@ pub struct RecordedEvent {
     pub time_offset_ms: u64,
 }
 
+impl RecordedEvent {
+    /// 按 (x, y) 系数线性缩放坐标，无坐标的键盘事件保持不变
+    pub fn scaled(mut self, (fx, fy): (f64, f64)) -> Self {
+        self.x = self.x.map(|x| (x as f64 * fx).round() as i32);
+        self.y = self.y.map(|y| (y as f64 * fy).round() as i32);
+        self
+    }
+}
+
 pub struct RecordingState {
     pub start_instant: Option<Instant>,
     pub events: Vec<RecordedEvent>,
@@ -70,6 +79,64 @@ impl Default for RecordingState {
     }
 }
 
+/// 录制时的屏幕环境，回放时用于检测分辨率或缩放变化
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CaptureEnv {
+    /// 主显示器宽度（像素）
+    pub width: i32,
+    /// 主显示器高度（像素）
+    pub height: i32,
+    /// DPI 缩放比例，1.0 表示 100%
+    pub scale: f64,
+}
+
+impl CaptureEnv {
+    #[cfg(target_os = "windows")]
+    pub fn current() -> Option<Self> {
+        use windows_sys::Win32::Graphics::Gdi::{GetDC, GetDeviceCaps, ReleaseDC, LOGPIXELSX};
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN,
+        };
+
+        unsafe {
+            let width = GetSystemMetrics(SM_CXSCREEN);
+            let height = GetSystemMetrics(SM_CYSCREEN);
+            if width <= 0 || height <= 0 {
+                return None;
+            }
+
+            let hdc = GetDC(0);
+            let dpi = if hdc != 0 {
+                let dpi = GetDeviceCaps(hdc, LOGPIXELSX as i32);
+                ReleaseDC(0, hdc);
+                dpi
+            } else {
+                0
+            };
+            let scale = if dpi > 0 { dpi as f64 / 96.0 } else { 1.0 };
+
+            Some(Self {
+                width,
+                height,
+                scale,
+            })
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    pub fn current() -> Option<Self> {
+        None
+    }
+
+    /// 从录制分辨率映射到 target 分辨率的 x/y 缩放系数
+    pub fn scale_factors_to(&self, target: &CaptureEnv) -> (f64, f64) {
+        (
+            target.width as f64 / self.width.max(1) as f64,
+            target.height as f64 / self.height.max(1) as f64,
+        )
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RecordingMeta {
     pub file_path: String,
@@ -77,4 +144,151 @@ pub struct RecordingMeta {
     pub duration_ms: u64,
     pub event_count: usize,
     pub created_at: String,
+    /// 旧录制文件没有该字段
+    #[serde(default)]
+    pub capture_env: Option<CaptureEnv>,
+}
+
+/// CSV 导出的列，顺序固定；button/delta 用于无损还原鼠标按键和滚轮事件
+const CSV_HEADER: &str = "type,x,y,key,button,delta,time_offset_ms";
+
+fn button_name(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "Left",
+        MouseButton::Right => "Right",
+        MouseButton::Middle => "Middle",
+    }
+}
+
+/// 将事件导出为 CSV，每行一个事件，便于在表格中编辑时间和坐标
+pub fn events_to_csv(events: &[RecordedEvent]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for event in events {
+        let (kind, key, button, delta) = match &event.event_type {
+            EventType::MouseMove => ("MouseMove", None, None, None),
+            EventType::MouseDown { button } => ("MouseDown", None, Some(*button), None),
+            EventType::MouseUp { button } => ("MouseUp", None, Some(*button), None),
+            EventType::MouseWheel { delta } => ("MouseWheel", None, None, Some(*delta)),
+            EventType::KeyDown { vk_code } => ("KeyDown", Some(*vk_code), None, None),
+            EventType::KeyUp { vk_code } => ("KeyUp", Some(*vk_code), None, None),
+        };
+        let opt = |v: Option<String>| v.unwrap_or_default();
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            kind,
+            opt(event.x.map(|v| v.to_string())),
+            opt(event.y.map(|v| v.to_string())),
+            opt(key.map(|v| v.to_string())),
+            button.map(button_name).unwrap_or_default(),
+            opt(delta.map(|v| v.to_string())),
+            event.time_offset_ms
+        ));
+    }
+    out
+}
+
+fn parse_optional<T: std::str::FromStr>(
+    value: &str,
+    column: &str,
+    line: usize,
+) -> Result<Option<T>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("第 {} 行: {} 列的值无效: {}", line, column, value))
+}
+
+fn require<T>(value: Option<T>, column: &str, line: usize) -> Result<T, String> {
+    value.ok_or_else(|| format!("第 {} 行: 缺少 {} 列", line, column))
+}
+
+/// 从 CSV 还原事件列表，校验表头、列数、类型和时间顺序
+pub fn events_from_csv(content: &str) -> Result<Vec<RecordedEvent>, String> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty());
+
+    let (_, header) = lines.next().ok_or_else(|| "CSV 文件为空".to_string())?;
+    let header: Vec<String> = header
+        .trim_start_matches('\u{feff}')
+        .split(',')
+        .map(|c| c.trim().to_lowercase())
+        .collect();
+    if header.join(",") != CSV_HEADER {
+        return Err(format!("CSV 表头无效，应为: {}", CSV_HEADER));
+    }
+
+    let mut events = Vec::new();
+    let mut last_offset = 0u64;
+    for (line, row) in lines {
+        let cols: Vec<&str> = row.split(',').map(str::trim).collect();
+        if cols.len() != 7 {
+            return Err(format!("第 {} 行: 应有 7 列，实际 {} 列", line, cols.len()));
+        }
+
+        let x = parse_optional::<i32>(cols[1], "x", line)?;
+        let y = parse_optional::<i32>(cols[2], "y", line)?;
+        let key = parse_optional::<u32>(cols[3], "key", line)?;
+        let button = match cols[4].to_lowercase().as_str() {
+            "" => None,
+            "left" => Some(MouseButton::Left),
+            "right" => Some(MouseButton::Right),
+            "middle" => Some(MouseButton::Middle),
+            other => return Err(format!("第 {} 行: button 列的值无效: {}", line, other)),
+        };
+        let delta = parse_optional::<i32>(cols[5], "delta", line)?;
+        let time_offset_ms = require(
+            parse_optional::<u64>(cols[6], "time_offset_ms", line)?,
+            "time_offset_ms",
+            line,
+        )?;
+        if time_offset_ms < last_offset {
+            return Err(format!(
+                "第 {} 行: time_offset_ms 必须按时间顺序递增（{} < {}）",
+                line, time_offset_ms, last_offset
+            ));
+        }
+        last_offset = time_offset_ms;
+
+        let event_type = match cols[0] {
+            "MouseMove" => EventType::MouseMove,
+            "MouseDown" => EventType::MouseDown {
+                button: require(button, "button", line)?,
+            },
+            "MouseUp" => EventType::MouseUp {
+                button: require(button, "button", line)?,
+            },
+            "MouseWheel" => EventType::MouseWheel {
+                delta: require(delta, "delta", line)?,
+            },
+            "KeyDown" => EventType::KeyDown {
+                vk_code: require(key, "key", line)?,
+            },
+            "KeyUp" => EventType::KeyUp {
+                vk_code: require(key, "key", line)?,
+            },
+            other => return Err(format!("第 {} 行: 未知的事件类型: {}", line, other)),
+        };
+        if matches!(event_type, EventType::MouseMove) && (x.is_none() || y.is_none()) {
+            return Err(format!("第 {} 行: MouseMove 需要 x 和 y", line));
+        }
+
+        events.push(RecordedEvent {
+            event_type,
+            x,
+            y,
+            time_offset_ms,
+        });
+    }
+
+    if events.is_empty() {
+        return Err("CSV 中没有事件".to_string());
+    }
+    Ok(events)
 }
@@ -0,0 +1,257 @@
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 单个撤销记录允许保存的原始文件字节总数，超出时需要改用完整文件夹备份
+pub const MAX_JOURNAL_BYTES: u64 = 100 * 1024 * 1024;
+/// 最多保留的撤销记录数，创建新记录时删除更早的记录
+pub const MAX_KEPT_JOURNALS: usize = 20;
+
+/// 撤销记录中的一步操作，撤销时按相反顺序回放
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// 文件内容被改写，`blob` 为原始内容在 blobs 目录中的文件名
+    Content { path: String, blob: String },
+    /// 文件或文件夹被重命名
+    Rename { from: String, to: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JournalManifest {
+    pub id: String,
+    pub folder_path: String,
+    pub created_at: String,
+    pub entries: Vec<JournalEntry>,
+}
+
+/// 撤销结果
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoReport {
+    pub restored_files: usize,
+    pub restored_renames: usize,
+    pub errors: Vec<String>,
+}
+
+/// 文件替换的撤销记录：写入/重命名之前记录原始状态
+pub struct ReplaceJournal {
+    dir: PathBuf,
+    manifest: JournalManifest,
+    bytes_recorded: u64,
+}
+
+pub fn journals_root(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("replace_journals")
+}
+
+fn journal_dir(app_data_dir: &Path, id: &str) -> Result<PathBuf, String> {
+    // id 只允许由时间戳生成的字符，防止路径穿越
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err("无效的撤销记录 ID".to_string());
+    }
+    Ok(journals_root(app_data_dir).join(id))
+}
+
+/// 只保留最新的 `keep` 个撤销记录；ID 由时间戳生成，按名称排序即按创建时间排序
+fn prune_journals(app_data_dir: &Path, keep: usize) {
+    let Ok(entries) = fs::read_dir(journals_root(app_data_dir)) else {
+        return;
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    if dirs.len() <= keep {
+        return;
+    }
+    dirs.sort();
+    let excess = dirs.len() - keep;
+    for dir in &dirs[..excess] {
+        let _ = fs::remove_dir_all(dir);
+    }
+}
+
+impl ReplaceJournal {
+    pub fn create(app_data_dir: &Path, folder_path: &Path) -> Result<Self, String> {
+        prune_journals(app_data_dir, MAX_KEPT_JOURNALS.saturating_sub(1));
+
+        let base_id = Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+        let mut id = base_id.clone();
+        let mut counter = 1;
+        while journals_root(app_data_dir).join(&id).exists() {
+            id = format!("{}_{}", base_id, counter);
+            counter += 1;
+        }
+
+        let dir = journal_dir(app_data_dir, &id)?;
+        fs::create_dir_all(dir.join("blobs"))
+            .map_err(|e| format!("创建撤销记录目录失败: {}", e))?;
+
+        Ok(ReplaceJournal {
+            dir,
+            manifest: JournalManifest {
+                id,
+                folder_path: folder_path.to_string_lossy().to_string(),
+                created_at: Local::now().to_rfc3339(),
+                entries: Vec::new(),
+            },
+            bytes_recorded: 0,
+        })
+    }
+
+    /// 在改写文件内容前保存原始字节
+    pub fn record_content(&mut self, path: &Path, original: &[u8]) -> Result<(), String> {
+        if self.bytes_recorded + original.len() as u64 > MAX_JOURNAL_BYTES {
+            return Err("撤销记录超出大小上限，请启用完整文件夹备份".to_string());
+        }
+        let blob = format!("{}.bin", self.manifest.entries.len());
+        fs::write(self.dir.join("blobs").join(&blob), original)
+            .map_err(|e| format!("写入撤销记录失败: {}", e))?;
+        self.bytes_recorded += original.len() as u64;
+        self.manifest.entries.push(JournalEntry::Content {
+            path: path.to_string_lossy().to_string(),
+            blob,
+        });
+        Ok(())
+    }
+
+    /// 在重命名之后记录映射（重命名失败时不应调用）
+    pub fn record_rename(&mut self, from: &Path, to: &Path) {
+        self.manifest.entries.push(JournalEntry::Rename {
+            from: from.to_string_lossy().to_string(),
+            to: to.to_string_lossy().to_string(),
+        });
+    }
+
+    /// 保存清单；没有任何操作时删除记录并返回 None
+    pub fn finish(self) -> Result<Option<String>, String> {
+        if self.manifest.entries.is_empty() {
+            let _ = fs::remove_dir_all(&self.dir);
+            return Ok(None);
+        }
+        let json = serde_json::to_string_pretty(&self.manifest)
+            .map_err(|e| format!("序列化撤销记录失败: {}", e))?;
+        fs::write(self.dir.join("journal.json"), json)
+            .map_err(|e| format!("保存撤销记录失败: {}", e))?;
+        Ok(Some(self.manifest.id))
+    }
+}
+
+/// 按相反顺序回放撤销记录，恢复文件内容和名称；全部成功后删除记录
+pub fn undo(app_data_dir: &Path, journal_id: &str) -> Result<UndoReport, String> {
+    let dir = journal_dir(app_data_dir, journal_id)?;
+    let content = fs::read_to_string(dir.join("journal.json"))
+        .map_err(|e| format!("读取撤销记录失败: {}", e))?;
+    let manifest: JournalManifest =
+        serde_json::from_str(&content).map_err(|e| format!("解析撤销记录失败: {}", e))?;
+
+    let mut report = UndoReport {
+        restored_files: 0,
+        restored_renames: 0,
+        errors: Vec::new(),
+    };
+
+    for entry in manifest.entries.iter().rev() {
+        match entry {
+            JournalEntry::Content { path, blob } => {
+                let result = fs::read(dir.join("blobs").join(blob))
+                    .and_then(|original| fs::write(path, original));
+                match result {
+                    Ok(()) => report.restored_files += 1,
+                    Err(e) => report.errors.push(format!("恢复 {} 失败: {}", path, e)),
+                }
+            }
+            JournalEntry::Rename { from, to } => {
+                if Path::new(from).exists() {
+                    report
+                        .errors
+                        .push(format!("无法还原 {}：原路径 {} 已存在", to, from));
+                    continue;
+                }
+                match fs::rename(to, from) {
+                    Ok(()) => report.restored_renames += 1,
+                    Err(e) => report.errors.push(format!("还原 {} 失败: {}", to, e)),
+                }
+            }
+        }
+    }
+
+    if report.errors.is_empty() {
+        let _ = fs::remove_dir_all(&dir);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn undo_restores_contents_and_renames() {
+        let base = temp_dir("journal", "undo");
+        let app_data = base.join("app");
+        let folder = base.join("work");
+        fs::create_dir_all(&folder).unwrap();
+        let file = folder.join("a.txt");
+        fs::write(&file, "old text").unwrap();
+
+        let mut journal = ReplaceJournal::create(&app_data, &folder).unwrap();
+        journal.record_content(&file, b"old text").unwrap();
+        fs::write(&file, "new text").unwrap();
+        let renamed = folder.join("b.txt");
+        fs::rename(&file, &renamed).unwrap();
+        journal.record_rename(&file, &renamed);
+        let id = journal.finish().unwrap().unwrap();
+
+        let report = undo(&app_data, &id).unwrap();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!((report.restored_files, report.restored_renames), (1, 1));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "old text");
+        assert!(!renamed.exists());
+        // 成功撤销后记录被删除
+        assert!(undo(&app_data, &id).is_err());
+        fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn empty_journal_is_discarded() {
+        let base = temp_dir("journal", "empty");
+        let journal = ReplaceJournal::create(&base, &base).unwrap();
+        assert_eq!(journal.finish().unwrap(), None);
+        assert_eq!(fs::read_dir(journals_root(&base)).unwrap().count(), 0);
+        fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn rejects_path_traversal_ids() {
+        let base = temp_dir("journal", "ids");
+        assert!(undo(&base, "../secret").is_err());
+        assert!(undo(&base, "").is_err());
+        fs::remove_dir_all(&base).ok();
+    }
+
+    #[test]
+    fn keeps_only_the_newest_journals() {
+        let base = temp_dir("journal", "prune");
+        let root = journals_root(&base);
+        for i in 0..MAX_KEPT_JOURNALS + 5 {
+            fs::create_dir_all(root.join(format!("20240101_000000_{:03}", i))).unwrap();
+        }
+
+        let journal = ReplaceJournal::create(&base, &base).unwrap();
+        let mut ids: Vec<String> = fs::read_dir(&root)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        ids.sort();
+        assert_eq!(ids.len(), MAX_KEPT_JOURNALS);
+        assert_eq!(ids[0], "20240101_000000_006");
+        assert!(ids.contains(&journal.manifest.id));
+        fs::remove_dir_all(&base).ok();
+    }
+}
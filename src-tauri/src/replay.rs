@@ -1,4 +1,4 @@
-use crate::recording::{EventType, MouseButton, RecordedEvent};
+use crate::recording::{CaptureEnv, EventType, MouseButton, RecordedEvent};
 use serde_json;
 use std::fs;
 use std::path::Path;
@@ -8,6 +8,7 @@ pub struct ReplayState {
     pub current_events: Vec<RecordedEvent>,
     pub current_index: usize,
     pub speed_multiplier: f32,
+    pub capture_env: Option<CaptureEnv>,
 }
 
 impl ReplayState {
@@ -17,6 +18,7 @@ impl ReplayState {
             current_events: Vec::new(),
             current_index: 0,
             speed_multiplier: 1.0,
+            capture_env: None,
         }
     }
 
@@ -37,6 +39,7 @@ impl ReplayState {
             .collect::<Result<Vec<_>, _>>()
             .map_err(|e| format!("Failed to parse events: {}", e))?;
 
+        self.capture_env = serde_json::from_value(json["capture_env"].clone()).unwrap_or(None);
         self.current_index = 0;
         Ok(())
     }
@@ -0,0 +1,350 @@
+use crate::app_search::AppInfo;
+use crate::everything_search::EverythingResult;
+use crate::file_history::FileHistoryItem;
+use crate::memos::MemoItem;
+use crate::shortcuts::ShortcutItem;
+use crate::system_folders_search::windows::SystemFolderItem;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// 标题匹配加成的上限
+const MAX_TITLE_BONUS: f64 = 0.5;
+/// 使用次数加成的上限，达到 USE_COUNT_SATURATION 次后不再增加
+const MAX_USE_COUNT_BONUS: f64 = 0.3;
+const USE_COUNT_SATURATION: u64 = 50;
+/// 最近使用加成的上限，在 RECENCY_WINDOW_DAYS 天内线性递减到 0
+const MAX_RECENCY_BONUS: f64 = 0.2;
+const RECENCY_WINDOW_DAYS: f64 = 30.0;
+/// 原始分数的上限（最高来源权重 + 各项加成），用于把分数归一化到 0..=1
+const MAX_RAW_SCORE: f64 = 1.0 + MAX_TITLE_BONUS + MAX_USE_COUNT_BONUS + MAX_RECENCY_BONUS;
+
+/// 统一搜索结果，`kind` 字段区分来源，`item` 为来源的原始结构
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "kind", content = "item", rename_all = "snake_case")]
+pub enum UnifiedItem {
+    App(AppInfo),
+    Shortcut(ShortcutItem),
+    FileHistory(FileHistoryItem),
+    Everything(EverythingResult),
+    SystemFolder(SystemFolderItem),
+    Memo(MemoItem),
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct UnifiedSearchResult {
+    #[serde(flatten)]
+    pub item: UnifiedItem,
+    /// 归一化分数，0..=1
+    pub score: f64,
+}
+
+/// 排序使用的使用记录：open_history 中的最近打开时间（键为小写路径）
+#[derive(Debug, Clone, Default)]
+pub struct UsageSignals {
+    last_opened: HashMap<String, u64>,
+    /// 当前 Unix 时间（秒）
+    now: u64,
+}
+
+impl UsageSignals {
+    pub fn new(open_history: HashMap<String, u64>, now: u64) -> Self {
+        Self {
+            last_opened: open_history
+                .into_iter()
+                .map(|(key, opened)| (key.to_lowercase(), opened))
+                .collect(),
+            now,
+        }
+    }
+
+    /// 使用频率和最近使用时间带来的加成
+    fn boost(&self, item: &UnifiedItem) -> f64 {
+        let (use_count, history_last_used) = match item {
+            UnifiedItem::FileHistory(file) => (file.use_count, file.last_used),
+            _ => (0, 0),
+        };
+        let last_used = self
+            .last_opened
+            .get(&item.dedup_key())
+            .copied()
+            .unwrap_or(0)
+            .max(history_last_used);
+
+        let use_count_bonus = MAX_USE_COUNT_BONUS
+            * use_count.min(USE_COUNT_SATURATION) as f64
+            / USE_COUNT_SATURATION as f64;
+        let recency_bonus = if last_used == 0 {
+            0.0
+        } else {
+            let days = self.now.saturating_sub(last_used) as f64 / 86_400.0;
+            MAX_RECENCY_BONUS * (1.0 - days / RECENCY_WINDOW_DAYS).max(0.0)
+        };
+        use_count_bonus + recency_bonus
+    }
+}
+
+impl UnifiedItem {
+    /// 各来源的基础权重，应用优先，Everything 结果最多但相关性最低
+    fn source_weight(&self) -> f64 {
+        match self {
+            UnifiedItem::App(_) => 1.0,
+            UnifiedItem::Shortcut(_) => 0.95,
+            UnifiedItem::SystemFolder(_) => 0.9,
+            UnifiedItem::FileHistory(_) => 0.8,
+            UnifiedItem::Memo(_) => 0.6,
+            UnifiedItem::Everything(_) => 0.5,
+        }
+    }
+
+    fn title(&self) -> &str {
+        match self {
+            UnifiedItem::App(app) => &app.name,
+            UnifiedItem::Shortcut(shortcut) => &shortcut.name,
+            UnifiedItem::FileHistory(item) => &item.name,
+            UnifiedItem::Everything(item) => &item.name,
+            UnifiedItem::SystemFolder(item) => &item.name,
+            UnifiedItem::Memo(memo) => &memo.title,
+        }
+    }
+
+    /// 用于去重的键（路径或 memo id）
+    fn dedup_key(&self) -> String {
+        match self {
+            UnifiedItem::App(app) => app.path.to_lowercase(),
+            UnifiedItem::Shortcut(shortcut) => shortcut.path.to_lowercase(),
+            UnifiedItem::FileHistory(item) => item.path.to_lowercase(),
+            UnifiedItem::Everything(item) => item.path.to_lowercase(),
+            UnifiedItem::SystemFolder(item) => item.path.to_lowercase(),
+            UnifiedItem::Memo(memo) => format!("memo:{}", memo.id),
+        }
+    }
+}
+
+/// 计算统一分数：来源权重 × 来源内排名衰减，加上标题匹配和使用记录加成，归一化到 0..=1
+pub fn score_item(
+    item: &UnifiedItem,
+    rank: usize,
+    source_len: usize,
+    query: &str,
+    usage: &UsageSignals,
+) -> f64 {
+    let rank_factor = 1.0 - rank as f64 / (source_len as f64 + 1.0);
+    let title = item.title().to_lowercase();
+    let query = query.trim().to_lowercase();
+    let title_bonus = if query.is_empty() {
+        0.0
+    } else if title == query {
+        MAX_TITLE_BONUS
+    } else if title.starts_with(&query) {
+        MAX_TITLE_BONUS / 2.0
+    } else {
+        0.0
+    };
+
+    let raw = item.source_weight() * rank_factor + title_bonus + usage.boost(item);
+    (raw / MAX_RAW_SCORE).clamp(0.0, 1.0)
+}
+
+/// 按名称或路径筛选快捷方式（不区分大小写），名称匹配的排在前面
+pub fn filter_shortcuts(query: &str, shortcuts: Vec<ShortcutItem>) -> Vec<ShortcutItem> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let (mut by_name, by_path): (Vec<_>, Vec<_>) = shortcuts
+        .into_iter()
+        .filter(|shortcut| {
+            shortcut.name.to_lowercase().contains(&query)
+                || shortcut.path.to_lowercase().contains(&query)
+        })
+        .partition(|shortcut| shortcut.name.to_lowercase().contains(&query));
+    by_name.extend(by_path);
+    by_name
+}
+
+/// 合并各来源的结果：计算分数、按路径去重（保留高分项）、排序并截断
+pub fn merge_results(
+    sources: Vec<Vec<UnifiedItem>>,
+    query: &str,
+    limit: usize,
+    usage: &UsageSignals,
+) -> Vec<UnifiedSearchResult> {
+    let mut merged: Vec<UnifiedSearchResult> = Vec::new();
+    for items in sources {
+        let len = items.len();
+        for (rank, item) in items.into_iter().enumerate() {
+            let score = score_item(&item, rank, len, query, usage);
+            merged.push(UnifiedSearchResult { item, score });
+        }
+    }
+
+    merged.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut seen = std::collections::HashSet::new();
+    merged.retain(|r| seen.insert(r.item.dedup_key()));
+    merged.truncate(limit);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str, path: &str) -> UnifiedItem {
+        UnifiedItem::App(AppInfo {
+            name: name.to_string(),
+            path: path.to_string(),
+            is_url: false,
+            icon: None,
+            description: None,
+            name_pinyin: None,
+            name_pinyin_initials: None,
+        })
+    }
+
+    fn shortcut(name: &str, path: &str) -> UnifiedItem {
+        UnifiedItem::Shortcut(ShortcutItem {
+            id: name.to_string(),
+            name: name.to_string(),
+            path: path.to_string(),
+            icon: None,
+            created_at: 0,
+            updated_at: 0,
+        })
+    }
+
+    fn history(name: &str, path: &str, use_count: u64, last_used: u64) -> UnifiedItem {
+        UnifiedItem::FileHistory(FileHistoryItem {
+            path: path.to_string(),
+            name: name.to_string(),
+            last_used,
+            use_count,
+            is_folder: Some(false),
+            last_action: String::new(),
+            is_symlink: None,
+            target: None,
+            target_exists: None,
+        })
+    }
+
+    fn everything(name: &str, path: &str) -> UnifiedItem {
+        UnifiedItem::Everything(EverythingResult {
+            path: path.to_string(),
+            name: name.to_string(),
+            size: None,
+            date_modified: None,
+            is_folder: Some(false),
+            name_highlights: Vec::new(),
+            path_highlights: Vec::new(),
+        })
+    }
+
+    fn paths(results: &[UnifiedSearchResult]) -> Vec<String> {
+        results.iter().map(|r| r.item.dedup_key()).collect()
+    }
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn source_weight_orders_sources() {
+        let usage = UsageSignals::new(HashMap::new(), NOW);
+        let results = merge_results(
+            vec![
+                vec![everything("e", "c:\\e.txt")],
+                vec![history("h", "c:\\h.txt", 0, 0)],
+                vec![shortcut("s", "c:\\s.lnk")],
+                vec![app("a", "c:\\a.exe")],
+            ],
+            "zzz",
+            10,
+            &usage,
+        );
+        assert_eq!(
+            paths(&results),
+            vec!["c:\\a.exe", "c:\\s.lnk", "c:\\h.txt", "c:\\e.txt"]
+        );
+        assert!(results.iter().all(|r| (0.0..=1.0).contains(&r.score)));
+    }
+
+    #[test]
+    fn rank_within_source_decays_score() {
+        let usage = UsageSignals::default();
+        let item = app("a", "c:\\a.exe");
+        assert!(score_item(&item, 0, 3, "", &usage) > score_item(&item, 2, 3, "", &usage));
+    }
+
+    #[test]
+    fn title_match_bonus() {
+        let usage = UsageSignals::new(HashMap::new(), NOW);
+        let item = everything("Report.docx", "c:\\report.docx");
+        let exact = score_item(&item, 0, 1, "report.DOCX", &usage);
+        let prefix = score_item(&item, 0, 1, "rep", &usage);
+        let none = score_item(&item, 0, 1, "docx", &usage);
+        assert!(exact > prefix && prefix > none);
+
+        // 标题完全匹配的 Everything 结果排在不匹配的文件历史之前
+        let results = merge_results(
+            vec![
+                vec![history("notes.txt", "c:\\notes.txt", 0, 0)],
+                vec![everything("report", "c:\\report")],
+            ],
+            "report",
+            10,
+            &usage,
+        );
+        assert_eq!(results[0].item.dedup_key(), "c:\\report");
+    }
+
+    #[test]
+    fn usage_and_recency_boost() {
+        let usage = UsageSignals::new(
+            HashMap::from([("C:\\Recent.txt".to_string(), NOW - 86_400)]),
+            NOW,
+        );
+        let recent = everything("recent.txt", "c:\\recent.txt");
+        let stale = everything("stale.txt", "c:\\stale.txt");
+        assert!(score_item(&recent, 0, 1, "", &usage) > score_item(&stale, 0, 1, "", &usage));
+
+        let frequent = history("a", "c:\\a.txt", 50, 0);
+        let rare = history("b", "c:\\b.txt", 1, 0);
+        assert!(score_item(&frequent, 0, 1, "", &usage) > score_item(&rare, 0, 1, "", &usage));
+
+        // 超出 RECENCY_WINDOW_DAYS 后不再有加成
+        let old = history("c", "c:\\c.txt", 0, NOW - 60 * 86_400);
+        let never = history("d", "c:\\d.txt", 0, 0);
+        assert_eq!(
+            score_item(&old, 0, 1, "", &usage),
+            score_item(&never, 0, 1, "", &usage)
+        );
+    }
+
+    #[test]
+    fn duplicates_keep_higher_score() {
+        let usage = UsageSignals::new(HashMap::new(), NOW);
+        let results = merge_results(
+            vec![
+                vec![everything("tool.exe", "C:\\Apps\\Tool.exe")],
+                vec![app("Tool", "c:\\apps\\tool.exe")],
+            ],
+            "",
+            10,
+            &usage,
+        );
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].item, UnifiedItem::App(_)));
+    }
+
+    #[test]
+    fn limit_truncates_after_sorting() {
+        let usage = UsageSignals::new(HashMap::new(), NOW);
+        let files: Vec<UnifiedItem> = (0..5)
+            .map(|i| everything(&format!("f{}", i), &format!("c:\\f{}", i)))
+            .collect();
+        let results = merge_results(vec![files, vec![app("a", "c:\\a.exe")]], "", 3, &usage);
+        assert_eq!(paths(&results), vec!["c:\\a.exe", "c:\\f0", "c:\\f1"]);
+    }
+}
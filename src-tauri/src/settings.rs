@@ -4,12 +4,19 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Settings {
     pub ollama: OllamaSettings,
     #[serde(default)]
     pub startup_enabled: bool,
+    /// 开机启动后延迟多少秒再加载，减轻登录时的负载
+    #[serde(default)]
+    pub startup_delay_seconds: Option<u64>,
+    /// 开机启动时不显示启动器，只驻留托盘
+    #[serde(default)]
+    pub startup_minimized: bool,
     #[serde(default)]
     pub hotkey: Option<HotkeyConfig>,
     #[serde(default)]
@@ -22,6 +29,262 @@ pub struct Settings {
     pub close_on_blur: bool,
     #[serde(default = "default_result_style")]
     pub result_style: String,
+    #[serde(default)]
+    pub everything_fallback: EverythingFallbackSettings,
+    /// 数据库备份默认以 gzip 压缩（.db.gz）
+    #[serde(default)]
+    pub compress_backups: bool,
+    /// 删除录制、备份等文件时移到回收站，关闭后永久删除
+    #[serde(default = "default_delete_to_trash")]
+    pub delete_to_trash: bool,
+    /// Everything 已安装但未运行时，启动器检查 Everything 状态时自动启动一次
+    #[serde(default)]
+    pub auto_start_everything: bool,
+    /// 已禁用的插件 ID，未列出的插件均为启用
+    #[serde(default)]
+    pub disabled_plugins: Vec<String>,
+    /// 界面主题："light" | "dark" | "system"
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    /// 启动器窗口不透明度（0.3–1.0）
+    #[serde(default = "default_launcher_opacity")]
+    pub launcher_opacity: f64,
+    /// 全局快捷键开关，关闭时暂停所有快捷键（免打扰）
+    #[serde(default = "default_hotkeys_enabled")]
+    pub hotkeys_enabled: bool,
+    /// 日志级别："error" | "warn" | "info" | "debug"
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    #[serde(default)]
+    pub translation: TranslationSettings,
+    /// 单位换算使用的汇率（1 美元可兑换的数量，如 {"CNY": 7.2}），覆盖内置汇率
+    #[serde(default)]
+    pub currency_rates: HashMap<String, f64>,
+    #[serde(default)]
+    pub ocr: OcrSettings,
+    /// "在终端中打开" 使用的终端，为空时自动选择。
+    /// Windows: "wt" | "powershell" | "pwsh" | "cmd"；macOS: 终端应用名，如 "iTerm"；Linux: 终端程序，如 "konsole"
+    #[serde(default)]
+    pub terminal: String,
+    /// 每次返回的应用搜索结果数
+    #[serde(default = "default_max_app_results")]
+    pub max_app_results: usize,
+    /// Everything 搜索未指定 maxResults 时的结果数
+    #[serde(default = "default_max_everything_results")]
+    pub max_everything_results: usize,
+    /// 文件历史搜索返回的最多条数
+    #[serde(default = "default_max_history_results")]
+    pub max_history_results: usize,
+    /// 文本预览最多读取的字节数
+    #[serde(default = "default_preview_max_bytes")]
+    pub preview_max_bytes: u64,
+    /// 扫描应用时额外忽略的规则（子串或通配符，匹配文件名或路径），
+    /// 以 ! 开头表示移除同名的内置规则，见 app_search::DEFAULT_APP_SCAN_IGNORE
+    #[serde(default)]
+    pub app_scan_ignore: Vec<String>,
+    /// 允许联网获取网址快捷方式的网站图标，关闭后不发出任何请求
+    #[serde(default = "default_fetch_favicons")]
+    pub fetch_favicons: bool,
+}
+
+fn default_fetch_favicons() -> bool {
+    true
+}
+
+/// 各来源结果数的上下限，加载设置时超出范围的值会被限制在其中
+pub const APP_RESULTS_RANGE: (usize, usize) = (1, 500);
+pub const EVERYTHING_RESULTS_RANGE: (usize, usize) = (1, 100_000);
+pub const HISTORY_RESULTS_RANGE: (usize, usize) = (1, 10_000);
+pub const PREVIEW_BYTES_RANGE: (u64, u64) = (4 * 1024, 4 * 1024 * 1024);
+/// 开机延迟启动的最长等待时间（秒）
+pub const MAX_STARTUP_DELAY_SECONDS: u64 = 600;
+
+fn default_max_app_results() -> usize {
+    crate::app_search::DEFAULT_APP_SEARCH_LIMIT
+}
+
+pub fn default_max_everything_results() -> usize {
+    50
+}
+
+fn default_max_history_results() -> usize {
+    500
+}
+
+pub fn default_preview_max_bytes() -> u64 {
+    32 * 1024
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_hotkeys_enabled() -> bool {
+    true
+}
+
+pub const THEMES: &[&str] = &["light", "dark", "system"];
+
+pub const MIN_LAUNCHER_OPACITY: f64 = 0.3;
+
+fn default_launcher_opacity() -> f64 {
+    1.0
+}
+
+/// 将不透明度限制在 0.3–1.0，非法值（NaN）按完全不透明处理
+pub fn clamp_launcher_opacity(opacity: f64) -> f64 {
+    if opacity.is_nan() {
+        1.0
+    } else {
+        opacity.clamp(MIN_LAUNCHER_OPACITY, 1.0)
+    }
+}
+
+fn default_theme() -> String {
+    "system".to_string()
+}
+
+impl Settings {
+    /// 把结果数、预览大小和开机延迟限制在允许范围内（手动编辑或旧版本写入的值可能越界）
+    pub fn clamp_limits(&mut self) {
+        self.startup_delay_seconds = self
+            .startup_delay_seconds
+            .map(|seconds| seconds.min(MAX_STARTUP_DELAY_SECONDS));
+        self.max_app_results = self
+            .max_app_results
+            .clamp(APP_RESULTS_RANGE.0, APP_RESULTS_RANGE.1);
+        self.max_everything_results = self
+            .max_everything_results
+            .clamp(EVERYTHING_RESULTS_RANGE.0, EVERYTHING_RESULTS_RANGE.1);
+        self.max_history_results = self
+            .max_history_results
+            .clamp(HISTORY_RESULTS_RANGE.0, HISTORY_RESULTS_RANGE.1);
+        self.preview_max_bytes = self
+            .preview_max_bytes
+            .clamp(PREVIEW_BYTES_RANGE.0, PREVIEW_BYTES_RANGE.1);
+    }
+
+    pub fn is_plugin_enabled(&self, plugin_id: &str) -> bool {
+        !self.disabled_plugins.iter().any(|id| id == plugin_id)
+    }
+
+    /// 更新插件的启用状态，disabled_plugins 中不会出现重复 ID
+    pub fn set_plugin_enabled(&mut self, plugin_id: &str, enabled: bool) {
+        self.disabled_plugins.retain(|id| id != plugin_id);
+        if !enabled {
+            self.disabled_plugins.push(plugin_id.to_string());
+        }
+    }
+
+    /// 保留由专用命令维护的字段（快捷键开关、插件启用状态、启动器不透明度、翻译 API Key），
+    /// 避免设置窗口用打开时加载的旧对象覆盖其他窗口做的修改
+    pub fn keep_managed_fields(&mut self, stored: &Settings) {
+        self.hotkeys_enabled = stored.hotkeys_enabled;
+        self.disabled_plugins = stored.disabled_plugins.clone();
+        self.launcher_opacity = stored.launcher_opacity;
+        self.translation.api_key = stored.translation.api_key.clone();
+    }
+
+    /// 发送给前端的设置：清空翻译 API Key，密钥只通过 set_translation_api_key 写入
+    pub fn without_secrets(mut self) -> Self {
+        self.translation.api_key.clear();
+        self
+    }
+
+    /// 需要注册的插件快捷键（跳过已禁用的插件）
+    pub fn enabled_plugin_hotkeys(&self) -> HashMap<String, HotkeyConfig> {
+        self.plugin_hotkeys
+            .iter()
+            .filter(|(id, _)| self.is_plugin_enabled(id))
+            .map(|(id, config)| (id.clone(), config.clone()))
+            .collect()
+    }
+}
+
+const STARTUP_DELAY_ARG: &str = "--delayed=";
+const STARTUP_MINIMIZED_ARG: &str = "--minimized";
+
+/// 开机启动参数：写入 Run 键的命令行，启动时由 main 解析
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StartupLaunchOptions {
+    pub delay_seconds: u64,
+    pub minimized: bool,
+}
+
+impl StartupLaunchOptions {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            delay_seconds: settings
+                .startup_delay_seconds
+                .unwrap_or(0)
+                .min(MAX_STARTUP_DELAY_SECONDS),
+            minimized: settings.startup_minimized,
+        }
+    }
+
+    /// 解析命令行参数，忽略其他参数
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Self {
+        let mut options = Self::default();
+        for arg in args {
+            if arg == STARTUP_MINIMIZED_ARG {
+                options.minimized = true;
+            } else if let Some(seconds) = arg.strip_prefix(STARTUP_DELAY_ARG) {
+                options.delay_seconds = seconds
+                    .parse::<u64>()
+                    .unwrap_or(0)
+                    .min(MAX_STARTUP_DELAY_SECONDS);
+            }
+        }
+        options
+    }
+
+    /// 启动完成后是否显示启动器：以 --minimized 开机启动时只驻留托盘
+    pub fn shows_launcher_on_startup(self) -> bool {
+        !self.minimized
+    }
+
+    /// 附加在 exe 路径后的参数，未启用任何选项时为空
+    pub fn to_args(self) -> String {
+        let mut args = Vec::new();
+        if self.delay_seconds > 0 {
+            args.push(format!("{}{}", STARTUP_DELAY_ARG, self.delay_seconds));
+        }
+        if self.minimized {
+            args.push(STARTUP_MINIMIZED_ARG.to_string());
+        }
+        args.join(" ")
+    }
+}
+
+/// Everything 不可用时的文件系统回退搜索
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EverythingFallbackSettings {
+    #[serde(default = "default_allow_fallback")]
+    pub allow_fallback: bool,
+    /// 搜索的根目录，为空时使用桌面、文档和下载文件夹
+    #[serde(default)]
+    pub roots: Vec<String>,
+    #[serde(default = "default_fallback_max_depth")]
+    pub max_depth: usize,
+}
+
+/// 遍历文件系统开销较大，默认关闭，由用户在设置中开启
+fn default_allow_fallback() -> bool {
+    false
+}
+
+fn default_fallback_max_depth() -> usize {
+    6
+}
+
+impl Default for EverythingFallbackSettings {
+    fn default() -> Self {
+        Self {
+            allow_fallback: default_allow_fallback(),
+            roots: Vec::new(),
+            max_depth: default_fallback_max_depth(),
+        }
+    }
 }
 
 fn default_result_style() -> String {
@@ -32,17 +295,42 @@ fn default_close_on_blur() -> bool {
     true
 }
 
+fn default_delete_to_trash() -> bool {
+    true
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
             ollama: OllamaSettings::default(),
             startup_enabled: false,
+            startup_delay_seconds: None,
+            startup_minimized: false,
             hotkey: None,
             app_center_hotkey: None,
             plugin_hotkeys: HashMap::new(),
             app_hotkeys: HashMap::new(),
             close_on_blur: default_close_on_blur(),
             result_style: default_result_style(),
+            everything_fallback: EverythingFallbackSettings::default(),
+            compress_backups: false,
+            delete_to_trash: default_delete_to_trash(),
+            auto_start_everything: false,
+            disabled_plugins: Vec::new(),
+            theme: default_theme(),
+            launcher_opacity: default_launcher_opacity(),
+            hotkeys_enabled: default_hotkeys_enabled(),
+            log_level: default_log_level(),
+            translation: TranslationSettings::default(),
+            currency_rates: HashMap::new(),
+            ocr: OcrSettings::default(),
+            terminal: String::new(),
+            max_app_results: default_max_app_results(),
+            max_everything_results: default_max_everything_results(),
+            max_history_results: default_max_history_results(),
+            preview_max_bytes: default_preview_max_bytes(),
+            app_scan_ignore: Vec::new(),
+            fetch_favicons: default_fetch_favicons(),
         }
     }
 }
@@ -53,6 +341,24 @@ pub struct HotkeyConfig {
     pub key: String,
 }
 
+impl HotkeyConfig {
+    /// 显示用的快捷键文本，如 "Alt+Space"；双击修饰键显示为 "Ctrl+Ctrl"
+    pub fn label(&self) -> String {
+        let is_double = self.modifiers.len() == 2 && self.modifiers[0] == self.modifiers[1];
+        let mut parts = self.modifiers.clone();
+        let key_is_modifier = is_double && self.key == self.modifiers[0];
+        if !self.key.is_empty() && !key_is_modifier {
+            parts.push(self.key.clone());
+        }
+        parts.join("+")
+    }
+}
+
+/// 启动器快捷键的显示文本，未配置时为默认的 Alt+Space
+pub fn launcher_hotkey_label(hotkey: Option<&HotkeyConfig>) -> String {
+    hotkey.map_or_else(|| "Alt+Space".to_string(), HotkeyConfig::label)
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OllamaSettings {
     pub model: String,
@@ -68,6 +374,58 @@ impl Default for OllamaSettings {
     }
 }
 
+/// 翻译服务（LibreTranslate 兼容接口，可指向本地自建服务），endpoint 为空表示未配置
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TranslationSettings {
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default = "default_translation_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+fn default_translation_timeout_seconds() -> u64 {
+    15
+}
+
+impl Default for TranslationSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            api_key: String::new(),
+            timeout_seconds: default_translation_timeout_seconds(),
+        }
+    }
+}
+
+/// 文字识别后端："auto"（优先外部命令，其次系统 OCR）、"windows"（Windows.Media.Ocr）或 "command"
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OcrSettings {
+    #[serde(default = "default_ocr_backend")]
+    pub backend: String,
+    /// 外部 OCR 程序，如 "tesseract"；为空表示未配置
+    #[serde(default)]
+    pub command: String,
+    /// 外部程序参数，{image} 替换为图片路径，{lang} 替换为语言；为空时只传图片路径
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+fn default_ocr_backend() -> String {
+    "auto".to_string()
+}
+
+impl Default for OcrSettings {
+    fn default() -> Self {
+        Self {
+            backend: default_ocr_backend(),
+            command: String::new(),
+            args: Vec::new(),
+        }
+    }
+}
+
 pub fn get_settings_file_path(app_data_dir: &Path) -> PathBuf {
     app_data_dir.join("settings.json")
 }
@@ -85,17 +443,46 @@ pub fn load_settings(app_data_dir: &Path) -> Result<Settings, String> {
         .optional()
         .map_err(|e| format!("Failed to load settings from database: {}", e))?;
 
-    if let Some(json) = value {
-        serde_json::from_str(&json)
-            .map_err(|e| format!("Failed to parse settings from database: {}", e))
-    } else {
-        Ok(Settings::default())
+    let mut settings: Settings = match value {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse settings from database: {}", e))?,
+        None => Settings::default(),
+    };
+    settings.clamp_limits();
+    Ok(settings)
+}
+
+/// 最近一次读取的设置，按数据目录区分
+static SETTINGS_CACHE: LazyLock<Mutex<Option<(PathBuf, Settings)>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// 搜索等每次输入都会调用的路径使用，命中缓存时不访问数据库
+pub fn load_settings_cached(app_data_dir: &Path) -> Result<Settings, String> {
+    let mut cache = SETTINGS_CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((dir, settings)) = cache.as_ref() {
+        if dir == app_data_dir {
+            return Ok(settings.clone());
+        }
     }
+    let settings = load_settings(app_data_dir)?;
+    *cache = Some((app_data_dir.to_path_buf(), settings.clone()));
+    Ok(settings)
+}
+
+/// 设置被保存或数据库被替换后调用，下次 load_settings_cached 重新读取
+pub fn invalidate_settings_cache() {
+    *SETTINGS_CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
 }
 
 pub fn save_settings(app_data_dir: &Path, settings: &Settings) -> Result<(), String> {
     let conn = db::get_connection(app_data_dir)?;
-    save_settings_with_conn(&conn, settings)
+    let result = save_settings_with_conn(&conn, settings);
+    invalidate_settings_cache();
+    result
 }
 
 fn save_settings_with_conn(conn: &rusqlite::Connection, settings: &Settings) -> Result<(), String> {
@@ -136,3 +523,202 @@ fn maybe_migrate_from_json(
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn hotkey(key: &str) -> HotkeyConfig {
+        HotkeyConfig {
+            modifiers: vec!["Alt".to_string()],
+            key: key.to_string(),
+        }
+    }
+
+    #[test]
+    fn startup_launch_options_round_trip_through_args() {
+        for options in [
+            StartupLaunchOptions::default(),
+            StartupLaunchOptions {
+                delay_seconds: 30,
+                minimized: false,
+            },
+            StartupLaunchOptions {
+                delay_seconds: 0,
+                minimized: true,
+            },
+            StartupLaunchOptions {
+                delay_seconds: 15,
+                minimized: true,
+            },
+        ] {
+            let args = options.to_args();
+            let parsed =
+                StartupLaunchOptions::from_args(args.split_whitespace().map(str::to_string));
+            assert_eq!(parsed, options, "{}", args);
+        }
+        assert_eq!(StartupLaunchOptions::default().to_args(), "");
+    }
+
+    #[test]
+    fn startup_launch_options_ignore_unrelated_and_invalid_args() {
+        let args = ["C:\\a.txt", "--delayed=abc", "--minimized"].map(str::to_string);
+        assert_eq!(
+            StartupLaunchOptions::from_args(args),
+            StartupLaunchOptions {
+                delay_seconds: 0,
+                minimized: true,
+            }
+        );
+        let args = ["--delayed=999999".to_string()];
+        assert_eq!(
+            StartupLaunchOptions::from_args(args).delay_seconds,
+            MAX_STARTUP_DELAY_SECONDS
+        );
+    }
+
+    #[test]
+    fn minimized_startup_skips_showing_launcher() {
+        let args = ["--delayed=10", "--minimized"].map(str::to_string);
+        assert!(!StartupLaunchOptions::from_args(args).shows_launcher_on_startup());
+        let args = ["--delayed=10".to_string()];
+        assert!(StartupLaunchOptions::from_args(args).shows_launcher_on_startup());
+        assert!(StartupLaunchOptions::from_args(Vec::new()).shows_launcher_on_startup());
+    }
+
+    #[test]
+    fn clamp_limits_caps_startup_delay() {
+        let mut settings = Settings {
+            startup_delay_seconds: Some(u64::MAX),
+            ..Settings::default()
+        };
+        settings.clamp_limits();
+        assert_eq!(
+            settings.startup_delay_seconds,
+            Some(MAX_STARTUP_DELAY_SECONDS)
+        );
+
+        settings.startup_delay_seconds = None;
+        settings.clamp_limits();
+        assert_eq!(settings.startup_delay_seconds, None);
+    }
+
+    #[test]
+    fn clamp_limits_keeps_results_in_range() {
+        let mut settings = Settings {
+            max_app_results: 0,
+            max_everything_results: usize::MAX,
+            max_history_results: 0,
+            preview_max_bytes: 1,
+            ..Settings::default()
+        };
+        settings.clamp_limits();
+        assert_eq!(settings.max_app_results, APP_RESULTS_RANGE.0);
+        assert_eq!(settings.max_everything_results, EVERYTHING_RESULTS_RANGE.1);
+        assert_eq!(settings.max_history_results, HISTORY_RESULTS_RANGE.0);
+        assert_eq!(settings.preview_max_bytes, PREVIEW_BYTES_RANGE.0);
+
+        let mut defaults = Settings::default();
+        defaults.clamp_limits();
+        assert_eq!(
+            defaults.max_everything_results,
+            default_max_everything_results()
+        );
+        assert_eq!(defaults.max_history_results, default_max_history_results());
+    }
+
+    #[test]
+    fn load_settings_clamps_stored_limits() {
+        let dir = temp_dir("settings", "limits");
+        let stored = Settings {
+            max_history_results: 50_000,
+            max_everything_results: 0,
+            ..Settings::default()
+        };
+        save_settings(&dir, &stored).unwrap();
+
+        let loaded = load_settings(&dir).unwrap();
+        assert_eq!(loaded.max_history_results, HISTORY_RESULTS_RANGE.1);
+        assert_eq!(loaded.max_everything_results, EVERYTHING_RESULTS_RANGE.0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn plugins_are_enabled_unless_disabled() {
+        let mut settings = Settings::default();
+        assert!(settings.is_plugin_enabled("json_formatter"));
+
+        settings.set_plugin_enabled("json_formatter", false);
+        settings.set_plugin_enabled("json_formatter", false);
+        assert!(!settings.is_plugin_enabled("json_formatter"));
+        assert_eq!(settings.disabled_plugins, vec!["json_formatter"]);
+
+        settings.set_plugin_enabled("json_formatter", true);
+        assert!(settings.is_plugin_enabled("json_formatter"));
+        assert!(settings.disabled_plugins.is_empty());
+    }
+
+    #[test]
+    fn disabled_plugin_hotkeys_are_skipped() {
+        let mut settings = Settings::default();
+        settings
+            .plugin_hotkeys
+            .insert("memo_center".to_string(), hotkey("M"));
+        settings
+            .plugin_hotkeys
+            .insert("json_formatter".to_string(), hotkey("J"));
+        settings.set_plugin_enabled("memo_center", false);
+
+        let hotkeys = settings.enabled_plugin_hotkeys();
+        assert_eq!(hotkeys.len(), 1);
+        assert_eq!(hotkeys["json_formatter"].key, "J");
+    }
+
+    #[test]
+    fn missing_disabled_plugins_defaults_to_empty() {
+        let settings: Settings =
+            serde_json::from_str(r#"{"ollama": {"model": "m", "base_url": "u"}}"#).unwrap();
+        assert!(settings.disabled_plugins.is_empty());
+        assert!(!settings.everything_fallback.allow_fallback);
+    }
+
+    #[test]
+    fn saving_from_settings_window_keeps_managed_fields() {
+        // 设置窗口打开后，其他窗口修改了快捷键开关、插件和不透明度
+        let mut stored = Settings {
+            hotkeys_enabled: false,
+            launcher_opacity: 0.6,
+            ..Settings::default()
+        };
+        stored.set_plugin_enabled("json_formatter", false);
+
+        let mut incoming = Settings {
+            theme: "dark".to_string(),
+            ..Settings::default()
+        };
+        incoming.keep_managed_fields(&stored);
+
+        assert_eq!(incoming.theme, "dark");
+        assert!(!incoming.hotkeys_enabled);
+        assert_eq!(incoming.disabled_plugins, vec!["json_formatter"]);
+        assert_eq!(incoming.launcher_opacity, 0.6);
+    }
+
+    #[test]
+    fn translation_api_key_is_hidden_from_frontend_and_kept_on_save() {
+        let mut stored = Settings::default();
+        stored.translation.endpoint = "https://translate.example.com".to_string();
+        stored.translation.api_key = "secret-key".to_string();
+
+        let sent = stored.clone().without_secrets();
+        assert_eq!(sent.translation.api_key, "");
+        assert_eq!(sent.translation.endpoint, "https://translate.example.com");
+
+        // 前端保存时带回的是空 Key，不应覆盖已保存的值
+        let mut incoming = sent;
+        incoming.translation.timeout_seconds = 30;
+        incoming.keep_managed_fields(&stored);
+        assert_eq!(incoming.translation.api_key, "secret-key");
+        assert_eq!(incoming.translation.timeout_seconds, 30);
+    }
+}
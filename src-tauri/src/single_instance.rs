@@ -0,0 +1,353 @@
+//! 单实例通信：第一个实例监听命名管道（Windows）/ Unix socket，
+//! 后启动的实例连接后发送消息再退出：`focus`，或每个文件一行 `open <path>`，以空行结束
+
+use crate::{log_error, log_warn};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+
+/// 单条消息的最大长度，防止异常连接占用内存
+const MAX_MESSAGE_LEN: u64 = 32 * 1024;
+
+/// 启动时命令行传入的路径，由启动器前端注册好监听后取走
+static STARTUP_PATHS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn set_startup_paths(paths: Vec<String>) {
+    if let Ok(mut pending) = STARTUP_PATHS.lock() {
+        *pending = paths;
+    }
+}
+
+/// 取走待处理的启动路径，只返回一次
+pub fn take_startup_paths() -> Vec<String> {
+    STARTUP_PATHS
+        .lock()
+        .map(|mut pending| std::mem::take(&mut *pending))
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstanceMessage {
+    /// 显示并聚焦启动器
+    Focus,
+    /// 打开命令行传入的文件（文件关联、“发送到”启动）
+    Open(Vec<String>),
+}
+
+impl InstanceMessage {
+    /// 编码为若干行文本，以空行结束
+    pub fn encode(&self) -> String {
+        match self {
+            InstanceMessage::Focus => "focus\n\n".to_string(),
+            InstanceMessage::Open(paths) => {
+                let mut text: String = paths.iter().map(|p| format!("open {}\n", p)).collect();
+                text.push('\n');
+                text
+            }
+        }
+    }
+
+    /// 解析一条消息（到第一个空行为止）
+    pub fn parse(data: &str) -> Result<Self, String> {
+        let lines: Vec<&str> = data
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .take_while(|line| !line.is_empty())
+            .collect();
+        if lines == ["focus"] {
+            return Ok(InstanceMessage::Focus);
+        }
+        let mut paths = Vec::new();
+        for line in &lines {
+            match line.strip_prefix("open ") {
+                Some(path) if !path.trim().is_empty() => paths.push(path.to_string()),
+                _ => return Err(format!("无法识别的单实例消息: {:?}", line)),
+            }
+        }
+        if paths.is_empty() {
+            return Err("单实例消息为空".to_string());
+        }
+        Ok(InstanceMessage::Open(paths))
+    }
+
+    /// 根据命令行参数生成消息：有路径参数时打开这些文件，否则只聚焦启动器
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I, current_dir: &Path) -> Self {
+        let paths = parse_path_args(args, current_dir);
+        if paths.is_empty() {
+            InstanceMessage::Focus
+        } else {
+            InstanceMessage::Open(paths)
+        }
+    }
+}
+
+/// 从命令行参数中提取路径：忽略以 - 开头的选项，去掉残留的成对引号，
+/// 相对路径按 current_dir 展开（运行中的实例工作目录可能不同）
+pub fn parse_path_args<I: IntoIterator<Item = String>>(args: I, current_dir: &Path) -> Vec<String> {
+    args.into_iter()
+        .filter_map(|arg| {
+            let arg = arg.trim();
+            let arg = arg
+                .strip_prefix('"')
+                .and_then(|a| a.strip_suffix('"'))
+                .unwrap_or(arg)
+                .trim();
+            if arg.is_empty() || arg.starts_with('-') {
+                return None;
+            }
+            let path = PathBuf::from(arg);
+            let path = if path.is_absolute() {
+                path
+            } else {
+                current_dir.join(path)
+            };
+            Some(path.to_string_lossy().to_string())
+        })
+        .collect()
+}
+
+/// 读取到第一个空行（或连接关闭）为止
+async fn read_message<R: AsyncRead + Unpin>(reader: R) -> Result<InstanceMessage, String> {
+    let mut reader = BufReader::new(reader.take(MAX_MESSAGE_LEN));
+    let mut data = Vec::new();
+    loop {
+        let start = data.len();
+        let read = reader
+            .read_until(b'\n', &mut data)
+            .await
+            .map_err(|e| format!("读取单实例消息失败: {}", e))?;
+        let line = &data[start..];
+        if read == 0 || line == b"\n" || line == b"\r\n" {
+            break;
+        }
+    }
+    let text = String::from_utf8(data).map_err(|_| "单实例消息不是有效的 UTF-8".to_string())?;
+    InstanceMessage::parse(&text)
+}
+
+#[cfg(target_os = "windows")]
+fn pipe_name() -> String {
+    // 命名管道在整台机器内可见，按用户区分，避免多用户会话互相唤起
+    let user = std::env::var("USERNAME").unwrap_or_default();
+    format!(r"\\.\pipe\ReFast-single-instance-{}", user)
+}
+
+/// socket 位置：优先放在每个用户独立的 $XDG_RUNTIME_DIR 中；
+/// 没有时放在临时目录，文件名带用户名，避免多用户共用同一个 socket
+#[cfg(not(target_os = "windows"))]
+fn socket_path_in(runtime_dir: Option<&Path>, user: &str, temp_dir: &Path) -> PathBuf {
+    if let Some(dir) = runtime_dir.filter(|dir| dir.is_absolute()) {
+        return dir.join("re-fast.sock");
+    }
+    let user: String = user
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        .collect();
+    temp_dir.join(format!("re-fast-{}.sock", user))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn socket_path() -> PathBuf {
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from);
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("LOGNAME"))
+        .unwrap_or_default();
+    socket_path_in(runtime_dir.as_deref(), &user, &std::env::temp_dir())
+}
+
+/// 通知已运行的实例
+#[cfg(target_os = "windows")]
+pub fn send_to_running_instance(message: &InstanceMessage) -> Result<(), String> {
+    let mut pipe = std::fs::OpenOptions::new()
+        .write(true)
+        .open(pipe_name())
+        .map_err(|e| format!("连接已运行的实例失败: {}", e))?;
+    pipe.write_all(message.encode().as_bytes())
+        .map_err(|e| format!("发送单实例消息失败: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn send_to_running_instance(message: &InstanceMessage) -> Result<(), String> {
+    let mut stream = std::os::unix::net::UnixStream::connect(socket_path())
+        .map_err(|e| format!("连接已运行的实例失败: {}", e))?;
+    stream
+        .write_all(message.encode().as_bytes())
+        .map_err(|e| format!("发送单实例消息失败: {}", e))
+}
+
+/// 在后台监听其他实例发来的消息，每条消息调用一次 on_message
+pub fn start_server<F>(on_message: F)
+where
+    F: Fn(InstanceMessage) + Send + Sync + 'static,
+{
+    let on_message = Arc::new(on_message);
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve(on_message).await {
+            log_error!("SingleInstance", "Server stopped: {}", e);
+        }
+    });
+}
+
+#[cfg(target_os = "windows")]
+async fn serve<F>(on_message: Arc<F>) -> Result<(), String>
+where
+    F: Fn(InstanceMessage) + Send + Sync + 'static,
+{
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = pipe_name();
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(&name)
+        .map_err(|e| format!("创建命名管道失败: {}", e))?;
+    loop {
+        server
+            .connect()
+            .await
+            .map_err(|e| format!("等待管道连接失败: {}", e))?;
+        // 先创建下一个管道实例再处理当前连接，避免期间的连接失败
+        let connected = server;
+        server = ServerOptions::new()
+            .create(&name)
+            .map_err(|e| format!("创建命名管道失败: {}", e))?;
+
+        let on_message = on_message.clone();
+        tauri::async_runtime::spawn(async move {
+            match read_message(connected).await {
+                Ok(message) => on_message(message),
+                Err(e) => log_warn!("SingleInstance", "{}", e),
+            }
+        });
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn serve<F>(on_message: Arc<F>) -> Result<(), String>
+where
+    F: Fn(InstanceMessage) + Send + Sync + 'static,
+{
+    let path = socket_path();
+    // 上一次异常退出可能留下 socket 文件；能走到这里说明锁文件检查已确认没有其他实例
+    let _ = std::fs::remove_file(&path);
+    let listener =
+        tokio::net::UnixListener::bind(&path).map_err(|e| format!("创建 socket 失败: {}", e))?;
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| format!("等待 socket 连接失败: {}", e))?;
+        let on_message = on_message.clone();
+        tauri::async_runtime::spawn(async move {
+            match read_message(stream).await {
+                Ok(message) => on_message(message),
+                Err(e) => log_warn!("SingleInstance", "{}", e),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_round_trip() {
+        for message in [
+            InstanceMessage::Focus,
+            InstanceMessage::Open(vec!["/a b/c.txt".to_string(), r"C:\x\y.md".to_string()]),
+        ] {
+            assert_eq!(InstanceMessage::parse(&message.encode()), Ok(message));
+        }
+        // 空行之后的内容属于下一条消息
+        assert_eq!(
+            InstanceMessage::parse("focus\r\n\r\nopen /ignored\n"),
+            Ok(InstanceMessage::Focus)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_or_empty_messages() {
+        assert!(InstanceMessage::parse("").is_err());
+        assert!(InstanceMessage::parse("\n").is_err());
+        assert!(InstanceMessage::parse("quit\n\n").is_err());
+        assert!(InstanceMessage::parse("open \n\n").is_err());
+        assert!(InstanceMessage::parse("focus\nopen /a\n\n").is_err());
+    }
+
+    #[test]
+    fn builds_message_from_args() {
+        let cwd = std::env::temp_dir();
+        assert_eq!(
+            InstanceMessage::from_args(vec!["--minimized".to_string()], &cwd),
+            InstanceMessage::Focus
+        );
+        let absolute = cwd.join("abs.txt").to_string_lossy().to_string();
+        let message = InstanceMessage::from_args(
+            vec![
+                "-v".to_string(),
+                "\"rel.txt\"".to_string(),
+                format!("  {}  ", absolute),
+                "  ".to_string(),
+            ],
+            &cwd,
+        );
+        assert_eq!(
+            message,
+            InstanceMessage::Open(vec![
+                cwd.join("rel.txt").to_string_lossy().to_string(),
+                absolute,
+            ])
+        );
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn socket_path_is_per_user() {
+        let temp = Path::new("/tmp");
+        assert_eq!(
+            socket_path_in(Some(Path::new("/run/user/1000")), "alice", temp),
+            Path::new("/run/user/1000").join("re-fast.sock")
+        );
+        assert_eq!(
+            socket_path_in(None, "alice", temp),
+            temp.join("re-fast-alice.sock")
+        );
+        assert_ne!(
+            socket_path_in(None, "alice", temp),
+            socket_path_in(None, "bob", temp)
+        );
+        assert_eq!(
+            socket_path_in(Some(Path::new("relative")), "../bob", temp),
+            temp.join("re-fast-..bob.sock")
+        );
+    }
+
+    #[test]
+    fn reads_message_until_blank_line() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let data: &[u8] = b"open /a\nopen /b\n\nfocus\n\n";
+        assert_eq!(
+            runtime.block_on(read_message(data)),
+            Ok(InstanceMessage::Open(vec![
+                "/a".to_string(),
+                "/b".to_string()
+            ]))
+        );
+        let unterminated: &[u8] = b"focus\n";
+        assert_eq!(
+            runtime.block_on(read_message(unterminated)),
+            Ok(InstanceMessage::Focus)
+        );
+    }
+
+    #[test]
+    fn startup_paths_are_taken_once() {
+        set_startup_paths(vec!["C:\\a.txt".to_string(), "C:\\b.txt".to_string()]);
+        assert_eq!(take_startup_paths(), vec!["C:\\a.txt", "C:\\b.txt"]);
+        // 前端重新加载后不会再次定位
+        assert!(take_startup_paths().is_empty());
+    }
+}
@@ -1,18 +1,260 @@
+use crate::db;
+use pinyin::ToPinyin;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 用户自定义的“虚拟文件夹”别名，与系统特殊文件夹一起参与搜索
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SystemFolderAlias {
+    pub label: String,
+    pub path: String,
+}
+
+/// 加载用户自定义文件夹别名
+pub fn load_folder_aliases(app_data_dir: &Path) -> Result<Vec<SystemFolderAlias>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = 'system_folder_aliases' LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to load folder aliases from database: {}", e))?;
+
+    if let Some(json) = value {
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse folder aliases from database: {}", e))
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+fn save_folder_aliases(app_data_dir: &Path, aliases: &[SystemFolderAlias]) -> Result<(), String> {
+    let conn = db::get_connection(app_data_dir)?;
+
+    let aliases_json = serde_json::to_string(aliases)
+        .map_err(|e| format!("Failed to serialize folder aliases: {}", e))?;
+
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES ('system_folder_aliases', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![aliases_json],
+    )
+    .map_err(|e| format!("Failed to save folder aliases to database: {}", e))?;
+
+    Ok(())
+}
+
+/// 添加（或按 label 覆盖）一个文件夹别名
+pub fn add_folder_alias(
+    app_data_dir: &Path,
+    label: String,
+    path: String,
+) -> Result<Vec<SystemFolderAlias>, String> {
+    let label = label.trim().to_string();
+    if label.is_empty() {
+        return Err("别名不能为空".to_string());
+    }
+    if path.trim().is_empty() {
+        return Err("路径不能为空".to_string());
+    }
+
+    let mut aliases = load_folder_aliases(app_data_dir)?;
+    aliases.retain(|a| a.label != label);
+    aliases.push(SystemFolderAlias { label, path });
+    save_folder_aliases(app_data_dir, &aliases)?;
+    Ok(aliases)
+}
+
+/// 按 label 删除文件夹别名
+pub fn remove_folder_alias(
+    app_data_dir: &Path,
+    label: &str,
+) -> Result<Vec<SystemFolderAlias>, String> {
+    let mut aliases = load_folder_aliases(app_data_dir)?;
+    aliases.retain(|a| a.label != label);
+    save_folder_aliases(app_data_dir, &aliases)?;
+    Ok(aliases)
+}
+
+/// 系统文件夹搜索结果
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SystemFolderItem {
+    pub name: String,
+    pub path: String,
+    pub display_name: String,
+    pub is_folder: bool,
+}
+
+/// Windows shell 特殊位置：(中文名, 英文名, shell 路径)，启动时通过 `explorer shell:...` 打开
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const SHELL_ITEMS: &[(&str, &str, &str)] = &[
+    ("我的电脑", "This PC", "shell:MyComputerFolder"),
+    ("网络", "Network", "shell:NetworkPlacesFolder"),
+    ("打印机", "Printers", "shell:PrintersFolder"),
+    ("设备和打印机", "Devices and Printers", "shell:::{A8A91A66-3A7D-4424-8D24-04E180695C7A}"),
+    ("网络连接", "Network Connections", "shell:ConnectionsFolder"),
+    ("程序和功能", "Programs and Features", "shell:ChangeRemoveProgramsFolder"),
+    ("管理工具", "Administrative Tools", "shell:Common Administrative Tools"),
+    ("所有控制面板项", "All Control Panel Items", "shell:ControlPanelFolder"),
+    ("所有任务", "God Mode", "shell:::{ED7BA470-8E54-465E-825C-99712043E01C}"),
+    ("电源选项", "Power Options", "shell:::{025A5937-A6BE-4686-A844-36FE4BEC8B6D}"),
+    ("用户账户", "User Accounts", "shell:::{60632754-C523-4B62-B45C-4172DA012619}"),
+    ("凭据管理器", "Credential Manager", "shell:::{1206F5F1-0569-412C-8FEC-3204630DFB70}"),
+    ("防火墙", "Windows Firewall", "shell:::{4026492F-2F69-46B8-B9BF-5654FC07E423}"),
+    ("所有应用", "Applications", "shell:AppsFolder"),
+    ("启动文件夹", "Startup", "shell:Startup"),
+    ("发送到", "Send To", "shell:SendTo"),
+];
+
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn shell_items() -> Vec<SystemFolderItem> {
+    SHELL_ITEMS
+        .iter()
+        .map(|(name_cn, name_en, path)| SystemFolderItem {
+            name: name_cn.to_string(),
+            path: path.to_string(),
+            display_name: format!("{} ({})", name_cn, name_en),
+            is_folder: true,
+        })
+        .collect()
+}
+
+// Convert Chinese characters to pinyin (full pinyin)
+fn to_pinyin(text: &str) -> String {
+    text.to_pinyin()
+        .filter_map(|p| p.map(|p| p.plain()))
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+// Convert Chinese characters to pinyin initials (first letter of each pinyin)
+fn to_pinyin_initials(text: &str) -> String {
+    text.to_pinyin()
+        .filter_map(|p| p.map(|p| p.plain().chars().next()))
+        .flatten()
+        .collect::<String>()
+}
+
+// Check if text contains Chinese characters
+fn contains_chinese(text: &str) -> bool {
+    text.chars().any(|c| {
+        matches!(c as u32,
+            0x4E00..=0x9FFF |  // CJK Unified Ideographs
+            0x3400..=0x4DBF |  // CJK Extension A
+            0x20000..=0x2A6DF | // CJK Extension B
+            0x2A700..=0x2B73F | // CJK Extension C
+            0x2B740..=0x2B81F | // CJK Extension D
+            0xF900..=0xFAFF |  // CJK Compatibility Ideographs
+            0x2F800..=0x2FA1F   // CJK Compatibility Ideographs Supplement
+        )
+    })
+}
+
+/// 按名称、显示名、拼音全拼/首字母和路径打分，返回按分数降序排列的匹配项（如 "dayinji" 匹配 "打印机"）；
+/// 查询为空时原样返回全部
+pub fn rank_system_folders(query: &str, folders: Vec<SystemFolderItem>) -> Vec<SystemFolderItem> {
+    if query.trim().is_empty() {
+        return folders;
+    }
+
+    let query_lower = query.trim().to_lowercase();
+    let query_is_pinyin = !contains_chinese(&query_lower);
+
+    let mut results: Vec<(SystemFolderItem, i32)> = folders
+        .into_iter()
+        .filter_map(|folder| {
+            let name_lower = folder.name.to_lowercase();
+            let display_lower = folder.display_name.to_lowercase();
+            let path_lower = folder.path.to_lowercase();
+
+            let mut score = 0;
+
+            // Direct text match (highest priority)
+            if name_lower == query_lower {
+                score += 1000;
+            } else if name_lower.starts_with(&query_lower) {
+                score += 500;
+            } else if name_lower.contains(&query_lower) {
+                score += 100;
+            }
+
+            // Display name match
+            if display_lower.contains(&query_lower) {
+                score += 50;
+            }
+
+            // Pinyin matching (if query is pinyin)
+            if query_is_pinyin {
+                let name_pinyin = to_pinyin(&folder.name).to_lowercase();
+                let name_pinyin_initials = to_pinyin_initials(&folder.name).to_lowercase();
+                let display_pinyin = to_pinyin(&folder.display_name).to_lowercase();
+                let display_pinyin_initials = to_pinyin_initials(&folder.display_name).to_lowercase();
+
+                // Full pinyin match on name
+                if name_pinyin == query_lower {
+                    score += 800;
+                } else if name_pinyin.starts_with(&query_lower) {
+                    score += 400;
+                } else if name_pinyin.contains(&query_lower) {
+                    score += 150;
+                }
+
+                // Pinyin initials match on name
+                if name_pinyin_initials == query_lower {
+                    score += 600;
+                } else if name_pinyin_initials.starts_with(&query_lower) {
+                    score += 300;
+                } else if name_pinyin_initials.contains(&query_lower) {
+                    score += 120;
+                }
+
+                // Full pinyin match on display name
+                if display_pinyin.contains(&query_lower) {
+                    score += 100;
+                }
+
+                // Pinyin initials match on display name
+                if display_pinyin_initials.contains(&query_lower) {
+                    score += 80;
+                }
+            }
+
+            // Path match gets lower score
+            if path_lower.contains(&query_lower) {
+                score += 10;
+            }
+
+            (score > 0).then_some((folder, score))
+        })
+        .collect();
+
+    // Sort by score (descending); sort_by_key 是稳定排序，同分保持原有顺序
+    results.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    results.into_iter().map(|(item, _)| item).collect()
+}
+
+impl From<SystemFolderAlias> for SystemFolderItem {
+    fn from(alias: SystemFolderAlias) -> Self {
+        let is_folder = Path::new(&alias.path).is_dir();
+        SystemFolderItem {
+            display_name: format!("{} ({})", alias.label, alias.path),
+            name: alias.label,
+            path: alias.path,
+            is_folder,
+        }
+    }
+}
+
 #[cfg(target_os = "windows")]
 pub mod windows {
-    use serde::{Deserialize, Serialize};
+    pub use super::SystemFolderItem;
     use std::ffi::OsString;
     use std::os::windows::ffi::OsStringExt;
     use windows_sys::Win32::UI::Shell::*;
-    use pinyin::ToPinyin;
-
-    #[derive(Serialize, Deserialize, Debug, Clone)]
-    pub struct SystemFolderItem {
-        pub name: String,
-        pub path: String,
-        pub display_name: String,
-        pub is_folder: bool,
-    }
 
     // Windows Shell API 常量定义
     // 参考：https://learn.microsoft.com/en-us/windows/win32/shell/csidl
@@ -20,8 +262,6 @@ pub mod windows {
     const CSIDL_CONTROLS: i32 = 0x0003; // 控制面板
     const CSIDL_DESKTOP: i32 = 0x0000; // 桌面
     const CSIDL_PERSONAL: i32 = 0x0005; // 我的文档
-    const CSIDL_DRIVES: i32 = 0x0011; // 我的电脑
-    const CSIDL_NETWORK: i32 = 0x0012; // 网络
     const CSIDL_FONTS: i32 = 0x0014; // 字体
     const CSIDL_PROGRAMS: i32 = 0x0002; // 程序
     const CSIDL_STARTUP: i32 = 0x0007; // 启动
@@ -37,8 +277,6 @@ pub mod windows {
         ("控制面板", CSIDL_CONTROLS, "Control Panel"),
         ("桌面", CSIDL_DESKTOP, "Desktop"),
         ("我的文档", CSIDL_PERSONAL, "My Documents"),
-        ("我的电脑", CSIDL_DRIVES, "My Computer"),
-        ("网络", CSIDL_NETWORK, "Network"),
         ("字体", CSIDL_FONTS, "Fonts"),
         ("程序", CSIDL_PROGRAMS, "Programs"),
         ("最近使用的文档", CSIDL_RECENT, "Recent"),
@@ -93,37 +331,6 @@ pub mod windows {
         None
     }
 
-    // Convert Chinese characters to pinyin (full pinyin)
-    fn to_pinyin(text: &str) -> String {
-        text.to_pinyin()
-            .filter_map(|p| p.map(|p| p.plain()))
-            .collect::<Vec<_>>()
-            .join("")
-    }
-
-    // Convert Chinese characters to pinyin initials (first letter of each pinyin)
-    fn to_pinyin_initials(text: &str) -> String {
-        text.to_pinyin()
-            .filter_map(|p| p.map(|p| p.plain().chars().next()))
-            .flatten()
-            .collect::<String>()
-    }
-
-    // Check if text contains Chinese characters
-    fn contains_chinese(text: &str) -> bool {
-        text.chars().any(|c| {
-            matches!(c as u32,
-                0x4E00..=0x9FFF |  // CJK Unified Ideographs
-                0x3400..=0x4DBF |  // CJK Extension A
-                0x20000..=0x2A6DF | // CJK Extension B
-                0x2A700..=0x2B73F | // CJK Extension C
-                0x2B740..=0x2B81F | // CJK Extension D
-                0xF900..=0xFAFF |  // CJK Compatibility Ideographs
-                0x2F800..=0x2FA1F   // CJK Compatibility Ideographs Supplement
-            )
-        })
-    }
-
     /// 获取所有系统特殊文件夹
     pub fn get_all_system_folders() -> Vec<SystemFolderItem> {
         let mut folders = Vec::new();
@@ -152,124 +359,132 @@ pub mod windows {
             }
         }
 
+        // 打印机、网络、控制面板项等 shell 位置
+        folders.extend(super::shell_items());
         folders
     }
 
-    /// 搜索系统特殊文件夹
-    pub fn search_system_folders(query: &str) -> Vec<SystemFolderItem> {
-        eprintln!("[DEBUG] search_system_folders called with query: '{}'", query);
-        
-        if query.trim().is_empty() {
-            return get_all_system_folders();
-        }
-
-        let query_lower = query.to_lowercase();
-        let query_is_pinyin = !contains_chinese(&query_lower);
-        let all_folders = get_all_system_folders();
-        
-        eprintln!("[DEBUG] Found {} system folders, query_is_pinyin: {}", all_folders.len(), query_is_pinyin);
-
-        let mut results: Vec<(SystemFolderItem, i32)> = all_folders
-            .into_iter()
-            .filter_map(|folder| {
-                let name_lower = folder.name.to_lowercase();
-                let display_lower = folder.display_name.to_lowercase();
-                let path_lower = folder.path.to_lowercase();
-
-                let mut score = 0;
-                
-                // Direct text match (highest priority)
-                if name_lower == query_lower {
-                    score += 1000;
-                } else if name_lower.starts_with(&query_lower) {
-                    score += 500;
-                } else if name_lower.contains(&query_lower) {
-                    score += 100;
-                }
-
-                // Display name match
-                if display_lower.contains(&query_lower) {
-                    score += 50;
-                }
-
-                // Pinyin matching (if query is pinyin)
-                if query_is_pinyin {
-                    let name_pinyin = to_pinyin(&folder.name).to_lowercase();
-                    let name_pinyin_initials = to_pinyin_initials(&folder.name).to_lowercase();
-                    let display_pinyin = to_pinyin(&folder.display_name).to_lowercase();
-                    let display_pinyin_initials = to_pinyin_initials(&folder.display_name).to_lowercase();
-
-                    // Full pinyin match on name
-                    if name_pinyin == query_lower {
-                        score += 800;
-                    } else if name_pinyin.starts_with(&query_lower) {
-                        score += 400;
-                    } else if name_pinyin.contains(&query_lower) {
-                        score += 150;
-                    }
-
-                    // Pinyin initials match on name
-                    if name_pinyin_initials == query_lower {
-                        score += 600;
-                    } else if name_pinyin_initials.starts_with(&query_lower) {
-                        score += 300;
-                    } else if name_pinyin_initials.contains(&query_lower) {
-                        score += 120;
-                    }
-
-                    // Full pinyin match on display name
-                    if display_pinyin.contains(&query_lower) {
-                        score += 100;
-                    }
-
-                    // Pinyin initials match on display name
-                    if display_pinyin_initials.contains(&query_lower) {
-                        score += 80;
-                    }
-                }
-
-                // Path match gets lower score
-                if path_lower.contains(&query_lower) {
-                    score += 10;
-                }
-
-                if score > 0 {
-                    eprintln!("[DEBUG] Match found: '{}' matches query '{}' with score {}", folder.name, query, score);
-                    Some((folder, score))
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        // Sort by score (descending)
-        results.sort_by(|a, b| b.1.cmp(&a.1));
-        
-        let final_results: Vec<SystemFolderItem> = results.into_iter().map(|(item, _)| item).collect();
-        
-        eprintln!("[DEBUG] search_system_folders returning {} results", final_results.len());
-        final_results
+    /// 搜索系统特殊文件夹，`extra` 为用户自定义别名，与内置文件夹一起匹配
+    pub fn search_system_folders(query: &str, extra: Vec<SystemFolderItem>) -> Vec<SystemFolderItem> {
+        let mut all_folders = get_all_system_folders();
+        all_folders.extend(extra);
+        super::rank_system_folders(query, all_folders)
     }
 }
 
 #[cfg(not(target_os = "windows"))]
 pub mod windows {
-    use serde::{Deserialize, Serialize};
-
-    #[derive(Serialize, Deserialize, Debug, Clone)]
-    pub struct SystemFolderItem {
-        pub name: String,
-        pub path: String,
-        pub display_name: String,
-        pub is_folder: bool,
-    }
+    pub use super::SystemFolderItem;
 
+    /// 非 Windows 平台没有内置的系统文件夹
     pub fn get_all_system_folders() -> Vec<SystemFolderItem> {
         Vec::new()
     }
 
-    pub fn search_system_folders(_query: &str) -> Vec<SystemFolderItem> {
-        Vec::new()
+    pub fn search_system_folders(query: &str, extra: Vec<SystemFolderItem>) -> Vec<SystemFolderItem> {
+        super::rank_system_folders(query, extra)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn folder(name: &str, display_name: &str, path: &str) -> SystemFolderItem {
+        SystemFolderItem {
+            name: name.to_string(),
+            path: path.to_string(),
+            display_name: display_name.to_string(),
+            is_folder: true,
+        }
+    }
+
+    fn sample_folders() -> Vec<SystemFolderItem> {
+        vec![
+            folder("网络", "网络 (Network)", "shell:NetworkPlacesFolder"),
+            folder("打印机", "打印机 (Printers)", "shell:PrintersFolder"),
+            folder(
+                "设备和打印机",
+                "设备和打印机 (Devices and Printers)",
+                "shell:::{A8A91A66}",
+            ),
+            folder("下载", "下载 (Downloads)", "C:\\Users\\me\\Downloads"),
+        ]
+    }
+
+    fn names(items: &[SystemFolderItem]) -> Vec<&str> {
+        items.iter().map(|item| item.name.as_str()).collect()
+    }
+
+    #[test]
+    fn empty_query_keeps_all_folders_in_order() {
+        let ranked = rank_system_folders("  ", sample_folders());
+        assert_eq!(names(&ranked), ["网络", "打印机", "设备和打印机", "下载"]);
+    }
+
+    #[test]
+    fn chinese_query_prefers_exact_name() {
+        let ranked = rank_system_folders("打印机", sample_folders());
+        assert_eq!(names(&ranked), ["打印机", "设备和打印机"]);
+    }
+
+    #[test]
+    fn full_pinyin_and_initials_match_chinese_names() {
+        let ranked = rank_system_folders("dayinji", sample_folders());
+        assert_eq!(names(&ranked), ["打印机", "设备和打印机"]);
+
+        let ranked = rank_system_folders("DYJ", sample_folders());
+        assert_eq!(names(&ranked), ["打印机", "设备和打印机"]);
+
+        let ranked = rank_system_folders("xiazai", sample_folders());
+        assert_eq!(names(&ranked), ["下载"]);
+    }
+
+    #[test]
+    fn english_display_name_and_path_match() {
+        let ranked = rank_system_folders("printers", sample_folders());
+        assert_eq!(names(&ranked), ["打印机", "设备和打印机"]);
+
+        let ranked = rank_system_folders("networkplaces", sample_folders());
+        assert_eq!(names(&ranked), ["网络"]);
+
+        assert!(rank_system_folders("nothing-matches", sample_folders()).is_empty());
+    }
+
+    #[test]
+    fn alias_hits_rank_with_system_folders() {
+        let dir = temp_dir("system-folders", "aliases");
+        let alias_path = dir.join("projects");
+        std::fs::create_dir_all(&alias_path).unwrap();
+        let alias_path = alias_path.to_string_lossy().to_string();
+
+        add_folder_alias(&dir, " 项目 ".to_string(), alias_path.clone()).unwrap();
+        let aliases = add_folder_alias(&dir, "工作".to_string(), "D:\\work".to_string()).unwrap();
+        assert_eq!(aliases.len(), 2);
+        assert!(add_folder_alias(&dir, "  ".to_string(), "D:\\x".to_string()).is_err());
+
+        let mut folders = sample_folders();
+        folders.extend(
+            load_folder_aliases(&dir)
+                .unwrap()
+                .into_iter()
+                .map(SystemFolderItem::from),
+        );
+
+        let ranked = rank_system_folders("xiangmu", folders.clone());
+        assert_eq!(names(&ranked), ["项目"]);
+        assert_eq!(ranked[0].path, alias_path);
+        assert_eq!(ranked[0].display_name, format!("项目 ({})", alias_path));
+        assert!(ranked[0].is_folder);
+
+        let ranked = rank_system_folders("gz", folders);
+        assert_eq!(names(&ranked), ["工作"]);
+        assert!(!ranked[0].is_folder);
+
+        let aliases = remove_folder_alias(&dir, "项目").unwrap();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(load_folder_aliases(&dir).unwrap()[0].label, "工作");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,17 @@
+//! 单元测试共用的辅助函数
+
+use std::fs;
+use std::path::PathBuf;
+
+/// 系统临时目录下的 refast-<prefix>-<pid>-<name>，不创建任何文件
+pub fn temp_path(prefix: &str, name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("refast-{}-{}-{}", prefix, std::process::id(), name))
+}
+
+/// 创建空的临时目录（已存在时先清空），返回其路径
+pub fn temp_dir(prefix: &str, name: &str) -> PathBuf {
+    let dir = temp_path(prefix, name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,130 @@
+// 文本翻译：由后端请求 LibreTranslate 兼容的接口（可为本地自建服务），
+// API Key 只在后端使用，不随请求出现在 webview 中
+
+use crate::settings::TranslationSettings;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 单次翻译允许的最大字符数
+const MAX_TRANSLATION_CHARS: usize = 5000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationResult {
+    pub text: String,
+    /// 源语言为 auto 时服务端识别出的语言
+    pub detected_language: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranslateResponse {
+    translated_text: Option<String>,
+    detected_language: Option<DetectedLanguage>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DetectedLanguage {
+    language: String,
+}
+
+/// 翻译接口地址：只填写服务根地址时补上 /translate
+fn translate_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim().trim_end_matches('/');
+    if endpoint.ends_with("/translate") {
+        endpoint.to_string()
+    } else {
+        format!("{}/translate", endpoint)
+    }
+}
+
+/// 翻译文本；from 为空或 "auto" 时由服务端识别源语言
+pub async fn translate(
+    settings: &TranslationSettings,
+    text: &str,
+    from: &str,
+    to: &str,
+) -> Result<TranslationResult, String> {
+    if settings.endpoint.trim().is_empty() {
+        return Err("未配置翻译服务，请先在设置中填写翻译接口地址".to_string());
+    }
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("翻译内容不能为空".to_string());
+    }
+    if text.chars().count() > MAX_TRANSLATION_CHARS {
+        return Err(format!("翻译内容过长，最多 {} 个字符", MAX_TRANSLATION_CHARS));
+    }
+    let to = to.trim();
+    if to.is_empty() {
+        return Err("目标语言不能为空".to_string());
+    }
+    let from = match from.trim() {
+        "" => "auto",
+        from => from,
+    };
+
+    let api_key = settings.api_key.trim();
+    let body = serde_json::to_string(&TranslateRequest {
+        q: text,
+        source: from,
+        target: to,
+        format: "text",
+        api_key: (!api_key.is_empty()).then_some(api_key),
+    })
+    .map_err(|e| format!("序列化翻译请求失败: {}", e))?;
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(settings.timeout_seconds.max(1)))
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+    let response = client
+        .post(translate_url(&settings.endpoint))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                "翻译服务响应超时".to_string()
+            } else {
+                format!("无法连接翻译服务: {}", e)
+            }
+        })?;
+
+    let status = response.status();
+    let payload = response
+        .text()
+        .await
+        .map_err(|e| format!("读取翻译结果失败: {}", e))?;
+    let parsed: Option<TranslateResponse> = serde_json::from_str(&payload).ok();
+
+    if !status.is_success() {
+        let detail = parsed
+            .and_then(|r| r.error)
+            .unwrap_or_else(|| status.to_string());
+        return Err(format!("翻译服务返回错误: {}", detail));
+    }
+    let parsed = parsed.ok_or_else(|| "无法解析翻译服务的响应".to_string())?;
+    if let Some(error) = parsed.error {
+        return Err(format!("翻译服务返回错误: {}", error));
+    }
+
+    Ok(TranslationResult {
+        text: parsed
+            .translated_text
+            .ok_or_else(|| "翻译服务未返回结果".to_string())?,
+        detected_language: parsed.detected_language.map(|d| d.language),
+    })
+}
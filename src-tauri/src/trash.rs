@@ -0,0 +1,274 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Windows 目录：目录本身及其中的任何内容都不能删除
+const WINDOWS_SYSTEM_VARS: &[&str] = &["SystemRoot", "windir"];
+/// 这些目录本身不能删除，其中的内容可以（如卸载残留的程序目录）；用户目录另见 USER_FOLDERS
+const WINDOWS_PROTECTED_VARS: &[&str] = &[
+    "ProgramFiles",
+    "ProgramFiles(x86)",
+    "ProgramW6432",
+    "ProgramData",
+    "PUBLIC",
+    "APPDATA",
+    "LOCALAPPDATA",
+];
+const UNIX_SYSTEM_DIRS: &[&str] = &[
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/lib64", "/proc", "/sbin", "/sys", "/usr", "/System",
+];
+const UNIX_PROTECTED_DIRS: &[&str] = &[
+    "/home",
+    "/Users",
+    "/Applications",
+    "/Library",
+    "/opt",
+    "/var",
+];
+/// 用户目录下同样不能整体删除的文件夹
+const USER_FOLDERS: &[&str] = &["Desktop", "Documents", "Downloads"];
+
+/// 比较用的路径：去掉 \\?\ 前缀和末尾分隔符，Windows 下不区分大小写和分隔符
+fn compare_key(path: &str) -> String {
+    let path = path.strip_prefix(r"\\?\").unwrap_or(path);
+    let path = if cfg!(target_os = "windows") {
+        path.replace('/', "\\").to_lowercase()
+    } else {
+        path.to_string()
+    };
+    path.trim_end_matches(['\\', '/']).to_string()
+}
+
+/// target 是否为系统目录（或位于 Windows 目录中），命中时返回该系统目录；
+/// 目录位置由 lookup 按环境变量名提供
+pub fn protected_folder_with<F>(target: &Path, lookup: F) -> Option<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let (system, mut protected): (Vec<String>, Vec<String>) = if cfg!(target_os = "windows") {
+        (
+            WINDOWS_SYSTEM_VARS
+                .iter()
+                .filter_map(|name| lookup(name))
+                .collect(),
+            WINDOWS_PROTECTED_VARS
+                .iter()
+                .filter_map(|name| lookup(name))
+                .collect(),
+        )
+    } else {
+        (
+            UNIX_SYSTEM_DIRS.iter().map(|dir| dir.to_string()).collect(),
+            UNIX_PROTECTED_DIRS
+                .iter()
+                .map(|dir| dir.to_string())
+                .collect(),
+        )
+    };
+    let home = lookup(if cfg!(target_os = "windows") {
+        "USERPROFILE"
+    } else {
+        "HOME"
+    });
+    if let Some(home) = home {
+        let home = PathBuf::from(home);
+        protected.extend(
+            home.parent()
+                .map(|parent| parent.to_string_lossy().to_string()),
+        );
+        protected.extend(
+            USER_FOLDERS
+                .iter()
+                .map(|folder| home.join(folder).to_string_lossy().to_string()),
+        );
+        protected.push(home.to_string_lossy().to_string());
+    }
+
+    let target_key = compare_key(&target.to_string_lossy());
+    let separator = std::path::MAIN_SEPARATOR;
+    system
+        .into_iter()
+        .filter(|dir| !dir.trim().is_empty())
+        .find(|dir| {
+            let key = compare_key(dir);
+            target_key == key || target_key.starts_with(&format!("{}{}", key, separator))
+        })
+        .or_else(|| {
+            protected
+                .into_iter()
+                .filter(|dir| !dir.trim().is_empty())
+                .find(|dir| compare_key(dir) == target_key)
+        })
+}
+
+/// 校验待删除路径：必须是已存在的绝对路径，且不能是磁盘根目录或系统目录
+pub fn validate_trash_target(path: &str) -> Result<PathBuf, String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("路径不能为空".to_string());
+    }
+
+    let target = PathBuf::from(trimmed);
+    if !target.is_absolute() {
+        return Err(format!("必须是绝对路径: {}", trimmed));
+    }
+    if target.parent().is_none() {
+        return Err(format!("不能删除根目录: {}", trimmed));
+    }
+    if let Some(folder) = protected_folder_with(&target, |name| std::env::var(name).ok()) {
+        return Err(format!("不能删除系统目录: {} ({})", trimmed, folder));
+    }
+    // symlink_metadata：失效的快捷方式/链接本身也允许删除
+    if fs::symlink_metadata(&target).is_err() {
+        return Err(format!("路径不存在: {}", trimmed));
+    }
+
+    Ok(target)
+}
+
+/// 删除文件：use_trash 为 true 时移到回收站，否则永久删除
+pub fn remove_file(path: &Path, use_trash: bool) -> Result<(), String> {
+    if use_trash {
+        move_to_trash(path)
+    } else {
+        fs::remove_file(path).map_err(|e| format!("删除文件失败 {}: {}", path.display(), e))
+    }
+}
+
+/// 通过 SHFileOperationW（FO_DELETE + FOF_ALLOWUNDO）移到回收站
+#[cfg(target_os = "windows")]
+pub fn move_to_trash(path: &Path) -> Result<(), String> {
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::UI::Shell::{
+        SHFileOperationW, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOERRORUI, FOF_SILENT, FO_DELETE,
+        SHFILEOPSTRUCTW,
+    };
+
+    // SHFileOperation 不支持 \\?\ 前缀，且 pFrom 需要以两个 0 结尾
+    let path_str = path.to_string_lossy().to_string();
+    let path_str = path_str.strip_prefix(r"\\?\").unwrap_or(&path_str);
+    let from: Vec<u16> = OsStr::new(path_str)
+        .encode_wide()
+        .chain([0, 0])
+        .collect();
+
+    let mut op = SHFILEOPSTRUCTW {
+        hwnd: 0,
+        wFunc: FO_DELETE,
+        pFrom: from.as_ptr(),
+        pTo: std::ptr::null(),
+        fFlags: (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_SILENT | FOF_NOERRORUI) as u16,
+        fAnyOperationsAborted: 0,
+        hNameMappings: std::ptr::null_mut(),
+        lpszProgressTitle: std::ptr::null(),
+    };
+
+    let result = unsafe { SHFileOperationW(&mut op) };
+    if result != 0 {
+        return Err(format!(
+            "移到回收站失败 {} (错误码 0x{:X})",
+            path.display(),
+            result
+        ));
+    }
+    if op.fAnyOperationsAborted != 0 {
+        return Err(format!("移到回收站已取消: {}", path.display()));
+    }
+    Ok(())
+}
+
+/// macOS 使用 `trash` 命令（macOS 14+ 自带），Linux 使用 `gio trash`
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+pub fn move_to_trash(path: &Path) -> Result<(), String> {
+    use std::process::Command;
+
+    #[cfg(target_os = "macos")]
+    let output = Command::new("trash").arg(path).output();
+    #[cfg(target_os = "linux")]
+    let output = Command::new("gio").arg("trash").arg(path).output();
+
+    let output = output.map_err(|e| format!("移到回收站失败 {}: {}", path.display(), e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "移到回收站失败 {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn move_to_trash(_path: &Path) -> Result<(), String> {
+    Err("Moving to trash is not supported on this platform".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_path;
+
+    #[test]
+    fn rejects_empty_relative_root_and_missing_paths() {
+        assert!(validate_trash_target("  ").is_err());
+        assert!(validate_trash_target("relative/file.txt").is_err());
+        let root = if cfg!(target_os = "windows") {
+            r"C:\"
+        } else {
+            "/"
+        };
+        assert!(validate_trash_target(root).unwrap_err().contains("根目录"));
+        let missing = std::env::temp_dir().join("refast-trash-definitely-missing");
+        assert!(validate_trash_target(&missing.to_string_lossy())
+            .unwrap_err()
+            .contains("不存在"));
+    }
+
+    #[test]
+    fn accepts_existing_file() {
+        let file = temp_path("trash", "existing.txt");
+        fs::write(&file, b"x").unwrap();
+        let target = validate_trash_target(&format!(" {} ", file.display())).unwrap();
+        assert_eq!(target, file);
+        let _ = fs::remove_file(&file);
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn protects_windows_system_folders() {
+        let lookup = |name: &str| match name {
+            "SystemRoot" => Some(r"C:\Windows".to_string()),
+            "ProgramFiles" => Some(r"C:\Program Files".to_string()),
+            "USERPROFILE" => Some(r"C:\Users\alice".to_string()),
+            _ => None,
+        };
+        let protected = |path: &str| protected_folder_with(Path::new(path), lookup);
+        assert!(protected(r"C:\Windows").is_some());
+        assert!(protected(r"c:\windows\System32\drivers").is_some());
+        assert!(protected(r"C:\Program Files\").is_some());
+        assert!(protected(r"C:\Users").is_some());
+        assert!(protected(r"C:\Users\Alice").is_some());
+        assert!(protected(r"C:\Users\alice\Desktop").is_some());
+        assert!(protected(r"\\?\C:\Users\alice\Documents").is_some());
+        assert!(protected(r"C:\Program Files\OldApp").is_none());
+        assert!(protected(r"C:\Users\alice\Desktop\notes.txt").is_none());
+        assert!(protected(r"C:\WindowsApps-backup").is_none());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn protects_unix_system_folders() {
+        let lookup = |name: &str| (name == "HOME").then(|| "/home/alice".to_string());
+        let protected = |path: &str| protected_folder_with(Path::new(path), lookup);
+        assert!(protected("/usr").is_some());
+        assert!(protected("/usr/bin/ls").is_some());
+        assert!(protected("/etc/").is_some());
+        assert!(protected("/home").is_some());
+        assert!(protected("/home/alice").is_some());
+        assert!(protected("/home/alice/Downloads").is_some());
+        assert!(protected("/home/alice/Downloads/file.zip").is_none());
+        assert!(protected("/opt/old-app").is_none());
+        assert!(protected("/usrdata").is_none());
+    }
+}
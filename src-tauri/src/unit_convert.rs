@@ -0,0 +1,344 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitCategory {
+    Length,
+    Weight,
+    Temperature,
+    DataSize,
+    Currency,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversionResult {
+    pub value: f64,
+    pub input_value: f64,
+    pub from_unit: String,
+    pub to_unit: String,
+    pub category: UnitCategory,
+    /// 便于直接展示的结果，如 "6.213712 mi"
+    pub formatted: String,
+}
+
+struct Unit {
+    symbol: &'static str,
+    aliases: &'static [&'static str],
+    category: UnitCategory,
+    /// 换算到基准单位的系数（长度: 米, 重量: 千克, 数据: 字节）；温度单独处理
+    factor: f64,
+}
+
+const UNITS: &[Unit] = &[
+    // 长度
+    Unit { symbol: "mm", aliases: &["millimeter", "millimeters", "毫米"], category: UnitCategory::Length, factor: 0.001 },
+    Unit { symbol: "cm", aliases: &["centimeter", "centimeters", "厘米"], category: UnitCategory::Length, factor: 0.01 },
+    Unit { symbol: "m", aliases: &["meter", "meters", "metre", "metres", "米"], category: UnitCategory::Length, factor: 1.0 },
+    Unit { symbol: "km", aliases: &["kilometer", "kilometers", "公里", "千米"], category: UnitCategory::Length, factor: 1000.0 },
+    Unit { symbol: "in", aliases: &["inch", "inches", "英寸"], category: UnitCategory::Length, factor: 0.0254 },
+    Unit { symbol: "ft", aliases: &["foot", "feet", "英尺"], category: UnitCategory::Length, factor: 0.3048 },
+    Unit { symbol: "yd", aliases: &["yard", "yards", "码"], category: UnitCategory::Length, factor: 0.9144 },
+    Unit { symbol: "mi", aliases: &["mile", "miles", "英里"], category: UnitCategory::Length, factor: 1609.344 },
+    // 重量
+    Unit { symbol: "mg", aliases: &["milligram", "milligrams", "毫克"], category: UnitCategory::Weight, factor: 0.000001 },
+    Unit { symbol: "g", aliases: &["gram", "grams", "克"], category: UnitCategory::Weight, factor: 0.001 },
+    Unit { symbol: "kg", aliases: &["kilogram", "kilograms", "千克", "公斤"], category: UnitCategory::Weight, factor: 1.0 },
+    Unit { symbol: "t", aliases: &["ton", "tons", "tonne", "tonnes", "吨"], category: UnitCategory::Weight, factor: 1000.0 },
+    Unit { symbol: "oz", aliases: &["ounce", "ounces", "盎司"], category: UnitCategory::Weight, factor: 0.028349523125 },
+    Unit { symbol: "lb", aliases: &["lbs", "pound", "pounds", "磅"], category: UnitCategory::Weight, factor: 0.45359237 },
+    Unit { symbol: "斤", aliases: &["jin"], category: UnitCategory::Weight, factor: 0.5 },
+    // 温度
+    Unit { symbol: "°C", aliases: &["c", "celsius", "摄氏度", "℃"], category: UnitCategory::Temperature, factor: 1.0 },
+    Unit { symbol: "°F", aliases: &["f", "fahrenheit", "华氏度", "℉"], category: UnitCategory::Temperature, factor: 1.0 },
+    Unit { symbol: "K", aliases: &["k", "kelvin", "开尔文"], category: UnitCategory::Temperature, factor: 1.0 },
+    // 数据大小（十进制与二进制前缀都支持）
+    Unit { symbol: "B", aliases: &["b", "byte", "bytes", "字节"], category: UnitCategory::DataSize, factor: 1.0 },
+    Unit { symbol: "KB", aliases: &["kb"], category: UnitCategory::DataSize, factor: 1e3 },
+    Unit { symbol: "MB", aliases: &["mb"], category: UnitCategory::DataSize, factor: 1e6 },
+    Unit { symbol: "GB", aliases: &["gb"], category: UnitCategory::DataSize, factor: 1e9 },
+    Unit { symbol: "TB", aliases: &["tb"], category: UnitCategory::DataSize, factor: 1e12 },
+    Unit { symbol: "KiB", aliases: &["kib"], category: UnitCategory::DataSize, factor: 1024.0 },
+    Unit { symbol: "MiB", aliases: &["mib"], category: UnitCategory::DataSize, factor: 1_048_576.0 },
+    Unit { symbol: "GiB", aliases: &["gib"], category: UnitCategory::DataSize, factor: 1_073_741_824.0 },
+    Unit { symbol: "TiB", aliases: &["tib"], category: UnitCategory::DataSize, factor: 1_099_511_627_776.0 },
+];
+
+/// 内置汇率：1 美元可兑换的各币种数量（近似值，可在设置的 currency_rates 中覆盖）
+const BUNDLED_CURRENCY_RATES: &[(&str, f64)] = &[
+    ("USD", 1.0),
+    ("CNY", 7.1),
+    ("EUR", 0.92),
+    ("JPY", 150.0),
+    ("GBP", 0.79),
+    ("HKD", 7.8),
+    ("TWD", 32.0),
+    ("KRW", 1350.0),
+    ("SGD", 1.34),
+    ("AUD", 1.52),
+    ("CAD", 1.36),
+    ("CHF", 0.88),
+    ("INR", 83.0),
+    ("RUB", 90.0),
+];
+
+/// 币种的常用别名
+const CURRENCY_ALIASES: &[(&str, &str)] = &[
+    ("rmb", "CNY"),
+    ("元", "CNY"),
+    ("人民币", "CNY"),
+    ("美元", "USD"),
+    ("$", "USD"),
+    ("欧元", "EUR"),
+    ("€", "EUR"),
+    ("日元", "JPY"),
+    ("円", "JPY"),
+    ("英镑", "GBP"),
+    ("£", "GBP"),
+    ("港币", "HKD"),
+    ("港元", "HKD"),
+    ("新台币", "TWD"),
+    ("韩元", "KRW"),
+];
+
+/// 汇率表：币种代码（大写）→ 1 美元可兑换的数量
+#[derive(Debug, Clone)]
+pub struct CurrencyRates {
+    rates: HashMap<String, f64>,
+}
+
+impl Default for CurrencyRates {
+    fn default() -> Self {
+        Self {
+            rates: BUNDLED_CURRENCY_RATES
+                .iter()
+                .map(|(code, rate)| (code.to_string(), *rate))
+                .collect(),
+        }
+    }
+}
+
+impl CurrencyRates {
+    /// 在内置汇率上应用设置中的汇率（同样以美元为基准），忽略非正数或非法值
+    pub fn with_overrides(overrides: &HashMap<String, f64>) -> Self {
+        let mut rates = Self::default();
+        for (code, rate) in overrides {
+            let code = code.trim().to_uppercase();
+            if !code.is_empty() && rate.is_finite() && *rate > 0.0 {
+                rates.rates.insert(code, *rate);
+            }
+        }
+        rates
+    }
+
+    /// 按代码（不区分大小写）或别名查找币种，返回标准代码
+    fn find(&self, name: &str) -> Option<String> {
+        let trimmed = name.trim();
+        let lower = trimmed.to_lowercase();
+        let code = CURRENCY_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == lower)
+            .map(|(_, code)| code.to_string())
+            .unwrap_or_else(|| trimmed.to_uppercase());
+        self.rates.contains_key(&code).then_some(code)
+    }
+
+    fn convert(&self, value: f64, from: &str, to: &str) -> ConversionResult {
+        let result = value / self.rates[from] * self.rates[to];
+        ConversionResult {
+            value: result,
+            input_value: value,
+            from_unit: from.to_string(),
+            to_unit: to.to_string(),
+            category: UnitCategory::Currency,
+            formatted: format!("{:.2} {}", result, to),
+        }
+    }
+}
+
+/// "X unit to unit"，分隔符支持 to / in / -> / = / →，数值可带千位分隔符
+static CONVERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*(-?(?:\d{1,3}(?:,\d{3})+|\d+)(?:\.\d+)?|-?\.\d+)\s*([^\s\d]+)(?:\s+(?:to|in)\s+|\s*(?:->|=|→)\s*)([^\s\d]+)\s*$",
+    )
+    .expect("invalid conversion regex")
+});
+
+fn find_unit(name: &str) -> Option<&'static Unit> {
+    // 先精确匹配符号（区分 KB 与 Kb 之类大小写），再不区分大小写匹配别名
+    let trimmed = name.trim().trim_start_matches('°');
+    UNITS
+        .iter()
+        .find(|u| u.symbol.trim_start_matches('°') == trimmed)
+        .or_else(|| {
+            let lower = trimmed.to_lowercase();
+            UNITS.iter().find(|u| {
+                u.symbol.trim_start_matches('°').to_lowercase() == lower
+                    || u.aliases.iter().any(|a| *a == lower)
+            })
+        })
+}
+
+fn to_celsius(value: f64, unit: &Unit) -> f64 {
+    match unit.symbol {
+        "°F" => (value - 32.0) * 5.0 / 9.0,
+        "K" => value - 273.15,
+        _ => value,
+    }
+}
+
+fn from_celsius(value: f64, unit: &Unit) -> f64 {
+    match unit.symbol {
+        "°F" => value * 9.0 / 5.0 + 32.0,
+        "K" => value + 273.15,
+        _ => value,
+    }
+}
+
+/// 按单位换算数值，类别不一致时报错
+pub fn convert_value(value: f64, from: &str, to: &str) -> Result<ConversionResult, String> {
+    let from_unit = find_unit(from).ok_or_else(|| format!("未知单位: {}", from))?;
+    let to_unit = find_unit(to).ok_or_else(|| format!("未知单位: {}", to))?;
+    if from_unit.category != to_unit.category {
+        return Err(format!(
+            "无法在不同类型的单位之间换算: {} -> {}",
+            from_unit.symbol, to_unit.symbol
+        ));
+    }
+
+    let result = if from_unit.category == UnitCategory::Temperature {
+        from_celsius(to_celsius(value, from_unit), to_unit)
+    } else {
+        value * from_unit.factor / to_unit.factor
+    };
+
+    Ok(ConversionResult {
+        value: result,
+        input_value: value,
+        from_unit: from_unit.symbol.to_string(),
+        to_unit: to_unit.symbol.to_string(),
+        category: from_unit.category,
+        formatted: format!("{} {}", format_number(result), to_unit.symbol),
+    })
+}
+
+/// 解析 "10 km to miles" / "72 f to c" / "100 usd in cny" 形式的输入并换算
+/// 输入不符合格式、单位未知或类别不一致时返回 None，启动器据此保持安静
+pub fn convert_expression(input: &str, rates: &CurrencyRates) -> Option<ConversionResult> {
+    let caps = CONVERSION_RE.captures(input)?;
+    let value: f64 = caps[1].replace(',', "").parse().ok()?;
+    if let (Some(from), Some(to)) = (rates.find(&caps[2]), rates.find(&caps[3])) {
+        return Some(rates.convert(value, &from, &to));
+    }
+    convert_value(value, &caps[2], &caps[3]).ok()
+}
+
+/// 最多保留 6 位有效小数并去掉末尾的 0
+pub fn format_number(value: f64) -> String {
+    if value.is_finite() && value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{}", value as i64);
+    }
+    let s = format!("{:.6}", value);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(input: &str) -> f64 {
+        convert_expression(input, &CurrencyRates::default())
+            .unwrap_or_else(|| panic!("no conversion: {}", input))
+            .value
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn temperature_uses_offsets() {
+        assert_close(convert("212 f to c"), 100.0);
+        assert_close(convert("-40 °C to °F"), -40.0);
+        assert_close(convert("0 c to k"), 273.15);
+        assert_close(convert("0 K to celsius"), -273.15);
+        assert_close(convert("300 kelvin -> f"), 80.33);
+        assert_close(convert("98.6 华氏度 to 摄氏度"), 37.0);
+    }
+
+    #[test]
+    fn data_sizes_distinguish_binary_prefixes() {
+        assert_close(convert("1 KiB to B"), 1024.0);
+        assert_close(convert("1 KB to B"), 1000.0);
+        assert_close(convert("1 MiB to KiB"), 1024.0);
+        assert_close(convert("1 MB to KB"), 1000.0);
+        assert_close(convert("1 MiB to KB"), 1048.576);
+        assert_close(convert("2,048 mib in gib"), 2.0);
+    }
+
+    #[test]
+    fn lengths_and_weights() {
+        let result = convert_expression("10 km to miles", &CurrencyRates::default()).unwrap();
+        assert_eq!(result.category, UnitCategory::Length);
+        assert_eq!(result.formatted, "6.213712 mi");
+        assert_close(convert("1 斤 to g"), 500.0);
+        assert_close(convert("1 lb = oz"), 16.0);
+    }
+
+    #[test]
+    fn currency_overrides_apply_on_top_of_bundled_rates() {
+        let overrides = HashMap::from([
+            ("cny".to_string(), 7.0),
+            ("XYZ".to_string(), -1.0),
+            ("EUR".to_string(), f64::NAN),
+        ]);
+        let rates = CurrencyRates::with_overrides(&overrides);
+
+        let result = convert_expression("100 usd to 人民币", &rates).unwrap();
+        assert_eq!(result.category, UnitCategory::Currency);
+        assert_eq!(result.to_unit, "CNY");
+        assert_close(result.value, 700.0);
+        assert_close(
+            convert_expression("70 元 to $", &rates).unwrap().value,
+            10.0,
+        );
+        // 非法的覆盖值被忽略，保留内置汇率
+        assert_close(
+            convert_expression("1 usd to eur", &rates).unwrap().value,
+            0.92,
+        );
+        assert!(convert_expression("1 usd to xyz", &rates).is_none());
+    }
+
+    #[test]
+    fn unknown_or_mixed_input_is_quiet() {
+        let rates = CurrencyRates::default();
+        for input in [
+            "10 km to kg",
+            "10 foo to bar",
+            "1 usd to km",
+            "10 km",
+            "hello world",
+            "km to mi",
+        ] {
+            assert!(convert_expression(input, &rates).is_none(), "{}", input);
+        }
+        assert!(convert_value(1.0, "c", "kg").is_err());
+        assert!(convert_value(1.0, "parsec", "m").is_err());
+    }
+
+    #[test]
+    fn format_number_trims_trailing_zeros() {
+        assert_eq!(format_number(84.0), "84");
+        assert_eq!(format_number(0.5), "0.5");
+        assert_eq!(format_number(1.0 / 3.0), "0.333333");
+        assert_eq!(format_number(-2.25), "-2.25");
+    }
+}
@@ -0,0 +1,86 @@
+// 用户添加的网址快捷方式：保存在 settings 表中，扫描应用时并入应用列表
+
+use crate::app_search;
+use crate::db;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UrlShortcut {
+    pub name: String,
+    pub url: String,
+    pub icon: Option<String>,
+}
+
+impl UrlShortcut {
+    pub fn to_app_info(&self) -> app_search::AppInfo {
+        app_search::windows::url_app_info(self.name.clone(), self.url.clone(), self.icon.clone())
+    }
+}
+
+pub fn load_url_shortcuts(app_data_dir: &Path) -> Result<Vec<UrlShortcut>, String> {
+    let conn = db::get_connection(app_data_dir)?;
+
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = 'url_shortcuts' LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to load url shortcuts from database: {}", e))?;
+
+    if let Some(json) = value {
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse url shortcuts from database: {}", e))
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+fn save_url_shortcuts(app_data_dir: &Path, shortcuts: &[UrlShortcut]) -> Result<(), String> {
+    let conn = db::get_connection(app_data_dir)?;
+
+    let shortcuts_json = serde_json::to_string(shortcuts)
+        .map_err(|e| format!("Failed to serialize url shortcuts: {}", e))?;
+
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES ('url_shortcuts', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![shortcuts_json],
+    )
+    .map_err(|e| format!("Failed to save url shortcuts to database: {}", e))?;
+
+    Ok(())
+}
+
+/// 添加网址快捷方式；同一网址已存在时更新名称和图标
+pub fn add_url_shortcut(
+    app_data_dir: &Path,
+    name: String,
+    url: String,
+    icon: Option<String>,
+) -> Result<UrlShortcut, String> {
+    let name = name.trim().to_string();
+    let url = url.trim().to_string();
+    if name.is_empty() {
+        return Err("名称不能为空".to_string());
+    }
+    if !app_search::is_url_with_scheme(&url) {
+        return Err(format!("无效的网址（需要包含协议，如 https://）: {}", url));
+    }
+
+    let shortcut = UrlShortcut {
+        name,
+        url,
+        icon: icon.filter(|icon| !icon.trim().is_empty()),
+    };
+    let mut shortcuts = load_url_shortcuts(app_data_dir)?;
+    match shortcuts.iter_mut().find(|s| s.url == shortcut.url) {
+        Some(existing) => *existing = shortcut.clone(),
+        None => shortcuts.push(shortcut.clone()),
+    }
+    save_url_shortcuts(app_data_dir, &shortcuts)?;
+    Ok(shortcut)
+}
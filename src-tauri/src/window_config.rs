@@ -112,3 +112,133 @@ fn maybe_migrate_from_json(
 }
 
 
+/// 窗口的位置和大小（物理像素），按窗口 label 保存在 window_config 表中
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 标题栏区域至少有这么多像素落在某个显示器内，才认为保存的位置仍然可用
+const MIN_VISIBLE_TITLE_BAR: i32 = 40;
+
+pub fn load_window_geometry(app_data_dir: &Path, label: &str) -> Option<WindowGeometry> {
+    let conn = db::get_connection(app_data_dir).ok()?;
+    let row: (Option<i32>, Option<i32>, Option<i64>, Option<i64>) = conn
+        .query_row(
+            "SELECT x, y, width, height FROM window_config WHERE key = ?1 LIMIT 1",
+            params![label],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()
+        .ok()??;
+
+    match row {
+        (Some(x), Some(y), Some(width), Some(height)) if width > 0 && height > 0 => {
+            Some(WindowGeometry {
+                x,
+                y,
+                width: u32::try_from(width).ok()?,
+                height: u32::try_from(height).ok()?,
+            })
+        }
+        _ => None,
+    }
+}
+
+pub fn save_window_geometry(
+    app_data_dir: &Path,
+    label: &str,
+    geometry: &WindowGeometry,
+) -> Result<(), String> {
+    let conn = db::get_connection(app_data_dir)?;
+    conn.execute(
+        "INSERT INTO window_config (key, x, y, width, height) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(key) DO UPDATE SET
+            x = excluded.x, y = excluded.y, width = excluded.width, height = excluded.height",
+        params![
+            label,
+            geometry.x,
+            geometry.y,
+            geometry.width as i64,
+            geometry.height as i64
+        ],
+    )
+    .map_err(|e| format!("Failed to save window geometry for {}: {}", label, e))?;
+    Ok(())
+}
+
+/// 窗口顶部（标题栏）与任一显示器有足够的重叠时返回 true，
+/// 避免显示器被拔掉或分辨率变化后把窗口恢复到屏幕外
+fn is_title_bar_visible(geometry: &WindowGeometry, monitors: &[tauri::Monitor]) -> bool {
+    let left = geometry.x;
+    let right = geometry.x.saturating_add(geometry.width.min(i32::MAX as u32) as i32);
+    let top = geometry.y;
+    let bottom = geometry.y.saturating_add(MIN_VISIBLE_TITLE_BAR);
+
+    monitors.iter().any(|monitor| {
+        let position = monitor.position();
+        let size = monitor.size();
+        let monitor_right = position.x.saturating_add(size.width as i32);
+        let monitor_bottom = position.y.saturating_add(size.height as i32);
+        let overlap_x = right.min(monitor_right) - left.max(position.x);
+        let overlap_y = bottom.min(monitor_bottom) - top.max(position.y);
+        overlap_x >= MIN_VISIBLE_TITLE_BAR && overlap_y >= MIN_VISIBLE_TITLE_BAR / 2
+    })
+}
+
+/// 把窗口恢复到上次关闭时的位置和大小；没有保存的记录或位置已不在任何显示器上时保持不变
+pub fn restore_window_geometry(window: &tauri::WebviewWindow, app_data_dir: &Path) -> bool {
+    use tauri::{PhysicalPosition, PhysicalSize};
+
+    let Some(geometry) = load_window_geometry(app_data_dir, window.label()) else {
+        return false;
+    };
+    let monitors = window.available_monitors().unwrap_or_default();
+    if !is_title_bar_visible(&geometry, &monitors) {
+        crate::log_debug!(
+            "Window",
+            "{} 保存的位置 ({}, {}) 不在任何显示器上，使用默认位置",
+            window.label(),
+            geometry.x,
+            geometry.y
+        );
+        return false;
+    }
+
+    let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+    true
+}
+
+/// 当前窗口的位置和大小；最小化或最大化时返回 None，避免保存 (-32000, -32000) 之类的坐标
+fn current_geometry(window: &tauri::WebviewWindow) -> Option<WindowGeometry> {
+    if window.is_minimized().unwrap_or(false) || window.is_maximized().unwrap_or(false) {
+        return None;
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    (size.width > 0 && size.height > 0).then_some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+/// 窗口关闭时保存其位置和大小，下次创建同 label 的窗口时由 restore_window_geometry 恢复
+pub fn remember_window_geometry(window: &tauri::WebviewWindow, app_data_dir: &Path) {
+    let tracked = window.clone();
+    let app_data_dir = app_data_dir.to_path_buf();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::CloseRequested { .. } = event {
+            if let Some(geometry) = current_geometry(&tracked) {
+                if let Err(e) = save_window_geometry(&app_data_dir, tracked.label(), &geometry) {
+                    crate::log_warn!("Window", "{}", e);
+                }
+            }
+        }
+    });
+}
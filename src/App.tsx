@@ -1,4 +1,5 @@
 import { useState, useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { RecordControls } from "./components/RecordControls";
 import { PlaybackControls } from "./components/PlaybackControls";
 import { RecordingList } from "./components/RecordingList";
@@ -73,6 +74,24 @@ function App() {
     return () => clearInterval(interval);
   }, [status]);
 
+  // 回放开始前的倒计时
+  useEffect(() => {
+    const unlistenPromise = listen<{ remainingSeconds: number; remainingMs: number }>(
+      "playback-countdown",
+      (event) => {
+        const { remainingSeconds } = event.payload;
+        setMessage(
+          remainingSeconds > 0
+            ? `回放将在 ${remainingSeconds} 秒后开始（按 Esc 取消）`
+            : "正在回放"
+        );
+      }
+    );
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, []);
+
   // Listen for Esc key to stop playback
   useEffect(() => {
     if (status !== "playing") {
@@ -130,11 +149,40 @@ function App() {
     }
   };
 
-  const handlePlayRecording = async (path: string, speed: number) => {
+  // 录制时与当前屏幕分辨率/缩放不同时，回放坐标可能偏移
+  const getEnvMismatchWarning = async (path: string): Promise<string> => {
+    const recorded = recordings.find((rec) => rec.file_path === path)?.capture_env;
+    if (!recorded) {
+      return "";
+    }
+    try {
+      const current = await tauriApi.getCaptureEnv();
+      if (
+        current &&
+        (current.width !== recorded.width ||
+          current.height !== recorded.height ||
+          current.scale !== recorded.scale)
+      ) {
+        return `（警告：录制时分辨率为 ${recorded.width}×${recorded.height} @${Math.round(recorded.scale * 100)}%，当前为 ${current.width}×${current.height} @${Math.round(current.scale * 100)}%，点击位置可能不准确）`;
+      }
+    } catch (error) {
+      console.error("Failed to get capture env:", error);
+    }
+    return "";
+  };
+
+  const handlePlayRecording = async (
+    path: string,
+    speed: number,
+    scaleToCurrentResolution: boolean,
+    startDelayMs: number
+  ) => {
     try {
-      await tauriApi.playRecording(path, speed);
+      // 已启用坐标缩放时不再提示分辨率差异
+      const warning = scaleToCurrentResolution ? "" : await getEnvMismatchWarning(path);
+      await tauriApi.playRecording(path, speed, scaleToCurrentResolution, startDelayMs);
       setStatus("playing");
-      setMessage(`正在回放: ${path} (${speed}x)`);
+      setMessage(`正在回放: ${path} (${speed}x)${warning}`);
       setProgress(0);
     } catch (error) {
       setMessage(`开始回放失败: ${error}`);
@@ -1,18 +1,69 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke as tauriInvoke } from "@tauri-apps/api/core";
 import type {
   RecordingMeta,
+  CaptureEnv,
   AppInfo,
+  AppSearchPage,
+  ShortcutInfo,
   FileHistoryItem,
+  FileHistoryStats,
   EverythingSearchResponse,
   EverythingSearchOptions,
   EverythingResult,
+  EverythingHealth,
+  SavedSearch,
+  CalcEntry,
+  SavedSearchOptions,
+  UnifiedSearchResult,
   ShortcutItem,
   MemoItem,
   IndexStatus,
+  Diagnostics,
   FilePreview,
   DatabaseBackupList,
+  DatabaseIntegrityReport,
+  DatabaseRepairResult,
   PluginUsage,
+  Settings,
 } from "../types";
+import type { PluginManifest, ScannedPlugin } from "../plugins/types";
+
+/** 后端 AppError 序列化后的结构 */
+export interface AppErrorPayload {
+  code: string;
+  message: string;
+}
+
+/** 结构化的命令错误：name 为错误码，String(error) 仍包含原始消息 */
+export class AppCommandError extends Error {
+  code: string;
+
+  constructor(payload: AppErrorPayload) {
+    super(payload.message);
+    this.name = payload.code;
+    this.code = payload.code;
+  }
+}
+
+function isAppErrorPayload(error: unknown): error is AppErrorPayload {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as AppErrorPayload).code === "string" &&
+    typeof (error as AppErrorPayload).message === "string"
+  );
+}
+
+async function invoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  try {
+    return await tauriInvoke<T>(cmd, args);
+  } catch (error) {
+    throw isAppErrorPayload(error) ? new AppCommandError(error) : error;
+  }
+}
+
+/** 应用 / 文件历史搜索被更新的查询取代时后端返回的错误，调用方可静默忽略 */
+export const SUPERSEDED_QUERY_ERROR = "查询已被更新的输入取代";
 
 export const tauriApi = {
   async getRecordingStatus(): Promise<boolean> {
@@ -31,12 +82,29 @@ export const tauriApi = {
     return invoke("list_recordings");
   },
 
+  async getCaptureEnv(): Promise<CaptureEnv | null> {
+    return invoke("get_capture_env");
+  },
+
+  async exportRecordingCsv(path: string): Promise<string> {
+    return invoke("export_recording_csv", { path });
+  },
+
+  async importRecordingCsv(path: string, name: string): Promise<string> {
+    return invoke("import_recording_csv", { path, name });
+  },
+
   async deleteRecording(path: string): Promise<void> {
     return invoke("delete_recording", { path });
   },
 
-  async playRecording(path: string, speed: number): Promise<void> {
-    return invoke("play_recording", { path, speed });
+  async playRecording(
+    path: string,
+    speed: number,
+    scaleToCurrentResolution?: boolean,
+    startDelayMs?: number
+  ): Promise<void> {
+    return invoke("play_recording", { path, speed, scaleToCurrentResolution, startDelayMs });
   },
 
   async stopPlayback(): Promise<void> {
@@ -63,14 +131,27 @@ export const tauriApi = {
     return invoke("populate_app_icons", { limit });
   },
 
-  async searchApplications(query: string): Promise<AppInfo[]> {
-    return invoke("search_applications", { query });
+  async searchApplications(
+    query: string,
+    limit?: number,
+    offset?: number
+  ): Promise<AppSearchPage> {
+    return invoke("search_applications", { query, limit, offset });
   },
 
   async launchApplication(app: AppInfo): Promise<void> {
     return invoke("launch_application", { app });
   },
 
+  /** 添加自定义网址快捷方式，作为应用出现在搜索结果中 */
+  async addUrlShortcut(name: string, url: string, icon?: string): Promise<AppInfo> {
+    return invoke("add_url_shortcut", { name, url, icon });
+  },
+
+  async fetchFavicon(url: string): Promise<string | null> {
+    return invoke("fetch_favicon", { url });
+  },
+
   async removeAppFromIndex(appPath: string): Promise<void> {
     return invoke("remove_app_from_index", { appPath });
   },
@@ -87,6 +168,11 @@ export const tauriApi = {
     return invoke("toggle_launcher");
   },
 
+  // 返回限制在 0.3–1.0 后实际生效的不透明度
+  async setLauncherOpacity(opacity: number): Promise<number> {
+    return invoke("set_launcher_opacity", { opacity });
+  },
+
   async hideLauncher(): Promise<void> {
     return invoke("hide_launcher");
   },
@@ -95,18 +181,37 @@ export const tauriApi = {
     return invoke("add_file_to_history", { path });
   },
 
-  async searchFileHistory(query: string): Promise<FileHistoryItem[]> {
-    return invoke("search_file_history", { query });
+  async addFilesToHistory(paths: string[]): Promise<number> {
+    return invoke("add_files_to_history", { paths });
+  },
+
+  async searchFileHistory(
+    query: string,
+    action?: "open" | "reveal"
+  ): Promise<FileHistoryItem[]> {
+    return invoke("search_file_history", { query, action });
   },
 
   async getAllFileHistory(): Promise<FileHistoryItem[]> {
     return invoke("get_all_file_history");
   },
 
+  async getRecentDocuments(limit?: number): Promise<FileHistoryItem[]> {
+    return invoke("get_recent_documents", { limit });
+  },
+
   async purgeFileHistory(days?: number): Promise<number> {
     return invoke("purge_file_history", { days });
   },
 
+  async getFileHistoryStats(top?: number): Promise<FileHistoryStats> {
+    return invoke("get_file_history_stats", { top });
+  },
+
+  async vacuumDatabase(): Promise<{ size_before: number; size_after: number }> {
+    return invoke("vacuum_database");
+  },
+
   async deleteFileHistory(path: string): Promise<void> {
     return invoke("delete_file_history", { path });
   },
@@ -123,10 +228,40 @@ export const tauriApi = {
     return invoke("launch_file", { path });
   },
 
+  async launchFileWith(path: string, appPath: string): Promise<void> {
+    return invoke("launch_file_with", { path, appPath });
+  },
+
+  async openWithDialog(path: string): Promise<void> {
+    return invoke("open_with_dialog", { path });
+  },
+
+  async runCommand(
+    program: string,
+    args: string[] = [],
+    workingDir?: string
+  ): Promise<number> {
+    return invoke("run_command", { program, args, workingDir });
+  },
+
+  async listProcesses(): Promise<
+    Array<{ pid: number; name: string; memory?: number | null }>
+  > {
+    return invoke("list_processes");
+  },
+
+  async killProcess(pid: number): Promise<void> {
+    return invoke("kill_process", { pid });
+  },
+
   async checkPathExists(path: string): Promise<FileHistoryItem | null> {
     return invoke("check_path_exists", { path });
   },
 
+  async resolveShortcut(path: string): Promise<ShortcutInfo> {
+    return invoke("resolve_shortcut", { path });
+  },
+
   async getClipboardFilePath(): Promise<string | null> {
     return invoke("get_clipboard_file_path");
   },
@@ -135,10 +270,34 @@ export const tauriApi = {
     return invoke("get_clipboard_text");
   },
 
+  async setClipboardText(text: string): Promise<void> {
+    return invoke("set_clipboard_text", { text });
+  },
+
+  async copyPathToClipboard(path: string): Promise<void> {
+    return invoke("copy_path_to_clipboard", { path });
+  },
+
+  async copyPathsToClipboard(paths: string[]): Promise<void> {
+    return invoke("copy_paths_to_clipboard", { paths });
+  },
+
   async saveClipboardImage(imageData: Uint8Array, extension: string): Promise<string> {
     return invoke("save_clipboard_image", { imageData: Array.from(imageData), extension });
   },
 
+  async getClipboardImage(): Promise<string | null> {
+    return invoke("get_clipboard_image");
+  },
+
+  async saveClipboardImageToFile(dest: string): Promise<string> {
+    return invoke("save_clipboard_image_to_file", { dest });
+  },
+
+  async ocrClipboardImage(lang?: string): Promise<string> {
+    return invoke("ocr_clipboard_image", { lang: lang ?? null });
+  },
+
   async writeDebugLog(message: string): Promise<void> {
     return invoke("write_debug_log", { message });
   },
@@ -171,6 +330,7 @@ export const tauriApi = {
       sortOrder?: "asc" | "desc";
       matchWholeWord?: boolean;
       matchFolderNameOnly?: boolean;
+      scopePath?: string;
     }
   ): Promise<{ sessionId: string; totalCount: number; truncated?: boolean }> {
     return invoke("start_everything_search_session", {
@@ -199,6 +359,12 @@ export const tauriApi = {
     });
   },
 
+  async getEverythingSearchGroups(
+    sessionId: string
+  ): Promise<{ folder: string; items: EverythingResult[] }[]> {
+    return invoke("get_everything_search_groups", { sessionId });
+  },
+
   async closeEverythingSearchSession(sessionId: string): Promise<void> {
     return invoke("close_everything_search_session", { sessionId });
   },
@@ -207,6 +373,10 @@ export const tauriApi = {
     return invoke("cancel_everything_search");
   },
 
+  async validateEverythingQuery(query: string): Promise<void> {
+    return invoke("validate_everything_query", { query });
+  },
+
   async isEverythingAvailable(): Promise<boolean> {
     return invoke("is_everything_available");
   },
@@ -219,6 +389,10 @@ export const tauriApi = {
     };
   },
 
+  async everythingHealth(): Promise<EverythingHealth> {
+    return invoke("everything_health");
+  },
+
   async getEverythingCustomFilters(): Promise<Array<{ id: string; label: string; extensions: string[] }>> {
     return invoke("get_everything_custom_filters");
   },
@@ -227,6 +401,28 @@ export const tauriApi = {
     return invoke("save_everything_custom_filters", { filters });
   },
 
+  async listSavedSearches(): Promise<SavedSearch[]> {
+    return invoke("list_saved_searches");
+  },
+
+  async addSavedSearch(
+    name: string,
+    query: string,
+    options?: SavedSearchOptions
+  ): Promise<SavedSearch> {
+    return invoke("add_saved_search", { name, query, options });
+  },
+
+  async deleteSavedSearch(id: string): Promise<void> {
+    return invoke("delete_saved_search", { id });
+  },
+
+  async runSavedSearch(
+    id: string
+  ): Promise<{ sessionId: string; totalCount: number; truncated?: boolean }> {
+    return invoke("run_saved_search", { id });
+  },
+
   async getIndexStatus(): Promise<IndexStatus> {
     return invoke("get_index_status");
   },
@@ -243,8 +439,16 @@ export const tauriApi = {
     return invoke("get_everything_log_file_path");
   },
 
-  async backupDatabase(): Promise<string> {
-    return invoke("backup_database");
+  async getLogFilePath(): Promise<string> {
+    return invoke("get_log_file_path");
+  },
+
+  async setLogLevel(level: "error" | "warn" | "info" | "debug"): Promise<void> {
+    return invoke("set_log_level", { level });
+  },
+
+  async backupDatabase(compress?: boolean): Promise<string> {
+    return invoke("backup_database", { compress });
   },
 
   async getDatabaseBackups(): Promise<DatabaseBackupList> {
@@ -259,8 +463,24 @@ export const tauriApi = {
     return invoke("restore_backup", { path });
   },
 
-  async getFilePreview(path: string): Promise<FilePreview> {
-    return invoke("get_file_preview", { path });
+  async checkDatabaseIntegrity(): Promise<DatabaseIntegrityReport> {
+    return invoke("check_database_integrity");
+  },
+
+  async repairDatabase(): Promise<DatabaseRepairResult> {
+    return invoke("repair_database");
+  },
+
+  async exportAllData(dest: string): Promise<string> {
+    return invoke("export_all_data", { dest });
+  },
+
+  async importAllData(src: string): Promise<string | null> {
+    return invoke("import_all_data", { src });
+  },
+
+  async getFilePreview(path: string, computeFolderSize?: boolean): Promise<FilePreview> {
+    return invoke("get_file_preview", { path, computeFolderSize });
   },
 
   async openEverythingDownload(): Promise<void> {
@@ -271,11 +491,28 @@ export const tauriApi = {
     return invoke("download_everything");
   },
 
+  async installEverything(installerPath: string, silent: boolean): Promise<void> {
+    return invoke("install_everything", { installerPath, silent });
+  },
+
 
   async startEverything(): Promise<void> {
     return invoke("start_everything");
   },
 
+  /** 按设置自动启动已安装但未运行的 Everything（每次运行最多一次），返回是否可用 */
+  async autoStartEverything(): Promise<boolean> {
+    return invoke("auto_start_everything");
+  },
+
+  async takeStartupPaths(): Promise<string[]> {
+    return invoke("take_startup_paths");
+  },
+
+  async refreshEverythingIndex(): Promise<void> {
+    return invoke("refresh_everything_index");
+  },
+
   async getAllShortcuts(): Promise<ShortcutItem[]> {
     return invoke("get_all_shortcuts");
   },
@@ -309,6 +546,18 @@ export const tauriApi = {
     return invoke("reveal_in_folder", { path });
   },
 
+  async revealInFolderMany(paths: string[]): Promise<void> {
+    return invoke("reveal_in_folder_many", { paths });
+  },
+
+  async openTerminalHere(path: string): Promise<void> {
+    return invoke("open_terminal_here", { path });
+  },
+
+  async moveToTrash(path: string): Promise<void> {
+    return invoke("move_to_trash", { path });
+  },
+
   // Memo APIs
   async getAllMemos(): Promise<MemoItem[]> {
     return invoke("get_all_memos");
@@ -334,6 +583,53 @@ export const tauriApi = {
     return invoke("search_memos", { query });
   },
 
+  async unifiedSearch(query: string, limit?: number): Promise<UnifiedSearchResult[]> {
+    return invoke("unified_search", { query, limit });
+  },
+
+  async translateText(
+    text: string,
+    from: string,
+    to: string
+  ): Promise<{ text: string; detectedLanguage: string | null }> {
+    return invoke("translate_text", { text, from, to });
+  },
+
+  async setTranslationApiKey(apiKey: string): Promise<void> {
+    return invoke("set_translation_api_key", { apiKey });
+  },
+
+  async convertUnits(input: string): Promise<{
+    value: number;
+    input_value: number;
+    from_unit: string;
+    to_unit: string;
+    category: "length" | "weight" | "temperature" | "data_size" | "currency";
+    formatted: string;
+  } | null> {
+    return invoke("convert_units", { input });
+  },
+
+  async evaluateExpression(input: string): Promise<{ value: number; formatted: string } | null> {
+    return invoke("evaluate_expression", { input });
+  },
+
+  async devTools(input: string): Promise<{
+    operation:
+      | "to_hex"
+      | "to_dec"
+      | "to_bin"
+      | "to_oct"
+      | "md5"
+      | "sha1"
+      | "sha256"
+      | "base64_encode"
+      | "base64_decode";
+    output: string;
+  } | null> {
+    return invoke("dev_tools", { input });
+  },
+
   async showMainWindow(): Promise<void> {
     return invoke("show_main_window");
   },
@@ -359,6 +655,51 @@ export const tauriApi = {
     return invoke("show_json_formatter_window");
   },
 
+  async formatJson(
+    input: string,
+    indent: number,
+    sortKeys: boolean
+  ): Promise<{
+    valid: boolean;
+    output: string | null;
+    error: { message: string; line: number; column: number } | null;
+  }> {
+    return invoke("format_json", { input, indent, sortKeys });
+  },
+
+  async validateJson(input: string): Promise<{
+    valid: boolean;
+    error: { message: string; line: number; column: number } | null;
+  }> {
+    return invoke("validate_json", { input });
+  },
+
+  async convertJson(input: string, to: "yaml" | "toml" | "csv"): Promise<string> {
+    return invoke("convert_json", { input, to });
+  },
+
+  async queryJson(input: string, path: string): Promise<string> {
+    return invoke("query_json", { input, path });
+  },
+
+  async generateQrCode(text: string, size: number): Promise<string> {
+    return invoke("generate_qr_code", { text, size });
+  },
+
+  async convertColor(input: string): Promise<{
+    hex: string;
+    rgb: string;
+    hsl: string;
+    red: number;
+    green: number;
+    blue: number;
+    alpha: number;
+    textColor: string;
+    contrastRatio: number;
+  }> {
+    return invoke("convert_color", { input });
+  },
+
   async showFileToolboxWindow(): Promise<void> {
     return invoke("show_file_toolbox_window");
   },
@@ -367,10 +708,27 @@ export const tauriApi = {
     return invoke("show_calculator_pad_window");
   },
 
+  async addCalcEntry(expression: string, result: string): Promise<CalcEntry> {
+    return invoke("add_calc_entry", { expression, result });
+  },
+
+  async getCalcHistory(limit?: number): Promise<CalcEntry[]> {
+    return invoke("get_calc_history", { limit: limit ?? null });
+  },
+
+  async clearCalcHistory(): Promise<number> {
+    return invoke("clear_calc_history");
+  },
+
   async showEverythingSearchWindow(): Promise<void> {
     return invoke("show_everything_search_window");
   },
 
+  /** 重新打开最近显示过的工具窗口，返回其窗口 label */
+  async reopenLastWindow(): Promise<string> {
+    return invoke("reopen_last_window");
+  },
+
   async showTranslationWindow(): Promise<void> {
     return invoke("show_translation_window");
   },
@@ -384,15 +742,20 @@ export const tauriApi = {
     caseSensitive: boolean;
     backupFolder: boolean;
     replaceFileName: boolean;
+    includeGlobs?: string[];
+    excludeGlobs?: string[];
+    binaryMode?: boolean;
   }): Promise<{
     results: Array<{
       filePath: string;
       matches: number;
       success: boolean;
       error?: string;
+      previewDiff?: Array<[string, string]> | null;
     }>;
     totalMatches: number;
     totalFiles: number;
+    cancelled: boolean;
   }> {
     return invoke("preview_file_replace", {
       folderPath: params.folderPath,
@@ -403,9 +766,43 @@ export const tauriApi = {
       caseSensitive: params.caseSensitive,
       backupFolder: params.backupFolder,
       replaceFileName: params.replaceFileName,
+      includeGlobs: params.includeGlobs,
+      excludeGlobs: params.excludeGlobs,
+      binaryMode: params.binaryMode,
     });
   },
 
+  async previewFileReplaceWithProgress(params: {
+    folderPath: string;
+    searchText: string;
+    replaceText: string;
+    fileExtensions: string[];
+    useRegex: boolean;
+    caseSensitive: boolean;
+    backupFolder: boolean;
+    replaceFileName: boolean;
+    includeGlobs?: string[];
+    excludeGlobs?: string[];
+    binaryMode?: boolean;
+  }): Promise<{
+    results: Array<{
+      filePath: string;
+      matches: number;
+      success: boolean;
+      error?: string;
+      previewDiff?: Array<[string, string]> | null;
+    }>;
+    totalMatches: number;
+    totalFiles: number;
+    cancelled: boolean;
+  }> {
+    return invoke("preview_file_replace_with_progress", { params });
+  },
+
+  async cancelFileReplace(): Promise<void> {
+    return invoke("cancel_file_replace");
+  },
+
   async executeFileReplace(params: {
     folderPath: string;
     searchText: string;
@@ -415,6 +812,9 @@ export const tauriApi = {
     caseSensitive: boolean;
     backupFolder: boolean;
     replaceFileName: boolean;
+    includeGlobs?: string[];
+    excludeGlobs?: string[];
+    binaryMode?: boolean;
   }): Promise<{
     results: Array<{
       filePath: string;
@@ -424,6 +824,8 @@ export const tauriApi = {
     }>;
     totalMatches: number;
     totalFiles: number;
+    journalId?: string | null;
+    cancelled: boolean;
   }> {
     return invoke("execute_file_replace", {
       folderPath: params.folderPath,
@@ -434,9 +836,40 @@ export const tauriApi = {
       caseSensitive: params.caseSensitive,
       backupFolder: params.backupFolder,
       replaceFileName: params.replaceFileName,
+      includeGlobs: params.includeGlobs,
+      excludeGlobs: params.excludeGlobs,
+      binaryMode: params.binaryMode,
     });
   },
 
+  async undoFileReplace(journalId: string): Promise<{
+    restoredFiles: number;
+    restoredRenames: number;
+    errors: string[];
+  }> {
+    return invoke("undo_file_replace", { journalId });
+  },
+
+  async bulkRename(
+    folder: string,
+    pattern: string,
+    replacement: string,
+    useRegex: boolean,
+    preview: boolean,
+    recursive?: boolean
+  ): Promise<{
+    renames: { oldPath: string; newPath: string; error: string | null }[];
+    conflicts: { oldPath: string; newPath: string; error: string | null }[];
+    renamed: number;
+    journalId: string | null;
+  }> {
+    return invoke("bulk_rename", { folder, pattern, replacement, useRegex, preview, recursive });
+  },
+
+  async computeFileHash(path: string, algo: "md5" | "sha1" | "sha256"): Promise<string> {
+    return invoke("compute_file_hash", { path, algo });
+  },
+
   async selectFolder(): Promise<string | null> {
     return invoke("select_folder");
   },
@@ -458,17 +891,38 @@ export const tauriApi = {
     return invoke("scan_plugin_directory", { directory });
   },
 
+  async scanPluginDirectoryWithManifests(directory: string): Promise<ScannedPlugin[]> {
+    return invoke("scan_plugin_directory", { directory, withManifests: true });
+  },
+
   async readPluginManifest(pluginDir: string): Promise<string> {
     return invoke("read_plugin_manifest", { pluginDir });
   },
 
+  async validatePluginManifest(pluginDir: string): Promise<PluginManifest> {
+    return invoke("validate_plugin_manifest", { pluginDir });
+  },
+
+  async installPlugin(zipPath: string, overwrite?: boolean): Promise<PluginManifest> {
+    return invoke("install_plugin", { zipPath, overwrite });
+  },
+
+  // 返回已禁用的插件 ID
+  async getPluginStates(): Promise<string[]> {
+    return invoke("get_plugin_states");
+  },
+
+  async setPluginEnabled(pluginId: string, enabled: boolean): Promise<void> {
+    return invoke("set_plugin_enabled", { pluginId, enabled });
+  },
+
 
   // Settings APIs
-  async getSettings(): Promise<{ ollama: { model: string; base_url: string }; startup_enabled?: boolean; result_style?: "compact" | "soft" | "skeuomorphic"; close_on_blur?: boolean }> {
+  async getSettings(): Promise<Settings> {
     return invoke("get_settings");
   },
 
-  async saveSettings(settings: { ollama: { model: string; base_url: string }; startup_enabled?: boolean; result_style?: "compact" | "soft" | "skeuomorphic"; close_on_blur?: boolean }): Promise<void> {
+  async saveSettings(settings: Settings): Promise<void> {
     return invoke("save_settings", { settings });
   },
 
@@ -485,6 +939,10 @@ export const tauriApi = {
     return invoke("set_startup_enabled", { enabled });
   },
 
+  async repairStartupEntry(): Promise<boolean> {
+    return invoke("repair_startup_entry");
+  },
+
   // Hotkey APIs
   async getHotkeyConfig(): Promise<{ modifiers: string[]; key: string } | null> {
     return invoke("get_hotkey_config");
@@ -503,6 +961,15 @@ export const tauriApi = {
   },
 
   // Plugin hotkey APIs
+  // 全局暂停/恢复所有快捷键（免打扰），状态会持久化
+  async setHotkeysEnabled(enabled: boolean): Promise<void> {
+    return invoke("set_hotkeys_enabled", { enabled });
+  },
+
+  async getHotkeysEnabled(): Promise<boolean> {
+    return invoke("get_hotkeys_enabled");
+  },
+
   async getPluginHotkeys(): Promise<Record<string, { modifiers: string[]; key: string }>> {
     return invoke("get_plugin_hotkeys");
   },
@@ -537,5 +1004,13 @@ export const tauriApi = {
   async getAppVersion(): Promise<string> {
     return invoke("get_app_version");
   },
+
+  async getDiagnostics(): Promise<Diagnostics> {
+    return invoke("get_diagnostics");
+  },
+
+  async collectDiagnostics(writeToLogs?: boolean): Promise<string> {
+    return invoke("collect_diagnostics", { writeToLogs });
+  },
 };
 
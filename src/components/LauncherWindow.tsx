@@ -2,7 +2,7 @@ import { useState, useEffect, useRef, useMemo, useCallback } from "react";
 import { flushSync } from "react-dom";
 import ReactMarkdown from "react-markdown";
 import remarkGfm from "remark-gfm";
-import { tauriApi } from "../api/tauri";
+import { tauriApi, SUPERSEDED_QUERY_ERROR } from "../api/tauri";
 import { trackEvent } from "../api/events";
 import type { AppInfo, FileHistoryItem, EverythingResult, MemoItem, PluginContext } from "../types";
 import { getCurrentWindow } from "@tauri-apps/api/window";
@@ -57,6 +57,9 @@ export function LauncherWindow() {
   const [everythingCurrentCount, setEverythingCurrentCount] = useState<number>(0); // 当前已加载的数量
   const [directPathResult, setDirectPathResult] = useState<FileHistoryItem | null>(null); // 绝对路径直达结果
   const [isEverythingAvailable, setIsEverythingAvailable] = useState(false);
+  // Everything 不可用时是否允许后端回退到文件系统搜索
+  const [allowSearchFallback, setAllowSearchFallback] = useState(false);
+  const canSearchFiles = isEverythingAvailable || allowSearchFallback;
   const [everythingPath, setEverythingPath] = useState<string | null>(null);
   const [everythingVersion, setEverythingVersion] = useState<string | null>(null);
   const [everythingError, setEverythingError] = useState<string | null>(null);
@@ -70,6 +73,8 @@ export function LauncherWindow() {
   const [selectedVerticalIndex, setSelectedVerticalIndex] = useState<number | null>(null);
   const [selectedIndex, setSelectedIndex] = useState(0); // Keep for now, will be computed from selectedHorizontalIndex/selectedVerticalIndex
   const [isLoading, setIsLoading] = useState(false);
+  // 首次运行没有应用缓存时的扫描进度（app-scan-progress 事件）
+  const [appScanProgress, setAppScanProgress] = useState<{ progress: number; message: string } | null>(null);
   const [isHoveringAiIcon, setIsHoveringAiIcon] = useState(false);
   const [isAiLoading, setIsAiLoading] = useState(false);
   const [aiAnswer, setAiAnswer] = useState<string | null>(null);
@@ -466,6 +471,7 @@ export function LauncherWindow() {
         const closeOnBlurSetting = settings.close_on_blur ?? true;
         setCloseOnBlur(closeOnBlurSetting);
         closeOnBlurRef.current = closeOnBlurSetting;
+        setAllowSearchFallback(settings.everything_fallback?.allow_fallback ?? false);
       } catch (error) {
         console.error("Failed to load settings:", error);
       }
@@ -486,7 +492,11 @@ export function LauncherWindow() {
   useEffect(() => {
     const checkEverything = async () => {
       try {
-        const status = await tauriApi.getEverythingStatus();
+        let status = await tauriApi.getEverythingStatus();
+        // 已安装但未运行时按设置自动启动一次，后端限制了等待时间
+        if (!status.available && (await tauriApi.autoStartEverything())) {
+          status = await tauriApi.getEverythingStatus();
+        }
         setIsEverythingAvailable(status.available);
         setEverythingError(status.error || null);
         
@@ -555,8 +565,17 @@ export function LauncherWindow() {
   }, []);
 
   // 静默预加载应用列表（组件挂载时，不显示加载状态）
+  // 有缓存时立即返回；首次运行需要完整扫描，后端会发送 app-scan-progress 事件，此时显示扫描进度
   useEffect(() => {
     let isMounted = true;
+    const unlistenScanProgress = listen<{ progress: number; message: string }>(
+      "app-scan-progress",
+      (event) => {
+        if (!isMounted) return;
+        const { progress, message } = event.payload;
+        setAppScanProgress(progress < 100 ? { progress, message } : null);
+      }
+    );
     const preloadApplications = async () => {
       try {
         // 静默加载，不设置 isLoading 状态
@@ -568,6 +587,10 @@ export function LauncherWindow() {
       } catch (error) {
         console.error("Failed to preload applications:", error);
         // 预加载失败不影响用户体验，静默处理
+      } finally {
+        if (isMounted) {
+          setAppScanProgress(null);
+        }
       }
     };
     // 延迟一小段时间，避免阻塞初始渲染
@@ -575,6 +598,7 @@ export function LauncherWindow() {
     return () => {
       isMounted = false;
       clearTimeout(timer);
+      unlistenScanProgress.then((fn) => fn());
     };
   }, []);
 
@@ -644,6 +668,39 @@ export function LauncherWindow() {
     };
   }, []);
 
+  // 命令行传入的路径（文件关联、“发送到”）已加入文件历史，搜索第一个路径以定位到该条目
+  useEffect(() => {
+    let unlistenFn: (() => void) | null = null;
+
+    const focusPath = (paths: string[]) => {
+      const [first] = paths;
+      if (first) {
+        setQuery(first);
+        setSelectedIndex(0);
+      }
+    };
+
+    listen<{ paths: string[] }>("open-path-requested", (event) => {
+      focusPath(event.payload.paths);
+    }).then((unlisten) => {
+      unlistenFn = unlisten;
+      // 监听注册好后再取走启动时传入的路径，避免事件早于监听发出而丢失
+      return tauriApi.takeStartupPaths();
+    }).then((paths) => {
+      if (paths) {
+        focusPath(paths);
+      }
+    }).catch((error) => {
+      console.error("Failed to take startup paths:", error);
+    });
+
+    return () => {
+      if (unlistenFn) {
+        unlistenFn();
+      }
+    };
+  }, []);
+
   // Listen for Everything download progress events
   useEffect(() => {
     if (!isDownloadingEverything) return;
@@ -1316,7 +1373,7 @@ export function LauncherWindow() {
       searchFileHistory(trimmedQuery);
       searchMemos(trimmedQuery);
       handleSearchPlugins(trimmedQuery);
-      if (isEverythingAvailable && !isPathQuery) {
+      if (canSearchFiles && !isPathQuery) {
         console.log("[搜索调试] 调用searchEverything:", trimmedQuery);
         searchEverything(trimmedQuery).catch((error) => {
           console.error("[搜索调试] searchEverything错误:", error);
@@ -1341,7 +1398,7 @@ export function LauncherWindow() {
       }
     };
     // eslint-disable-next-line react-hooks/exhaustive-deps
-  }, [query, isEverythingAvailable]);
+  }, [query, canSearchFiles]);
 
   const searchMemos = async (q: string) => {
     try {
@@ -2966,7 +3023,7 @@ export function LauncherWindow() {
         setFilteredApps([]);
         return;
       }
-      const results = await tauriApi.searchApplications(searchQuery);
+      const { items: results } = await tauriApi.searchApplications(searchQuery);
       
       // Final check: only update if query hasn't changed
       const currentQueryTrimmed = query.trim();
@@ -2979,6 +3036,9 @@ export function LauncherWindow() {
         setFilteredApps([]);
       }
     } catch (error) {
+      if (error === SUPERSEDED_QUERY_ERROR) {
+        return;
+      }
       console.error("Failed to search applications:", error);
       // Only clear on error if query is empty
       if (!searchQuery || searchQuery.trim() === "") {
@@ -3051,6 +3111,9 @@ export function LauncherWindow() {
         setFilteredFiles([]);
       }
     } catch (error) {
+      if (error === SUPERSEDED_QUERY_ERROR) {
+        return;
+      }
       console.error("Failed to search file history:", error);
       if (!searchQuery || searchQuery.trim() === "") {
         setFilteredFiles([]);
@@ -3151,7 +3214,7 @@ export function LauncherWindow() {
       return;
     }
     
-    if (!isEverythingAvailable) {
+    if (!canSearchFiles) {
       setEverythingResults([]);
       setEverythingTotalCount(null);
       setEverythingCurrentCount(0);
@@ -4927,14 +4990,18 @@ export function LauncherWindow() {
           ) : null}
 
           {/* Loading or Empty State */}
-          {!showAiAnswer && isLoading && (
+          {!showAiAnswer && (isLoading || appScanProgress) && (
             <div className="px-6 py-8 text-center text-gray-500 flex-1 flex flex-col items-center justify-center">
               <div className="inline-block animate-spin rounded-full h-6 w-6 border-b-2 border-gray-400 mb-2"></div>
-              <div>正在扫描应用...</div>
+              <div>
+                {appScanProgress
+                  ? `${appScanProgress.message} (${appScanProgress.progress}%)`
+                  : "正在扫描应用..."}
+              </div>
             </div>
           )}
 
-          {!showAiAnswer && !isLoading && results.length === 0 && query && (
+          {!showAiAnswer && !isLoading && !appScanProgress && results.length === 0 && query && (
             <div className="px-6 py-8 text-center text-gray-500 flex-1 flex items-center justify-center">
               未找到匹配的应用或文件
             </div>
@@ -4998,7 +5065,7 @@ export function LauncherWindow() {
             </div>
           )}
 
-          {!showAiAnswer && !isLoading && results.length === 0 && !query && (
+          {!showAiAnswer && !isLoading && !appScanProgress && results.length === 0 && !query && (
             <div className="px-6 py-8 text-center text-gray-400 text-sm flex-1 flex items-center justify-center">
               输入关键词搜索应用，或粘贴文件路径
             </div>
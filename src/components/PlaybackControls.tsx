@@ -5,7 +5,12 @@ interface PlaybackControlsProps {
   recordings: Array<{ file_path: string; file_name: string }>;
   selectedPath?: string;
   onSelectPath?: (path: string) => void;
-  onPlay: (path: string, speed: number) => void;
+  onPlay: (
+    path: string,
+    speed: number,
+    scaleToCurrentResolution: boolean,
+    startDelayMs: number
+  ) => void;
   onStop: () => void;
 }
 
@@ -19,6 +24,8 @@ export const PlaybackControls: React.FC<PlaybackControlsProps> = ({
 }) => {
   const [selectedPath, setSelectedPath] = useState<string>(externalSelectedPath);
   const [speed, setSpeed] = useState<number>(1.0);
+  const [scaleToCurrentResolution, setScaleToCurrentResolution] = useState<boolean>(false);
+  const [startDelayMs, setStartDelayMs] = useState<number>(0);
 
   // Sync external selectedPath with internal state
   useEffect(() => {
@@ -32,7 +39,7 @@ export const PlaybackControls: React.FC<PlaybackControlsProps> = ({
 
   const handlePlay = () => {
     if (selectedPath) {
-      onPlay(selectedPath, speed);
+      onPlay(selectedPath, speed, scaleToCurrentResolution, startDelayMs);
     }
   };
 
@@ -67,6 +74,29 @@ export const PlaybackControls: React.FC<PlaybackControlsProps> = ({
             <option value="2.0">2x</option>
           </select>
         </div>
+        <div className="flex items-center gap-2">
+          <label className="text-sm">开始前等待:</label>
+          <select
+            value={startDelayMs}
+            onChange={(e) => setStartDelayMs(parseInt(e.target.value, 10))}
+            disabled={isPlaying}
+            className="px-3 py-2 border border-gray-300 rounded disabled:bg-gray-100"
+          >
+            <option value="0">立即开始</option>
+            <option value="3000">3 秒</option>
+            <option value="5000">5 秒</option>
+            <option value="10000">10 秒</option>
+          </select>
+        </div>
+        <label className="flex items-center gap-2 text-sm">
+          <input
+            type="checkbox"
+            checked={scaleToCurrentResolution}
+            onChange={(e) => setScaleToCurrentResolution(e.target.checked)}
+            disabled={isPlaying}
+          />
+          按当前分辨率缩放坐标
+        </label>
         <div className="flex gap-2">
           <button
             onClick={handlePlay}
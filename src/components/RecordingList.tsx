@@ -43,6 +43,8 @@ export const RecordingList: React.FC<RecordingListProps> = ({
               <div className="font-medium">{rec.file_name}</div>
               <div className="text-sm text-gray-500 mt-1">
                 时长: {formatDuration(rec.duration_ms)} | 事件数: {rec.event_count}
+                {rec.capture_env &&
+                  ` | 分辨率: ${rec.capture_env.width}×${rec.capture_env.height} @${Math.round(rec.capture_env.scale * 100)}%`}
               </div>
               <div className="text-xs text-gray-400 mt-1">
                 创建时间: {rec.created_at}
@@ -1,5 +1,6 @@
 import { tauriApi } from "../api/tauri";
 import { useEffect, useState } from "react";
+import type { EverythingHealth } from "../types";
 
 const handleCheckUpdate = async () => {
   try {
@@ -120,6 +121,34 @@ export function SystemSettingsPage({
   onSettingsChange,
   onOpenHotkeySettings,
 }: SystemSettingsProps) {
+  const [everythingHealth, setEverythingHealth] = useState<EverythingHealth | null>(null);
+  const [isCheckingEverything, setIsCheckingEverything] = useState(false);
+
+  const handleCheckEverything = async () => {
+    setIsCheckingEverything(true);
+    try {
+      setEverythingHealth(await tauriApi.everythingHealth());
+    } catch (error) {
+      console.error("Failed to check Everything health:", error);
+      setEverythingHealth(null);
+      alert("检测 Everything 状态失败");
+    } finally {
+      setIsCheckingEverything(false);
+    }
+  };
+
+  const everythingHealthText = (health: EverythingHealth) => {
+    if (!health.available) {
+      return health.errorCode === "NOT_INSTALLED"
+        ? "Everything 未安装"
+        : `Everything 不可用（${health.errorCode ?? "未知错误"}）`;
+    }
+    if (health.indexing) {
+      return `Everything 响应较慢（${health.latencyMs ?? "-"} ms），可能仍在建立索引`;
+    }
+    return `Everything 已就绪（${health.latencyMs ?? "-"} ms）`;
+  };
+
   return (
     <div className="space-y-6">
       <div>
@@ -204,6 +233,34 @@ export function SystemSettingsPage({
             </label>
           </div>
           
+          <div className="border-t border-gray-200 pt-6">
+            <div className="flex items-center justify-between">
+              <div className="flex-1">
+                <label className="block text-sm font-medium text-gray-700 mb-1">
+                  Everything 状态
+                </label>
+                <p
+                  className={`text-xs ${
+                    everythingHealth && (!everythingHealth.available || everythingHealth.indexing)
+                      ? "text-orange-600"
+                      : "text-gray-500"
+                  }`}
+                >
+                  {everythingHealth
+                    ? everythingHealthText(everythingHealth)
+                    : "检测 Everything 是否可用及其响应速度"}
+                </p>
+              </div>
+              <button
+                onClick={handleCheckEverything}
+                disabled={isCheckingEverything}
+                className="px-4 py-2 bg-blue-600 text-white rounded-md hover:bg-blue-700 transition-colors text-sm disabled:opacity-50"
+              >
+                {isCheckingEverything ? "检测中..." : "检测"}
+              </button>
+            </div>
+          </div>
+
           <div className="border-t border-gray-200 pt-6">
             <div className="flex items-center justify-between">
               <div className="flex-1">
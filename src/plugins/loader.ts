@@ -107,15 +107,16 @@ export class PluginLoader {
       // 外部插件：使用 Tauri API 扫描目录
       try {
         const { tauriApi } = await import("../api/tauri");
-        const pluginDirs = await tauriApi.scanPluginDirectory(directory);
+        const scanned = await tauriApi.scanPluginDirectoryWithManifests(directory);
         
-        for (const dir of pluginDirs) {
+        for (const { dir, manifest, error, enabled } of scanned) {
           try {
-            // 读取 manifest.json
-            const manifestContent = await tauriApi.readPluginManifest(dir);
-            const manifest: PluginManifest = JSON.parse(manifestContent);
+            // manifest.json 已在后端解析并校验
+            if (!manifest) {
+              throw new Error(error ?? "Invalid manifest.json");
+            }
             
-            if (manifest.enabled !== false) {
+            if (enabled && manifest.enabled !== false) {
               // 对于外部插件，需要特殊处理导入路径
               // 这里暂时跳过，因为外部插件的动态导入比较复杂
               // 可以考虑使用 eval 或其他方式
@@ -11,6 +11,15 @@ export interface PluginManifest {
   dependencies?: Record<string, string>;
   enabled?: boolean;
   icon?: string; // 插件图标路径
+  permissions?: string[];
+  hotkey?: string;
+}
+
+export interface ScannedPlugin {
+  dir: string;
+  manifest: PluginManifest | null;
+  error: string | null; // manifest 无效时的原因
+  enabled: boolean;
 }
 
 export interface LoadedPlugin extends Plugin {
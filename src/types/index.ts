@@ -26,25 +26,71 @@ export interface RecordingMeta {
   duration_ms: number;
   event_count: number;
   created_at: string;
+  capture_env?: CaptureEnv | null;
+}
+
+export interface CaptureEnv {
+  width: number;
+  height: number;
+  scale: number;
 }
 
 export type AppStatus = "idle" | "recording" | "playing";
 
 export interface AppInfo {
   name: string;
-  path: string;
+  path: string; // 网址快捷方式时为网址
+  is_url?: boolean; // 网址快捷方式（.url 文件或用户添加），启动时用浏览器打开
   icon?: string;
   description?: string;
   name_pinyin?: string; // 拼音全拼（用于拼音搜索）
   name_pinyin_initials?: string; // 拼音首字母（用于拼音首字母搜索）
 }
 
+export interface AppSearchPage {
+  items: AppInfo[];
+  totalCount: number; // 匹配的应用总数
+  offset: number;
+}
+
+export interface ShortcutInfo {
+  path: string; // .lnk 文件本身
+  target: string | null; // 指向虚拟位置（如控制面板项）时为 null
+  targetExists: boolean;
+  arguments: string | null;
+  workingDir: string | null;
+  iconLocation: string | null;
+  iconIndex: number;
+  description: string | null;
+  resolvedBy: "native" | "wscript";
+}
+
 export interface FileHistoryItem {
   path: string;
   name: string;
   last_used: number;
   use_count: number;
   is_folder?: boolean | null; // 是否为文件夹
+  last_action?: "open" | "reveal" | ""; // 最近一次操作：打开 / 在文件夹中定位
+  is_symlink?: boolean; // 仅 checkPathExists 返回：是否为符号链接 / 目录联接
+  target?: string; // 链接指向的目标路径
+  target_exists?: boolean; // 链接目标是否存在（false 表示悬空链接）
+}
+
+export interface FileHistoryExtensionStat {
+  extension: string; // 小写扩展名，没有扩展名时为空字符串
+  count: number;
+  use_count: number;
+}
+
+export interface FileHistoryStats {
+  total: number;
+  total_uses: number;
+  folders: number;
+  oldest_last_used?: number | null;
+  newest_last_used?: number | null;
+  by_extension: FileHistoryExtensionStat[];
+  most_used: FileHistoryItem[];
 }
 
 export interface EverythingResult {
@@ -54,6 +100,34 @@ export interface EverythingResult {
   date_modified?: string;
   // 是否为文件夹（包括磁盘、根目录等目录类型）
   is_folder?: boolean | null;
+  // name / path 中匹配查询词的字符区间 [start, end)
+  name_highlights?: [number, number][];
+  path_highlights?: [number, number][];
+}
+
+export interface EverythingHealth {
+  available: boolean;
+  errorCode: string | null;
+  latencyMs: number | null;
+  hasResults: boolean;
+  // 探测查询很慢或超时，Everything 可能仍在建立索引
+  indexing: boolean;
+}
+
+export interface SavedSearchOptions {
+  extensions?: string[] | null;
+  maxResults?: number | null;
+  sortKey?: "size" | "type" | "name" | null;
+  sortOrder?: "asc" | "desc" | null;
+  matchFolderNameOnly?: boolean | null;
+}
+
+export interface SavedSearch {
+  id: string;
+  name: string;
+  query: string;
+  options: SavedSearchOptions;
+  createdAt: number;
 }
 
 export interface EverythingSearchResponse {
@@ -69,6 +143,8 @@ export interface EverythingSearchOptions {
   maxResults?: number;
   matchWholeWord?: boolean;
   matchFolderNameOnly?: boolean;
+  /** 只搜索该文件夹（含子文件夹）下的结果 */
+  scopePath?: string;
 }
 
 export interface IndexEverythingStatus {
@@ -96,11 +172,33 @@ export interface IndexStatus {
   file_history: IndexFileHistoryStatus;
 }
 
+/** 单项诊断探测：成功时有 value，失败时有 error */
+export interface DiagnosticsProbe<T> {
+  value?: T;
+  error?: string;
+}
+
+export interface Diagnostics {
+  generatedAt: string;
+  appVersion: string;
+  os: { os: string; family: string; arch: string; version: DiagnosticsProbe<string> };
+  indexStatus: DiagnosticsProbe<IndexStatus>;
+  logDir: string;
+  hotkeyLogPath: DiagnosticsProbe<string>;
+  historyDbSize: DiagnosticsProbe<number>;
+  pluginCount: DiagnosticsProbe<number>;
+  startupEnabled: DiagnosticsProbe<boolean>;
+  lockFilePath: string;
+  settings: DiagnosticsProbe<Record<string, unknown>>;
+}
+
 export interface DatabaseBackupInfo {
   name: string;
   path: string;
   size: number;
   modified?: number | null;
+  compressed: boolean;
+  uncompressed_size?: number | null;
 }
 
 export interface DatabaseBackupList {
@@ -108,6 +206,19 @@ export interface DatabaseBackupList {
   items: DatabaseBackupInfo[];
 }
 
+export interface DatabaseIntegrityReport {
+  ok: boolean;
+  problems: string[];
+}
+
+export interface DatabaseRepairResult {
+  method: "none" | "rebuilt" | "restored_backup";
+  before: DatabaseIntegrityReport;
+  after: DatabaseIntegrityReport;
+  skipped: string[]; // 重建时未能恢复的表和索引
+  restored_from?: string | null;
+}
+
 export type FilePreviewKind =
   | "text"
   | "image"
@@ -123,6 +234,18 @@ export interface FilePreviewMetadata {
   height?: number;
 }
 
+export interface FolderChild {
+  name: string;
+  is_dir: boolean;
+  size?: number;
+}
+
+export interface FolderPreview {
+  children: FolderChild[];
+  child_count: number;
+  total_size?: number;
+}
+
 export interface FilePreview {
   kind: FilePreviewKind;
   size?: number;
@@ -133,6 +256,10 @@ export interface FilePreview {
   imageDataUrl?: string;
   truncated?: boolean;
   metadata?: FilePreviewMetadata;
+  line_count?: number;
+  encoding?: string;
+  thumbnail?: boolean;
+  folder?: FolderPreview;
   error?: string;
 }
 
@@ -154,6 +281,23 @@ export interface MemoItem {
   updated_at: number;
 }
 
+export interface SystemFolderItem {
+  name: string;
+  path: string;
+  display_name: string;
+  is_folder: boolean;
+}
+
+// unified_search 的结果：kind 区分来源，score 为归一化分数（0..1）
+export type UnifiedSearchResult = (
+  | { kind: "app"; item: AppInfo }
+  | { kind: "shortcut"; item: ShortcutItem }
+  | { kind: "file_history"; item: FileHistoryItem }
+  | { kind: "everything"; item: EverythingResult }
+  | { kind: "system_folder"; item: SystemFolderItem }
+  | { kind: "memo"; item: MemoItem }
+) & { score: number };
+
 // 插件系统类型定义
 export interface PluginContext {
   // 可以传递给插件执行函数的上下文信息
@@ -191,3 +335,39 @@ export interface PluginUsage {
   lastOpened: number;
 }
 
+export interface CalcEntry {
+  id: number;
+  expression: string;
+  result: string;
+  createdAt: number;
+}
+
+
+export interface Settings {
+  ollama: { model: string; base_url: string };
+  startup_enabled?: boolean;
+  result_style?: "compact" | "soft" | "skeuomorphic";
+  close_on_blur?: boolean;
+  everything_fallback?: { allow_fallback: boolean; roots: string[]; max_depth: number };
+  compress_backups?: boolean;
+  delete_to_trash?: boolean;
+  auto_start_everything?: boolean;
+  disabled_plugins?: string[];
+  theme?: "light" | "dark" | "system";
+  launcher_opacity?: number;
+  hotkeys_enabled?: boolean;
+  log_level?: "error" | "warn" | "info" | "debug";
+  startup_delay_seconds?: number | null;
+  startup_minimized?: boolean;
+  // api_key 不会返回给前端，通过 setTranslationApiKey 单独设置
+  translation?: { endpoint: string; timeout_seconds?: number };
+  currency_rates?: Record<string, number>;
+  ocr?: { backend: string; command: string; args: string[] };
+  terminal?: string;
+  max_app_results?: number;
+  max_everything_results?: number;
+  max_history_results?: number;
+  preview_max_bytes?: number;
+  app_scan_ignore?: string[];
+  fetch_favicons?: boolean;
+}